@@ -0,0 +1,91 @@
+//! Latency of [`RemoteConfig::load`] hot path.
+//!
+//! + `fresh_hit` - cached data is valid, so it is returned without locking
+//! + `stale_hit` - cached data is stale, but may be served while revalidation runs in background
+//! + `must_revalidate/{threads}` - cached data is stale and must be revalidated, so every load of an iteration
+//!   waits for one slow revalidation. Each iteration runs one load per worker thread.
+//!
+//! Run with `cargo bench --bench load`. Compare against saved baseline with
+//! `cargo bench --bench load -- --save-baseline main` on base branch and `-- --baseline main` on changed branch.
+//!
+//! Baseline (release profile, single core VM, median):
+//!
+//! | Scenario             | Time    |
+//! |----------------------|---------|
+//! | `fresh_hit`          | 97 ns   |
+//! | `stale_hit`          | 280 ns  |
+//! | `must_revalidate/1`  | 2.12 ms |
+//! | `must_revalidate/2`  | 2.11 ms |
+//! | `must_revalidate/4`  | 2.10 ms |
+//! | `must_revalidate/8`  | 2.10 ms |
+//!
+//! Contended cases are dominated by simulated revalidation delay (1 ms, rounded up by tokio timer),
+//! so their growth with thread count shows overhead of waiting for single revalidation.
+use std::error::Error;
+use std::time::{Duration, SystemTime};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use tokio::runtime::Runtime;
+use remote_config::config::RemoteConfig;
+use remote_config::data_providers::data_provider::{DataLoadResult, DataProvider};
+
+/// Data provider that returns the same data after optional delay
+#[derive(Debug)]
+struct BenchProvider {
+    ttl: Duration,
+    must_revalidate: bool,
+    delay: Duration
+}
+
+impl DataProvider<Vec<u64>> for BenchProvider {
+    async fn load_data(&self) -> Result<DataLoadResult<Vec<u64>>, Box<dyn Error + Send + Sync>> {
+        if !self.delay.is_zero() {
+            tokio::time::sleep(self.delay).await;
+        }
+        Ok(DataLoadResult::new((0..64).collect(), self.must_revalidate, SystemTime::now() + self.ttl))
+    }
+}
+
+fn config(runtime: &Runtime, data_provider: BenchProvider) -> &'static RemoteConfig<Vec<u64>, BenchProvider> {
+    #[cfg(feature = "tracing")]
+    let builder = RemoteConfig::builder("Benchmark".to_string(), data_provider);
+    #[cfg(not (feature = "tracing"))]
+    let builder = RemoteConfig::builder(data_provider);
+
+    let config = runtime.block_on(builder.with_retry_interval(Duration::from_secs(1)).build()).unwrap();
+    Box::leak(Box::new(config))
+}
+
+fn fresh_hit(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let config = config(&runtime, BenchProvider { ttl: Duration::from_secs(3600), must_revalidate: false, delay: Duration::ZERO });
+    c.bench_function("fresh_hit", |b| b.to_async(&runtime).iter(|| async {
+        config.load().await.unwrap()
+    }));
+}
+
+fn stale_hit(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    // Loaded data is already stale, so every load finds it stale and revalidation keeps running in background
+    let config = config(&runtime, BenchProvider { ttl: Duration::ZERO, must_revalidate: false, delay: Duration::ZERO });
+    c.bench_function("stale_hit", |b| b.to_async(&runtime).iter(|| async {
+        config.load().await.unwrap()
+    }));
+}
+
+fn must_revalidate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("must_revalidate");
+    for threads in [1, 2, 4, 8] {
+        let runtime = tokio::runtime::Builder::new_multi_thread().worker_threads(threads).enable_all().build().unwrap();
+        let config = config(&runtime, BenchProvider { ttl: Duration::ZERO, must_revalidate: true, delay: Duration::from_millis(1) });
+        group.bench_with_input(BenchmarkId::from_parameter(threads), &threads, |b, &threads| b.to_async(&runtime).iter(|| async move {
+            let tasks: Vec<_> = (0..threads).map(|_| tokio::spawn(config.load())).collect();
+            for task in tasks {
+                task.await.unwrap().unwrap();
+            }
+        }));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, fresh_hit, stale_hit, must_revalidate);
+criterion_main!(benches);
@@ -0,0 +1,38 @@
+/* C ABI of remote_config crate, available with `ffi` feature. See `ffi` module docs. */
+#ifndef REMOTE_CONFIG_H
+#define REMOTE_CONFIG_H
+
+#include <stdbool.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct FfiConfig FfiConfig;
+
+/* Receives JSON snapshot (valid only during the call) and user data passed at registration */
+typedef void (*RemoteConfigChangeCallback)(const char *snapshot, void *user_data);
+
+/* Message of last error in calling thread, or NULL. Valid until next call in the same thread. */
+const char *remote_config_last_error(void);
+
+/* Creates config and loads initial data. Format is "json", "yaml", "toml", "xml" or "auto". Returns NULL on error. */
+FfiConfig *remote_config_create(const char *url, const char *format);
+
+/* Current data as JSON string, or NULL on error. Free with remote_config_free_string. */
+char *remote_config_snapshot(const FfiConfig *config);
+
+/* Calls callback from background thread every time data changes. Returns false on error. */
+bool remote_config_on_change(FfiConfig *config, RemoteConfigChangeCallback callback, void *user_data);
+
+/* Frees string returned by this library */
+void remote_config_free_string(char *string);
+
+/* Stops callbacks, waits for running ones, and frees config. Must not be called from change callback. */
+void remote_config_destroy(FfiConfig *config);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,133 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use semver::{Version, VersionReq};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use crate::merge::merge;
+
+/// Config document with default values and overrides for ranges of application versions, so one remote document can drive
+/// staged behavior across installed base that updates slowly. Application resolves effective values locally with its own version.
+///
+/// Overrides are applied in document order on top of defaults, so later overrides win. Objects are merged recursively,
+/// and any other value replaces previous one. Version ranges use [semver](https://crates.io/crates/semver) syntax,
+/// which doesn't match pre-release versions unless comparator has pre-release itself.
+/// ```json
+/// {
+///     "values": {"checkout": {"provider": "legacy", "retries": 3}},
+///     "overrides": [
+///         {"versions": ">=2.4.0", "values": {"checkout": {"provider": "v2"}}},
+///         {"versions": ">=2.4.0, <2.4.3", "values": {"checkout": {"retries": 1}}}
+///     ]
+/// }
+/// ```
+/// Document is usually loaded as `Data` of config (e.g. with `SerdeDataExtractor`), so ranges are validated on load.
+/// # Examples
+/// ```
+/// use std::collections::HashMap;
+/// use semver::Version;
+/// use remote_config::app_version::VersionedDocument;
+///
+/// let document: VersionedDocument = serde_json::from_str(r#"{
+///     "values": {"banner": "off"},
+///     "overrides": [{"versions": ">=3.1", "values": {"banner": "on"}}]
+/// }"#).unwrap();
+/// let values: HashMap<String, String> = document.resolve(&Version::new(3, 2, 0)).unwrap();
+/// assert_eq!(values["banner"], "on");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct VersionedDocument {
+    /// Values for all versions
+    #[serde(default)]
+    pub values: Map<String, Value>,
+    /// Overrides in order of application
+    #[serde(default)]
+    pub overrides: Vec<VersionOverride>
+}
+
+/// Values that override defaults for range of application versions
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct VersionOverride {
+    /// Versions that override applies to
+    pub versions: VersionReq,
+    /// Overriding values
+    pub values: Map<String, Value>
+}
+
+impl VersionedDocument {
+    /// Effective values for specified application version
+    pub fn effective_values(&self, version: &Version) -> Map<String, Value> {
+        let mut values = self.values.clone();
+        for version_override in self.overrides.iter().filter(|version_override| version_override.versions.matches(version)) {
+            merge(&mut values, &version_override.values);
+        }
+        values
+    }
+
+    /// Deserializes effective values for specified application version.
+    /// Values are merged on every call, so cache result if it is used on hot path.
+    /// # Errors
+    /// If effective values can't be deserialized into `T`
+    pub fn resolve<T: DeserializeOwned>(&self, version: &Version) -> Result<T, ResolveError> {
+        serde_json::from_value(Value::Object(self.effective_values(version)))
+            .map_err(|err| ResolveError { version: version.clone(), source: err })
+    }
+}
+
+/// Effective values of version can't be deserialized
+#[derive(Debug)]
+pub struct ResolveError {
+    /// Application version
+    pub version: Version,
+    source: serde_json::Error
+}
+
+impl Display for ResolveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "effective config values for application version {} are invalid: {}", self.version, self.source)
+    }
+}
+
+impl Error for ResolveError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use semver::Version;
+    use serde_json::json;
+    use crate::app_version::VersionedDocument;
+
+    #[test]
+    fn overrides_are_merged_in_order() {
+        let document: VersionedDocument = serde_json::from_value(json!({
+            "values": {"checkout": {"provider": "legacy", "retries": 3}, "banner": "off"},
+            "overrides": [
+                {"versions": ">=2.4.0", "values": {"checkout": {"provider": "v2"}}},
+                {"versions": ">=2.4.0, <2.4.3", "values": {"checkout": {"retries": 1}, "banner": "on"}}
+            ]
+        })).unwrap();
+
+        assert_eq!(document.effective_values(&Version::new(2, 3, 9)), document.values);
+        assert_eq!(
+            serde_json::to_value(document.effective_values(&Version::new(2, 4, 1))).unwrap(),
+            json!({"checkout": {"provider": "v2", "retries": 1}, "banner": "on"})
+        );
+        assert_eq!(
+            serde_json::to_value(document.effective_values(&Version::new(2, 5, 0))).unwrap(),
+            json!({"checkout": {"provider": "v2", "retries": 3}, "banner": "off"})
+        );
+        // Pre-release doesn't match ranges without pre-release
+        assert_eq!(document.effective_values(&Version::parse("2.4.1-beta.1").unwrap()), document.values);
+    }
+
+    #[test]
+    fn invalid_range_is_rejected() {
+        let document = serde_json::from_value::<VersionedDocument>(json!({
+            "overrides": [{"versions": "two and above", "values": {}}]
+        }));
+        assert!(document.is_err());
+    }
+}
@@ -0,0 +1,116 @@
+use std::fmt::{Debug, Formatter};
+use std::time::{Duration, SystemTime};
+
+/// Network link state, as indicated by application or detector callback
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum LinkState {
+    /// No bandwidth constraints
+    #[default]
+    Unmetered,
+    /// Link is metered (e.g. cellular), traffic should be reduced
+    Metered,
+    /// Link has very low bandwidth (e.g. satellite or LPWAN), traffic should be minimal
+    Constrained
+}
+
+impl LinkState {
+    pub(crate) fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Self::Metered,
+            2 => Self::Constrained,
+            _ => Self::Unmetered
+        }
+    }
+
+    pub(crate) fn as_u8(self) -> u8 {
+        match self {
+            Self::Unmetered => 0,
+            Self::Metered => 1,
+            Self::Constrained => 2
+        }
+    }
+}
+
+/// Refresh policy for constrained links.
+/// While link is metered or constrained, data provider is called at most once per configured interval,
+/// and cached data is served in between, even if it is stale and must be revalidated.
+///
+/// Link state is set with [`RemoteConfig::set_link_state`](crate::config::RemoteConfig::set_link_state),
+/// or reported by detector callback, if one is configured.
+/// Combine it with conditional requests (e.g. HTTP validator store) to reduce traffic of each refresh too.
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use remote_config::bandwidth::{BandwidthPolicy, LinkState};
+///
+/// let policy = BandwidthPolicy::new()
+///     .with_metered_interval(Duration::from_secs(30 * 60))
+///     .with_constrained_interval(Duration::from_secs(6 * 60 * 60))
+///     .with_detector(|| LinkState::Metered);
+/// ```
+pub struct BandwidthPolicy {
+    metered_interval: Duration,
+    constrained_interval: Duration,
+    detector: Option<Box<dyn Fn() -> LinkState + Send + Sync>>
+}
+
+impl BandwidthPolicy {
+    /// Creates policy with 15 minutes interval for metered links and 1 hour interval for constrained links
+    pub fn new() -> Self {
+        Self {
+            metered_interval: Duration::from_secs(15 * 60),
+            constrained_interval: Duration::from_secs(60 * 60),
+            detector: None
+        }
+    }
+
+    /// Minimal interval between data loads on metered link
+    pub fn with_metered_interval(mut self, interval: Duration) -> Self {
+        self.metered_interval = interval;
+        self
+    }
+
+    /// Minimal interval between data loads on constrained link
+    pub fn with_constrained_interval(mut self, interval: Duration) -> Self {
+        self.constrained_interval = interval;
+        self
+    }
+
+    /// Callback that reports current link state. It is called before each data load, so it must be cheap.
+    /// If set, state configured with `set_link_state` is ignored.
+    pub fn with_detector(mut self, detector: impl Fn() -> LinkState + Send + Sync + 'static) -> Self {
+        self.detector = Some(Box::new(detector));
+        self
+    }
+
+    /// Link state reported by detector, or specified fallback
+    pub(crate) fn link_state(&self, fallback: LinkState) -> LinkState {
+        self.detector.as_ref().map_or(fallback, |detector| detector())
+    }
+
+    /// Checks if data load must be postponed
+    pub(crate) fn is_postponed(&self, link: LinkState, last_fetch: Option<SystemTime>, time: SystemTime) -> bool {
+        let interval = match link {
+            LinkState::Unmetered => return false,
+            LinkState::Metered => self.metered_interval,
+            LinkState::Constrained => self.constrained_interval
+        };
+        last_fetch.is_some_and(|last_fetch| time < last_fetch + interval)
+    }
+}
+
+impl Default for BandwidthPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for BandwidthPolicy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BandwidthPolicy")
+            .field("metered_interval", &self.metered_interval)
+            .field("constrained_interval", &self.constrained_interval)
+            .field("detector", &self.detector.is_some())
+            .finish()
+    }
+}
@@ -0,0 +1,172 @@
+use std::error::Error;
+use std::ops::Deref;
+use std::time::Duration;
+use reqwest::{Client, Url};
+use reqwest::header::CONTENT_TYPE;
+use serde::Serialize;
+use tokio::task::JoinHandle;
+use crate::config::{ConfigStatus, RemoteConfig};
+use crate::data_providers::data_provider::DataProvider;
+use crate::random::random_duration;
+
+/// Default interval between reports
+pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Report sent by [`Beacon`].
+/// Contains only config revision and health, no data, host names or addresses.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[non_exhaustive]
+pub struct BeaconReport {
+    /// Client label, if configured (see [`Beacon::with_client_id`])
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    /// Revision of active data, if data provider reports it
+    pub revision: Option<String>,
+    /// Whether active data is fresh and budget is not exhausted
+    pub healthy: bool,
+    /// Whether active data is stale
+    pub stale: bool,
+    /// Whether offline mode is enabled
+    pub offline: bool,
+    /// Whether any budget is exhausted
+    pub budget_exhausted: bool,
+    /// Number of data load attempts
+    pub requests: u64,
+    /// Number of failed data load attempts
+    pub failures: u64
+}
+
+impl BeaconReport {
+    /// Builds report from config status
+    pub fn from_status(status: &ConfigStatus, client_id: Option<String>) -> Self {
+        Self {
+            client_id,
+            revision: status.revision.clone(),
+            healthy: !status.stale && !status.budget_exhausted,
+            stale: status.stale,
+            offline: status.offline,
+            budget_exhausted: status.budget_exhausted,
+            requests: status.usage.requests,
+            failures: status.usage.failures
+        }
+    }
+}
+
+/// Periodically POSTs [`BeaconReport`] as JSON to configured endpoint, so config control plane can
+/// show rollout progress and detect stuck clients.
+///
+/// If endpoint is the origin itself, [`crate::data_providers::http::HttpDataProvider::with_active_revision_header`]
+/// may be used instead.
+#[derive(Debug, Clone)]
+pub struct Beacon {
+    client: Client,
+    endpoint: Url,
+    interval: Duration,
+    client_id: Option<String>
+}
+
+impl Beacon {
+    /// Constructs new beacon that reports to endpoint every [`DEFAULT_INTERVAL`]
+    pub fn new(client: Client, endpoint: Url) -> Self {
+        Self {
+            client,
+            endpoint,
+            interval: DEFAULT_INTERVAL,
+            client_id: None
+        }
+    }
+
+    /// Sets interval between reports. Up to 10% of random jitter is added to every interval.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Include label in reports. Nothing identifying the client is sent unless it is set.
+    pub fn with_client_id(mut self, client_id: String) -> Self {
+        self.client_id = Some(client_id);
+        self
+    }
+
+    /// Sends single report
+    /// # Errors
+    /// If report can't be serialized, request fails or endpoint responds with error status
+    pub async fn report(&self, report: &BeaconReport) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.client.post(self.endpoint.clone())
+            .header(CONTENT_TYPE, "application/json")
+            .body(serde_json::to_vec(report)?)
+            .send().await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    /// Spawns task that reports config status until it is aborted.
+    /// Failed reports are ignored.
+    pub fn spawn<Data, Provider, Handle>(self, config: Handle) -> JoinHandle<()>
+    where
+        Data: Send + Sync + 'static,
+        Provider: DataProvider<Data> + Send + 'static,
+        Handle: Deref<Target = RemoteConfig<Data, Provider>> + Send + 'static
+    {
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(self.interval + random_duration(self.interval / 10)).await;
+                let report = BeaconReport::from_status(&config.status(), self.client_id.clone());
+                if let Err(_err) = self.report(&report).await {
+                    #[cfg(feature = "tracing")]
+                    tracing::debug!(endpoint = %self.endpoint, "Failed to send beacon report: {_err}");
+                }
+            }
+        })
+    }
+}
+
+#[cfg(all(test, feature = "json"))]
+mod tests {
+    use std::collections::HashMap;
+    use std::time::Duration;
+    use mockito::Matcher;
+    use reqwest::{Client, Url};
+    use serde_json::json;
+    use crate::beacon::Beacon;
+    use crate::config::RemoteConfig;
+    use crate::data_providers::http::HttpDataProvider;
+    use crate::data_providers::http::serde_extractor::SerdeDataExtractor;
+
+    #[tokio::test]
+    async fn reports_active_revision() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/cfg")
+            .with_header("Content-Type", "application/json")
+            .with_header("Cache-Control", "public, max-age=60")
+            .with_header("ETag", "\"v7\"")
+            .with_body(json!({"key": "value"}).to_string())
+            .create_async()
+            .await;
+        let beacon_mock = server
+            .mock("POST", "/beacon")
+            .match_body(Matcher::Json(json!({"revision": "\"v7\"", "healthy": true, "stale": false, "offline": false, "budget_exhausted": false, "requests": 1, "failures": 0})))
+            .create_async()
+            .await;
+
+        let provider: HttpDataProvider<HashMap<String, String>, _> = HttpDataProvider::new(Client::new(), Url::parse(&(server.url() + "/cfg")).unwrap(), SerdeDataExtractor::new());
+        #[cfg(feature = "tracing")]
+        let config = RemoteConfig::builder("Beacon".to_string(), provider).build().await.unwrap();
+        #[cfg(not(feature = "tracing"))]
+        let config = RemoteConfig::builder(provider).build().await.unwrap();
+        assert_eq!(config.status().revision.as_deref(), Some("\"v7\""));
+
+        let task = Beacon::new(Client::new(), Url::parse(&(server.url() + "/beacon")).unwrap())
+            .with_interval(Duration::from_millis(10))
+            .spawn(Box::leak(Box::new(config)) as &_);
+        for _ in 0..200 {
+            if beacon_mock.matched_async().await {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        task.abort();
+        assert!(beacon_mock.matched_async().await);
+    }
+}
@@ -0,0 +1,188 @@
+use std::time::{Duration, SystemTime};
+
+/// Limit on data provider usage within fixed time window.
+/// When any budget of config is exhausted, config enters degraded mode:
+/// cached data is served even if it is stale and must be revalidated, and data provider is not called until the window ends.
+/// # Examples
+/// ```
+/// use remote_config::budget::Budget;
+/// // At most 60 requests per hour and 1 GB per month
+/// let budgets = [
+///     Budget::hourly().with_max_requests(60),
+///     Budget::monthly().with_max_bytes(1 << 30)
+/// ];
+/// ```
+#[derive(Debug, Clone)]
+pub struct Budget {
+    window: Duration,
+    max_requests: Option<u64>,
+    max_bytes: Option<u64>
+}
+
+impl Budget {
+    /// Creates unlimited budget with specified window
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            max_requests: None,
+            max_bytes: None
+        }
+    }
+
+    /// Unlimited budget with one hour window
+    pub fn hourly() -> Self {
+        Self::new(Duration::from_secs(60 * 60))
+    }
+
+    /// Unlimited budget with 30 days window
+    pub fn monthly() -> Self {
+        Self::new(Duration::from_secs(30 * 24 * 60 * 60))
+    }
+
+    /// Maximum number of data load attempts within window
+    pub fn with_max_requests(mut self, max_requests: u64) -> Self {
+        self.max_requests = Some(max_requests);
+        self
+    }
+
+    /// Maximum size of loaded data within window, as reported by data provider in [`LoadMetadata::size`](crate::data_providers::data_provider::LoadMetadata::size)
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+}
+
+/// Data provider usage statistics
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Usage {
+    /// Number of data load attempts, including initial load
+    pub requests: u64,
+    /// Number of failed data load attempts
+    pub failures: u64,
+    /// Total size of loaded data, as reported by data provider
+    pub bytes: u64,
+    /// Total time spent loading data
+    pub fetch_time: Duration,
+    /// Duration of last data load attempt
+    pub last_fetch_time: Option<Duration>
+}
+
+#[derive(Debug)]
+struct Window {
+    budget: Budget,
+    start: Option<SystemTime>,
+    requests: u64,
+    bytes: u64
+}
+
+impl Window {
+    fn roll(&mut self, time: SystemTime) {
+        if let Some(start) = self.start {
+            if time >= start + self.budget.window {
+                self.start = None;
+                self.requests = 0;
+                self.bytes = 0;
+            }
+        }
+    }
+
+    fn is_exhausted(&self) -> bool {
+        self.budget.max_requests.is_some_and(|max| self.requests >= max) ||
+            self.budget.max_bytes.is_some_and(|max| self.bytes >= max)
+    }
+}
+
+/// Usage statistics and budget windows of single config
+#[derive(Debug)]
+pub(crate) struct Accounting {
+    usage: Usage,
+    last_fetch_at: Option<SystemTime>,
+    windows: Vec<Window>
+}
+
+impl Accounting {
+    pub(crate) fn new(budgets: Vec<Budget>) -> Self {
+        Self {
+            usage: Usage::default(),
+            last_fetch_at: None,
+            windows: budgets.into_iter().map(|budget| Window {
+                budget,
+                start: None,
+                requests: 0,
+                bytes: 0
+            }).collect()
+        }
+    }
+
+    /// Records data load attempt finished at specified time
+    pub(crate) fn record(&mut self, time: SystemTime, size: Option<u64>, duration: Duration, success: bool) {
+        let size = size.unwrap_or_default();
+
+        self.usage.requests += 1;
+        self.usage.bytes += size;
+        self.usage.fetch_time += duration;
+        self.usage.last_fetch_time = Some(duration);
+        self.last_fetch_at = Some(time);
+        if !success {
+            self.usage.failures += 1;
+        }
+
+        for window in &mut self.windows {
+            window.roll(time);
+            window.start.get_or_insert(time);
+            window.requests += 1;
+            window.bytes += size;
+        }
+    }
+
+    /// Checks if any budget is exhausted at specified time
+    pub(crate) fn is_exhausted(&mut self, time: SystemTime) -> bool {
+        self.windows.iter_mut().any(|window| {
+            window.roll(time);
+            window.is_exhausted()
+        })
+    }
+
+    pub(crate) fn usage(&self) -> Usage {
+        self.usage
+    }
+
+    /// Time when last data load attempt finished
+    pub(crate) fn last_fetch_at(&self) -> Option<SystemTime> {
+        self.last_fetch_at
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+    use crate::budget::{Accounting, Budget};
+
+    #[test]
+    fn budget_window() {
+        let start = SystemTime::UNIX_EPOCH;
+        let mut accounting = Accounting::new(vec![
+            Budget::new(Duration::from_secs(10)).with_max_requests(2),
+            Budget::new(Duration::from_secs(100)).with_max_bytes(100)
+        ]);
+
+        accounting.record(start, Some(10), Duration::from_millis(5), true);
+        assert!(!accounting.is_exhausted(start));
+        accounting.record(start + Duration::from_secs(1), None, Duration::from_millis(5), false);
+        assert!(accounting.is_exhausted(start + Duration::from_secs(1)));
+
+        // Request budget window ends
+        assert!(!accounting.is_exhausted(start + Duration::from_secs(10)));
+
+        // Byte budget is exhausted until its window ends
+        accounting.record(start + Duration::from_secs(10), Some(90), Duration::from_millis(5), true);
+        assert!(accounting.is_exhausted(start + Duration::from_secs(21)));
+        assert!(!accounting.is_exhausted(start + Duration::from_secs(100)));
+
+        let usage = accounting.usage();
+        assert_eq!(usage.requests, 3);
+        assert_eq!(usage.failures, 1);
+        assert_eq!(usage.bytes, 100);
+        assert_eq!(usage.fetch_time, Duration::from_millis(15));
+    }
+}
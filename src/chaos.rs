@@ -0,0 +1,60 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// Runtime switches that simulate config delivery degradation.
+/// Use [`RemoteConfig::chaos`](crate::config::RemoteConfig::chaos) to access switches of specific config instance.
+/// # Examples
+/// ```ignore
+/// // Every load will try to revalidate data
+/// CONFIG.get().unwrap().chaos().force_stale();
+/// // Next three revalidation attempts fail without reaching data provider
+/// CONFIG.get().unwrap().chaos().fail_next_loads(3);
+/// // Back to normal
+/// CONFIG.get().unwrap().chaos().reset();
+/// ```
+#[derive(Debug, Default)]
+pub struct Chaos {
+    force_stale: AtomicBool,
+    failing_loads: AtomicUsize
+}
+
+impl Chaos {
+    /// Treat cached data as stale regardless of its `valid_until` until [`Chaos::reset`] is called
+    pub fn force_stale(&self) {
+        self.force_stale.store(true, Ordering::Relaxed);
+    }
+
+    /// Fail next `count` data load attempts with [`InjectedFailure`] without calling data provider.
+    /// Overrides previously set count.
+    pub fn fail_next_loads(&self, count: usize) {
+        self.failing_loads.store(count, Ordering::Relaxed);
+    }
+
+    /// Disable all injected faults
+    pub fn reset(&self) {
+        self.force_stale.store(false, Ordering::Relaxed);
+        self.failing_loads.store(0, Ordering::Relaxed);
+    }
+
+    pub(crate) fn is_stale_forced(&self) -> bool {
+        self.force_stale.load(Ordering::Relaxed)
+    }
+
+    /// Returns true if current load attempt must fail
+    pub(crate) fn take_failure(&self) -> bool {
+        self.failing_loads.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1)).is_ok()
+    }
+}
+
+/// Error that is returned instead of data provider result when failure is injected with [`Chaos::fail_next_loads`]
+#[derive(Debug)]
+pub struct InjectedFailure;
+
+impl Display for InjectedFailure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "data load failure injected by chaos hook")
+    }
+}
+
+impl Error for InjectedFailure {}
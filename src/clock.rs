@@ -0,0 +1,19 @@
+use std::fmt::Debug;
+use std::time::SystemTime;
+
+/// Source of current time for [`RemoteConfig`](crate::config::RemoteConfig).
+/// Custom implementations allow to drive config timing logic deterministically (e.g. in simulation tests).
+pub trait Clock: Debug + Send + Sync {
+    /// Current time
+    fn now(&self) -> SystemTime;
+}
+
+/// Clock that returns [`SystemTime::now`]. Used by default.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
@@ -0,0 +1,291 @@
+use std::error::Error;
+use std::fmt::{Debug, Formatter};
+use std::io;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use crate::data_providers::data_provider::{DataLoadResult, DataProvider, Revalidation};
+
+/// Settings of in-memory compression, that trade CPU time for memory.
+/// Higher level makes payloads smaller, but compression (on every data load) slower.
+/// Without cache, payload is decompressed on every access. With [`DecompressedCache`],
+/// decompressed forms of recently accessed payloads are kept, up to cache capacity.
+///
+/// Pass settings to [`RemoteConfigBuilder::with_compression`](crate::config::RemoteConfigBuilder::with_compression)
+/// to keep bytes loaded by data provider compressed, or compress bytes in parser of data provider.
+/// # Examples
+/// ```
+/// use std::sync::Arc;
+/// use remote_config::compressed::{Compression, DecompressedCache};
+///
+/// // Shared by all configs, so only hot ones stay decompressed
+/// let cache = Arc::new(DecompressedCache::new(4 * 1024 * 1024));
+/// let compression = Compression::new().with_level(9).with_cache(cache);
+/// let bytes = compression.compress(br#"{"key": "value"}"#).unwrap();
+/// assert_eq!(&bytes.decompress().unwrap()[..], br#"{"key": "value"}"#);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Compression {
+    level: i32,
+    cache: Option<Arc<DecompressedCache>>
+}
+
+impl Compression {
+    /// Creates settings with default zstd level and without cache
+    pub fn new() -> Self {
+        Self { level: zstd::DEFAULT_COMPRESSION_LEVEL, cache: None }
+    }
+
+    /// zstd compression level, from 1 (fastest) to 22 (smallest). Default is 3.
+    pub fn with_level(mut self, level: i32) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Keep decompressed forms of recently accessed payloads in specified cache
+    pub fn with_cache(mut self, cache: Arc<DecompressedCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Compresses bytes, e.g. in parser of data provider
+    /// # Errors
+    /// If zstd fails to compress bytes
+    pub fn compress(&self, bytes: &[u8]) -> io::Result<CompressedBytes> {
+        Ok(CompressedBytes {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            compressed: zstd::bulk::compress(bytes, self.level)?.into(),
+            len: bytes.len(),
+            cache: self.cache.clone()
+        })
+    }
+}
+
+impl Default for Compression {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Id of next compressed payload, that identifies it in cache
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Bytes kept compressed with zstd in memory, for configs that are large and rarely read.
+/// Use it as config data, and deserialize values from [`CompressedBytes::decompress`] result when they are needed.
+/// Created with [`Compression::compress`].
+pub struct CompressedBytes {
+    id: u64,
+    compressed: Box<[u8]>,
+    len: usize,
+    cache: Option<Arc<DecompressedCache>>
+}
+
+impl CompressedBytes {
+    /// Decompressed bytes, taken from cache if they were decompressed recently
+    /// # Errors
+    /// If zstd fails to decompress bytes
+    pub fn decompress(&self) -> io::Result<Arc<[u8]>> {
+        if let Some(bytes) = self.cache.as_ref().and_then(|cache| cache.get(self.id)) {
+            return Ok(bytes);
+        }
+        let bytes: Arc<[u8]> = zstd::bulk::decompress(&self.compressed, self.len)?.into();
+        if let Some(cache) = &self.cache {
+            cache.insert(self.id, bytes.clone());
+        }
+        Ok(bytes)
+    }
+
+    /// Size of decompressed bytes
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether decompressed bytes are empty
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Size of bytes kept in memory
+    pub fn compressed_len(&self) -> usize {
+        self.compressed.len()
+    }
+}
+
+impl Drop for CompressedBytes {
+    fn drop(&mut self) {
+        if let Some(cache) = &self.cache {
+            cache.remove(self.id);
+        }
+    }
+}
+
+impl Debug for CompressedBytes {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CompressedBytes")
+            .field("len", &self.len)
+            .field("compressed_len", &self.compressed.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// This data provider compresses bytes loaded by inner provider. Created with
+/// [`RemoteConfigBuilder::with_compression`](crate::config::RemoteConfigBuilder::with_compression).
+///
+/// Inner provider revalidates data decompressed from cached payload, so it can still report data as not modified.
+pub struct CompressingDataProvider<Data, Provider> {
+    inner: Provider,
+    compression: Compression,
+    data_type: PhantomData<Data>
+}
+
+impl <Data, Provider> CompressingDataProvider<Data, Provider> {
+    /// Creates data provider, that compresses data loaded by inner provider with specified settings
+    pub fn new(inner: Provider, compression: Compression) -> Self {
+        Self { inner, compression, data_type: PhantomData }
+    }
+
+    /// Inner data provider
+    pub fn inner(&self) -> &Provider {
+        &self.inner
+    }
+
+    fn compress(&self, result: DataLoadResult<Data>) -> io::Result<DataLoadResult<CompressedBytes>>
+    where Data: AsRef<[u8]>
+    {
+        let mut compressed = DataLoadResult::new(self.compression.compress(result.data.as_ref())?, result.must_revalidate, result.valid_until);
+        compressed.metadata = result.metadata;
+        Ok(compressed)
+    }
+}
+
+impl <Data, Provider> DataProvider<CompressedBytes> for CompressingDataProvider<Data, Provider>
+where Data: AsRef<[u8]> + From<Vec<u8>> + Send + Sync, Provider: DataProvider<Data> + Send + Sync
+{
+    /// Loads data with inner provider and compresses it
+    /// # Errors
+    /// If inner provider fails, or zstd fails to compress data
+    async fn load_data(&self) -> Result<DataLoadResult<CompressedBytes>, Box<dyn Error + Send + Sync>> {
+        Ok(self.compress(self.inner.load_data().await?)?)
+    }
+
+    /// Revalidates decompressed data with inner provider, and compresses modified data
+    async fn revalidate_data<'a>(&'a self, current: &'a DataLoadResult<CompressedBytes>) -> Result<Revalidation<CompressedBytes>, Box<dyn Error + Send + Sync>> {
+        let mut decompressed = DataLoadResult::new(Data::from(current.data.decompress()?.to_vec()), current.must_revalidate, current.valid_until);
+        decompressed.metadata = current.metadata.clone();
+        Ok(match self.inner.revalidate_data(&decompressed).await? {
+            Revalidation::Modified(result) => Revalidation::Modified(self.compress(result)?),
+            Revalidation::NotModified { must_revalidate, valid_until, invalidation, fetched_at } =>
+                Revalidation::NotModified { must_revalidate, valid_until, invalidation, fetched_at }
+        })
+    }
+}
+
+impl <Data, Provider: Debug> Debug for CompressingDataProvider<Data, Provider> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CompressingDataProvider")
+            .field("inner", &self.inner)
+            .field("compression", &self.compression)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Least recently used decompressed payloads, limited by their total size.
+/// Payload larger than capacity is never cached.
+#[derive(Debug)]
+pub struct DecompressedCache {
+    capacity: usize,
+    /// Ordered from least recently used
+    entries: Mutex<Vec<(u64, Arc<[u8]>)>>
+}
+
+impl DecompressedCache {
+    /// Creates cache with specified capacity in bytes
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity, entries: Mutex::new(Vec::new()) }
+    }
+
+    /// Total size of cached payloads
+    pub fn size(&self) -> usize {
+        self.entries.lock().unwrap().iter().map(|(_, bytes)| bytes.len()).sum()
+    }
+
+    fn get(&self, id: u64) -> Option<Arc<[u8]>> {
+        let mut entries = self.entries.lock().unwrap();
+        let index = entries.iter().position(|(entry_id, _)| *entry_id == id)?;
+        let entry = entries.remove(index);
+        let bytes = entry.1.clone();
+        entries.push(entry);
+        Some(bytes)
+    }
+
+    fn insert(&self, id: u64, bytes: Arc<[u8]>) {
+        if bytes.len() > self.capacity {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|(entry_id, _)| *entry_id != id);
+        let mut size = entries.iter().map(|(_, bytes)| bytes.len()).sum::<usize>() + bytes.len();
+        while size > self.capacity {
+            size -= entries.remove(0).1.len();
+        }
+        entries.push((id, bytes));
+    }
+
+    fn remove(&self, id: u64) {
+        self.entries.lock().unwrap().retain(|(entry_id, _)| *entry_id != id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use crate::compressed::{Compression, DecompressedCache};
+    use crate::config::RemoteConfig;
+    use crate::data_providers::memory::InMemoryDataProvider;
+
+    #[test]
+    fn least_recently_used_payloads_are_evicted() {
+        let cache = Arc::new(DecompressedCache::new(250));
+        let compression = Compression::new().with_cache(cache.clone());
+        let first = compression.compress(&[1; 100]).unwrap();
+        let second = compression.compress(&[2; 100]).unwrap();
+        let third = compression.compress(&[3; 100]).unwrap();
+        assert_eq!(first.len(), 100);
+        assert!(first.compressed_len() < 100);
+
+        assert_eq!(&first.decompress().unwrap()[..], &[1; 100]);
+        assert_eq!(&second.decompress().unwrap()[..], &[2; 100]);
+        let cached = first.decompress().unwrap();
+        assert_eq!(cache.size(), 200);
+        // Second is the least recently used
+        assert_eq!(&third.decompress().unwrap()[..], &[3; 100]);
+        assert_eq!(cache.size(), 200);
+        assert!(Arc::ptr_eq(&cached, &first.decompress().unwrap()));
+        assert!(cache.get(second.id).is_none());
+
+        drop(first);
+        assert_eq!(cache.size(), 100);
+    }
+
+    #[tokio::test]
+    async fn builder_keeps_loaded_data_compressed() {
+        let data_provider = InMemoryDataProvider::new(vec![7u8; 1000]);
+        let handle = data_provider.clone();
+        #[cfg(feature = "tracing")]
+        let builder = RemoteConfig::builder("Compressed".to_string(), data_provider);
+        #[cfg(not(feature = "tracing"))]
+        let builder = RemoteConfig::builder(data_provider);
+        let cache = Arc::new(DecompressedCache::new(4096));
+        let config: &_ = Box::leak(Box::new(builder.with_compression(Compression::new().with_cache(cache.clone())).build().await.unwrap()));
+
+        let data = config.load().await.unwrap();
+        assert_eq!(data.len(), 1000);
+        assert!(data.compressed_len() < 100);
+        assert_eq!(&data.decompress().unwrap()[..], &[7; 1000]);
+        assert_eq!(cache.size(), 1000);
+
+        handle.set(vec![8; 10]);
+        let data = config.refresh().await.unwrap();
+        assert_eq!(&data.decompress().unwrap()[..], &[8; 10]);
+    }
+}
@@ -1,17 +1,37 @@
+use std::collections::HashMap;
 use std::error::Error;
 use std::fmt::{Debug, Display, Formatter};
-#[cfg(feature = "non_static")] use std::future::Future;
+use std::future::{poll_fn, Future};
 use std::marker::PhantomData;
 use std::ops::Deref;
+use std::pin::pin;
 use std::sync::Arc;
-use std::time::{Duration, SystemTime};
-#[cfg(feature = "non_static")] use arc_swap::{ArcSwap, AsRaw, Guard};
-#[cfg(not (feature = "non_static"))] use arc_swap::{ArcSwap, Guard};
-use tokio::spawn;
-use tokio::sync::Mutex;
-use crate::data_providers::data_provider::{DataLoadResult, DataProvider};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicU8, AtomicUsize, Ordering};
+use std::task::Poll;
+use std::time::{Duration, Instant, SystemTime};
+use arc_swap::{ArcSwap, Guard};
+#[cfg(feature = "stream")]
+use futures_util::Stream;
+#[cfg(feature = "tracing")] use tokio::spawn;
+use tokio::sync::{Mutex, Notify, oneshot};
+use crate::bandwidth::{BandwidthPolicy, LinkState};
+use crate::load_shedding::LoadSheddingPolicy;
+use crate::budget::{Accounting, Budget, Usage};
+use crate::content_hash;
+use crate::control::Control;
+use crate::clock::{Clock, SystemClock};
+use crate::flapping::{FlappingAlarm, FlappingDetector, FlappingPolicy};
+use crate::deprecation::{EndpointDeprecation, SunsetMonitor, SunsetPolicy};
+use crate::policy::GlobalPolicy;
+use crate::preset::Preset;
+use crate::profile::AccessProfile;
+use crate::spawner::{Spawner, TaskCancelled, TokioSpawner};
+use crate::data_providers::data_provider::{DataLoadResult, DataProvider, InvalidationToken, LoadMetadata, Revalidation, RetryHint};
+use crate::random::random_duration;
+use crate::revalidation::{decide, Decision, Freshness, Lock};
 
-#[cfg(feature = "tracing")] use tracing::{warn, error};
+#[cfg(feature = "tracing")] use tracing::{info, warn, error};
+#[cfg(feature = "chaos")] use crate::chaos::{Chaos, InjectedFailure};
 
 #[derive(Debug)]
 struct Revalidator <Data: Send + Sync, Provider: DataProvider<Data> + Send> {
@@ -47,7 +67,10 @@ struct Revalidator <Data: Send + Sync, Provider: DataProvider<Data> + Send> {
 ///
 ///     let data_provider = HttpDataProvider::new(client, Url::parse("https://example.com").unwrap(), SerdeDataExtractor::new());
 ///
+///     #[cfg(feature = "tracing")]
 ///     return RemoteConfig::new("Example named config".to_owned(), data_provider, Duration::from_secs(5)).await.unwrap();
+///     #[cfg(not (feature = "tracing"))]
+///     return RemoteConfig::new(data_provider, Duration::from_secs(5)).await.unwrap();
 /// }
 /// // Note, that async OnceCell is used. You can use blocking OnceCell by changing init_config() to sync and using block_on() to wait for data load
 /// static CONFIG: OnceCell<RemoteConfig<Data, HttpDataProvider<Data, SerdeDataExtractor<Data>>>> = OnceCell::const_new();
@@ -69,23 +92,70 @@ pub struct RemoteConfig<Data: Send + Sync, Provider: DataProvider<Data> + Send>
     #[cfg(feature = "tracing")] name: String,
     /// Minimal amount of time between data loading attempts in case of error
     retry_interval: Duration,
+    /// Limit of retry interval, that is doubled after every consecutive failure, if backoff is enabled
+    max_retry_interval: Option<Duration>,
+    /// Number of consecutive failed loads
+    failures: AtomicU32,
+    /// Time after which data provider call fails with [`LoadTimeout`]
+    load_timeout: Option<Duration>,
+    /// Limits of time for which loaded data is fresh
+    min_ttl: Duration,
+    max_ttl: Option<Duration>,
+    /// Source of current time
+    clock: Box<dyn Clock>,
+    /// Runs revalidation tasks
+    spawner: Box<dyn Spawner>,
+    /// Maximal time to wait for revalidation of stale data that must be revalidated, before serving it anyway
+    max_revalidation_wait: Option<Duration>,
+    /// Named freshness policies, selected with [`RemoteConfig::load_as`]
+    profiles: HashMap<String, AccessProfile>,
+    /// Stale tolerance of loads without access profile
+    stale_tolerance: Duration,
     /// Cached config, loaded from remote source
-    cached_response: ArcSwap<DataLoadResult<Data>>,
-    /// Used for revalidation
-    revalidator: Mutex<Revalidator<Data, Provider>>
+    cached_response: ArcSwap<Entry<Data>>,
+    /// Used for revalidation.
+    /// Wrapped in [`Arc`], so that lock guard can be moved into revalidation task
+    revalidator: Arc<Mutex<Revalidator<Data, Provider>>>,
+    /// Usage statistics and budgets
+    accounting: std::sync::Mutex<Accounting>,
+    /// Read statistics, shared with unused config watcher
+    access: Arc<AccessStats>,
+    /// If set, data provider is not called and cached data is served regardless of staleness
+    offline: AtomicBool,
+    /// Refresh policy for constrained links
+    bandwidth_policy: Option<BandwidthPolicy>,
+    /// Refresh policy for overloaded process
+    load_shedding: Option<LoadSheddingPolicy>,
+    /// Link state set by application, stored as [`LinkState::as_u8`]
+    link_state: AtomicU8,
+    /// Last origin directives
+    control: std::sync::Mutex<Option<Control>>,
+    /// Set by `force_refresh_now` directive, cleared after successful revalidation
+    forced_refresh: AtomicBool,
+    /// Notified every time cached data is replaced or revalidated
+    changed: Notify,
+    /// Number of spawned revalidation tasks that are not finished yet
+    live_tasks: Arc<AtomicUsize>,
+    /// Change rate tracking, if flapping detection is enabled
+    flapping: Option<std::sync::Mutex<FlappingDetector>>,
+    /// Sunset stage of config endpoint
+    sunset: std::sync::Mutex<SunsetMonitor>,
+    /// Computes content hash of loaded data, if it is not reported by data provider
+    content_hasher: Option<fn(&Data) -> u64>,
+    /// Whether metrics are recorded
+    #[cfg(feature = "metrics")] metrics: bool,
+    /// Fault injection switches
+    #[cfg(feature = "chaos")] chaos: Chaos
 }
 
 /// Wrapper around error that is returned by data provider
 #[derive(Debug)]
 pub struct DataProviderError {
-    source: Option<Box<dyn Error>>,
-    timestamp: SystemTime
+    source: Option<Box<dyn Error + Send + Sync>>,
+    timestamp: SystemTime,
+    /// Delay before next attempt, if origin hinted it (see [`RetryHint`])
+    retry_after: Option<Duration>
 }
-// Data provider errors are always wrapped in Arc and additionally guarded by mutex.
-// And when they are returned in results, they are immutable.
-
-unsafe impl Send for DataProviderError {}
-unsafe impl Sync for DataProviderError {}
 
 impl Display for DataProviderError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
@@ -95,35 +165,223 @@ impl Display for DataProviderError {
 
 impl Error for DataProviderError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
-        self.source.as_deref()
+        self.source.as_deref().map(|err| err as &(dyn Error + 'static))
+    }
+}
+
+impl From<Box<dyn Error + Send + Sync>> for DataProviderError{
+    fn from(value: Box<dyn Error + Send + Sync>) -> Self {
+        DataProviderError::new(value, SystemTime::now())
     }
 }
 
-impl From<Box<dyn Error>> for DataProviderError{
-    fn from(value: Box<(dyn Error + 'static)>) -> Self {
+impl DataProviderError {
+    fn new(source: Box<dyn Error + Send + Sync>, timestamp: SystemTime) -> Self {
+        let mut error: Option<&(dyn Error + 'static)> = Some(source.as_ref());
+        let mut retry_after = None;
+        while let Some(err) = error {
+            if let Some(hint) = err.downcast_ref::<RetryHint>() {
+                retry_after = Some(hint.retry_after);
+                break;
+            }
+            error = err.source();
+        }
         DataProviderError{
-            source: Some(value),
-            timestamp: SystemTime::now()
+            source: Some(source),
+            timestamp,
+            retry_after
+        }
+    }
+
+    /// Delay before next attempt that origin asked for (see [`RetryHint`])
+    pub fn retry_after(&self) -> Option<Duration> {
+        self.retry_after
+    }
+}
+
+/// Config did not satisfy condition before timeout (see [`RemoteConfig::wait_for`], [`RemoteConfig::expect_version`]
+/// and [`ConfigManager::barrier`](crate::manager::ConfigManager::barrier))
+#[derive(Debug)]
+pub struct WaitTimeout {
+    /// Error of last load attempt, if it failed
+    pub last_error: Option<Arc<DataProviderError>>
+}
+
+impl Display for WaitTimeout {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "config did not satisfy condition before timeout")
+    }
+}
+
+impl Error for WaitTimeout {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.last_error.as_deref().map(|err| err as &(dyn Error + 'static))
+    }
+}
+
+/// Data provider did not complete load before timeout (see [`RemoteConfigBuilder::with_load_timeout`])
+#[derive(Debug)]
+pub struct LoadTimeout {
+    /// Load timeout
+    pub timeout: Duration
+}
+
+impl Display for LoadTimeout {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "data was not loaded within {:?}", self.timeout)
+    }
+}
+
+impl Error for LoadTimeout {}
+
+/// Fails load that is not completed before timeout, if it is set
+async fn limit_load<T>(load: impl Future<Output = Result<T, Box<dyn Error + Send + Sync>>>, timeout: Option<Duration>) -> Result<T, Box<dyn Error + Send + Sync>> {
+    match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, load).await.unwrap_or_else(|_| Err(Box::new(LoadTimeout { timeout }))),
+        None => load.await
+    }
+}
+
+/// Moves expiration time of data loaded at `now` into TTL limits. Maximal TTL wins if limits overlap.
+fn clamp_ttl(valid_until: SystemTime, now: SystemTime, min_ttl: Duration, max_ttl: Option<Duration>) -> SystemTime {
+    let valid_until = valid_until.max(now + min_ttl);
+    match max_ttl {
+        Some(max_ttl) => valid_until.min(now + max_ttl),
+        None => valid_until
+    }
+}
+
+/// Cached load result with its current freshness.
+/// Freshness is stored separately, so that it can be updated without replacing data when it was not modified.
+#[derive(Debug)]
+struct Entry<Data> {
+    result: Arc<DataLoadResult<Data>>,
+    valid_until: SystemTime,
+    must_revalidate: bool,
+    /// Token of loaded data, or replacement reported by later revalidation
+    invalidation: Option<InvalidationToken>,
+    /// See [`CachedData::content_hash`]
+    content_hash: Option<u64>,
+    /// See [`CachedData::fetched_at`]
+    fetched_at: SystemTime
+}
+
+impl <Data> Entry<Data> {
+    /// Creates entry of data loaded at `now`
+    fn new(result: DataLoadResult<Data>, hasher: Option<fn(&Data) -> u64>, now: SystemTime) -> Self {
+        let content_hash = match hasher {
+            Some(hasher) => Some(hasher(&result.data)),
+            None => result.metadata.revision.as_deref().map(|revision| content_hash::fnv1a(revision.as_bytes()))
+        };
+        Self {
+            valid_until: result.valid_until,
+            must_revalidate: result.must_revalidate,
+            invalidation: result.metadata.invalidation.clone(),
+            content_hash,
+            fetched_at: result.metadata.fetched_at.unwrap_or(now),
+            result: Arc::new(result)
         }
     }
 }
 
 /// Convenient wrapper around pointer to load result that dereferences to data
 #[derive(Debug)]
-pub struct CachedData<Data>(Guard<Arc<DataLoadResult<Data>>>);
+pub struct CachedData<Data>(Guard<Arc<Entry<Data>>>);
+
+impl <Data> CachedData<Data> {
+    /// Metadata of cached config version, reported by data provider
+    pub fn metadata(&self) -> &LoadMetadata {
+        &self.0.result.metadata
+    }
+
+    /// Stable hash of cached config version, that can be embedded in cache keys, ETags or client-visible version strings.
+    /// It is the same in every process that loaded the same version, and changes when data changes.
+    ///
+    /// Hash is computed from data with [`RemoteConfigBuilder::with_content_hash`],
+    /// otherwise it is derived from revision reported by data provider. Returns `None` if neither is available.
+    pub fn content_hash(&self) -> Option<u64> {
+        self.0.content_hash
+    }
+
+    /// Time when cached version was fetched from source, or last confirmed by revalidation.
+    /// Data restored from cache is as old as its [`LoadMetadata::fetched_at`].
+    pub fn fetched_at(&self) -> SystemTime {
+        self.0.fetched_at
+    }
+}
 
 impl <Data> Deref for CachedData<Data> {
     type Target = Data;
 
     fn deref(&self) -> &Self::Target {
-        &self.0.data
+        &self.0.result.data
     }
 }
 type LoadResult<Data> = Result<CachedData<Data>, Arc<DataProviderError>>;
 
+/// Snapshot of config state, returned by [`RemoteConfig::status`]
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct ConfigStatus {
+    /// Time when cached data becomes stale
+    pub valid_until: SystemTime,
+    /// Whether cached data must be revalidated once stale
+    pub must_revalidate: bool,
+    /// Whether cached data is stale
+    pub stale: bool,
+    /// Revision of cached data, if data provider reports it
+    pub revision: Option<String>,
+    /// Keys of cached data that were overridden locally
+    pub overrides: Vec<String>,
+    /// Data provider usage statistics
+    pub usage: Usage,
+    /// Whether any budget is exhausted, and config is in degraded mode
+    pub budget_exhausted: bool,
+    /// Number of [`RemoteConfig::load`] calls
+    pub reads: u64,
+    /// Time of last [`RemoteConfig::load`] call, if config was ever read
+    pub last_accessed: Option<SystemTime>,
+    /// Whether offline mode is enabled
+    pub offline: bool,
+    /// Current link state
+    pub link_state: LinkState,
+    /// Last origin directives
+    pub control: Option<Control>,
+    /// Number of background revalidation tasks that are spawned, but not finished yet
+    pub background_tasks: usize,
+    /// Active flapping alarm, if config changes more often than allowed by [`FlappingPolicy`]
+    pub flapping: Option<FlappingAlarm>,
+    /// Deprecation of config endpoint, announced with cached data
+    pub deprecation: Option<EndpointDeprecation>
+}
+
+/// Config read statistics, updated on every load
+#[derive(Debug, Default)]
+struct AccessStats {
+    reads: AtomicU64,
+    /// Nanoseconds since [`SystemTime::UNIX_EPOCH`], zero if config was never read
+    last_accessed: AtomicU64
+}
+
+impl AccessStats {
+    fn record(&self, time: SystemTime) {
+        self.reads.fetch_add(1, Ordering::Relaxed);
+        let nanos = time.duration_since(SystemTime::UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_nanos() as u64);
+        self.last_accessed.fetch_max(nanos, Ordering::Relaxed);
+    }
+
+    fn last_accessed(&self) -> Option<SystemTime> {
+        match self.last_accessed.load(Ordering::Relaxed) {
+            0 => None,
+            nanos => Some(SystemTime::UNIX_EPOCH + Duration::from_nanos(nanos))
+        }
+    }
+}
+
 impl <Data: Send + Sync, Provider: DataProvider<Data> + Send> RemoteConfig<Data, Provider> {
     /// Constructs new remote config instance.
     /// If `tracing` feature is activated, name should be assigned to config instance.
+    /// Use [`RemoteConfig::builder`] to configure optional settings.
     /// # Errors
     /// Returns error if initial data load failed.
     pub async fn new(
@@ -131,139 +389,1042 @@ impl <Data: Send + Sync, Provider: DataProvider<Data> + Send> RemoteConfig<Data,
         data_provider: Provider,
         retry_interval: Duration
     ) -> Result<Self, DataProviderError> {
-        let data = data_provider.load_data().await.map_err(DataProviderError::from)?;
-        let revalidator = Revalidator{
+        Self::builder(
+            #[cfg(feature = "tracing")] name,
+            data_provider
+        ).with_retry_interval(retry_interval).build().await
+    }
+
+    /// Creates builder for remote config instance.
+    /// If `tracing` feature is activated, name should be assigned to config instance.
+    pub fn builder(
+        #[cfg(feature = "tracing")] name: String,
+        data_provider: Provider
+    ) -> RemoteConfigBuilder<Data, Provider> {
+        RemoteConfigBuilder {
+            #[cfg(feature = "tracing")] name,
             data_provider,
-            revalidation_error: None,
+            retry_interval: DEFAULT_RETRY_INTERVAL,
+            max_retry_interval: None,
+            load_timeout: None,
+            min_ttl: Duration::ZERO,
+            max_ttl: None,
+            clock: Box::new(SystemClock),
+            spawner: Box::new(TokioSpawner),
+            max_revalidation_wait: None,
+            profiles: HashMap::new(),
+            stale_tolerance: Duration::ZERO,
+            budgets: Vec::new(),
+            bandwidth_policy: None,
+            load_shedding: None,
+            startup_splay: Duration::ZERO,
+            flapping_policy: None,
+            sunset_policy: SunsetPolicy::default(),
+            content_hasher: None,
+            #[cfg(feature = "tracing")] unused_warning: None,
+            #[cfg(feature = "metrics")] metrics: true,
             data_type: PhantomData
+        }.with_policy(GlobalPolicy::current())
+    }
+
+    /// Fault injection switches of this config instance.
+    /// See [`Chaos`] docs.
+    #[cfg(feature = "chaos")]
+    pub fn chaos(&self) -> &Chaos {
+        &self.chaos
+    }
+
+    /// Replaces data provider and returns previous one. Cached data is kept.
+    /// If revalidation is in progress, waits for it to finish.
+    /// Last revalidation error is cleared, so new provider is used as soon as data becomes stale.
+    pub async fn replace_provider(&self, data_provider: Provider) -> Provider {
+        let mut guard = self.revalidator.lock().await;
+        guard.revalidation_error = None;
+        self.failures.store(0, Ordering::Relaxed);
+        std::mem::replace(&mut guard.data_provider, data_provider)
+    }
+
+    /// Enables or disables offline mode.
+    /// In offline mode data provider is not called, and cached data is served even if it is stale and must be revalidated.
+    /// Normal behavior resumes as soon as offline mode is disabled.
+    /// Applications can toggle it explicitly or in response to OS network state changes.
+    pub fn set_offline(&self, offline: bool) {
+        self.offline.store(offline, Ordering::Relaxed);
+    }
+
+    /// Checks if offline mode is enabled
+    pub fn is_offline(&self) -> bool {
+        self.offline.load(Ordering::Relaxed)
+    }
+
+    /// Sets network link state used by [`BandwidthPolicy`].
+    /// Has no effect if policy is not configured or it has link state detector.
+    pub fn set_link_state(&self, state: LinkState) {
+        self.link_state.store(state.as_u8(), Ordering::Relaxed);
+    }
+
+    /// Current network link state, reported by policy detector or set with [`RemoteConfig::set_link_state`]
+    pub fn link_state(&self) -> LinkState {
+        let state = LinkState::from_u8(self.link_state.load(Ordering::Relaxed));
+        self.bandwidth_policy.as_ref().map_or(state, |policy| policy.link_state(state))
+    }
+
+    /// Applies origin directives, replacing previously received ones.
+    /// Use it to deliver directives through separate control channel.
+    /// If `force_refresh_now` is set, cached data becomes stale, and it is revalidated on next load even if updates are paused.
+    pub fn apply_control(&self, control: Control) {
+        if control.force_refresh_now {
+            self.forced_refresh.store(true, Ordering::Relaxed);
+        }
+        *self.control.lock().unwrap() = Some(control);
+    }
+
+    /// Current state of config
+    pub fn status(&self) -> ConfigStatus {
+        let curr = self.cached_response.load();
+        let now = self.clock.now();
+        let mut accounting = self.accounting.lock().unwrap();
+        ConfigStatus {
+            valid_until: curr.valid_until,
+            must_revalidate: curr.must_revalidate,
+            stale: self.is_stale(&curr, now),
+            revision: curr.result.metadata.revision.clone(),
+            overrides: curr.result.metadata.overrides.clone(),
+            usage: accounting.usage(),
+            budget_exhausted: accounting.is_exhausted(now),
+            reads: self.access.reads.load(Ordering::Relaxed),
+            last_accessed: self.access.last_accessed(),
+            offline: self.is_offline(),
+            link_state: self.link_state(),
+            control: self.control.lock().unwrap().clone(),
+            background_tasks: self.live_tasks.load(Ordering::Relaxed),
+            flapping: self.flapping.as_ref().and_then(|detector| detector.lock().unwrap().alarm(now)),
+            deprecation: curr.result.metadata.deprecation.clone()
+        }
+    }
+
+    /// Name of revalidation tasks, shown by tokio-console
+    fn task_name(&self) -> String {
+        #[cfg(feature = "tracing")]
+        let name = format!("remote_config::revalidate {}", self.name);
+        #[cfg(not (feature = "tracing"))]
+        let name = "remote_config::revalidate".to_string();
+        name
+    }
+
+    /// Records finished data load attempt in usage statistics and metrics
+    fn record_fetch(&self, size: Option<u64>, duration: Duration, success: bool) {
+        self.accounting.lock().unwrap().record(self.clock.now(), size, duration, success);
+
+        #[cfg(feature = "metrics")]
+        if self.metrics {
+            let outcome = if success { "success" } else { "error" };
+            metrics::counter!("remote_config_fetches_total", "config" => self.name.clone(), "outcome" => outcome).increment(1);
+            metrics::counter!("remote_config_fetched_bytes_total", "config" => self.name.clone()).increment(size.unwrap_or_default());
+            metrics::histogram!("remote_config_fetch_duration_seconds", "config" => self.name.clone()).record(duration.as_secs_f64());
+        }
+    }
+
+    /// Records data change for flapping detection, and reports alarm if config started flapping
+    fn record_change(&self, metadata: &LoadMetadata) {
+        let Some(detector) = &self.flapping else {
+            return;
         };
-        Ok(Self {
-            #[cfg(feature = "tracing")] name,
-            retry_interval,
-            cached_response: ArcSwap::new(Arc::new(data)),
-            revalidator: Mutex::new(revalidator)
-        })
+        let Some(_alarm) = detector.lock().unwrap().record(self.clock.now(), metadata.revision.clone()) else {
+            return;
+        };
+        #[cfg(feature = "tracing")]
+        warn!(
+            config = self.name,
+            changes = _alarm.changes,
+            window = ?_alarm.window,
+            recent_revisions = ?_alarm.recent_revisions,
+            "Config is flapping"
+        );
+        #[cfg(feature = "metrics")]
+        if self.metrics {
+            metrics::counter!("remote_config_flapping_alarms_total", "config" => self.name.clone()).increment(1);
+        }
+    }
+
+    /// Tracks sunset of config endpoint, and reports warning if endpoint reached next sunset stage
+    fn record_deprecation(&self, metadata: &LoadMetadata) {
+        let Some(_warning) = self.sunset.lock().unwrap().record(self.clock.now(), metadata.deprecation.as_ref()) else {
+            return;
+        };
+        #[cfg(feature = "tracing")]
+        warn!(
+            config = self.name,
+            stage = ?_warning.stage,
+            sunset = ?_warning.deprecation.sunset,
+            successor = _warning.deprecation.successor,
+            documentation = _warning.deprecation.documentation,
+            "Config endpoint is deprecated"
+        );
+        #[cfg(feature = "metrics")]
+        if self.metrics {
+            metrics::counter!("remote_config_sunset_warnings_total", "config" => self.name.clone()).increment(1);
+        }
+    }
+
+    /// Emits audit event for activated config version
+    #[cfg(feature = "tracing")]
+    fn audit_activation(&self, metadata: &LoadMetadata) {
+        let provenance = metadata.provenance.clone().unwrap_or_default();
+        info!(
+            target: "remote_config::audit",
+            config = self.name,
+            publisher = provenance.publisher,
+            pipeline_run = provenance.pipeline_run,
+            signature_subject = provenance.signature_subject,
+            source_commit = provenance.source_commit,
+            overrides = metadata.overrides.join(","),
+            "Config version activated"
+        );
+    }
+
+    /// Checks if data must be revalidated at specified time
+    fn is_stale(&self, entry: &Entry<Data>, time: SystemTime) -> bool {
+        #[cfg(feature = "chaos")] {
+            if self.chaos.is_stale_forced() {
+                return true;
+            }
+        }
+        entry.valid_until < time ||
+            entry.invalidation.as_ref().is_some_and(|token| token.is_invalidated()) ||
+            self.forced_refresh.load(Ordering::Relaxed)
+    }
+
+    /// Data within stale tolerance is treated as if it does not need to be revalidated
+    fn freshness(&self, entry: &Entry<Data>, time: SystemTime, stale_tolerance: Duration) -> Freshness {
+        Freshness {
+            stale: self.is_stale(entry, time),
+            must_revalidate: entry.must_revalidate && (stale_tolerance.is_zero() || entry.valid_until + stale_tolerance <= time)
+        }
+    }
+
+    /// Policy of named profile. Default policy is used for unknown profiles.
+    fn profile(&self, name: Option<&str>) -> AccessProfile {
+        let default = AccessProfile::new().with_stale_tolerance(self.stale_tolerance);
+        let Some(name) = name else {
+            return default;
+        };
+        match self.profiles.get(name) {
+            Some(profile) => *profile,
+            None => {
+                #[cfg(feature = "tracing")] {
+                    warn!("Access profile '{name}' is not registered for config '{cfg_name}', default policy is used", cfg_name = self.name)
+                }
+                default
+            }
+        }
+    }
+
+    /// Whether fetch budget is exhausted at `time`, and time of last fetch
+    fn budget_state(&self, time: SystemTime) -> (bool, Option<SystemTime>) {
+        let mut accounting = self.accounting.lock().unwrap();
+        (accounting.is_exhausted(time), accounting.last_fetch_at())
+    }
+
+    /// Whether origin paused updates or asked to poll less often, and refresh was not forced
+    fn is_throttled(&self, last_fetch: Option<SystemTime>, time: SystemTime) -> bool {
+        !self.forced_refresh.load(Ordering::Relaxed) &&
+            self.control.lock().unwrap().as_ref().is_some_and(|control| control.is_throttled(last_fetch, time))
+    }
+
+    fn serve_stale(&self, data: Guard<Arc<Entry<Data>>>) -> LoadResult<Data> {
+        #[cfg(feature = "tracing")] {
+            warn!("Stale configuration data is being used for config '{cfg_name}'", cfg_name = self.name)
+        }
+        Ok(CachedData(data))
     }
 
     /// Loads current config.
     /// If cached data is still valid, it is returned.
     /// If not, but `must_revalidate` is false, cached data is returned, and revalidation is started in background if necessary.
-    /// If stale data must be revalidated, this method returns only after revalidation attempt is finished.
+    /// If stale data must be revalidated, this method returns only after revalidation attempt is finished,
+    /// or after [`RemoteConfigBuilder::with_max_revalidation_wait`] elapses.
     /// ## Why static?
     /// When data is stale, but `!must_revalidate`, revalidation task is spawned to perform revalidation in background. Lifetime must be `'static` to spawn this task.
     /// Enable feature `non_static` to support usage of [`Arc`] instead of static reference.
     /// # Errors
-    /// If stale data must be revalidated and last revalidation attempt failed
-    /// # Panics
-    /// If underlying data provider panics.
+    /// If stale data must be revalidated and last revalidation attempt failed,
+    /// or revalidation task was dropped before completion (e.g. because data provider panicked)
     pub async fn load_with_time(&'static self, time: SystemTime) -> LoadResult<Data> {
-        let curr = self.cached_response.load();
+        Self::load_shared(self, time, None).await
+    }
 
-        if curr.valid_until < time {
-            return match self.revalidator.try_lock() {
-                // Revalidation is in progress
-                Err(_) => {
-                    if curr.must_revalidate {
-                        // Wait for revalidation to finish
-                        let guard = self.revalidator.lock().await;
+    /// Waits until active config satisfies predicate, e.g. until maintenance flag is cleared.
+    /// Predicate is checked again every time data is replaced, and data is revalidated as soon as it becomes stale,
+    /// so the source is not polled more often than its freshness allows.
+    /// # Errors
+    /// If predicate is not satisfied before timeout. Load errors are retried until timeout and the last one is reported.
+    pub async fn wait_for(&'static self, predicate: impl Fn(&Data) -> bool, timeout: Duration) -> Result<CachedData<Data>, WaitTimeout> {
+        Self::wait_for_shared(self, predicate, timeout).await
+    }
 
-                        if let Some(ref error) = guard.revalidation_error {
-                            // Revalidation failed
-                            // Error is wrapped in arc for thread safety
-                            Err(error.clone())
-                        } else {
-                            // Revalidation was successful, so we can use data without additional checks
-                            Ok(CachedData(self.cached_response.load()))
-                        }
-                    } else {
-                        #[cfg(feature = "tracing")] {
-                            warn!("Stale configuration data is being used for config '{cfg_name}'", cfg_name = self.name)
-                        }
-                        Ok(CachedData(curr))
-                    }
-                },
-                // Revalidation should be started
-                Ok(mut guard) => {
-
-                    // Quick return if it is too early to retry after error
-                    if let Some(ref err) = guard.revalidation_error {
-                        if time < err.timestamp + self.retry_interval {
-                            return if curr.must_revalidate {
-                                Err(err.clone())
-                            } else {
-                                Ok(CachedData(curr))
-                            }
-                        }
-                    }
+    /// Revalidates data as soon as its invalidation token is invalidated (see [`LoadMetadata::invalidation`]),
+    /// instead of waiting for next load. Use it with data providers that receive pushed updates
+    /// (e.g. WebSocket or file watcher), so that new data is applied immediately and waiters of [`RemoteConfig::wait_for`] are woken.
+    ///
+    /// This future never completes, run it in background task:
+    /// ```ignore
+    /// tokio::spawn(CONFIG.get().unwrap().follow_updates());
+    /// ```
+    pub async fn follow_updates(&'static self) {
+        Self::follow_updates_shared(self).await
+    }
 
-                    let handle = spawn(async move {
-                        return match guard.data_provider.load_data().await {
-                            Ok(load_result) => {
-                                self.cached_response.store(Arc::new(load_result));
-                                guard.revalidation_error = None;
-                                Ok(CachedData(self.cached_response.load()))
-                            },
-                            Err(err) => {
-                                #[cfg(feature = "tracing")] {
-                                    if let Some(source) = err.source() {
-                                        error!("Failed to load data for config {cfg_name}. Error: {error}", cfg_name = self.name, error = source);
-                                    } else {
-                                        error!("Failed to load data for config {cfg_name}. No source error provided", cfg_name = self.name)
-                                    }
-                                }
-                                let dp_err = Arc::new(DataProviderError::from(err));
-                                guard.revalidation_error = Some(dp_err.clone());
-                                Err(dp_err)
-                            }
-                        }
-                    });
+    /// Stream of activated config versions, that starts with current version.
+    /// Next version is yielded once cached data is replaced (revalidations that didn't modify data are skipped).
+    /// Data is revalidated as soon as it becomes stale while stream is polled, so the source is not polled more often than its freshness allows.
+    ///
+    /// Versions are not queued: if stream is polled less often than data changes, only the latest one is yielded.
+    /// ```ignore
+    /// CONFIG.get().unwrap().stream()
+    ///     .for_each(|data| async move { apply_limits(&data.limits) })
+    ///     .await;
+    /// ```
+    #[cfg(feature = "stream")]
+    pub fn stream(&'static self) -> impl Stream<Item = CachedData<Data>> + Send {
+        Self::stream_shared(self)
+    }
 
-                    if curr.must_revalidate {
-                        // Wait for validation attempt to finish
-                        handle.await.unwrap()
-                    } else {
-                        // Return immediately
-                        Ok(CachedData(curr))
-                    }
-                }
+    /// Revalidates data now, even if it is fresh, and waits for result. If revalidation is already in progress, waits for it instead.
+    /// Revalidation runs in calling task, so it fits short background execution windows of mobile platforms
+    /// (background fetch on iOS, WorkManager on Android), and doesn't require `'static` config.
+    /// In offline mode (see [`RemoteConfig::set_offline`]), while origin paused updates or asked to poll less often
+    /// (see [`Control`]), or fetch budget is exhausted, cached data is returned without revalidation.
+    /// # Errors
+    /// If revalidation failed
+    pub async fn refresh(&self) -> LoadResult<Data> {
+        let time = self.clock.now();
+        let (budget_exhausted, last_fetch) = self.budget_state(time);
+        if self.is_offline() || budget_exhausted || self.is_throttled(last_fetch, time) {
+            return Ok(CachedData(self.cached_response.load()));
+        }
+        let mut revalidator = match self.revalidator.try_lock() {
+            Ok(revalidator) => revalidator,
+            Err(_) => {
+                let revalidator = self.revalidator.lock().await;
+                return match &revalidator.revalidation_error {
+                    Some(error) => Err(error.clone()),
+                    None => Ok(CachedData(self.cached_response.load()))
+                };
             }
+        };
+        self.access.record(self.clock.now());
+        self.revalidate(&mut revalidator).await
+    }
+
+    /// Waits until published version becomes active, refreshing data every retry interval (or delay hinted by origin) until then.
+    /// Gives read-your-write consistency to tools that both publish and consume config (e.g. migration jobs).
+    /// Version is compared with revision reported by data provider (see [`LoadMetadata::revision`]), e.g. `ETag` of HTTP response,
+    /// and providers that revalidate with conditional requests keep refreshes of unchanged data cheap.
+    /// # Errors
+    /// If version is not active before timeout. Refresh errors are retried until timeout and the last one is reported.
+    pub async fn expect_version(&self, revision: &str, timeout: Duration) -> Result<CachedData<Data>, WaitTimeout> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        let current = CachedData(self.cached_response.load());
+        if current.metadata().revision.as_deref() == Some(revision) {
+            return Ok(current);
+        }
+        loop {
+            let last_error = match self.refresh().await {
+                Ok(data) if data.metadata().revision.as_deref() == Some(revision) => return Ok(data),
+                Ok(_) => None,
+                Err(err) => Some(err)
+            };
+            let retry_interval = last_error.as_ref().and_then(|err| err.retry_after).unwrap_or_else(|| self.current_retry_interval());
+            let retry_at = tokio::time::Instant::now() + retry_interval;
+            if retry_at >= deadline {
+                return Err(WaitTimeout { last_error });
+            }
+            tokio::time::sleep_until(retry_at).await;
         }
+    }
 
-        // Return valid data
-        Ok(CachedData(curr))
+    /// Refreshes data until cached version was fetched within `max_age` (see [`CachedData::fetched_at`]).
+    /// Refresh is retried every retry interval (or delay hinted by origin), while it fails or returns data that is still too old.
+    /// # Errors
+    /// If data fetched within `max_age` is not loaded before deadline. Refresh errors are retried until deadline and the last one is reported.
+    pub(crate) async fn refresh_until_fetched_within(&self, max_age: Duration, deadline: tokio::time::Instant) -> Result<(), WaitTimeout> {
+        loop {
+            let last_error = match self.refresh().await {
+                Ok(data) if self.clock.now() <= data.fetched_at() + max_age => return Ok(()),
+                Ok(_) => None,
+                Err(err) => Some(err)
+            };
+            let retry_interval = last_error.as_ref().and_then(|err| err.retry_after).unwrap_or_else(|| self.current_retry_interval());
+            let retry_at = tokio::time::Instant::now() + retry_interval;
+            if retry_at >= deadline {
+                return Err(WaitTimeout { last_error });
+            }
+            tokio::time::sleep_until(retry_at).await;
+        }
     }
 
+    /// Checks if cached version was fetched within `max_age`
+    pub(crate) fn is_fetched_within(&self, max_age: Duration) -> bool {
+        self.clock.now() <= self.cached_response.load().fetched_at + max_age
+    }
+
+    /// Loads current config with freshness policy of named profile
+    /// (see [`RemoteConfigBuilder::with_access_profile`]).
+    /// If profile is not registered, default policy is used.
+    /// See [`RemoteConfig::load_with_time`] docs.
+    pub async fn load_as(&'static self, profile: &str) -> LoadResult<Data> {
+        Self::load_shared(self, self.clock.now(), Some(profile)).await
+    }
+
+    /// Loads current config using time provided by config's [`Clock`].
     /// See [`RemoteConfig::load_with_time`] docs
     pub async fn load(&'static self) -> LoadResult<Data> {
-        self.load_with_time(SystemTime::now()).await
+        self.load_with_time(self.clock.now()).await
     }
 }
 
-#[cfg(feature = "non_static")]
-pub trait NonStaticRemoteConfig <Data: Send + Sync>
-where Self: Send + Sync + Clone
+/// Default minimal amount of time between data loading attempts in case of error
+pub const DEFAULT_RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Builder for [`RemoteConfig`].
+/// Created with [`RemoteConfig::builder`].
+/// # Examples
+/// ```ignore
+/// let config = RemoteConfig::builder(data_provider)
+///     .with_retry_interval(Duration::from_secs(10))
+///     .build()
+///     .await?;
+/// ```
+#[derive(Debug)]
+pub struct RemoteConfigBuilder<Data: Send + Sync, Provider: DataProvider<Data> + Send> {
+    #[cfg(feature = "tracing")] name: String,
+    data_provider: Provider,
+    retry_interval: Duration,
+    max_retry_interval: Option<Duration>,
+    load_timeout: Option<Duration>,
+    min_ttl: Duration,
+    max_ttl: Option<Duration>,
+    clock: Box<dyn Clock>,
+    spawner: Box<dyn Spawner>,
+    max_revalidation_wait: Option<Duration>,
+    profiles: HashMap<String, AccessProfile>,
+    stale_tolerance: Duration,
+    budgets: Vec<Budget>,
+    bandwidth_policy: Option<BandwidthPolicy>,
+    load_shedding: Option<LoadSheddingPolicy>,
+    startup_splay: Duration,
+    flapping_policy: Option<FlappingPolicy>,
+    sunset_policy: SunsetPolicy,
+    content_hasher: Option<fn(&Data) -> u64>,
+    #[cfg(feature = "tracing")] unused_warning: Option<Duration>,
+    #[cfg(feature = "metrics")] metrics: bool,
+    data_type: PhantomData<Data>
+}
+
+impl <Data: Send + Sync, Provider: DataProvider<Data> + Send> RemoteConfigBuilder<Data, Provider> {
+    /// Minimal amount of time between data loading attempts in case of error.
+    /// Default is [`DEFAULT_RETRY_INTERVAL`]. If error carries [`RetryHint`] (e.g. `Retry-After` of 503 response), hinted delay is used instead.
+    pub fn with_retry_interval(mut self, retry_interval: Duration) -> Self {
+        self.retry_interval = retry_interval;
+        self
+    }
+
+    /// Enables exponential backoff: retry interval is doubled after every consecutive failed load, up to specified limit,
+    /// and is reset after successful load. By default, retry interval is constant.
+    pub fn with_retry_backoff(mut self, max_retry_interval: Duration) -> Self {
+        self.max_retry_interval = Some(max_retry_interval);
+        self
+    }
+
+    /// Load that is not completed by data provider within timeout fails with [`LoadTimeout`] error (and is retried after retry interval).
+    /// By default, loads are not limited.
+    pub fn with_load_timeout(mut self, timeout: Duration) -> Self {
+        self.load_timeout = Some(timeout);
+        self
+    }
+
+    /// Loaded data is fresh for at least specified time, even if data provider reports shorter lifetime,
+    /// e.g. to protect origin from clients when it sends `max-age=0`. Invalidation tokens still apply. Default is zero.
+    pub fn with_min_ttl(mut self, ttl: Duration) -> Self {
+        self.min_ttl = ttl;
+        self
+    }
+
+    /// Loaded data is revalidated after specified time at most, even if data provider reports longer lifetime.
+    /// Takes precedence over minimal TTL. By default, lifetime is not limited.
+    pub fn with_max_ttl(mut self, ttl: Duration) -> Self {
+        self.max_ttl = Some(ttl);
+        self
+    }
+
+    /// Source of current time used by [`RemoteConfig::load`] and for error timestamps.
+    /// Default is [`SystemClock`].
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Box::new(clock);
+        self
+    }
+
+    /// Runs background revalidation tasks with specified spawner instead of detached [`tokio::spawn`],
+    /// e.g. inside application's [`tokio::task::JoinSet`] (see [`Spawner`] docs).
+    /// Default is [`TokioSpawner`].
+    pub fn with_spawner(mut self, spawner: impl Spawner + 'static) -> Self {
+        self.spawner = Box::new(spawner);
+        self
+    }
+
+    /// Limits time that [`RemoteConfig::load`] waits for revalidation of stale data that must be revalidated.
+    /// If revalidation does not finish in time, stale data is served, and revalidation is completed in background.
+    /// By default, load waits until revalidation attempt is finished.
+    pub fn with_max_revalidation_wait(mut self, wait: Duration) -> Self {
+        self.max_revalidation_wait = Some(wait);
+        self
+    }
+
+    /// Registers named freshness policy, that can be selected with [`RemoteConfig::load_as`].
+    /// See [`AccessProfile`] docs.
+    pub fn with_access_profile(mut self, name: impl Into<String>, profile: AccessProfile) -> Self {
+        self.profiles.insert(name.into(), profile);
+        self
+    }
+
+    /// Stale tolerance of loads without access profile (see [`AccessProfile::with_stale_tolerance`]).
+    /// Default is zero.
+    pub fn with_stale_tolerance(mut self, tolerance: Duration) -> Self {
+        self.stale_tolerance = tolerance;
+        self
+    }
+
+    /// Applies retry interval, stale tolerance, max revalidation wait and startup splay of preset.
+    /// Call other builder methods after this one to override individual settings. See [`Preset`] docs.
+    pub fn with_preset(mut self, preset: Preset) -> Self {
+        self.retry_interval = preset.retry_interval();
+        self.max_retry_interval = Some(preset.max_retry_interval());
+        self.load_timeout = Some(preset.load_timeout());
+        self.min_ttl = preset.min_ttl();
+        self.max_ttl = preset.max_ttl();
+        self.stale_tolerance = preset.stale_tolerance();
+        self.max_revalidation_wait = Some(preset.max_revalidation_wait());
+        self.startup_splay = preset.startup_splay();
+        self
+    }
+
+    /// Applies settings that are set in policy. Policy installed with [`GlobalPolicy::install`] is applied when builder is created,
+    /// so this method is needed only to apply another policy, e.g. shared by configs of one team
+    /// (see also [`ConfigManager::with_policy`](crate::manager::ConfigManager::with_policy)).
+    /// Call other builder methods after this one to override individual settings.
+    pub fn with_policy(mut self, policy: &GlobalPolicy) -> Self {
+        self.retry_interval = policy.retry_interval.unwrap_or(self.retry_interval);
+        self.max_retry_interval = policy.max_retry_interval.or(self.max_retry_interval);
+        self.load_timeout = policy.load_timeout.or(self.load_timeout);
+        self.max_revalidation_wait = policy.max_revalidation_wait.or(self.max_revalidation_wait);
+        self.stale_tolerance = policy.stale_tolerance.unwrap_or(self.stale_tolerance);
+        self.startup_splay = policy.startup_splay.unwrap_or(self.startup_splay);
+        #[cfg(feature = "metrics")] {
+            self.metrics = policy.metrics.unwrap_or(self.metrics);
+        }
+        self
+    }
+
+    /// Adds data provider usage budget. See [`Budget`] docs.
+    pub fn with_budget(mut self, budget: Budget) -> Self {
+        self.budgets.push(budget);
+        self
+    }
+
+    /// Refresh policy for metered and constrained links. See [`BandwidthPolicy`] docs.
+    pub fn with_bandwidth_policy(mut self, policy: BandwidthPolicy) -> Self {
+        self.bandwidth_policy = Some(policy);
+        self
+    }
+
+    /// Refresh policy for overloaded process. See [`LoadSheddingPolicy`] docs.
+    pub fn with_load_shedding(mut self, policy: LoadSheddingPolicy) -> Self {
+        self.load_shedding = Some(policy);
+        self
+    }
+
+    /// Delays initial data load by random duration within specified window.
+    /// When many instances start simultaneously (e.g. after deploy), this spreads their initial loads,
+    /// so that following revalidations are not synchronized either.
+    /// Default is zero (no delay).
+    pub fn with_startup_splay(mut self, window: Duration) -> Self {
+        self.startup_splay = window;
+        self
+    }
+
+    /// Raises alarm when data changes more often than allowed by policy. See [`FlappingPolicy`] docs.
+    pub fn with_flapping_detection(mut self, policy: FlappingPolicy) -> Self {
+        self.flapping_policy = Some(policy);
+        self
+    }
+
+    /// Reaction to deprecation of config endpoint announced by origin. See [`SunsetPolicy`] docs.
+    /// By default, warning is traced at every sunset stage.
+    pub fn with_sunset_policy(mut self, policy: SunsetPolicy) -> Self {
+        self.sunset_policy = policy;
+        self
+    }
+
+    /// Emits tracing warning every time config was not read during specified period.
+    /// Helps to find configs that are no longer used and can be retired.
+    #[cfg(feature = "tracing")]
+    pub fn with_unused_warning(mut self, period: Duration) -> Self {
+        self.unused_warning = Some(period);
+        self
+    }
+
+    /// Records data provider usage with metrics facade. Default is enabled.
+    #[cfg(feature = "metrics")]
+    pub fn with_metrics(mut self, enabled: bool) -> Self {
+        self.metrics = enabled;
+        self
+    }
+
+    /// Performs initial data load and constructs remote config instance.
+    /// If startup splay is configured, waits for it first.
+    /// # Errors
+    /// Returns error if initial data load failed.
+    pub async fn build(self) -> Result<RemoteConfig<Data, Provider>, DataProviderError> {
+        if !self.startup_splay.is_zero() {
+            tokio::time::sleep(random_duration(self.startup_splay)).await;
+        }
+        let started = Instant::now();
+        let data = match limit_load(self.data_provider.load_data(), self.load_timeout).await {
+            Ok(data) => data,
+            Err(err) => return Err(DataProviderError::new(err, self.clock.now()))
+        };
+        let size = data.metadata.size;
+        let control = data.metadata.control.clone();
+        let now = self.clock.now();
+        let mut entry = Entry::new(data, self.content_hasher, now);
+        entry.valid_until = clamp_ttl(entry.valid_until, now, self.min_ttl, self.max_ttl);
+        let revalidator = Revalidator{
+            data_provider: self.data_provider,
+            revalidation_error: None,
+            data_type: PhantomData
+        };
+        let config = RemoteConfig {
+            #[cfg(feature = "tracing")] name: self.name,
+            retry_interval: self.retry_interval,
+            max_retry_interval: self.max_retry_interval,
+            failures: AtomicU32::new(0),
+            load_timeout: self.load_timeout,
+            min_ttl: self.min_ttl,
+            max_ttl: self.max_ttl,
+            clock: self.clock,
+            spawner: self.spawner,
+            max_revalidation_wait: self.max_revalidation_wait,
+            profiles: self.profiles,
+            stale_tolerance: self.stale_tolerance,
+            cached_response: ArcSwap::new(Arc::new(entry)),
+            revalidator: Arc::new(Mutex::new(revalidator)),
+            accounting: std::sync::Mutex::new(Accounting::new(self.budgets)),
+            access: Arc::new(AccessStats::default()),
+            offline: AtomicBool::new(false),
+            bandwidth_policy: self.bandwidth_policy,
+            load_shedding: self.load_shedding,
+            link_state: AtomicU8::new(LinkState::Unmetered.as_u8()),
+            control: std::sync::Mutex::new(control),
+            forced_refresh: AtomicBool::new(false),
+            changed: Notify::new(),
+            live_tasks: Arc::new(AtomicUsize::new(0)),
+            flapping: self.flapping_policy.map(|policy| std::sync::Mutex::new(FlappingDetector::new(policy))),
+            sunset: std::sync::Mutex::new(SunsetMonitor::new(self.sunset_policy)),
+            content_hasher: self.content_hasher,
+            #[cfg(feature = "metrics")] metrics: self.metrics,
+            #[cfg(feature = "chaos")] chaos: Chaos::default()
+        };
+        #[cfg(feature = "tracing")] {
+            if let Some(period) = self.unused_warning {
+                spawn(watch_unused(config.name.clone(), Arc::downgrade(&config.access), period));
+            }
+        }
+        config.record_fetch(size, started.elapsed(), true);
+        config.record_deprecation(&config.cached_response.load().result.metadata);
+        #[cfg(feature = "tracing")]
+        config.audit_activation(&config.cached_response.load().result.metadata);
+        Ok(config)
+    }
+}
+
+impl <Data: Send + Sync, Provider: DataProvider<Data> + Send> RemoteConfigBuilder<Data, Provider> {
+    /// Replaces data provider with the one that stores data in another form, keeping other settings.
+    /// Content hasher can't hash data of another type, so it is removed.
+    #[cfg(any(feature = "zstd", all(feature = "sealed", target_os = "linux")))]
+    fn map_data_provider<Stored: Send + Sync, Mapped: DataProvider<Stored> + Send>(self, map: impl FnOnce(Provider) -> Mapped) -> RemoteConfigBuilder<Stored, Mapped> {
+        RemoteConfigBuilder {
+            #[cfg(feature = "tracing")] name: self.name,
+            data_provider: map(self.data_provider),
+            retry_interval: self.retry_interval,
+            max_retry_interval: self.max_retry_interval,
+            load_timeout: self.load_timeout,
+            min_ttl: self.min_ttl,
+            max_ttl: self.max_ttl,
+            clock: self.clock,
+            spawner: self.spawner,
+            max_revalidation_wait: self.max_revalidation_wait,
+            profiles: self.profiles,
+            stale_tolerance: self.stale_tolerance,
+            budgets: self.budgets,
+            bandwidth_policy: self.bandwidth_policy,
+            load_shedding: self.load_shedding,
+            startup_splay: self.startup_splay,
+            flapping_policy: self.flapping_policy,
+            sunset_policy: self.sunset_policy,
+            content_hasher: None,
+            #[cfg(feature = "tracing")] unused_warning: self.unused_warning,
+            #[cfg(feature = "metrics")] metrics: self.metrics,
+            data_type: PhantomData
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl <Data: Send + Sync + serde::Serialize, Provider: DataProvider<Data> + Send> RemoteConfigBuilder<Data, Provider> {
+    /// Computes [`CachedData::content_hash`] from data serialized as JSON, instead of revision reported by data provider.
+    /// Map keys are sorted before hashing, so hash is stable even for data with `HashMap`s.
+    /// Data that can't be serialized (e.g. map with non-string keys) gets new hash on every load, so it is always treated as changed.
+    pub fn with_content_hash(mut self) -> Self {
+        self.content_hasher = Some(|data| content_hash::serialized(data).unwrap_or_else(|_| content_hash::unique()));
+        self
+    }
+}
+
+#[cfg(feature = "zstd")]
+impl <Data, Provider> RemoteConfigBuilder<Data, Provider>
+where Data: AsRef<[u8]> + From<Vec<u8>> + Send + Sync, Provider: DataProvider<Data> + Send + Sync
 {
-    fn load_with_time(&self, time: SystemTime) -> impl Future<Output = LoadResult<Data>> + Send;
-    fn load(&self) -> impl Future<Output = LoadResult<Data>> + Send;
-    
+    /// Keeps bytes loaded by data provider compressed in memory, and decompresses them on access:
+    /// cached data uses less memory, at the cost of compression on every load and decompression on every access
+    /// (unless decompressed payload is kept in [`DecompressedCache`](crate::compressed::DecompressedCache) of settings).
+    ///
+    /// Content hasher set with `with_content_hash` is removed, so hash is computed from revision reported by data provider.
+    pub fn with_compression(self, compression: crate::compressed::Compression)
+        -> RemoteConfigBuilder<crate::compressed::CompressedBytes, crate::compressed::CompressingDataProvider<Data, Provider>>
+    {
+        self.map_data_provider(|data_provider| crate::compressed::CompressingDataProvider::new(data_provider, compression))
+    }
+}
+
+#[cfg(all(feature = "sealed", target_os = "linux"))]
+impl <Data, Provider> RemoteConfigBuilder<Data, Provider>
+where Data: AsRef<[u8]> + From<Vec<u8>> + Send + Sync, Provider: DataProvider<Data> + Send + Sync
+{
+    /// Keeps bytes loaded by data provider in sealed read-only memory file (see [`SealedBytes`](crate::sealed::SealedBytes)).
+    /// Config data is copied into new file on every load.
+    ///
+    /// Content hasher set with `with_content_hash` is removed, so hash is computed from revision reported by data provider.
+    pub fn with_sealed_storage(self) -> RemoteConfigBuilder<crate::sealed::SealedBytes, crate::sealed::SealingDataProvider<Data, Provider>> {
+        self.map_data_provider(crate::sealed::SealingDataProvider::new)
+    }
+}
+
+/// Warns when config is not read during period. Stops when config is dropped.
+#[cfg(feature = "tracing")]
+async fn watch_unused(name: String, access: std::sync::Weak<AccessStats>, period: Duration) {
+    let mut last_reads = 0;
+    loop {
+        tokio::time::sleep(period).await;
+        let Some(access) = access.upgrade() else {
+            return;
+        };
+        let reads = access.reads.load(Ordering::Relaxed);
+        if reads == last_reads {
+            if reads == 0 {
+                warn!("Config '{name}' was never read during {period:?} since creation");
+            } else {
+                warn!("Config '{name}' was not read during last {period:?}");
+            }
+        }
+        last_reads = reads;
+    }
+}
+
+impl <Data: Send + Sync, Provider: DataProvider<Data> + Send> RemoteConfig<Data, Provider> {
+    /// Retry interval after consecutive failed loads, doubled after every failure up to limit, if backoff is enabled
+    fn current_retry_interval(&self) -> Duration {
+        let Some(max_retry_interval) = self.max_retry_interval else {
+            return self.retry_interval;
+        };
+        let doublings = self.failures.load(Ordering::Relaxed).saturating_sub(1).min(31);
+        self.retry_interval.saturating_mul(1 << doublings).min(max_retry_interval.max(self.retry_interval))
+    }
+
+    /// Time until cached data should be loaded again by tasks waiting for change: until it becomes stale,
+    /// but no longer than retry interval if load failed or data can be invalidated (invalidation tokens are checked every retry interval)
+    fn wake_after(&self, now: SystemTime, failed: bool) -> Duration {
+        let entry = self.cached_response.load();
+        let retry_interval = self.current_retry_interval();
+        let wake_after = entry.valid_until.duration_since(now).unwrap_or(Duration::ZERO).max(retry_interval);
+        if entry.invalidation.is_some() || failed {
+            wake_after.min(retry_interval)
+        } else {
+            wake_after
+        }
+    }
+
+    /// Implementation of [`RemoteConfig::wait_for`] for all handle types
+    async fn wait_for_shared<Handle: ConfigHandle<Data, Provider>>(this: Handle, predicate: impl Fn(&Data) -> bool, timeout: Duration) -> Result<CachedData<Data>, WaitTimeout>
+    where Data: 'static, Provider: 'static
+    {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            // Subscribe before loading, so that change made in between is not missed
+            let changed = this.changed.notified();
+            let mut changed = pin!(changed);
+            changed.as_mut().enable();
+
+            let now = this.clock.now();
+            let last_error = match Self::load_shared(this.clone(), now, None).await {
+                Ok(data) if predicate(&data) => return Ok(data),
+                Ok(_) => None,
+                Err(err) => Some(err)
+            };
+
+            // Wake up when data changes or becomes stale
+            let wake_at = deadline.min(tokio::time::Instant::now() + this.wake_after(now, last_error.is_some()));
+            let _ = tokio::time::timeout_at(wake_at, changed).await;
+            if tokio::time::Instant::now() >= deadline {
+                return Err(WaitTimeout { last_error });
+            }
+        }
+    }
+}
+
+impl <Data: Send + Sync, Provider: DataProvider<Data> + Send> RemoteConfig<Data, Provider> {
+    /// Implementation of [`RemoteConfig::follow_updates`] for all handle types
+    async fn follow_updates_shared<Handle: ConfigHandle<Data, Provider>>(this: Handle)
+    where Data: 'static, Provider: 'static
+    {
+        loop {
+            // Subscribe before checking, so that data replaced in between is not missed
+            let changed = this.changed.notified();
+            let mut changed = pin!(changed);
+            changed.as_mut().enable();
+
+            let entry = this.cached_response.load_full();
+            match &entry.invalidation {
+                Some(token) if token.is_invalidated() => {
+                    // Revalidation may finish in background, or fail. Either way it is retried after retry interval at most
+                    let _ = Self::load_shared(this.clone(), this.clock.now(), None).await;
+                    let _ = tokio::time::timeout(this.current_retry_interval(), changed).await;
+                },
+                Some(token) => {
+                    let mut invalidated = token.invalidated();
+                    poll_fn(|cx| match invalidated.as_mut().poll(cx) {
+                        Poll::Ready(()) => Poll::Ready(()),
+                        Poll::Pending => changed.as_mut().poll(cx)
+                    }).await;
+                },
+                // Data provider does not push updates for this data, wait until it is replaced by regular load
+                None => changed.await
+            }
+        }
+    }
+
+    /// Calls `apply` with current data immediately, and returns future that calls it with every version activated later, until it returns `false`.
+    /// Data is revalidated as soon as it becomes stale, like in [`RemoteConfig::wait_for`]
+    pub(crate) fn follow_versions<Handle: ConfigHandle<Data, Provider>>(this: Handle, mut apply: impl FnMut(&Data) -> bool + Send + 'static) -> impl Future<Output = ()> + Send
+    where Data: 'static, Provider: 'static
+    {
+        let mut last = this.cached_response.load().result.clone();
+        let follow = apply(&last.data);
+        async move {
+            if !follow {
+                return;
+            }
+            loop {
+                let data = Self::next_version(this.clone(), Some(&last)).await;
+                if !apply(&data) {
+                    return;
+                }
+                last = data.0.result.clone();
+            }
+        }
+    }
+
+    /// Waits until loaded version differs from the last one
+    async fn next_version<Handle: ConfigHandle<Data, Provider>>(this: Handle, last: Option<&Arc<DataLoadResult<Data>>>) -> CachedData<Data>
+    where Data: 'static, Provider: 'static
+    {
+        loop {
+            // Subscribe before loading, so that change made in between is not missed
+            let changed = this.changed.notified();
+            let mut changed = pin!(changed);
+            changed.as_mut().enable();
+
+            let now = this.clock.now();
+            let failed = match Self::load_shared(this.clone(), now, None).await {
+                Ok(data) if last.is_none_or(|last| !Arc::ptr_eq(last, &data.0.result)) => return data,
+                Ok(_) => false,
+                Err(_) => true
+            };
+            let _ = tokio::time::timeout(this.wake_after(now, failed), changed).await;
+        }
+    }
+}
+
+#[cfg(feature = "stream")]
+impl <Data: Send + Sync, Provider: DataProvider<Data> + Send> RemoteConfig<Data, Provider> {
+    /// Implementation of [`RemoteConfig::stream`] for all handle types
+    fn stream_shared<Handle: ConfigHandle<Data, Provider>>(this: Handle) -> impl Stream<Item = CachedData<Data>> + Send
+    where Data: 'static, Provider: 'static
+    {
+        futures_util::stream::unfold((this, None), |(this, last)| async move {
+            let data = Self::next_version(this.clone(), last.as_ref()).await;
+            let result = data.0.result.clone();
+            Some((data, (this, Some(result))))
+        })
+    }
+}
+
+/// Counts spawned revalidation task until it is finished or dropped
+struct LiveTask {
+    counter: Arc<AtomicUsize>,
+    /// Config name, if config records metrics
+    #[cfg(feature = "metrics")] name: Option<String>
+}
+
+impl LiveTask {
+    fn new<Data: Send + Sync, Provider: DataProvider<Data> + Send>(config: &RemoteConfig<Data, Provider>) -> Self {
+        let _live = config.live_tasks.fetch_add(1, Ordering::Relaxed) + 1;
+        #[cfg(feature = "metrics")]
+        let name = config.metrics.then(|| config.name.clone());
+        #[cfg(feature = "metrics")]
+        if let Some(name) = &name {
+            metrics::gauge!("remote_config_background_tasks", "config" => name.clone()).set(_live as f64);
+        }
+        Self {
+            counter: config.live_tasks.clone(),
+            #[cfg(feature = "metrics")] name
+        }
+    }
+}
+
+impl Drop for LiveTask {
+    fn drop(&mut self) {
+        let _live = self.counter.fetch_sub(1, Ordering::Relaxed) - 1;
+        #[cfg(feature = "metrics")]
+        if let Some(name) = &self.name {
+            metrics::gauge!("remote_config_background_tasks", "config" => name.clone()).set(_live as f64);
+        }
+    }
 }
 
+/// Handle to config that can be moved into revalidation task
+pub(crate) trait ConfigHandle<Data: Send + Sync, Provider: DataProvider<Data> + Send>: Deref<Target = RemoteConfig<Data, Provider>> + Clone + Send + 'static {}
+
+impl <Data: Send + Sync, Provider: DataProvider<Data> + Send> ConfigHandle<Data, Provider> for &'static RemoteConfig<Data, Provider> {}
+
 #[cfg(feature = "non_static")]
-impl <Data: Send + Sync + 'static, Provider: DataProvider<Data> + Send + 'static> NonStaticRemoteConfig<Data> for Arc<RemoteConfig<Data, Provider>> {
-    /// See [`RemoteConfig::load_with_time`] docs
-    async fn load_with_time(&self, time: SystemTime) -> LoadResult<Data> {
-        let curr = self.cached_response.load();
+impl <Data: Send + Sync + 'static, Provider: DataProvider<Data> + Send + 'static> ConfigHandle<Data, Provider> for Arc<RemoteConfig<Data, Provider>> {}
 
-        // Self is cloned and moved into spawned task, so reference validity is guaranteed
-        let self_static: &'static RemoteConfig<Data, Provider> = unsafe{&*self.as_raw()};
-        
-        if curr.valid_until < time {
-            return match self_static.revalidator.try_lock() {
-                // Revalidation is in progress
-                Err(_) => {
-                    if curr.must_revalidate {
+impl <Data: Send + Sync, Provider: DataProvider<Data> + Send> RemoteConfig<Data, Provider> {
+    /// Revalidates data with data provider, and replaces cached data with result.
+    /// Caller must hold revalidation lock.
+    async fn revalidate(&self, revalidator: &mut Revalidator<Data, Provider>) -> LoadResult<Data> {
+        let current = self.cached_response.load_full();
+        let started = Instant::now();
+        #[cfg(feature = "chaos")]
+        let result = if self.chaos.take_failure() {
+            Err(Box::new(InjectedFailure) as Box<dyn Error + Send + Sync>)
+        } else {
+            limit_load(revalidator.data_provider.revalidate_data(&current.result), self.load_timeout).await
+        };
+        #[cfg(not (feature = "chaos"))]
+        let result = limit_load(revalidator.data_provider.revalidate_data(&current.result), self.load_timeout).await;
+
+        let size = match &result {
+            Ok(Revalidation::Modified(data)) => data.metadata.size,
+            _ => None
+        };
+        self.record_fetch(size, started.elapsed(), result.is_ok());
+
+        match result {
+            Ok(revalidation) => {
+                let now = self.clock.now();
+                let mut entry = match revalidation {
+                    Revalidation::Modified(load_result) => {
+                        #[cfg(feature = "tracing")]
+                        self.audit_activation(&load_result.metadata);
+                        self.record_change(&load_result.metadata);
+                        self.record_deprecation(&load_result.metadata);
+                        if let Some(control) = &load_result.metadata.control {
+                            *self.control.lock().unwrap() = Some(control.clone());
+                        }
+                        Entry::new(load_result, self.content_hasher, now)
+                    },
+                    // Data is kept, only freshness is updated
+                    Revalidation::NotModified { must_revalidate, valid_until, invalidation, fetched_at } => {
+                        // Sunset may come closer without data change
+                        self.record_deprecation(&current.result.metadata);
+                        Entry {
+                            result: current.result.clone(),
+                            valid_until,
+                            must_revalidate,
+                            invalidation: invalidation.or_else(|| current.invalidation.clone()),
+                            content_hash: current.content_hash,
+                            fetched_at: fetched_at.unwrap_or(now)
+                        }
+                    }
+                };
+                entry.valid_until = clamp_ttl(entry.valid_until, now, self.min_ttl, self.max_ttl);
+                self.cached_response.store(Arc::new(entry));
+                self.failures.store(0, Ordering::Relaxed);
+                self.changed.notify_waiters();
+                self.forced_refresh.store(false, Ordering::Relaxed);
+                revalidator.revalidation_error = None;
+                Ok(CachedData(self.cached_response.load()))
+            },
+            Err(err) => {
+                #[cfg(feature = "tracing")] {
+                    if let Some(source) = err.source() {
+                        error!("Failed to load data for config {cfg_name}. Error: {error}", cfg_name = self.name, error = source);
+                    } else {
+                        error!("Failed to load data for config {cfg_name}. No source error provided", cfg_name = self.name)
+                    }
+                }
+                let dp_err = Arc::new(DataProviderError::new(err, self.clock.now()));
+                revalidator.revalidation_error = Some(dp_err.clone());
+                self.failures.fetch_add(1, Ordering::Relaxed);
+                Err(dp_err)
+            }
+        }
+    }
+
+    /// Implementation of [`RemoteConfig::load_with_time`] for all handle types
+    async fn load_shared<Handle: ConfigHandle<Data, Provider>>(this: Handle, time: SystemTime, profile: Option<&str>) -> LoadResult<Data>
+    where Data: 'static, Provider: 'static
+    {
+        let profile = this.profile(profile);
+        let max_wait = profile.max_revalidation_wait.or(this.max_revalidation_wait);
+        this.access.record(time);
+        let curr = this.cached_response.load();
+
+        if !this.is_stale(&curr, time) {
+            // Return valid data
+            return Ok(CachedData(curr));
+        }
+
+        let (mut guard, curr) = match this.revalidator.clone().try_lock_owned() {
+            // Revalidation can be started
+            Ok(guard) => {
+                // Revalidation could finish before lock was acquired, so data is loaded again
+                (guard, this.cached_response.load())
+            },
+            // Revalidation is in progress
+            Err(_) => {
+                return match decide(this.freshness(&curr, time, profile.stale_tolerance), Lock::Busy, time, this.current_retry_interval()) {
+                    Decision::WaitForRevalidation => {
                         // Wait for revalidation to finish
-                        let guard = self_static.revalidator.lock().await;
+                        let guard = match max_wait {
+                            Some(limit) => match tokio::time::timeout(limit, this.revalidator.lock()).await {
+                                Ok(guard) => guard,
+                                Err(_) => return this.serve_stale(curr)
+                            },
+                            None => this.revalidator.lock().await
+                        };
 
                         if let Some(ref error) = guard.revalidation_error {
                             // Revalidation failed
@@ -271,72 +1432,113 @@ impl <Data: Send + Sync + 'static, Provider: DataProvider<Data> + Send + 'static
                             Err(error.clone())
                         } else {
                             // Revalidation was successful, so we can use data without additional checks
-                            Ok(CachedData(self_static.cached_response.load()))
-                        }
-                    } else {
-                        #[cfg(feature = "tracing")] {
-                            warn!("Stale configuration data is being used for config '{cfg_name}'", cfg_name = self_static.name)
-                        }
-                        Ok(CachedData(curr))
-                    }
-                },
-                // Revalidation should be started
-                Ok(mut guard) => {
-
-                    // Quick return if it is too early to retry after error
-                    if let Some(ref err) = guard.revalidation_error {
-                        if time < err.timestamp + self_static.retry_interval {
-                            return if curr.must_revalidate {
-                                Err(err.clone())
-                            } else {
-                                Ok(CachedData(curr))
-                            }
+                            Ok(CachedData(this.cached_response.load()))
                         }
-                    }
+                    },
+                    _ => this.serve_stale(curr)
+                }
+            }
+        };
 
-                    // We clone and move self to the async closure to uphold 'static lifetime guarantee
-                    let cloned = self.clone();
-                    
-                    let handle = spawn(async move {
-                        // Guard is still valid because of cloned value
-                        return match guard.data_provider.load_data().await {
-                            Ok(load_result) => {
-                                cloned.cached_response.store(Arc::new(load_result));
-                                guard.revalidation_error = None;
-                                Ok(CachedData(cloned.cached_response.load()))
-                            },
-                            Err(err) => {
-                                #[cfg(feature = "tracing")] {
-                                    if let Some(source) = err.source() {
-                                        error!("Failed to load data for config {cfg_name}. Error: {error}", cfg_name = cloned.name, error = source);
-                                    } else {
-                                        error!("Failed to load data for config {cfg_name}. No source error provided", cfg_name = cloned.name)
-                                    }
-                                }
-                                let dp_err = Arc::new(DataProviderError::from(err));
-                                guard.revalidation_error = Some(dp_err.clone());
-                                Err(dp_err)
-                            }
-                        }
-                    });
+        let last_error = guard.revalidation_error.as_ref().map(|err| err.timestamp);
+        let (budget_exhausted, last_fetch) = this.budget_state(time);
+        #[cfg(feature = "metrics")]
+        if this.metrics {
+            metrics::gauge!("remote_config_budget_exhausted", "config" => this.name.clone()).set(if budget_exhausted { 1.0 } else { 0.0 });
+        }
+        let postponed = this.bandwidth_policy.as_ref().is_some_and(|policy| policy.is_postponed(this.link_state(), last_fetch, time));
+        let throttled = this.is_throttled(last_fetch, time);
+        let freshness = this.freshness(&curr, time, profile.stale_tolerance);
+        // Only background refreshes are deferred, data that must be revalidated is not served stale
+        let shed = !freshness.must_revalidate && this.load_shedding.as_ref().is_some_and(|policy| policy.is_deferred(curr.valid_until, time));
+        #[cfg(feature = "tracing")]
+        if shed {
+            tracing::debug!("Refresh of config {cfg_name} is deferred, process is overloaded", cfg_name = this.name);
+        }
+        let suppressed = budget_exhausted || postponed || throttled || shed || this.is_offline();
+        // Origin's retry hint replaces retry interval
+        let retry_interval = guard.revalidation_error.as_ref().and_then(|err| err.retry_after).unwrap_or_else(|| this.current_retry_interval());
+        match decide(freshness, Lock::Acquired { last_error, suppressed }, time, retry_interval) {
+            Decision::Serve => Ok(CachedData(curr)),
+            Decision::ServeStale => this.serve_stale(curr),
+            // Quick return if it is too early to retry after error
+            Decision::ReturnError => Err(guard.revalidation_error.clone().expect("decision is based on last error")),
+            Decision::WaitForRevalidation => unreachable!("revalidation lock is held by current task"),
+            Decision::Revalidate { wait } => {
+                // Handle is moved into the task, so config outlives it
+                let config = this.clone();
 
-                    if curr.must_revalidate {
-                        // Wait for validation attempt to finish
-                        handle.await.unwrap()
-                    } else {
-                        // Return immediately
-                        Ok(CachedData(curr))
-                    }
+                let (sender, receiver) = oneshot::channel();
+                let live_task = LiveTask::new(&this);
+                this.spawner.spawn_named(&this.task_name(), Box::pin(async move {
+                    let _live_task = live_task;
+                    let result = config.revalidate(&mut guard).await;
+                    // Nobody waits for result if revalidation is performed in background
+                    let _ = sender.send(result);
+                }));
+
+                if wait {
+                    // Wait for validation attempt to finish, or serve stale data if it takes too long
+                    let result = match max_wait {
+                        Some(limit) => match tokio::time::timeout(limit, receiver).await {
+                            Ok(result) => result,
+                            Err(_) => return this.serve_stale(curr)
+                        },
+                        None => receiver.await
+                    };
+                    result.unwrap_or_else(|_| Err(Arc::new(DataProviderError::new(Box::new(TaskCancelled), this.clock.now()))))
+                } else {
+                    // Return immediately
+                    Ok(CachedData(curr))
                 }
             }
         }
+    }
+}
 
-        // Return valid data
-        Ok(CachedData(curr))
+#[cfg(feature = "non_static")]
+pub trait NonStaticRemoteConfig <Data: Send + Sync>
+where Self: Send + Sync + Clone
+{
+    fn load_with_time(&self, time: SystemTime) -> impl Future<Output = LoadResult<Data>> + Send;
+    fn load(&self) -> impl Future<Output = LoadResult<Data>> + Send;
+    fn load_as(&self, profile: &str) -> impl Future<Output = LoadResult<Data>> + Send;
+    fn wait_for(&self, predicate: impl Fn(&Data) -> bool + Send, timeout: Duration) -> impl Future<Output = Result<CachedData<Data>, WaitTimeout>> + Send;
+    fn follow_updates(&self) -> impl Future<Output = ()> + Send;
+    #[cfg(feature = "stream")]
+    fn into_stream(self) -> impl Stream<Item = CachedData<Data>> + Send;
+}
+
+#[cfg(feature = "non_static")]
+impl <Data: Send + Sync + 'static, Provider: DataProvider<Data> + Send + 'static> NonStaticRemoteConfig<Data> for Arc<RemoteConfig<Data, Provider>> {
+    /// See [`RemoteConfig::load_with_time`] docs
+    async fn load_with_time(&self, time: SystemTime) -> LoadResult<Data> {
+        RemoteConfig::load_shared(self.clone(), time, None).await
     }
 
     /// See [`RemoteConfig::load_with_time`] docs
     async fn load(&self) -> LoadResult<Data> {
-        self.load_with_time(SystemTime::now()).await
+        self.load_with_time(self.clock.now()).await
+    }
+
+    /// See [`RemoteConfig::load_as`] docs
+    async fn load_as(&self, profile: &str) -> LoadResult<Data> {
+        RemoteConfig::load_shared(self.clone(), self.clock.now(), Some(profile)).await
+    }
+
+    /// See [`RemoteConfig::wait_for`] docs
+    async fn wait_for(&self, predicate: impl Fn(&Data) -> bool + Send, timeout: Duration) -> Result<CachedData<Data>, WaitTimeout> {
+        RemoteConfig::wait_for_shared(self.clone(), predicate, timeout).await
+    }
+
+    /// See [`RemoteConfig::follow_updates`] docs
+    async fn follow_updates(&self) {
+        RemoteConfig::follow_updates_shared(self.clone()).await
+    }
+
+    /// See [`RemoteConfig::stream`] docs
+    #[cfg(feature = "stream")]
+    fn into_stream(self) -> impl Stream<Item = CachedData<Data>> + Send {
+        RemoteConfig::stream_shared(self)
     }
 }
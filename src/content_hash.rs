@@ -0,0 +1,96 @@
+/// FNV-1a hash, that is stable between processes, platforms and compiler versions,
+/// unlike [`std::collections::hash_map::DefaultHasher`]
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3))
+}
+
+/// Hash of data serialized as JSON. Object keys are sorted, so hash does not depend on iteration order
+/// of maps like [`std::collections::HashMap`] (or on `preserve_order` feature of `serde_json`).
+/// # Errors
+/// If data can't be serialized as JSON (e.g. map has non-string keys)
+#[cfg(feature = "json")]
+pub(crate) fn serialized<Data: serde::Serialize>(data: &Data) -> Result<u64, serde_json::Error> {
+    let mut bytes = Vec::new();
+    write_canonical(&serde_json::to_value(data)?, &mut bytes);
+    Ok(fnv1a(&bytes))
+}
+
+/// Hash that is different on every call, for data that can't be hashed, so that it is always treated as changed
+#[cfg(feature = "json")]
+pub(crate) fn unique() -> u64 {
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    static NEXT: AtomicU64 = AtomicU64::new(0);
+    let mut bytes = std::process::id().to_le_bytes().to_vec();
+    bytes.extend(NEXT.fetch_add(1, Ordering::Relaxed).to_le_bytes());
+    fnv1a(&bytes)
+}
+
+/// Writes JSON with sorted object keys
+#[cfg(any(feature = "json", feature = "dynamodb"))]
+pub(crate) fn write_canonical(value: &serde_json::Value, bytes: &mut Vec<u8>) {
+    use serde_json::Value;
+
+    match value {
+        Value::Object(object) => {
+            let mut entries: Vec<_> = object.iter().collect();
+            entries.sort_unstable_by_key(|(key, _)| *key);
+            bytes.push(b'{');
+            for (index, (key, value)) in entries.into_iter().enumerate() {
+                if index > 0 {
+                    bytes.push(b',');
+                }
+                bytes.extend(serde_json::to_vec(key).unwrap_or_default());
+                bytes.push(b':');
+                write_canonical(value, bytes);
+            }
+            bytes.push(b'}');
+        },
+        Value::Array(items) => {
+            bytes.push(b'[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    bytes.push(b',');
+                }
+                write_canonical(item, bytes);
+            }
+            bytes.push(b']');
+        },
+        scalar => bytes.extend(serde_json::to_vec(scalar).unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::content_hash::fnv1a;
+
+    #[test]
+    fn known_values() {
+        assert_eq!(fnv1a(b""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a(b"a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(fnv1a(b"foobar"), 0x85944171f73967e8);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn map_order_does_not_matter() {
+        use std::collections::HashMap;
+        use crate::content_hash::serialized;
+
+        let first: HashMap<String, u32> = (0..32).map(|i| (i.to_string(), i)).collect();
+        let second: HashMap<String, u32> = (0..32).rev().map(|i| (i.to_string(), i)).collect();
+        assert_eq!(serialized(&first).unwrap(), serialized(&second).unwrap());
+        assert_ne!(serialized(&first).unwrap(), serialized(&HashMap::<String, u32>::new()).unwrap());
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn unserializable_data_is_not_hashed() {
+        use std::collections::HashMap;
+        use crate::content_hash::{serialized, unique};
+
+        let data: HashMap<(u32, u32), u32> = HashMap::from([((1, 2), 3)]);
+        assert!(serialized(&data).is_err());
+        assert_ne!(unique(), unique());
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::sync::{Arc, Mutex};
+use tokio::sync::OnceCell;
+use crate::config::{CachedData, DataProviderError, RemoteConfig, RemoteConfigBuilder};
+use crate::data_providers::data_provider::DataProvider;
+
+/// Caller attributes that select variant of config, e.g. tenant and locale.
+/// Data providers send them to origin (see `HttpDataProvider::with_request_context`),
+/// and [`ContextualConfig`] keeps separate cached config for every context.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct RequestContext {
+    tenant: Option<String>,
+    locale: Option<String>,
+    region: Option<String>
+}
+
+impl RequestContext {
+    /// Creates empty context, that selects default variant
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Tenant id
+    pub fn with_tenant(mut self, tenant: impl Into<String>) -> Self {
+        self.tenant = Some(tenant.into());
+        self
+    }
+
+    /// Locale as language tag, e.g. `de-CH`
+    pub fn with_locale(mut self, locale: impl Into<String>) -> Self {
+        self.locale = Some(locale.into());
+        self
+    }
+
+    /// Deployment or user region
+    pub fn with_region(mut self, region: impl Into<String>) -> Self {
+        self.region = Some(region.into());
+        self
+    }
+
+    /// Tenant id
+    pub fn tenant(&self) -> Option<&str> {
+        self.tenant.as_deref()
+    }
+
+    /// Locale
+    pub fn locale(&self) -> Option<&str> {
+        self.locale.as_deref()
+    }
+
+    /// Region
+    pub fn region(&self) -> Option<&str> {
+        self.region.as_deref()
+    }
+
+    /// Attributes that are set, named `tenant`, `locale` and `region`
+    pub fn attributes(&self) -> impl Iterator<Item = (&'static str, &str)> {
+        [("tenant", &self.tenant), ("locale", &self.locale), ("region", &self.region)].into_iter()
+            .filter_map(|(name, value)| Some((name, value.as_deref()?)))
+    }
+}
+
+type Factory<Data, Provider> = Box<dyn Fn(&RequestContext) -> RemoteConfigBuilder<Data, Provider> + Send + Sync>;
+/// Config of context, that is set once it is built
+type Slot<Data, Provider> = Arc<OnceCell<&'static RemoteConfig<Data, Provider>>>;
+
+/// Set of configs keyed by [`RequestContext`], so one instance serves correctly negotiated variant to every caller.
+/// Config of context is built with specified factory on its first load, and then it is cached and revalidated
+/// independently of other contexts, as any other [`RemoteConfig`].
+///
+/// Configs are never dropped (like static [`RemoteConfig`]), so contexts should come from bounded set,
+/// e.g. known tenants and supported locales, and not from arbitrary user input.
+/// # Examples
+/// ```ignore
+/// static CONFIG: LazyLock<ContextualConfig<Data, HttpDataProvider<Data, SerdeDataExtractor<Data>>>> = LazyLock::new(|| {
+///     ContextualConfig::new(|context| {
+///         let data_provider = HttpDataProvider::new(Client::default(), url.clone(), SerdeDataExtractor::new())
+///             .with_request_context(context.clone(), ContextPlacement::Headers);
+///         RemoteConfig::builder(data_provider)
+///     })
+/// });
+///
+/// let cfg = CONFIG.load(&RequestContext::new().with_tenant("acme").with_locale("de-CH")).await?;
+/// ```
+pub struct ContextualConfig<Data: Send + Sync + 'static, Provider: DataProvider<Data> + Send + 'static> {
+    factory: Factory<Data, Provider>,
+    configs: Mutex<HashMap<RequestContext, Slot<Data, Provider>>>
+}
+
+impl <Data: Send + Sync + 'static, Provider: DataProvider<Data> + Send + 'static> ContextualConfig<Data, Provider> {
+    /// Creates empty set, that builds configs with specified factory
+    pub fn new(factory: impl Fn(&RequestContext) -> RemoteConfigBuilder<Data, Provider> + Send + Sync + 'static) -> Self {
+        Self {
+            factory: Box::new(factory),
+            configs: Mutex::new(HashMap::new())
+        }
+    }
+
+    /// Config of context. It is built on first call, concurrent callers wait for the same build.
+    /// # Errors
+    /// If initial data load of context failed. Build is attempted again on next call.
+    pub async fn config(&self, context: &RequestContext) -> Result<&'static RemoteConfig<Data, Provider>, DataProviderError> {
+        let cell = self.configs.lock().unwrap().entry(context.clone()).or_default().clone();
+        let config = cell.get_or_try_init(|| async {
+            let config = (self.factory)(context).build().await?;
+            Ok::<_, DataProviderError>(&*Box::leak(Box::new(config)))
+        }).await?;
+        Ok(*config)
+    }
+
+    /// Loads config of context. See [`RemoteConfig::load`] docs.
+    /// # Errors
+    /// If config of context can't be built, or its load fails
+    pub async fn load(&self, context: &RequestContext) -> Result<CachedData<Data>, Arc<DataProviderError>> {
+        self.config(context).await?.load().await
+    }
+
+    /// Contexts that have built config
+    pub fn contexts(&self) -> Vec<RequestContext> {
+        self.configs.lock().unwrap().iter()
+            .filter(|(_, cell)| cell.initialized())
+            .map(|(context, _)| context.clone())
+            .collect()
+    }
+}
+
+impl <Data: Send + Sync + 'static, Provider: DataProvider<Data> + Send + 'static> Debug for ContextualConfig<Data, Provider> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ContextualConfig")
+            .field("contexts", &self.contexts())
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,95 @@
+use std::time::{Duration, SystemTime};
+
+/// Directives that let origin throttle or pause fetching of all clients, e.g. during incidents.
+///
+/// Directives are received with config data (see [`LoadMetadata::control`](crate::data_providers::data_provider::LoadMetadata::control))
+/// or applied with [`RemoteConfig::apply_control`](crate::config::RemoteConfig::apply_control), e.g. from separate control channel.
+/// With `serde` feature, directives can be deserialized from reserved document section:
+/// ```json
+/// {"__control": {"client_min_poll_interval": 300, "pause_updates_until": 1718000000, "force_refresh_now": false}}
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[non_exhaustive]
+pub struct Control {
+    /// Minimal interval between data loads. Deserialized from seconds
+    #[cfg_attr(feature = "serde", serde(rename = "client_min_poll_interval", deserialize_with = "serde_impl::seconds"))]
+    pub min_poll_interval: Option<Duration>,
+    /// Data loads are paused until this time, and cached data is served regardless of staleness.
+    /// Deserialized from unix timestamp
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "serde_impl::timestamp"))]
+    pub pause_updates_until: Option<SystemTime>,
+    /// Revalidate cached data on next load, ignoring other directives.
+    /// Has effect only when applied with [`RemoteConfig::apply_control`](crate::config::RemoteConfig::apply_control),
+    /// because data received together with it was just loaded.
+    pub force_refresh_now: bool
+}
+
+impl Control {
+    /// Checks if data loads are throttled by directives at specified time
+    pub(crate) fn is_throttled(&self, last_fetch: Option<SystemTime>, time: SystemTime) -> bool {
+        let paused = self.pause_updates_until.is_some_and(|until| time < until);
+        let too_early = match (self.min_poll_interval, last_fetch) {
+            (Some(interval), Some(last_fetch)) => time < last_fetch + interval,
+            _ => false
+        };
+        paused || too_early
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use std::time::{Duration, SystemTime};
+    use serde::{Deserialize, Deserializer};
+
+    pub(super) fn seconds<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Duration>, D::Error> {
+        Ok(Option::<u64>::deserialize(deserializer)?.map(Duration::from_secs))
+    }
+
+    pub(super) fn timestamp<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<SystemTime>, D::Error> {
+        Ok(seconds(deserializer)?.map(|elapsed| SystemTime::UNIX_EPOCH + elapsed))
+    }
+}
+
+/// Document with reserved control section, used by extractors to read directives
+#[cfg(feature = "serde")]
+#[derive(Debug, Default, serde::Deserialize)]
+pub(crate) struct ControlSection {
+    #[serde(rename = "__control")]
+    pub control: Option<Control>
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+    use crate::control::Control;
+
+    #[test]
+    fn throttling() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1000);
+        let control = Control {
+            min_poll_interval: Some(Duration::from_secs(60)),
+            ..Control::default()
+        };
+        assert!(control.is_throttled(Some(now - Duration::from_secs(30)), now));
+        assert!(!control.is_throttled(Some(now - Duration::from_secs(60)), now));
+
+        let control = Control {
+            pause_updates_until: Some(now + Duration::from_secs(1)),
+            ..Control::default()
+        };
+        assert!(control.is_throttled(None, now));
+        assert!(!control.is_throttled(None, now + Duration::from_secs(1)));
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn deserialize_section() {
+        let section: crate::control::ControlSection = serde_json::from_str(r#"{"__control": {"client_min_poll_interval": 300, "pause_updates_until": 10}, "key": "value"}"#).unwrap();
+        let control = section.control.unwrap();
+        assert_eq!(control.min_poll_interval, Some(Duration::from_secs(300)));
+        assert_eq!(control.pause_updates_until, Some(SystemTime::UNIX_EPOCH + Duration::from_secs(10)));
+        assert!(!control.force_refresh_now);
+    }
+}
@@ -0,0 +1,274 @@
+use std::env;
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+use hmac::{Hmac, Mac};
+use reqwest::header::{HeaderValue, AUTHORIZATION, CONTENT_TYPE};
+use reqwest::{StatusCode, Url};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+/// Data provider that loads configuration profiles from AWS AppConfig
+#[cfg(feature = "appconfig")]
+pub mod appconfig;
+
+/// Data provider that loads parameters from AWS Systems Manager Parameter Store
+#[cfg(feature = "ssm")]
+pub mod ssm;
+
+/// Data provider that loads secrets from AWS Secrets Manager
+#[cfg(feature = "secretsmanager")]
+pub mod secretsmanager;
+
+/// Data provider that reads items from Amazon DynamoDB
+#[cfg(feature = "dynamodb")]
+pub mod dynamodb;
+
+/// Static AWS credentials, used to sign requests with Signature Version 4
+#[derive(Clone)]
+pub struct AwsCredentials {
+    access_key_id: String,
+    secret_access_key: String,
+    session_token: Option<String>
+}
+
+impl AwsCredentials {
+    /// Creates long-term credentials
+    pub fn new(access_key_id: impl Into<String>, secret_access_key: impl Into<String>) -> Self {
+        Self {
+            access_key_id: access_key_id.into(),
+            secret_access_key: secret_access_key.into(),
+            session_token: None
+        }
+    }
+
+    /// Session token of temporary credentials
+    pub fn with_session_token(mut self, session_token: impl Into<String>) -> Self {
+        self.session_token = Some(session_token.into());
+        self
+    }
+
+    /// Reads credentials from `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY` and `AWS_SESSION_TOKEN`.
+    /// Returns `None` if key id or secret key is not set.
+    pub fn from_env() -> Option<Self> {
+        let var = |name| env::var(name).ok().filter(|value: &String| !value.is_empty());
+        Some(Self {
+            access_key_id: var("AWS_ACCESS_KEY_ID")?,
+            secret_access_key: var("AWS_SECRET_ACCESS_KEY")?,
+            session_token: var("AWS_SESSION_TOKEN")
+        })
+    }
+}
+
+impl Debug for AwsCredentials {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AwsCredentials").field("access_key_id", &self.access_key_id).finish_non_exhaustive()
+    }
+}
+
+/// AWS service returned an error
+#[derive(Debug)]
+pub struct AwsError {
+    /// Http status
+    pub status: StatusCode,
+    /// Error code (e.g. `ResourceNotFoundException`), if reported
+    pub code: Option<String>,
+    /// Error message, if reported
+    pub message: Option<String>
+}
+
+impl AwsError {
+    /// Reads error from response of JSON or REST-JSON API
+    pub(crate) async fn from_response(response: reqwest::Response) -> Self {
+        let status = response.status();
+        let header_code = response.headers().get("x-amzn-errortype")
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.split(':').next().unwrap_or(value).to_string());
+        let body: Value = response.bytes().await.ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default();
+        let field = |names: &[&str]| names.iter().find_map(|name| body[*name].as_str()).map(str::to_string);
+        Self {
+            status,
+            // JSON protocol reports code as `namespace#Code`
+            code: header_code.or_else(|| field(&["__type", "code"]).map(|code| code.rsplit('#').next().unwrap_or_default().to_string())),
+            message: field(&["message", "Message"])
+        }
+    }
+}
+
+impl Display for AwsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "AWS request failed with status {}", self.status)?;
+        if let Some(code) = &self.code {
+            write!(f, ": {code}")?;
+        }
+        if let Some(message) = &self.message {
+            write!(f, " ({message})")?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for AwsError {}
+
+/// Endpoint of AWS service in specified region, that signs every request
+#[derive(Debug)]
+pub(crate) struct AwsService {
+    pub client: reqwest::Client,
+    pub endpoint: Url,
+    pub region: String,
+    pub credentials: AwsCredentials,
+    /// Signing name of service
+    pub name: &'static str,
+    /// Content type of JSON protocol requests
+    pub json_content_type: &'static str
+}
+
+impl AwsService {
+    /// Creates service with regional endpoint `https://{prefix}.{region}.amazonaws.com`
+    pub fn new(client: reqwest::Client, prefix: &str, region: String, credentials: AwsCredentials, name: &'static str) -> Self {
+        Self {
+            client,
+            endpoint: Url::parse(&format!("https://{prefix}.{region}.amazonaws.com")).expect("valid url"),
+            region,
+            credentials,
+            name,
+            json_content_type: "application/x-amz-json-1.1"
+        }
+    }
+
+    /// Signs and sends request
+    /// # Errors
+    /// If request fails or service returns an error
+    pub async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response, Box<dyn Error + Send + Sync>> {
+        let mut request = request.build()?;
+        sign(&mut request, &self.credentials, &self.region, self.name, SystemTime::now())?;
+        let response = self.client.execute(request).await?;
+        if !response.status().is_success() {
+            return Err(AwsError::from_response(response).await.into());
+        }
+        Ok(response)
+    }
+
+    /// Calls operation of JSON protocol API (e.g. `AmazonSSM.GetParameter`)
+    /// # Errors
+    /// If request fails, service returns an error or response is not valid JSON
+    pub async fn call(&self, target: &str, body: &Value) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        let request = self.client.post(self.endpoint.clone())
+            .header(CONTENT_TYPE, self.json_content_type)
+            .header("x-amz-target", target)
+            .body(serde_json::to_vec(body)?);
+        Ok(serde_json::from_slice(&self.send(request).await?.bytes().await?)?)
+    }
+}
+
+/// Signs request with Signature Version 4.
+/// Host, `x-amz-*` and `content-type` headers are signed.
+pub(crate) fn sign(request: &mut reqwest::Request, credentials: &AwsCredentials, region: &str, service: &str, time: SystemTime) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let (date, date_time) = amz_date(time);
+    let headers = request.headers_mut();
+    headers.insert("x-amz-date", HeaderValue::from_str(&date_time)?);
+    if let Some(token) = &credentials.session_token {
+        headers.insert("x-amz-security-token", HeaderValue::from_str(token)?);
+    }
+
+    let url = request.url();
+    let host = match url.port() {
+        Some(port) => format!("{}:{port}", url.host_str().unwrap_or_default()),
+        None => url.host_str().unwrap_or_default().to_string()
+    };
+    let mut signed: Vec<(String, String)> = request.headers().iter()
+        .filter(|(name, _)| name.as_str().starts_with("x-amz-") || *name == "content-type")
+        .map(|(name, value)| Ok((name.to_string(), value.to_str()?.trim().to_string())))
+        .collect::<Result<_, reqwest::header::ToStrError>>()?;
+    signed.push(("host".to_string(), host));
+    signed.sort();
+
+    let mut query: Vec<(String, String)> = url.query_pairs().map(|(key, value)| (uri_encode(&key), uri_encode(&value))).collect();
+    query.sort();
+    let canonical_query = query.iter().map(|(key, value)| format!("{key}={value}")).collect::<Vec<_>>().join("&");
+    let canonical_headers: String = signed.iter().map(|(name, value)| format!("{name}:{value}\n")).collect();
+    let signed_headers = signed.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>().join(";");
+    let payload = request.body().and_then(|body| body.as_bytes()).unwrap_or_default();
+    let canonical_request = format!(
+        "{}\n{}\n{canonical_query}\n{canonical_headers}\n{signed_headers}\n{}",
+        request.method(), request.url().path(), hex(&Sha256::digest(payload))
+    );
+
+    let scope = format!("{date}/{region}/{service}/aws4_request");
+    let string_to_sign = format!("AWS4-HMAC-SHA256\n{date_time}\n{scope}\n{}", hex(&Sha256::digest(canonical_request.as_bytes())));
+    let key = [date.as_str(), region, service, "aws4_request"].iter()
+        .fold(format!("AWS4{}", credentials.secret_access_key).into_bytes(), |key, part| hmac(&key, part.as_bytes()));
+    let signature = hex(&hmac(&key, string_to_sign.as_bytes()));
+
+    let authorization = format!("AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}", credentials.access_key_id);
+    request.headers_mut().insert(AUTHORIZATION, HeaderValue::from_str(&authorization)?);
+    Ok(())
+}
+
+fn hmac(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::with_capacity(bytes.len() * 2), |mut out, byte| {
+        let _ = write!(out, "{byte:02x}");
+        out
+    })
+}
+
+/// Percent-encodes everything except unreserved characters, as required for canonical query string
+fn uri_encode(value: &str) -> String {
+    value.bytes().fold(String::new(), |mut out, byte| {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+            out.push(byte as char);
+        } else {
+            let _ = write!(out, "%{byte:02X}");
+        }
+        out
+    })
+}
+
+/// Date (`YYYYMMDD`) and date-time (`YYYYMMDD'T'HHMMSS'Z'`) in UTC
+fn amz_date(time: SystemTime) -> (String, String) {
+    let seconds = time.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    let (days, rest) = (seconds / 86400, seconds % 86400);
+    // Civil date from days since epoch (proleptic Gregorian calendar)
+    let z = days as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    let date = format!("{year:04}{month:02}{day:02}");
+    let date_time = format!("{date}T{:02}{:02}{:02}Z", rest / 3600, rest % 3600 / 60, rest % 60);
+    (date, date_time)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+    use crate::data_providers::aws::{amz_date, sign, AwsCredentials};
+
+    #[test]
+    fn signature_v4() {
+        // "get-vanilla" case of AWS Signature Version 4 test suite
+        let time = UNIX_EPOCH + Duration::from_secs(1440938160);
+        assert_eq!(amz_date(time), ("20150830".to_string(), "20150830T123600Z".to_string()));
+
+        let mut request = reqwest::Client::new().get("https://example.amazonaws.com/").build().unwrap();
+        let credentials = AwsCredentials::new("AKIDEXAMPLE", "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY");
+        sign(&mut request, &credentials, "us-east-1", "service", time).unwrap();
+        assert_eq!(
+            request.headers()["authorization"],
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, SignedHeaders=host;x-amz-date, Signature=5fa00fa31553b73ebf1942676e86291e8372ff2a2260956d9b8aae1d763fbf31"
+        );
+    }
+}
@@ -0,0 +1,270 @@
+use std::error::Error;
+use std::fmt::{Debug, Formatter};
+use std::marker::PhantomData;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use reqwest::header::CONTENT_TYPE;
+use reqwest::Url;
+use serde_json::{json, Value};
+use crate::data_providers::aws::{AwsCredentials, AwsService};
+use crate::data_providers::data_provider::{DataLoadResult, DataProvider, Revalidation};
+
+/// Signing name of AppConfig Data API
+const SERVICE: &str = "appconfig";
+/// Header with token for the next poll
+const NEXT_TOKEN_HEADER: &str = "next-poll-configuration-token";
+/// Header with minimal interval before the next poll
+const NEXT_POLL_INTERVAL_HEADER: &str = "next-poll-interval-in-seconds";
+/// Header with version label of configuration
+const VERSION_LABEL_HEADER: &str = "version-label";
+/// Poll interval used if server does not report it
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Configuration profile deployed to environment of AppConfig application.
+/// Identifiers can be names or ids.
+#[derive(Debug, Clone)]
+pub struct AppConfigProfile {
+    application: String,
+    environment: String,
+    profile: String
+}
+
+impl AppConfigProfile {
+    /// Creates profile identifier
+    pub fn new(application: impl Into<String>, environment: impl Into<String>, profile: impl Into<String>) -> Self {
+        Self {
+            application: application.into(),
+            environment: environment.into(),
+            profile: profile.into()
+        }
+    }
+}
+
+/// Session state of AppConfig Data API
+#[derive(Debug, Default)]
+struct Session {
+    /// Token for the next `GetLatestConfiguration` call
+    token: Option<String>,
+    /// Server must not be polled before this time
+    next_poll_at: Option<SystemTime>
+}
+
+/// This data provider loads configuration with AWS AppConfig Data API and parses it with specified function,
+/// so deployment strategies, rollbacks and validators of AppConfig apply to `RemoteConfig` too.
+///
+/// Session is started with `StartConfigurationSession`, then `GetLatestConfiguration` is polled with the token
+/// returned by the previous call. Data stays valid for the poll interval reported by server,
+/// and data is not parsed again if server reports that it did not change. Version label, if set, is reported as revision.
+/// # Examples
+/// ```
+/// # #[cfg(feature = "json")] {
+/// use std::collections::HashMap;
+/// use remote_config::data_providers::aws::AwsCredentials;
+/// use remote_config::data_providers::aws::appconfig::{AppConfigDataProvider, AppConfigProfile};
+///
+/// let credentials = AwsCredentials::from_env().unwrap_or_else(|| AwsCredentials::new("AKID", "SECRET"));
+/// let profile = AppConfigProfile::new("checkout", "production", "feature-flags");
+/// let data_provider = AppConfigDataProvider::new(reqwest::Client::default(), "eu-west-1", credentials, profile, |bytes: &[u8]| {
+///     Ok(serde_json::from_slice::<HashMap<String, bool>>(bytes)?)
+/// });
+/// # }
+/// ```
+pub struct AppConfigDataProvider<Data: Send + Sync, Parser> {
+    service: AwsService,
+    profile: AppConfigProfile,
+    min_poll_interval: Option<Duration>,
+    session: Mutex<Session>,
+    parser: Parser,
+    data_type: PhantomData<Data>
+}
+
+impl <Data, Parser> AppConfigDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&[u8]) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Creates data provider for configuration profile in specified region
+    pub fn new(client: reqwest::Client, region: impl Into<String>, credentials: AwsCredentials, profile: AppConfigProfile, parser: Parser) -> Self {
+        Self {
+            service: AwsService::new(client, "appconfigdata", region.into(), credentials, SERVICE),
+            profile,
+            min_poll_interval: None,
+            session: Mutex::new(Session::default()),
+            parser,
+            data_type: PhantomData
+        }
+    }
+
+    /// AppConfig Data API endpoint. Default is regional endpoint
+    pub fn with_endpoint(mut self, endpoint: Url) -> Self {
+        self.service.endpoint = endpoint;
+        self
+    }
+
+    /// Minimal poll interval requested for the session (at least 15 seconds).
+    /// Server-side default is used if not set.
+    pub fn with_min_poll_interval(mut self, interval: Duration) -> Self {
+        self.min_poll_interval = Some(interval);
+        self
+    }
+
+    fn url(&self, segment: &str) -> Url {
+        let mut url = self.service.endpoint.clone();
+        url.path_segments_mut().expect("endpoint is a base url").pop_if_empty().push(segment);
+        url
+    }
+
+    /// Starts new session and returns initial token
+    async fn start_session(&self) -> Result<String, Box<dyn Error + Send + Sync>> {
+        let mut body = json!({
+            "ApplicationIdentifier": self.profile.application,
+            "EnvironmentIdentifier": self.profile.environment,
+            "ConfigurationProfileIdentifier": self.profile.profile
+        });
+        if let Some(interval) = self.min_poll_interval {
+            body["RequiredMinimumPollIntervalInSeconds"] = json!(interval.as_secs().max(15));
+        }
+        let request = self.service.client.post(self.url("configurationsessions"))
+            .header(CONTENT_TYPE, "application/json")
+            .body(serde_json::to_vec(&body)?);
+        let response: Value = serde_json::from_slice(&self.service.send(request).await?.bytes().await?)?;
+        response["InitialConfigurationToken"].as_str()
+            .map(str::to_string)
+            .ok_or_else(|| "InitialConfigurationToken is missing in response".into())
+    }
+
+    /// Polls latest configuration. If current data is provided, existing session is continued.
+    async fn load(&self, current: Option<&DataLoadResult<Data>>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        let (token, next_poll_at) = {
+            let session = self.session.lock().unwrap();
+            (session.token.clone().filter(|_| current.is_some()), session.next_poll_at)
+        };
+        // Polling before interval ends is rejected by server
+        if let (Some(_), Some(next_poll_at)) = (current, next_poll_at) {
+            if SystemTime::now() < next_poll_at {
+                return Ok(Revalidation::NotModified { must_revalidate: false, valid_until: next_poll_at, invalidation: None, fetched_at: None });
+            }
+        }
+        let token = match token {
+            Some(token) => token,
+            None => self.start_session().await?
+        };
+
+        let mut url = self.url("configuration");
+        url.query_pairs_mut().append_pair("configuration_token", &token);
+        let response = match self.service.send(self.service.client.get(url)).await {
+            Ok(response) => response,
+            Err(err) => {
+                // Token may be expired or already used, so new session is started next time
+                self.session.lock().unwrap().token = None;
+                return Err(err);
+            }
+        };
+
+        let header = |name| response.headers().get(name).and_then(|value| value.to_str().ok()).map(str::to_string);
+        let interval = header(NEXT_POLL_INTERVAL_HEADER)
+            .and_then(|value| value.parse().ok())
+            .map_or(DEFAULT_POLL_INTERVAL, Duration::from_secs);
+        let valid_until = SystemTime::now() + interval;
+        let revision = header(VERSION_LABEL_HEADER);
+        *self.session.lock().unwrap() = Session {
+            token: header(NEXT_TOKEN_HEADER),
+            next_poll_at: Some(valid_until)
+        };
+
+        let bytes = response.bytes().await?;
+        // Empty body means that configuration did not change since the previous poll of this session
+        if bytes.is_empty() && current.is_some() {
+            return Ok(Revalidation::NotModified { must_revalidate: false, valid_until, invalidation: None, fetched_at: None });
+        }
+        let mut result = DataLoadResult::new((self.parser)(&bytes)?, false, valid_until);
+        result.metadata.size = Some(bytes.len() as u64);
+        result.metadata.revision = revision;
+        Ok(Revalidation::Modified(result))
+    }
+}
+
+impl <Data, Parser> DataProvider<Data> for AppConfigDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&[u8]) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Starts new session and parses latest configuration
+    /// # Errors
+    /// If request fails, AppConfig returns an error or parser returns an error
+    async fn load_data(&self) -> Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>> {
+        match self.load(None).await? {
+            Revalidation::Modified(result) => Ok(result),
+            Revalidation::NotModified { .. } => unreachable!("new session always returns configuration")
+        }
+    }
+
+    /// Polls latest configuration in existing session, but parses it only if it changed
+    async fn revalidate_data<'a>(&'a self, current: &'a DataLoadResult<Data>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        self.load(Some(current)).await
+    }
+}
+
+impl <Data: Send + Sync, Parser> Debug for AppConfigDataProvider<Data, Parser> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AppConfigDataProvider")
+            .field("endpoint", &self.service.endpoint)
+            .field("region", &self.service.region)
+            .field("profile", &self.profile)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::time::{Duration, SystemTime};
+    use mockito::Matcher;
+    use reqwest::Url;
+    use crate::data_providers::aws::AwsCredentials;
+    use crate::data_providers::aws::appconfig::{AppConfigDataProvider, AppConfigProfile};
+    use crate::data_providers::data_provider::{DataProvider, Revalidation};
+
+    #[tokio::test]
+    async fn session_tokens() {
+        let mut server = mockito::Server::new_async().await;
+        let session = server
+            .mock("POST", "/configurationsessions")
+            .match_header("authorization", Matcher::Regex(r"^AWS4-HMAC-SHA256 Credential=AKID/\d{8}/eu-west-1/appconfig/aws4_request".to_string()))
+            .match_body(Matcher::PartialJsonString(r#"{"ApplicationIdentifier": "app", "RequiredMinimumPollIntervalInSeconds": 30}"#.to_string()))
+            .with_status(201)
+            .with_body(r#"{"InitialConfigurationToken": "t1"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        for (token, next, body) in [("t1", "t2", "flag=true"), ("t2", "t3", "")] {
+            server
+                .mock("GET", "/configuration")
+                .match_query(Matcher::UrlEncoded("configuration_token".to_string(), token.to_string()))
+                .with_header("Next-Poll-Configuration-Token", next)
+                .with_header("Next-Poll-Interval-In-Seconds", "30")
+                .with_header("Version-Label", "v1")
+                .with_body(body)
+                .create_async()
+                .await;
+        }
+
+        let profile = AppConfigProfile::new("app", "prod", "flags");
+        let data_provider = AppConfigDataProvider::new(reqwest::Client::default(), "eu-west-1", AwsCredentials::new("AKID", "SECRET"), profile, |bytes: &[u8]| {
+            Ok(std::str::from_utf8(bytes)?.split_once('=').map(|(key, value)| (key.to_string(), value.to_string())).into_iter().collect::<HashMap<_, _>>())
+        }).with_endpoint(Url::parse(&server.url()).unwrap()).with_min_poll_interval(Duration::from_secs(30));
+
+        let mut current = data_provider.load_data().await.unwrap();
+        assert_eq!(current.data["flag"], "true");
+        assert_eq!(current.metadata.revision.as_deref(), Some("v1"));
+
+        // Poll interval did not pass yet, server is not called
+        assert!(matches!(data_provider.revalidate_data(&current).await.unwrap(), Revalidation::NotModified { .. }));
+
+        // Empty body means not modified
+        current.valid_until = SystemTime::now();
+        data_provider.session.lock().unwrap().next_poll_at = None;
+        let Revalidation::NotModified { valid_until, .. } = data_provider.revalidate_data(&current).await.unwrap() else {
+            panic!("Expected empty configuration to be reported as not modified");
+        };
+        assert!(valid_until > SystemTime::now() + Duration::from_secs(29));
+        assert_eq!(data_provider.session.lock().unwrap().token.as_deref(), Some("t3"));
+        session.assert_async().await;
+    }
+}
@@ -0,0 +1,297 @@
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::marker::PhantomData;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use reqwest::Url;
+use serde_json::{json, Map, Value};
+use crate::content_hash;
+use crate::data_providers::aws::{AwsCredentials, AwsService};
+use crate::data_providers::data_provider::{DataLoadResult, DataProvider, Revalidation};
+
+/// Signing name of DynamoDB API
+const SERVICE: &str = "dynamodb";
+
+/// Items to read from table. Attribute values are specified in DynamoDB JSON (e.g. `{"S": "checkout"}`)
+#[derive(Debug, Clone)]
+pub enum DynamoDbSelector {
+    /// Single item with specified primary key (e.g. `{"pk": {"S": "checkout"}, "sk": {"S": "prod"}}`)
+    Item(Map<String, Value>),
+    /// All items with specified partition key, in sort key order
+    Partition {
+        /// Name of partition key attribute
+        attribute: String,
+        /// Value of partition key
+        value: Value
+    }
+}
+
+/// Item selected by [`DynamoDbSelector::Item`] does not exist
+#[derive(Debug)]
+pub struct ItemNotFound {
+    /// Table name
+    pub table: String
+}
+
+impl Display for ItemNotFound {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "item not found in DynamoDB table {}", self.table)
+    }
+}
+
+impl Error for ItemNotFound {}
+
+/// This data provider gets item or queries partition of Amazon DynamoDB table, and assembles data from items with specified function.
+/// Parser receives items as plain JSON objects: numbers are converted to JSON numbers, sets to arrays, and binary values are kept as base64 strings.
+///
+/// Items are read again after configured TTL. If TTL attribute is configured (see [`DynamoDbDataProvider::with_ttl_attribute`]),
+/// data expires at the earliest expiration time of items instead, when it is sooner. Hash of items is reported as revision,
+/// so data is not assembled again if no item changed.
+/// # Examples
+/// ```
+/// use serde_json::json;
+/// use remote_config::data_providers::aws::AwsCredentials;
+/// use remote_config::data_providers::aws::dynamodb::{DynamoDbDataProvider, DynamoDbSelector};
+///
+/// let credentials = AwsCredentials::from_env().unwrap_or_else(|| AwsCredentials::new("AKID", "SECRET"));
+/// let selector = DynamoDbSelector::Partition { attribute: "service".to_string(), value: json!({"S": "checkout"}) };
+/// let data_provider = DynamoDbDataProvider::new(reqwest::Client::default(), "eu-west-1", credentials, "feature-flags", selector, |items: &[serde_json::Value]| {
+///     Ok(items.iter().filter(|item| item["enabled"] == true).filter_map(|item| item["flag"].as_str().map(str::to_string)).collect::<Vec<_>>())
+/// }).with_consistent_read().with_ttl_attribute("expires_at");
+/// ```
+pub struct DynamoDbDataProvider<Data: Send + Sync, Parser> {
+    service: AwsService,
+    table: String,
+    selector: DynamoDbSelector,
+    consistent_read: bool,
+    ttl: Duration,
+    ttl_attribute: Option<String>,
+    parser: Parser,
+    data_type: PhantomData<Data>
+}
+
+impl <Data, Parser> DynamoDbDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&[Value]) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Creates data provider for items of table in specified region
+    pub fn new(client: reqwest::Client, region: impl Into<String>, credentials: AwsCredentials, table: impl Into<String>, selector: DynamoDbSelector, parser: Parser) -> Self {
+        let mut service = AwsService::new(client, "dynamodb", region.into(), credentials, SERVICE);
+        service.json_content_type = "application/x-amz-json-1.0";
+        Self {
+            service,
+            table: table.into(),
+            selector,
+            consistent_read: false,
+            ttl: Duration::from_secs(5 * 60),
+            ttl_attribute: None,
+            parser,
+            data_type: PhantomData
+        }
+    }
+
+    /// DynamoDB endpoint (e.g. DynamoDB Local). Default is regional endpoint
+    pub fn with_endpoint(mut self, endpoint: Url) -> Self {
+        self.service.endpoint = endpoint;
+        self
+    }
+
+    /// Use strongly consistent reads, that cost twice as much capacity, but always return the latest written items
+    pub fn with_consistent_read(mut self) -> Self {
+        self.consistent_read = true;
+        self
+    }
+
+    /// Time after which items are read again. Default is five minutes.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Number attribute with expiration time of item in seconds since Unix epoch, as used by DynamoDB Time to Live.
+    /// Data expires no later than the earliest expiration time of items. Times in the past are ignored,
+    /// as DynamoDB deletes expired items with delay.
+    pub fn with_ttl_attribute(mut self, attribute: impl Into<String>) -> Self {
+        self.ttl_attribute = Some(attribute.into());
+        self
+    }
+
+    /// Reads items in DynamoDB JSON
+    async fn items(&self) -> Result<Vec<Map<String, Value>>, Box<dyn Error + Send + Sync>> {
+        let item = |value: &Value| value.as_object().cloned().ok_or("item is not an object");
+        match &self.selector {
+            DynamoDbSelector::Item(key) => {
+                let request = json!({"TableName": self.table, "Key": key, "ConsistentRead": self.consistent_read});
+                let response = self.service.call("DynamoDB_20120810.GetItem", &request).await?;
+                match response.get("Item") {
+                    Some(value) => Ok(vec![item(value)?]),
+                    None => Err(ItemNotFound { table: self.table.clone() }.into())
+                }
+            },
+            DynamoDbSelector::Partition { attribute, value } => {
+                let mut items = Vec::new();
+                let mut start_key: Option<Value> = None;
+                loop {
+                    let mut request = json!({
+                        "TableName": self.table,
+                        "KeyConditionExpression": "#pk = :pk",
+                        "ExpressionAttributeNames": {"#pk": attribute},
+                        "ExpressionAttributeValues": {":pk": value},
+                        "ConsistentRead": self.consistent_read
+                    });
+                    if let Some(key) = start_key {
+                        request["ExclusiveStartKey"] = key;
+                    }
+                    let response = self.service.call("DynamoDB_20120810.Query", &request).await?;
+                    for value in response["Items"].as_array().into_iter().flatten() {
+                        items.push(item(value)?);
+                    }
+                    start_key = response.get("LastEvaluatedKey").cloned();
+                    if start_key.is_none() {
+                        break;
+                    }
+                }
+                Ok(items)
+            }
+        }
+    }
+
+    /// Earliest future expiration time of items
+    fn expiration(&self, items: &[Value], now: SystemTime) -> Option<SystemTime> {
+        let attribute = self.ttl_attribute.as_deref()?;
+        items.iter()
+            .filter_map(|item| item[attribute].as_f64())
+            .filter(|seconds| *seconds >= 0.0)
+            .map(|seconds| UNIX_EPOCH + Duration::from_secs_f64(seconds))
+            .filter(|expiration| *expiration > now)
+            .min()
+    }
+
+    async fn load(&self, current_revision: Option<&str>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        let items = Value::Array(self.items().await?.into_iter().map(Value::Object).collect());
+        let mut bytes = Vec::new();
+        content_hash::write_canonical(&items, &mut bytes);
+        let revision = format!("{:016x}", content_hash::fnv1a(&bytes));
+
+        let items: Vec<Value> = items.as_array().into_iter().flatten().map(|item| plain(&json!({"M": item}))).collect();
+        let now = SystemTime::now();
+        let valid_until = self.expiration(&items, now).map_or(now + self.ttl, |expiration| expiration.min(now + self.ttl));
+        if current_revision == Some(revision.as_str()) {
+            return Ok(Revalidation::NotModified { must_revalidate: false, valid_until, invalidation: None, fetched_at: None });
+        }
+
+        let mut result = DataLoadResult::new((self.parser)(&items)?, false, valid_until);
+        result.metadata.size = Some(bytes.len() as u64);
+        result.metadata.revision = Some(revision);
+        Ok(Revalidation::Modified(result))
+    }
+}
+
+/// Converts DynamoDB attribute value to plain JSON value
+fn plain(value: &Value) -> Value {
+    let Some((kind, inner)) = value.as_object().and_then(|value| value.iter().next()) else {
+        return Value::Null;
+    };
+    let number = |number: &Value| number.as_str().and_then(|number| serde_json::from_str(number).ok()).unwrap_or_else(|| number.clone());
+    match kind.as_str() {
+        "N" => number(inner),
+        "NS" => Value::Array(inner.as_array().into_iter().flatten().map(number).collect()),
+        "M" => Value::Object(inner.as_object().into_iter().flatten().map(|(key, value)| (key.clone(), plain(value))).collect()),
+        "L" => Value::Array(inner.as_array().into_iter().flatten().map(plain).collect()),
+        "NULL" => Value::Null,
+        // Strings, booleans, binary values and string or binary sets
+        _ => inner.clone()
+    }
+}
+
+impl <Data, Parser> DataProvider<Data> for DynamoDbDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&[Value]) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Reads items and assembles them into data
+    /// # Errors
+    /// If request fails, DynamoDB returns an error (e.g. `ResourceNotFoundException`), item doesn't exist or parser returns an error
+    async fn load_data(&self) -> Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>> {
+        Ok(self.load(None).await?.into_loaded())
+    }
+
+    /// Reads items, but assembles data only if any item changed
+    async fn revalidate_data<'a>(&'a self, current: &'a DataLoadResult<Data>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        self.load(current.metadata.revision.as_deref()).await
+    }
+}
+
+impl <Data: Send + Sync, Parser> Debug for DynamoDbDataProvider<Data, Parser> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DynamoDbDataProvider")
+            .field("endpoint", &self.service.endpoint)
+            .field("region", &self.service.region)
+            .field("table", &self.table)
+            .field("selector", &self.selector)
+            .field("consistent_read", &self.consistent_read)
+            .field("ttl", &self.ttl)
+            .field("ttl_attribute", &self.ttl_attribute)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+    use mockito::Matcher;
+    use reqwest::Url;
+    use serde_json::{json, Value};
+    use crate::data_providers::aws::AwsCredentials;
+    use crate::data_providers::aws::dynamodb::{DynamoDbDataProvider, DynamoDbSelector};
+    use crate::data_providers::data_provider::{DataProvider, Revalidation};
+
+    #[tokio::test]
+    async fn partition_query() {
+        let expires = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() + 60;
+        let mut server = mockito::Server::new_async().await;
+        let pages = [
+            (json!({"TableName": "flags", "ConsistentRead": true, "ExpressionAttributeValues": {":pk": {"S": "checkout"}}}),
+             json!({"Items": [{"service": {"S": "checkout"}, "flag": {"S": "new-cart"}, "rollout": {"N": "0.25"}, "regions": {"SS": ["eu"]}}], "LastEvaluatedKey": {"service": {"S": "checkout"}, "flag": {"S": "new-cart"}}})),
+            (json!({"ExclusiveStartKey": {"flag": {"S": "new-cart"}}}),
+             json!({"Items": [{"service": {"S": "checkout"}, "flag": {"S": "wallet"}, "limits": {"M": {"max": {"N": "10"}}}, "expires_at": {"N": expires.to_string()}}]}))
+        ];
+        // Second page request matches both mocks, the last created one is used
+        for (request, response) in pages {
+            server
+                .mock("POST", "/")
+                .match_header("x-amz-target", "DynamoDB_20120810.Query")
+                .match_header("content-type", "application/x-amz-json-1.0")
+                .match_body(Matcher::PartialJson(request))
+                .with_body(response.to_string())
+                .create_async()
+                .await;
+        }
+
+        let selector = DynamoDbSelector::Partition { attribute: "service".to_string(), value: json!({"S": "checkout"}) };
+        let data_provider = DynamoDbDataProvider::new(reqwest::Client::default(), "eu-west-1", AwsCredentials::new("AKID", "SECRET"), "flags", selector, |items: &[Value]| Ok(items.to_vec()))
+            .with_endpoint(Url::parse(&server.url()).unwrap())
+            .with_consistent_read()
+            .with_ttl_attribute("expires_at");
+
+        let result = data_provider.load_data().await.unwrap();
+        assert_eq!(result.data[0], json!({"service": "checkout", "flag": "new-cart", "rollout": 0.25, "regions": ["eu"]}));
+        assert_eq!(result.data[1]["limits"], json!({"max": 10}));
+        assert_eq!(result.valid_until, UNIX_EPOCH + Duration::from_secs(expires));
+        // Items did not change
+        assert!(matches!(data_provider.revalidate_data(&result).await.unwrap(), Revalidation::NotModified { .. }));
+    }
+
+    #[tokio::test]
+    async fn missing_item() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/")
+            .match_header("x-amz-target", "DynamoDB_20120810.GetItem")
+            .match_body(Matcher::PartialJson(json!({"TableName": "config", "Key": {"pk": {"S": "app"}}})))
+            .with_body("{}")
+            .create_async()
+            .await;
+
+        let key = json!({"pk": {"S": "app"}}).as_object().unwrap().clone();
+        let data_provider = DynamoDbDataProvider::new(reqwest::Client::default(), "eu-west-1", AwsCredentials::new("AKID", "SECRET"), "config", DynamoDbSelector::Item(key), |items: &[Value]| Ok(items.len()))
+            .with_endpoint(Url::parse(&server.url()).unwrap());
+        assert_eq!(data_provider.load_data().await.unwrap_err().to_string(), "item not found in DynamoDB table config");
+    }
+}
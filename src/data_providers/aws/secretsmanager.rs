@@ -0,0 +1,188 @@
+use std::error::Error;
+use std::fmt::{Debug, Formatter};
+use std::marker::PhantomData;
+use std::time::{Duration, SystemTime};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use reqwest::Url;
+use serde_json::json;
+use crate::data_providers::aws::{AwsCredentials, AwsService};
+use crate::data_providers::data_provider::{DataLoadResult, DataProvider, Revalidation};
+
+/// Signing name of Secrets Manager API
+const SERVICE: &str = "secretsmanager";
+
+/// Staging label of secret version
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VersionStage {
+    /// Current version (`AWSCURRENT`)
+    Current,
+    /// Version that is being rotated in (`AWSPENDING`)
+    Pending,
+    /// Previous version (`AWSPREVIOUS`)
+    Previous,
+    /// Custom staging label
+    Custom(String)
+}
+
+impl VersionStage {
+    fn label(&self) -> &str {
+        match self {
+            VersionStage::Current => "AWSCURRENT",
+            VersionStage::Pending => "AWSPENDING",
+            VersionStage::Previous => "AWSPREVIOUS",
+            VersionStage::Custom(label) => label
+        }
+    }
+}
+
+/// This data provider loads secret value from AWS Secrets Manager and parses it with specified function.
+/// Binary secrets are base64-decoded before parsing.
+///
+/// Version id is reported as revision, so secret is parsed again only after it is rotated.
+/// Secret is checked again after configured TTL, so short TTL makes rotation visible sooner.
+/// # Examples
+/// ```
+/// # #[cfg(feature = "json")] {
+/// use std::collections::HashMap;
+/// use std::time::Duration;
+/// use remote_config::data_providers::aws::AwsCredentials;
+/// use remote_config::data_providers::aws::secretsmanager::SecretsManagerDataProvider;
+///
+/// let credentials = AwsCredentials::from_env().unwrap_or_else(|| AwsCredentials::new("AKID", "SECRET"));
+/// let data_provider = SecretsManagerDataProvider::new(reqwest::Client::default(), "eu-west-1", credentials, "prod/checkout/db", |bytes: &[u8]| {
+///     Ok(serde_json::from_slice::<HashMap<String, String>>(bytes)?)
+/// }).with_ttl(Duration::from_secs(60));
+/// # }
+/// ```
+pub struct SecretsManagerDataProvider<Data: Send + Sync, Parser> {
+    service: AwsService,
+    secret_id: String,
+    version_stage: VersionStage,
+    ttl: Duration,
+    parser: Parser,
+    data_type: PhantomData<Data>
+}
+
+impl <Data, Parser> SecretsManagerDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&[u8]) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Creates data provider for secret in specified region. Secret id can be name or ARN.
+    pub fn new(client: reqwest::Client, region: impl Into<String>, credentials: AwsCredentials, secret_id: impl Into<String>, parser: Parser) -> Self {
+        Self {
+            service: AwsService::new(client, "secretsmanager", region.into(), credentials, SERVICE),
+            secret_id: secret_id.into(),
+            version_stage: VersionStage::Current,
+            ttl: Duration::from_secs(5 * 60),
+            parser,
+            data_type: PhantomData
+        }
+    }
+
+    /// Secrets Manager endpoint. Default is regional endpoint
+    pub fn with_endpoint(mut self, endpoint: Url) -> Self {
+        self.service.endpoint = endpoint;
+        self
+    }
+
+    /// Version stage to load. Default is [`VersionStage::Current`].
+    pub fn with_version_stage(mut self, version_stage: VersionStage) -> Self {
+        self.version_stage = version_stage;
+        self
+    }
+
+    /// Time after which secret is checked again. Default is five minutes.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    async fn load(&self, current_revision: Option<&str>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        let response = self.service.call("secretsmanager.GetSecretValue", &json!({
+            "SecretId": self.secret_id,
+            "VersionStage": self.version_stage.label()
+        })).await?;
+        let revision = response["VersionId"].as_str().map(str::to_string);
+        let valid_until = SystemTime::now() + self.ttl;
+        if revision.is_some() && current_revision == revision.as_deref() {
+            return Ok(Revalidation::NotModified { must_revalidate: false, valid_until, invalidation: None, fetched_at: None });
+        }
+
+        let bytes = match (response["SecretString"].as_str(), response["SecretBinary"].as_str()) {
+            (Some(secret), _) => secret.as_bytes().to_vec(),
+            (None, Some(secret)) => STANDARD.decode(secret)?,
+            (None, None) => return Err("secret value is missing in response".into())
+        };
+        let mut result = DataLoadResult::new((self.parser)(&bytes)?, false, valid_until);
+        result.metadata.size = Some(bytes.len() as u64);
+        result.metadata.revision = revision;
+        Ok(Revalidation::Modified(result))
+    }
+}
+
+impl <Data, Parser> DataProvider<Data> for SecretsManagerDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&[u8]) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Loads and parses secret value
+    /// # Errors
+    /// If request fails, Secrets Manager returns an error (e.g. `ResourceNotFoundException`) or parser returns an error
+    async fn load_data(&self) -> Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>> {
+        Ok(self.load(None).await?.into_loaded())
+    }
+
+    /// Loads secret value, but parses it only if version id changed
+    async fn revalidate_data<'a>(&'a self, current: &'a DataLoadResult<Data>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        self.load(current.metadata.revision.as_deref()).await
+    }
+}
+
+impl <Data: Send + Sync, Parser> Debug for SecretsManagerDataProvider<Data, Parser> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SecretsManagerDataProvider")
+            .field("endpoint", &self.service.endpoint)
+            .field("region", &self.service.region)
+            .field("secret_id", &self.secret_id)
+            .field("version_stage", &self.version_stage)
+            .field("ttl", &self.ttl)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mockito::{Matcher, Mock, ServerGuard};
+    use reqwest::Url;
+    use crate::data_providers::aws::AwsCredentials;
+    use crate::data_providers::aws::secretsmanager::{SecretsManagerDataProvider, VersionStage};
+    use crate::data_providers::data_provider::{DataProvider, Revalidation};
+
+    fn secret(server: &mut ServerGuard, version: &str, value: &str) -> Mock {
+        server
+            .mock("POST", "/")
+            .match_header("x-amz-target", "secretsmanager.GetSecretValue")
+            .match_body(Matcher::PartialJsonString(r#"{"SecretId": "db", "VersionStage": "AWSPENDING"}"#.to_string()))
+            .with_body(format!(r#"{{"Name": "db", "VersionId": "{version}", "SecretBinary": "{value}", "VersionStages": ["AWSPENDING"]}}"#))
+    }
+
+    #[tokio::test]
+    async fn rotation() {
+        let mut server = mockito::Server::new_async().await;
+        let data_provider = SecretsManagerDataProvider::new(reqwest::Client::default(), "eu-west-1", AwsCredentials::new("AKID", "SECRET"), "db", |bytes: &[u8]| {
+            Ok(String::from_utf8(bytes.to_vec())?)
+        }).with_endpoint(Url::parse(&server.url()).unwrap()).with_version_stage(VersionStage::Pending);
+
+        let first = secret(&mut server, "v1", "aHVudGVyMg==").create_async().await;
+        let result = data_provider.load_data().await.unwrap();
+        assert_eq!(result.data, "hunter2");
+        assert_eq!(result.metadata.revision.as_deref(), Some("v1"));
+        assert!(matches!(data_provider.revalidate_data(&result).await.unwrap(), Revalidation::NotModified { .. }));
+        first.remove_async().await;
+
+        // Secret was rotated
+        secret(&mut server, "v2", "aHVudGVyMw==").create_async().await;
+        let Revalidation::Modified(result) = data_provider.revalidate_data(&result).await.unwrap() else {
+            panic!("Expected rotated secret to be loaded");
+        };
+        assert_eq!(result.data, "hunter3");
+    }
+}
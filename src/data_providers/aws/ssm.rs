@@ -0,0 +1,207 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::{Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::time::{Duration, SystemTime};
+use reqwest::Url;
+use serde_json::{json, Value};
+use crate::data_providers::aws::{AwsCredentials, AwsService};
+use crate::data_providers::data_provider::{DataLoadResult, DataProvider, Revalidation};
+
+/// Signing name of Systems Manager API
+const SERVICE: &str = "ssm";
+
+/// Parameters loaded by [`SsmDataProvider`]: parameter names mapped to (decrypted) values
+pub type SsmParameters = BTreeMap<String, String>;
+
+/// Parameter or hierarchy of parameters in Parameter Store
+#[derive(Debug, Clone)]
+pub enum SsmSelector {
+    /// Single parameter. It is reported under its full name
+    Parameter(String),
+    /// All parameters under path, recursively. They are reported under names relative to path
+    /// (e.g. `db/host` for `/app/prod/db/host` under `/app/prod`)
+    Path(String)
+}
+
+/// This data provider reads parameter or path hierarchy from AWS Systems Manager Parameter Store,
+/// and assembles it into data with specified function. `SecureString` values are decrypted.
+///
+/// Parameters are read again after configured TTL. Revision is derived from parameter versions,
+/// so data is not assembled again if no parameter changed.
+/// # Examples
+/// ```
+/// use remote_config::data_providers::aws::AwsCredentials;
+/// use remote_config::data_providers::aws::ssm::{SsmDataProvider, SsmParameters, SsmSelector};
+///
+/// struct Database {
+///     host: String,
+///     password: String
+/// }
+///
+/// let credentials = AwsCredentials::from_env().unwrap_or_else(|| AwsCredentials::new("AKID", "SECRET"));
+/// let selector = SsmSelector::Path("/checkout/prod/db".to_string());
+/// let data_provider = SsmDataProvider::new(reqwest::Client::default(), "eu-west-1", credentials, selector, |parameters: &SsmParameters| {
+///     let get = |name: &str| parameters.get(name).cloned().ok_or_else(|| format!("parameter '{name}' is missing"));
+///     Ok(Database { host: get("host")?, password: get("password")? })
+/// });
+/// ```
+pub struct SsmDataProvider<Data: Send + Sync, Parser> {
+    service: AwsService,
+    selector: SsmSelector,
+    ttl: Duration,
+    parser: Parser,
+    data_type: PhantomData<Data>
+}
+
+impl <Data, Parser> SsmDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&SsmParameters) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Creates data provider for parameters in specified region
+    pub fn new(client: reqwest::Client, region: impl Into<String>, credentials: AwsCredentials, selector: SsmSelector, parser: Parser) -> Self {
+        Self {
+            service: AwsService::new(client, "ssm", region.into(), credentials, SERVICE),
+            selector,
+            ttl: Duration::from_secs(5 * 60),
+            parser,
+            data_type: PhantomData
+        }
+    }
+
+    /// Systems Manager endpoint. Default is regional endpoint
+    pub fn with_endpoint(mut self, endpoint: Url) -> Self {
+        self.service.endpoint = endpoint;
+        self
+    }
+
+    /// Time after which parameters are read again. Default is five minutes.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Reads parameters with their versions
+    async fn parameters(&self) -> Result<Vec<(String, String, u64)>, Box<dyn Error + Send + Sync>> {
+        let parameter = |value: &Value| -> Result<(String, String, u64), Box<dyn Error + Send + Sync>> {
+            let name = value["Name"].as_str().ok_or("parameter name is missing in response")?;
+            let value_field = value["Value"].as_str().ok_or("parameter value is missing in response")?;
+            Ok((name.to_string(), value_field.to_string(), value["Version"].as_u64().unwrap_or_default()))
+        };
+        match &self.selector {
+            SsmSelector::Parameter(name) => {
+                let response = self.service.call("AmazonSSM.GetParameter", &json!({"Name": name, "WithDecryption": true})).await?;
+                Ok(vec![parameter(&response["Parameter"])?])
+            },
+            SsmSelector::Path(path) => {
+                let prefix = format!("{}/", path.trim_end_matches('/'));
+                let mut parameters = Vec::new();
+                let mut next_token: Option<String> = None;
+                loop {
+                    let mut request = json!({"Path": path, "Recursive": true, "WithDecryption": true});
+                    if let Some(token) = &next_token {
+                        request["NextToken"] = json!(token);
+                    }
+                    let response = self.service.call("AmazonSSM.GetParametersByPath", &request).await?;
+                    for value in response["Parameters"].as_array().into_iter().flatten() {
+                        let (name, value, version) = parameter(value)?;
+                        let name = name.strip_prefix(&prefix).map(str::to_string).unwrap_or(name);
+                        parameters.push((name, value, version));
+                    }
+                    next_token = response["NextToken"].as_str().map(str::to_string);
+                    if next_token.is_none() {
+                        break;
+                    }
+                }
+                Ok(parameters)
+            }
+        }
+    }
+
+    async fn load(&self, current_revision: Option<&str>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        let mut parameters = self.parameters().await?;
+        parameters.sort();
+
+        let mut hasher = DefaultHasher::new();
+        parameters.iter().for_each(|(name, _, version)| (name, version).hash(&mut hasher));
+        let revision = format!("{:016x}", hasher.finish());
+        let valid_until = SystemTime::now() + self.ttl;
+        if current_revision == Some(revision.as_str()) {
+            return Ok(Revalidation::NotModified { must_revalidate: false, valid_until, invalidation: None, fetched_at: None });
+        }
+
+        let size = parameters.iter().map(|(name, value, _)| name.len() + value.len()).sum::<usize>();
+        let parameters: SsmParameters = parameters.into_iter().map(|(name, value, _)| (name, value)).collect();
+        let mut result = DataLoadResult::new((self.parser)(&parameters)?, false, valid_until);
+        result.metadata.size = Some(size as u64);
+        result.metadata.revision = Some(revision);
+        Ok(Revalidation::Modified(result))
+    }
+}
+
+impl <Data, Parser> DataProvider<Data> for SsmDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&SsmParameters) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Reads parameters and assembles them into data
+    /// # Errors
+    /// If request fails, Parameter Store returns an error (e.g. `ParameterNotFound`) or parser returns an error
+    async fn load_data(&self) -> Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>> {
+        Ok(self.load(None).await?.into_loaded())
+    }
+
+    /// Reads parameters, but assembles data only if any parameter version changed
+    async fn revalidate_data<'a>(&'a self, current: &'a DataLoadResult<Data>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        self.load(current.metadata.revision.as_deref()).await
+    }
+}
+
+impl <Data: Send + Sync, Parser> Debug for SsmDataProvider<Data, Parser> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SsmDataProvider")
+            .field("endpoint", &self.service.endpoint)
+            .field("region", &self.service.region)
+            .field("selector", &self.selector)
+            .field("ttl", &self.ttl)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mockito::Matcher;
+    use reqwest::Url;
+    use crate::data_providers::aws::AwsCredentials;
+    use crate::data_providers::aws::ssm::{SsmDataProvider, SsmParameters, SsmSelector};
+    use crate::data_providers::data_provider::{DataProvider, Revalidation};
+
+    #[tokio::test]
+    async fn path_hierarchy() {
+        let mut server = mockito::Server::new_async().await;
+        let pages = [
+            (r#"{"Path": "/app/prod", "Recursive": true, "WithDecryption": true}"#, r#"{"Parameters": [{"Name": "/app/prod/db/host", "Value": "db.local", "Version": 3}], "NextToken": "page-2"}"#),
+            (r#"{"NextToken": "page-2"}"#, r#"{"Parameters": [{"Name": "/app/prod/db/password", "Type": "SecureString", "Value": "hunter2", "Version": 1}]}"#)
+        ];
+        // Second page request matches both mocks, the last created one is used
+        for (request, response) in pages {
+            server
+                .mock("POST", "/")
+                .match_header("x-amz-target", "AmazonSSM.GetParametersByPath")
+                .match_header("content-type", "application/x-amz-json-1.1")
+                .match_body(Matcher::PartialJsonString(request.to_string()))
+                .with_body(response)
+                .create_async()
+                .await;
+        }
+
+        let selector = SsmSelector::Path("/app/prod".to_string());
+        let data_provider = SsmDataProvider::new(reqwest::Client::default(), "eu-west-1", AwsCredentials::new("AKID", "SECRET"), selector, |parameters: &SsmParameters| Ok(parameters.clone()))
+            .with_endpoint(Url::parse(&server.url()).unwrap());
+
+        let result = data_provider.load_data().await.unwrap();
+        assert_eq!(result.data["db/host"], "db.local");
+        assert_eq!(result.data["db/password"], "hunter2");
+        // Versions did not change
+        assert!(matches!(data_provider.revalidate_data(&result).await.unwrap(), Revalidation::NotModified { .. }));
+    }
+}
@@ -0,0 +1,435 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::marker::PhantomData;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use hmac::{Hmac, Mac};
+use reqwest::header::{HeaderValue, ACCEPT, AUTHORIZATION, ETAG, IF_NONE_MATCH};
+use reqwest::{Response, StatusCode, Url};
+use serde::Deserialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use crate::data_providers::data_provider::{DataLoadResult, DataProvider, Revalidation};
+
+/// Version of App Configuration data plane API
+const API_VERSION: &str = "1.0";
+/// Header with sync tokens, that is returned by server and sent back by client
+const SYNC_TOKEN_HEADER: &str = "sync-token";
+/// Media type of key-value list
+const KV_SET_MEDIA_TYPE: &str = "application/vnd.microsoft.appconfig.kvset+json, application/problem+json";
+
+/// Key-value loaded from Azure App Configuration
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[non_exhaustive]
+pub struct AzureSetting {
+    /// Key
+    pub key: String,
+    /// Label, `None` for key-values without label
+    pub label: Option<String>,
+    /// Value
+    pub value: Option<String>,
+    /// Content type of value (e.g. `application/json`)
+    pub content_type: Option<String>
+}
+
+/// Credential used to authenticate requests to App Configuration store
+#[derive(Clone)]
+pub enum AzureCredential {
+    /// Access key of the store. Requests are signed with HMAC-SHA256
+    AccessKey {
+        /// Access key id (`Id` in connection string)
+        id: String,
+        /// Base64 encoded access key secret (`Secret` in connection string)
+        secret: String
+    },
+    /// Microsoft Entra ID access token for `https://azconfig.io` audience
+    BearerToken(String)
+}
+
+impl Debug for AzureCredential {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AzureCredential::AccessKey { id, .. } => f.debug_struct("AccessKey").field("id", id).finish_non_exhaustive(),
+            AzureCredential::BearerToken(_) => f.debug_tuple("BearerToken").finish_non_exhaustive()
+        }
+    }
+}
+
+/// Connection string is malformed
+#[derive(Debug)]
+pub struct InvalidConnectionString;
+
+impl Display for InvalidConnectionString {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "connection string must contain valid Endpoint, Id and Secret")
+    }
+}
+
+impl Error for InvalidConnectionString {}
+
+/// App Configuration returned an error
+#[derive(Debug)]
+pub struct AzureError {
+    /// Http status
+    pub status: StatusCode,
+    /// Problem title or detail, if reported
+    pub message: Option<String>
+}
+
+impl Display for AzureError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "App Configuration request failed with status {}", self.status)?;
+        if let Some(message) = &self.message {
+            write!(f, " ({message})")?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for AzureError {}
+
+/// Pages of currently loaded key-values
+#[derive(Debug, Default)]
+struct Pages {
+    /// Revision of loaded data
+    revision: Option<String>,
+    /// Url and ETag of every page
+    etags: Vec<(Url, String)>
+}
+
+/// This data provider loads key-values from Azure App Configuration store and assembles them into data with specified function.
+///
+/// Key-values are selected with key and label filters. ETag of every page is remembered, and data is revalidated with
+/// conditional requests, so it is not assembled again if no page changed. Sync tokens returned by the store are sent
+/// back with following requests, so data is never older than previously observed one. Tokens received from
+/// change notifications (e.g. Event Grid) can be added with [`AzureAppConfigProvider::add_sync_token`].
+/// # Examples
+/// ```
+/// use std::collections::HashMap;
+/// use remote_config::data_providers::azure::{AzureAppConfigProvider, AzureSetting};
+///
+/// let connection_string = "Endpoint=https://checkout.azconfig.io;Id=key-id;Secret=c2VjcmV0";
+/// let data_provider = AzureAppConfigProvider::from_connection_string(reqwest::Client::default(), connection_string, |settings: &[AzureSetting]| {
+///     Ok(settings.iter().map(|setting| (setting.key.clone(), setting.value.clone().unwrap_or_default())).collect::<HashMap<_, _>>())
+/// }).unwrap().with_key_filter("checkout:*").with_label_filter("production");
+/// ```
+pub struct AzureAppConfigProvider<Data: Send + Sync, Parser> {
+    client: reqwest::Client,
+    endpoint: Url,
+    credential: AzureCredential,
+    key_filter: Option<String>,
+    label_filter: Option<String>,
+    max_age: Duration,
+    pages: Mutex<Pages>,
+    /// Latest sync token value and sequence number by token id
+    sync_tokens: Mutex<HashMap<String, (String, u64)>>,
+    parser: Parser,
+    data_type: PhantomData<Data>
+}
+
+impl <Data, Parser> AzureAppConfigProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&[AzureSetting]) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Creates data provider for store at specified endpoint (e.g. `https://{store}.azconfig.io`)
+    pub fn new(client: reqwest::Client, endpoint: Url, credential: AzureCredential, parser: Parser) -> Self {
+        Self {
+            client,
+            endpoint,
+            credential,
+            key_filter: None,
+            label_filter: None,
+            max_age: Duration::from_secs(30),
+            pages: Mutex::new(Pages::default()),
+            sync_tokens: Mutex::new(HashMap::new()),
+            parser,
+            data_type: PhantomData
+        }
+    }
+
+    /// Creates data provider from connection string of access key (`Endpoint=...;Id=...;Secret=...`)
+    /// # Errors
+    /// If connection string is malformed
+    pub fn from_connection_string(client: reqwest::Client, connection_string: &str, parser: Parser) -> Result<Self, InvalidConnectionString> {
+        let fields: HashMap<&str, &str> = connection_string.split(';')
+            .filter_map(|field| field.split_once('='))
+            .collect();
+        let endpoint = fields.get("Endpoint").and_then(|endpoint| Url::parse(endpoint).ok()).ok_or(InvalidConnectionString)?;
+        let credential = AzureCredential::AccessKey {
+            id: fields.get("Id").ok_or(InvalidConnectionString)?.to_string(),
+            secret: fields.get("Secret").ok_or(InvalidConnectionString)?.to_string()
+        };
+        Ok(Self::new(client, endpoint, credential, parser))
+    }
+
+    /// Key filter, e.g. `app:*` for all keys that start with `app:`. By default, all keys are loaded.
+    pub fn with_key_filter(mut self, filter: impl Into<String>) -> Self {
+        self.key_filter = Some(filter.into());
+        self
+    }
+
+    /// Label filter. Use `\0` to select key-values without label. By default, key-values without label are loaded.
+    pub fn with_label_filter(mut self, filter: impl Into<String>) -> Self {
+        self.label_filter = Some(filter.into());
+        self
+    }
+
+    /// Time after which store is checked for changes. Default is 30 seconds.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Adds sync token (value of `Sync-Token` header or `syncToken` of change notification).
+    /// Following requests are guaranteed to observe changes up to this token.
+    pub fn add_sync_token(&self, token: &str) {
+        let mut sync_tokens = self.sync_tokens.lock().unwrap();
+        for token in token.split(',') {
+            let mut parts = token.trim().split(';');
+            let Some((id, value)) = parts.next().and_then(|part| part.split_once('=')) else {
+                continue;
+            };
+            let sequence = parts.find_map(|part| part.strip_prefix("sn=")).and_then(|sn| sn.parse().ok()).unwrap_or_default();
+            let entry = sync_tokens.entry(id.to_string()).or_insert_with(|| (value.to_string(), sequence));
+            if sequence >= entry.1 {
+                *entry = (value.to_string(), sequence);
+            }
+        }
+    }
+
+    fn first_page(&self) -> Url {
+        let mut url = self.endpoint.clone();
+        url.path_segments_mut().expect("endpoint is a base url").pop_if_empty().push("kv");
+        {
+            let mut query = url.query_pairs_mut();
+            if let Some(filter) = &self.key_filter {
+                query.append_pair("key", filter);
+            }
+            if let Some(filter) = &self.label_filter {
+                query.append_pair("label", filter);
+            }
+            query.append_pair("api-version", API_VERSION);
+        }
+        url
+    }
+
+    /// Authenticates and sends GET request
+    async fn get(&self, url: Url, etag: Option<&str>) -> Result<Response, Box<dyn Error + Send + Sync>> {
+        let mut request = self.client.get(url).header(ACCEPT, KV_SET_MEDIA_TYPE);
+        if let Some(etag) = etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        let sync_token = self.sync_tokens.lock().unwrap().iter()
+            .map(|(id, (value, _))| format!("{id}={value}"))
+            .collect::<Vec<_>>()
+            .join(",");
+        if !sync_token.is_empty() {
+            request = request.header(SYNC_TOKEN_HEADER, sync_token);
+        }
+        let mut request = request.build()?;
+        match &self.credential {
+            AzureCredential::AccessKey { id, secret } => sign(&mut request, id, secret, SystemTime::now())?,
+            AzureCredential::BearerToken(token) => {
+                request.headers_mut().insert(AUTHORIZATION, HeaderValue::from_str(&format!("Bearer {token}"))?);
+            }
+        }
+
+        let response = self.client.execute(request).await?;
+        if let Some(token) = response.headers().get(SYNC_TOKEN_HEADER).and_then(|value| value.to_str().ok()) {
+            self.add_sync_token(token);
+        }
+        if !response.status().is_success() && response.status() != StatusCode::NOT_MODIFIED {
+            let status = response.status();
+            let body: Value = response.bytes().await.ok()
+                .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+                .unwrap_or_default();
+            let message = body["detail"].as_str().or(body["title"].as_str()).map(str::to_string);
+            return Err(AzureError { status, message }.into());
+        }
+        Ok(response)
+    }
+
+    /// Checks pages of current data with conditional requests. Returns `true` if no page changed.
+    async fn is_not_modified(&self, current_revision: Option<&str>) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        let etags = {
+            let pages = self.pages.lock().unwrap();
+            if current_revision.is_none() || pages.revision.as_deref() != current_revision {
+                return Ok(false);
+            }
+            pages.etags.clone()
+        };
+        for (url, etag) in etags {
+            if self.get(url, Some(&etag)).await?.status() != StatusCode::NOT_MODIFIED {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    async fn load(&self, current_revision: Option<&str>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        if self.is_not_modified(current_revision).await? {
+            let valid_until = SystemTime::now() + self.max_age;
+            return Ok(Revalidation::NotModified { must_revalidate: false, valid_until, invalidation: None, fetched_at: None });
+        }
+
+        let mut settings = Vec::new();
+        let mut etags = Vec::new();
+        let mut size = 0;
+        let mut next = Some(self.first_page());
+        while let Some(url) = next.take() {
+            let response = self.get(url.clone(), None).await?;
+            let etag = response.headers().get(ETAG).and_then(|value| value.to_str().ok()).map(str::to_string);
+            let bytes = response.bytes().await?;
+            size += bytes.len();
+            let mut page: Value = serde_json::from_slice(&bytes)?;
+            settings.extend(serde_json::from_value::<Vec<AzureSetting>>(page["items"].take())?);
+            if let Some(link) = page["@nextLink"].as_str() {
+                next = Some(self.endpoint.join(link)?);
+            }
+            etags.extend(etag.map(|etag| (url, etag)));
+        }
+
+        let revision = etags.iter().map(|(_, etag)| etag.as_str()).collect::<Vec<_>>().join(",");
+        let mut result = DataLoadResult::new((self.parser)(&settings)?, false, SystemTime::now() + self.max_age);
+        result.metadata.size = Some(size as u64);
+        result.metadata.revision = Some(revision.clone());
+        *self.pages.lock().unwrap() = Pages { revision: Some(revision), etags };
+        Ok(Revalidation::Modified(result))
+    }
+}
+
+impl <Data, Parser> DataProvider<Data> for AzureAppConfigProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&[AzureSetting]) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Loads all pages of selected key-values and assembles them into data
+    /// # Errors
+    /// If request fails, store returns an error or parser returns an error
+    async fn load_data(&self) -> Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>> {
+        Ok(self.load(None).await?.into_loaded())
+    }
+
+    /// Checks pages with their ETags, and loads key-values again only if any page changed
+    async fn revalidate_data<'a>(&'a self, current: &'a DataLoadResult<Data>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        self.load(current.metadata.revision.as_deref()).await
+    }
+}
+
+impl <Data: Send + Sync, Parser> Debug for AzureAppConfigProvider<Data, Parser> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AzureAppConfigProvider")
+            .field("endpoint", &self.endpoint)
+            .field("credential", &self.credential)
+            .field("key_filter", &self.key_filter)
+            .field("label_filter", &self.label_filter)
+            .field("max_age", &self.max_age)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Signs request with access key, as described in
+/// [App Configuration HMAC authentication](https://learn.microsoft.com/azure/azure-app-configuration/rest-api-authentication-hmac)
+fn sign(request: &mut reqwest::Request, id: &str, secret: &str, time: SystemTime) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let url = request.url();
+    let host = match url.port() {
+        Some(port) => format!("{}:{port}", url.host_str().unwrap_or_default()),
+        None => url.host_str().unwrap_or_default().to_string()
+    };
+    let path_and_query = match url.query() {
+        Some(query) => format!("{}?{query}", url.path()),
+        None => url.path().to_string()
+    };
+    let date = httpdate::fmt_http_date(time);
+    let payload = request.body().and_then(|body| body.as_bytes()).unwrap_or_default();
+    let content_hash = STANDARD.encode(Sha256::digest(payload));
+    let string_to_sign = format!("{}\n{path_and_query}\n{date};{host};{content_hash}", request.method());
+
+    let mut mac = Hmac::<Sha256>::new_from_slice(&STANDARD.decode(secret)?).expect("HMAC accepts keys of any length");
+    mac.update(string_to_sign.as_bytes());
+    let signature = STANDARD.encode(mac.finalize().into_bytes());
+
+    let headers = request.headers_mut();
+    headers.insert("x-ms-date", HeaderValue::from_str(&date)?);
+    headers.insert("x-ms-content-sha256", HeaderValue::from_str(&content_hash)?);
+    headers.insert(AUTHORIZATION, HeaderValue::from_str(&format!(
+        "HMAC-SHA256 Credential={id}&SignedHeaders=x-ms-date;host;x-ms-content-sha256&Signature={signature}"
+    ))?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use mockito::Matcher;
+    use reqwest::Url;
+    use crate::data_providers::azure::{AzureAppConfigProvider, AzureCredential, AzureSetting};
+    use crate::data_providers::data_provider::{DataProvider, Revalidation};
+
+    #[tokio::test]
+    async fn etags_and_sync_tokens() {
+        let mut server = mockito::Server::new_async().await;
+        let first_page = server
+            .mock("GET", "/kv")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("key".to_string(), "app:*".to_string()),
+                Matcher::UrlEncoded("label".to_string(), "prod".to_string())
+            ]))
+            .match_header("authorization", Matcher::Regex(r"^HMAC-SHA256 Credential=key-id&SignedHeaders=x-ms-date;host;x-ms-content-sha256&Signature=".to_string()))
+            .match_header("if-none-match", Matcher::Missing)
+            .with_header("ETag", "\"page-1\"")
+            .with_header("Sync-Token", "zAJw6V16=MTowOjE3;sn=17")
+            .with_body(r#"{"items": [{"key": "app:color", "label": "prod", "value": "blue"}], "@nextLink": "/kv?key=app%3A%2A&label=prod&api-version=1.0&after=YXBwOmNvbG9y"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let second_page = server
+            .mock("GET", "/kv")
+            .match_query(Matcher::UrlEncoded("after".to_string(), "YXBwOmNvbG9y".to_string()))
+            .match_header("sync-token", "zAJw6V16=MTowOjE3")
+            .match_header("if-none-match", Matcher::Missing)
+            .with_header("ETag", "\"page-2\"")
+            .with_body(r#"{"items": [{"key": "app:size", "label": "prod", "value": "10", "content_type": "text/plain"}]}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let not_modified = server
+            .mock("GET", "/kv")
+            .match_query(Matcher::Any)
+            .match_header("if-none-match", Matcher::Regex("\"page-[12]\"".to_string()))
+            .with_status(304)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let credential = AzureCredential::AccessKey { id: "key-id".to_string(), secret: "c2VjcmV0".to_string() };
+        let data_provider = AzureAppConfigProvider::new(reqwest::Client::default(), Url::parse(&server.url()).unwrap(), credential, |settings: &[AzureSetting]| {
+            Ok(settings.iter().map(|setting| (setting.key.clone(), setting.value.clone().unwrap_or_default())).collect::<HashMap<_, _>>())
+        }).with_key_filter("app:*").with_label_filter("prod");
+
+        let result = data_provider.load_data().await.unwrap();
+        assert_eq!(result.data["app:color"], "blue");
+        assert_eq!(result.data["app:size"], "10");
+        assert_eq!(result.metadata.revision.as_deref(), Some("\"page-1\",\"page-2\""));
+
+        // Both pages are checked with conditional requests
+        assert!(matches!(data_provider.revalidate_data(&result).await.unwrap(), Revalidation::NotModified { .. }));
+        first_page.assert_async().await;
+        second_page.assert_async().await;
+        not_modified.assert_async().await;
+    }
+
+    #[test]
+    fn sync_token_with_higher_sequence_number_wins() {
+        let data_provider = AzureAppConfigProvider::from_connection_string(
+            reqwest::Client::default(),
+            "Endpoint=https://example.azconfig.io;Id=key-id;Secret=c2VjcmV0",
+            |settings: &[AzureSetting]| Ok(settings.len())
+        ).unwrap();
+        data_provider.add_sync_token("a=v2;sn=2,b=v1;sn=1");
+        data_provider.add_sync_token("a=v1;sn=1");
+        let sync_tokens = data_provider.sync_tokens.lock().unwrap();
+        assert_eq!(sync_tokens["a"], ("v2".to_string(), 2));
+        assert_eq!(sync_tokens["b"], ("v1".to_string(), 1));
+    }
+}
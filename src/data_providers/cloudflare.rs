@@ -0,0 +1,201 @@
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::marker::PhantomData;
+use std::time::{Duration, SystemTime};
+use reqwest::header::AUTHORIZATION;
+use reqwest::{StatusCode, Url};
+use crate::content_hash;
+use crate::data_providers::data_provider::{DataLoadResult, DataProvider, Revalidation};
+
+/// Default Cloudflare API address
+pub const DEFAULT_API_URL: &str = "https://api.cloudflare.com/client/v4";
+
+/// This data provider reads value of single key from Cloudflare Workers KV namespace with Cloudflare API,
+/// and parses it with specified function. API token needs `Workers KV Storage Read` permission.
+///
+/// API doesn't return validators for values, so hash of value is reported as revision, and value is not parsed again if it did not change.
+/// Workers KV is eventually consistent, and changes may take up to a minute to be visible, so short max age gives little benefit.
+/// # Examples
+/// ```
+/// # #[cfg(feature = "json")] {
+/// use std::collections::HashMap;
+/// use remote_config::data_providers::cloudflare::CloudflareKvDataProvider;
+///
+/// let data_provider = CloudflareKvDataProvider::new(reqwest::Client::default(), "023e105f4ecef8ad9ca31a8372d0c353", "0f2ac74b498b48028cb68387c421e279", "app-config", |bytes: &[u8]| {
+///     Ok(serde_json::from_slice::<HashMap<String, String>>(bytes)?)
+/// }).with_api_token("...");
+/// # }
+/// ```
+pub struct CloudflareKvDataProvider<Data: Send + Sync, Parser> {
+    client: reqwest::Client,
+    api_url: Url,
+    account_id: String,
+    namespace_id: String,
+    key: String,
+    api_token: Option<String>,
+    parser: Parser,
+    max_age: Duration,
+    data_type: PhantomData<Data>
+}
+
+impl <Data, Parser> CloudflareKvDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&[u8]) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Creates data provider for key of namespace, that belongs to specified account
+    pub fn new(client: reqwest::Client, account_id: impl Into<String>, namespace_id: impl Into<String>, key: impl Into<String>, parser: Parser) -> Self {
+        Self {
+            client,
+            api_url: Url::parse(DEFAULT_API_URL).expect("valid url"),
+            account_id: account_id.into(),
+            namespace_id: namespace_id.into(),
+            key: key.into(),
+            api_token: None,
+            parser,
+            max_age: Duration::from_secs(60),
+            data_type: PhantomData
+        }
+    }
+
+    /// API token that is sent as bearer token. Without it, client must authenticate requests on its own (e.g. with default headers).
+    pub fn with_api_token(mut self, token: impl Into<String>) -> Self {
+        self.api_token = Some(token.into());
+        self
+    }
+
+    /// Cloudflare API address. Default is [`DEFAULT_API_URL`].
+    pub fn with_api_url(mut self, api_url: Url) -> Self {
+        self.api_url = api_url;
+        self
+    }
+
+    /// Time after which key is read again. Default is 60 seconds.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    fn value_url(&self) -> Url {
+        let mut url = self.api_url.clone();
+        // Key is single segment, so slashes in it are encoded
+        url.path_segments_mut()
+            .expect("api url is a base url")
+            .pop_if_empty()
+            .extend(["accounts", &self.account_id, "storage", "kv", "namespaces", &self.namespace_id, "values", &self.key]);
+        url
+    }
+
+    async fn load(&self, current_revision: Option<&str>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        let mut request = self.client.get(self.value_url());
+        if let Some(token) = &self.api_token {
+            request = request.header(AUTHORIZATION, format!("Bearer {token}"));
+        }
+        let response = request.send().await?;
+        match response.status() {
+            status if status.is_success() => {},
+            StatusCode::NOT_FOUND => return Err(CloudflareKvError::KeyNotFound.into()),
+            status => return Err(CloudflareKvError::Status(status).into())
+        }
+
+        let bytes = response.bytes().await?;
+        let valid_until = SystemTime::now() + self.max_age;
+        let revision = format!("{:016x}", content_hash::fnv1a(&bytes));
+        if current_revision == Some(revision.as_str()) {
+            return Ok(Revalidation::NotModified { must_revalidate: false, valid_until, invalidation: None, fetched_at: None });
+        }
+
+        let mut result = DataLoadResult::new((self.parser)(&bytes)?, false, valid_until);
+        result.metadata.size = Some(bytes.len() as u64);
+        result.metadata.revision = Some(revision);
+        Ok(Revalidation::Modified(result))
+    }
+}
+
+impl <Data, Parser> DataProvider<Data> for CloudflareKvDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&[u8]) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Reads and parses value of the key
+    /// # Errors
+    /// If request fails, key doesn't exist or parser returns an error
+    async fn load_data(&self) -> Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>> {
+        Ok(self.load(None).await?.into_loaded())
+    }
+
+    /// Reads value of the key, but parses it only if it changed
+    async fn revalidate_data<'a>(&'a self, current: &'a DataLoadResult<Data>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        self.load(current.metadata.revision.as_deref()).await
+    }
+}
+
+impl <Data: Send + Sync, Parser> Debug for CloudflareKvDataProvider<Data, Parser> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CloudflareKvDataProvider")
+            .field("api_url", &self.api_url)
+            .field("account_id", &self.account_id)
+            .field("namespace_id", &self.namespace_id)
+            .field("key", &self.key)
+            .field("max_age", &self.max_age)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Workers KV specific errors
+#[derive(Debug)]
+pub enum CloudflareKvError {
+    /// Key does not exist in namespace
+    KeyNotFound,
+    /// Unexpected http status
+    Status(StatusCode)
+}
+
+impl Display for CloudflareKvError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::KeyNotFound => write!(f, "key not found in Workers KV namespace"),
+            Self::Status(status) => write!(f, "unexpected response status code: {status}")
+        }
+    }
+}
+
+impl Error for CloudflareKvError {}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use reqwest::Url;
+    use crate::data_providers::cloudflare::CloudflareKvDataProvider;
+    use crate::data_providers::data_provider::{DataProvider, Revalidation};
+
+    fn parse(bytes: &[u8]) -> Result<String, Box<dyn Error + Send + Sync>> {
+        Ok(String::from_utf8(bytes.to_vec())?)
+    }
+
+    #[tokio::test]
+    async fn value_is_parsed_only_when_changed() {
+        let mut server = mockito::Server::new_async().await;
+        let value = server
+            .mock("GET", "/accounts/acc/storage/kv/namespaces/ns/values/app%2Fconfig")
+            .match_header("Authorization", "Bearer secret")
+            .with_body("v1")
+            .expect(2)
+            .create_async()
+            .await;
+
+        let data_provider = CloudflareKvDataProvider::new(reqwest::Client::default(), "acc", "ns", "app/config", parse)
+            .with_api_url(Url::parse(&server.url()).unwrap())
+            .with_api_token("secret");
+
+        let loaded = data_provider.load_data().await.unwrap();
+        assert_eq!(loaded.data, "v1");
+        assert!(matches!(data_provider.revalidate_data(&loaded).await.unwrap(), Revalidation::NotModified { .. }));
+        value.assert_async().await;
+
+        let _missing = server
+            .mock("GET", "/accounts/acc/storage/kv/namespaces/ns/values/other")
+            .with_status(404)
+            .create_async()
+            .await;
+        let data_provider = CloudflareKvDataProvider::new(reqwest::Client::default(), "acc", "ns", "other", parse)
+            .with_api_url(Url::parse(&server.url()).unwrap());
+        assert_eq!(data_provider.load_data().await.unwrap_err().to_string(), "key not found in Workers KV namespace");
+    }
+}
@@ -0,0 +1,315 @@
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use reqwest::{Response, StatusCode, Url};
+use tokio::task::AbortHandle;
+use crate::data_providers::data_provider::{DataLoadResult, DataProvider, InvalidationToken, Revalidation};
+
+/// Default Consul agent address
+pub const DEFAULT_ADDRESS: &str = "http://127.0.0.1:8500";
+/// Header with Consul index of returned data
+const INDEX_HEADER: &str = "X-Consul-Index";
+/// Header with ACL token
+const TOKEN_HEADER: &str = "X-Consul-Token";
+
+/// State shared with blocking query task
+#[derive(Debug, Default)]
+struct WatchState {
+    index: Mutex<Option<u64>>,
+    token: Mutex<InvalidationToken>
+}
+
+/// This data provider reads value of single key from Consul KV store and parses it with specified function.
+/// `X-Consul-Index` is reported as revision, and data is not parsed again if index did not change.
+///
+/// With [`ConsulDataProvider::with_blocking_queries`], background task waits for changes with blocking queries,
+/// and invalidates loaded data as soon as key is modified.
+/// # Examples
+/// ```
+/// # #[cfg(feature = "json")] {
+/// use std::collections::HashMap;
+/// use std::time::Duration;
+/// use remote_config::data_providers::consul::ConsulDataProvider;
+///
+/// let data_provider = ConsulDataProvider::new(reqwest::Client::default(), "app/config", |bytes: &[u8]| {
+///     Ok(serde_json::from_slice::<HashMap<String, String>>(bytes)?)
+/// }).with_blocking_queries(Duration::from_secs(5 * 60));
+/// # }
+/// ```
+pub struct ConsulDataProvider<Data: Send + Sync, Parser> {
+    client: reqwest::Client,
+    address: Url,
+    key: String,
+    acl_token: Option<String>,
+    parser: Parser,
+    max_age: Duration,
+    blocking_wait: Option<Duration>,
+    state: Arc<WatchState>,
+    /// Blocking query task, started on first load
+    watcher: Mutex<Option<AbortHandle>>,
+    data_type: PhantomData<Data>
+}
+
+impl <Data, Parser> ConsulDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&[u8]) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Creates data provider for specified key, that uses local agent at [`DEFAULT_ADDRESS`]
+    pub fn new(client: reqwest::Client, key: impl Into<String>, parser: Parser) -> Self {
+        Self {
+            client,
+            address: Url::parse(DEFAULT_ADDRESS).expect("valid url"),
+            key: key.into(),
+            acl_token: None,
+            parser,
+            max_age: Duration::from_secs(60),
+            blocking_wait: None,
+            state: Arc::new(WatchState::default()),
+            watcher: Mutex::new(None),
+            data_type: PhantomData
+        }
+    }
+
+    /// Consul agent address
+    pub fn with_address(mut self, address: Url) -> Self {
+        self.address = address;
+        self
+    }
+
+    /// ACL token that is sent with every request
+    pub fn with_acl_token(mut self, token: impl Into<String>) -> Self {
+        self.acl_token = Some(token.into());
+        self
+    }
+
+    /// Time after which key is read again. Default is 60 seconds.
+    /// With blocking queries it can be much longer, as changes are detected immediately.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Watch key with blocking queries, that wait up to specified time for changes.
+    /// Watching starts on first data load, and stops when data provider is dropped.
+    pub fn with_blocking_queries(mut self, wait: Duration) -> Self {
+        self.blocking_wait = Some(wait);
+        self
+    }
+
+    /// Sends request for raw value, optionally blocking until index changes
+    async fn get(client: &reqwest::Client, url: Url, acl_token: Option<&str>) -> Result<(Response, u64), Box<dyn Error + Send + Sync>> {
+        let mut request = client.get(url);
+        if let Some(token) = acl_token {
+            request = request.header(TOKEN_HEADER, token);
+        }
+        let response = request.send().await?;
+        match response.status() {
+            status if status.is_success() => {},
+            StatusCode::NOT_FOUND => return Err(ConsulError::KeyNotFound.into()),
+            status => return Err(ConsulError::Status(status).into())
+        }
+        let index = response.headers().get(INDEX_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse().ok())
+            .ok_or(ConsulError::MissingIndex)?;
+        Ok((response, index))
+    }
+
+    fn key_url(&self) -> Url {
+        let mut url = self.address.clone();
+        url.path_segments_mut()
+            .expect("address is a base url")
+            .pop_if_empty()
+            .extend(["v1", "kv"])
+            .extend(self.key.split('/'));
+        url.query_pairs_mut().append_pair("raw", "");
+        url
+    }
+
+    /// Starts blocking query task, if it is enabled and not started yet
+    fn start_watching(&self) {
+        let Some(wait) = self.blocking_wait else {
+            return;
+        };
+        let mut watcher = self.watcher.lock().unwrap();
+        if watcher.is_some() {
+            return;
+        }
+
+        let client = self.client.clone();
+        let url = self.key_url();
+        let acl_token = self.acl_token.clone();
+        let state = Arc::downgrade(&self.state);
+        let handle = tokio::spawn(async move {
+            loop {
+                let Some(index) = state.upgrade().and_then(|state| *state.index.lock().unwrap()) else {
+                    return;
+                };
+                let mut url = url.clone();
+                url.query_pairs_mut()
+                    .append_pair("index", &index.to_string())
+                    .append_pair("wait", &format!("{}s", wait.as_secs()));
+
+                match Self::get(&client, url, acl_token.as_deref()).await {
+                    Ok((_, new_index)) if new_index != index => {
+                        let Some(state) = state.upgrade() else {
+                            return;
+                        };
+                        state.token.lock().unwrap().invalidate();
+                        *state.index.lock().unwrap() = Some(new_index);
+                    },
+                    Ok(_) => {},
+                    Err(_err) => {
+                        #[cfg(feature = "tracing")]
+                        tracing::warn!("Consul blocking query failed: {_err}");
+                        tokio::time::sleep(Duration::from_secs(1)).await;
+                    }
+                }
+            }
+        });
+        *watcher = Some(handle.abort_handle());
+    }
+
+    async fn load(&self, current_revision: Option<&str>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        // Token is replaced before request, so changes made during request are not missed
+        let token = InvalidationToken::new();
+        *self.state.token.lock().unwrap() = token.clone();
+
+        let (response, index) = Self::get(&self.client, self.key_url(), self.acl_token.as_deref()).await?;
+        *self.state.index.lock().unwrap() = Some(index);
+        self.start_watching();
+
+        let valid_until = SystemTime::now() + self.max_age;
+        let revision = index.to_string();
+        if current_revision == Some(revision.as_str()) {
+            return Ok(Revalidation::NotModified { must_revalidate: false, valid_until, invalidation: Some(token), fetched_at: None });
+        }
+
+        let bytes = response.bytes().await?;
+        let mut result = DataLoadResult::new((self.parser)(&bytes)?, false, valid_until);
+        result.metadata.size = Some(bytes.len() as u64);
+        result.metadata.revision = Some(revision);
+        result.metadata.invalidation = Some(token);
+        Ok(Revalidation::Modified(result))
+    }
+}
+
+impl <Data, Parser> DataProvider<Data> for ConsulDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&[u8]) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Reads and parses value of the key
+    /// # Errors
+    /// If request fails, key doesn't exist or parser returns an error
+    async fn load_data(&self) -> Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>> {
+        Ok(self.load(None).await?.into_loaded())
+    }
+
+    /// Reads value of the key, but parses it only if Consul index changed
+    async fn revalidate_data<'a>(&'a self, current: &'a DataLoadResult<Data>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        self.load(current.metadata.revision.as_deref()).await
+    }
+}
+
+impl <Data: Send + Sync, Parser> Drop for ConsulDataProvider<Data, Parser> {
+    fn drop(&mut self) {
+        if let Some(handle) = self.watcher.lock().unwrap().take() {
+            handle.abort();
+        }
+    }
+}
+
+impl <Data: Send + Sync, Parser> Debug for ConsulDataProvider<Data, Parser> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConsulDataProvider")
+            .field("address", &self.address)
+            .field("key", &self.key)
+            .field("max_age", &self.max_age)
+            .field("blocking_wait", &self.blocking_wait)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Consul specific errors
+#[derive(Debug)]
+pub enum ConsulError {
+    /// Key does not exist
+    KeyNotFound,
+    /// Response does not contain valid `X-Consul-Index` header
+    MissingIndex,
+    /// Unexpected http status
+    Status(StatusCode)
+}
+
+impl Display for ConsulError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::KeyNotFound => write!(f, "key not found in Consul KV store"),
+            Self::MissingIndex => write!(f, "response does not contain valid {INDEX_HEADER} header"),
+            Self::Status(status) => write!(f, "unexpected response status code: {status}")
+        }
+    }
+}
+
+impl Error for ConsulError {}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use std::time::Duration;
+    use mockito::Matcher;
+    use reqwest::Url;
+    use crate::data_providers::consul::ConsulDataProvider;
+    use crate::data_providers::data_provider::{DataProvider, Revalidation};
+
+    fn parse(bytes: &[u8]) -> Result<String, Box<dyn Error + Send + Sync>> {
+        Ok(String::from_utf8(bytes.to_vec())?)
+    }
+
+    #[tokio::test]
+    async fn revision_and_blocking_queries() {
+        let mut server = mockito::Server::new_async().await;
+        let read = server
+            .mock("GET", "/v1/kv/app/config")
+            .match_query(Matcher::Exact("raw=".into()))
+            .with_header("X-Consul-Index", "7")
+            .with_body("v1")
+            .expect(2)
+            .create_async()
+            .await;
+        let blocking = server
+            .mock("GET", "/v1/kv/app/config")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("index".into(), "7".into()),
+                Matcher::UrlEncoded("wait".into(), "10s".into())
+            ]))
+            .with_header("X-Consul-Index", "8")
+            .with_body("v2")
+            .expect(1)
+            .create_async()
+            .await;
+
+        let data_provider = ConsulDataProvider::new(reqwest::Client::default(), "app/config", parse)
+            .with_address(Url::parse(&server.url()).unwrap())
+            .with_blocking_queries(Duration::from_secs(10));
+
+        let first = data_provider.load_data().await.unwrap();
+        assert_eq!(first.data, "v1");
+        assert_eq!(first.metadata.revision.as_deref(), Some("7"));
+
+        // Index changed according to blocking query
+        let token = first.metadata.invalidation.clone().unwrap();
+        for _ in 0..100 {
+            if token.is_invalidated() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(token.is_invalidated());
+        blocking.assert_async().await;
+
+        // Same index is returned by read, so data is not parsed again
+        assert!(matches!(data_provider.revalidate_data(&first).await.unwrap(), Revalidation::NotModified { .. }));
+        read.assert_async().await;
+    }
+}
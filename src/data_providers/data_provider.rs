@@ -1,5 +1,14 @@
 use std::error::Error;
-use std::time::SystemTime;
+use std::fmt::{Debug, Display, Formatter};
+use std::future::{poll_fn, Future};
+use std::pin::{pin, Pin};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::task::Poll;
+use std::time::{Duration, SystemTime};
+use tokio::sync::Notify;
+use crate::control::Control;
+use crate::deprecation::EndpointDeprecation;
 /// Result of successful data load
 /// # What if I don't need caching?
 /// Just set `valid_until` to some time in the past or current time.
@@ -10,13 +19,285 @@ pub struct DataLoadResult<T> {
     /// If true, once the data becomes stale, it can't be used until revalidated successfully.
     pub must_revalidate: bool,
     /// Time in the future when `data` becomes stale
-    pub valid_until: SystemTime
+    pub valid_until: SystemTime,
+    /// Optional information about loaded data
+    pub metadata: LoadMetadata
 }
+
+impl <T> DataLoadResult<T> {
+    /// Constructs load result with empty metadata
+    pub fn new(data: T, must_revalidate: bool, valid_until: SystemTime) -> Self {
+        Self {
+            data,
+            must_revalidate,
+            valid_until,
+            metadata: LoadMetadata::default()
+        }
+    }
+}
+
+/// Optional information about loaded data, that is reported by data provider.
+/// New fields can be added in future versions, so construct it with [`LoadMetadata::default`].
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct LoadMetadata {
+    /// Size of loaded data in bytes (e.g. response body size).
+    /// Used for usage accounting and budgets.
+    pub size: Option<u64>,
+    /// Token that marks data as stale before `valid_until`, once invalidated.
+    /// Allows data providers that are notified about changes (e.g. file watchers) to trigger revalidation.
+    pub invalidation: Option<InvalidationToken>,
+    /// Origin of this config version
+    pub provenance: Option<Provenance>,
+    /// Opaque revision of loaded data (e.g. Consul index or object generation), if source reports it
+    pub revision: Option<String>,
+    /// Origin directives received with data. They replace previously received directives
+    pub control: Option<Control>,
+    /// Keys that were overridden locally (see [`crate::data_providers::overlay::OverlayProvider`])
+    pub overrides: Vec<String>,
+    /// Response headers captured by data provider, with lowercase names
+    /// (see [`HttpDataProvider::with_captured_headers`](crate::data_providers::http::HttpDataProvider::with_captured_headers))
+    pub headers: Vec<(String, String)>,
+    /// Deprecation of source endpoint, if origin announced it
+    pub deprecation: Option<EndpointDeprecation>,
+    /// Time when data was fetched from origin, if provider serves it from persisted or cached copy
+    /// (e.g. file written by sidecar, or HTTP response served by CDN with Age header). `None` means that data was just fetched
+    pub fetched_at: Option<SystemTime>
+}
+
+impl LoadMetadata {
+    /// Value of first captured header with specified name (case-insensitive)
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(header, _)| header.eq_ignore_ascii_case(name)).map(|(_, value)| value.as_str())
+    }
+}
+
+/// Information about origin of config version, used to trace active config to a change request.
+/// With `serde` feature, it can be deserialized from config document fields.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[non_exhaustive]
+pub struct Provenance {
+    /// Who published this version
+    pub publisher: Option<String>,
+    /// URL of pipeline run that published this version
+    pub pipeline_run: Option<String>,
+    /// Subject of signature that this version was signed with
+    pub signature_subject: Option<String>,
+    /// Source control commit this version was built from
+    pub source_commit: Option<String>
+}
+
+impl Provenance {
+    /// Checks if no provenance information is present
+    pub fn is_empty(&self) -> bool {
+        self.publisher.is_none() && self.pipeline_run.is_none() && self.signature_subject.is_none() && self.source_commit.is_none()
+    }
+}
+
+/// Shared flag that marks loaded data as stale.
+/// Data provider keeps a clone and calls [`InvalidationToken::invalidate`] when source data changes.
+#[derive(Debug, Clone, Default)]
+pub struct InvalidationToken(Arc<TokenState>);
+
+#[derive(Debug, Default)]
+struct TokenState {
+    invalidated: AtomicBool,
+    linked: Vec<InvalidationToken>,
+    /// Wakes tasks waiting in [`InvalidationToken::invalidated`]
+    notify: Notify
+}
+
+impl InvalidationToken {
+    /// Creates token that is not invalidated
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates token that is invalidated once any of specified tokens is invalidated.
+    /// Used by data providers that combine data from several sources.
+    pub fn any(tokens: impl IntoIterator<Item = InvalidationToken>) -> Self {
+        Self(Arc::new(TokenState {
+            invalidated: AtomicBool::new(false),
+            linked: tokens.into_iter().collect(),
+            notify: Notify::new()
+        }))
+    }
+
+    /// Marks data associated with this token as stale
+    pub fn invalidate(&self) {
+        self.0.invalidated.store(true, Ordering::Release);
+        self.0.notify.notify_waiters();
+    }
+
+    /// Waits until token (or any of linked tokens) is invalidated
+    pub fn invalidated(&self) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        Box::pin(async move {
+            // Subscribe before checking, so that invalidation in between is not missed
+            let mut notified = pin!(self.0.notify.notified());
+            notified.as_mut().enable();
+            if self.0.invalidated.load(Ordering::Acquire) {
+                return;
+            }
+            let mut linked: Vec<_> = self.0.linked.iter().map(InvalidationToken::invalidated).collect();
+            poll_fn(|cx| {
+                if notified.as_mut().poll(cx).is_ready() || linked.iter_mut().any(|token| token.as_mut().poll(cx).is_ready()) {
+                    Poll::Ready(())
+                } else {
+                    Poll::Pending
+                }
+            }).await
+        })
+    }
+
+    /// Checks if token was invalidated
+    pub fn is_invalidated(&self) -> bool {
+        self.0.invalidated.load(Ordering::Acquire) || self.0.linked.iter().any(InvalidationToken::is_invalidated)
+    }
+}
+/// Result of revalidation of previously loaded data
+#[derive(Debug)]
+// Modified is the common case, so it is not boxed to avoid extra allocation per load
+#[allow(clippy::large_enum_variant)]
+pub enum Revalidation<Data> {
+    /// Data was modified, or provider can't tell
+    Modified(DataLoadResult<Data>),
+    /// Data was not modified. Cached data and its metadata are kept, only freshness is updated
+    NotModified {
+        /// See [`DataLoadResult::must_revalidate`]
+        must_revalidate: bool,
+        /// See [`DataLoadResult::valid_until`]
+        valid_until: SystemTime,
+        /// Replaces invalidation token of cached data, if set (see [`LoadMetadata::invalidation`])
+        invalidation: Option<InvalidationToken>,
+        /// Time when cached data was fetched from origin, if provider serves it from persisted copy (see [`LoadMetadata::fetched_at`]).
+        /// `None` means that origin just confirmed it
+        fetched_at: Option<SystemTime>
+    }
+}
+
+impl <Data> Revalidation<Data> {
+    /// Data loaded without current revision, that can't be reported as not modified
+    /// # Panics
+    /// If data is reported as not modified
+    pub(crate) fn into_loaded(self) -> DataLoadResult<Data> {
+        match self {
+            Revalidation::Modified(result) => result,
+            Revalidation::NotModified { .. } => unreachable!("there is no current revision")
+        }
+    }
+}
+
+/// Load error with origin's hint when to retry, e.g. `Retry-After` header of 429 or 503 response.
+/// [`RemoteConfig`](crate::config::RemoteConfig) waits for hinted delay instead of its retry interval,
+/// if error returned by data provider or any of its sources is `RetryHint`.
+#[derive(Debug)]
+pub struct RetryHint {
+    /// Delay before next attempt
+    pub retry_after: Duration,
+    source: Box<dyn Error + Send + Sync>
+}
+
+impl RetryHint {
+    /// Wraps load error
+    pub fn new(retry_after: Duration, source: Box<dyn Error + Send + Sync>) -> Self {
+        Self { retry_after, source }
+    }
+}
+
+impl Display for RetryHint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (retry after {}s)", self.source, self.retry_after.as_secs())
+    }
+}
+
+impl Error for RetryHint {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
 /// Remote data provider trait.
 /// Data provider loads data from external sources and returns [`DataLoadResult`]
 /// # Errors
-/// Any error can be returned by custom implementation.
+/// Any error that is [`Send`] and [`Sync`] can be returned by custom implementation.
 pub trait DataProvider<Data: Send + Sync> {
     /// Try to load data
-    fn load_data(&self) -> impl std::future::Future<Output = Result<DataLoadResult<Data>, Box<dyn Error>>> + Send;
-}
\ No newline at end of file
+    fn load_data(&self) -> impl Future<Output = Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>>> + Send;
+
+    /// Revalidates currently cached data.
+    /// Providers that can detect unchanged data (e.g. by revision) should return [`Revalidation::NotModified`],
+    /// so that data is not parsed and replaced again.
+    /// Default implementation loads data with [`DataProvider::load_data`].
+    fn revalidate_data<'a>(&'a self, current: &'a DataLoadResult<Data>) -> impl Future<Output = Result<Revalidation<Data>, Box<dyn Error + Send + Sync>>> + Send {
+        let _ = current;
+        let future = self.load_data();
+        async move { Ok(Revalidation::Modified(future.await?)) }
+    }
+}
+
+/// Future returned by [`DynDataProvider::load_data_boxed`]
+pub type BoxedLoadFuture<'a, Data> = Pin<Box<dyn Future<Output = Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>>> + Send + 'a>>;
+
+/// Future returned by [`DynDataProvider::revalidate_data_boxed`]
+pub type BoxedRevalidationFuture<'a, Data> = Pin<Box<dyn Future<Output = Result<Revalidation<Data>, Box<dyn Error + Send + Sync>>> + Send + 'a>>;
+
+/// Object safe version of [`DataProvider`].
+/// It is implemented for every [`DataProvider`], so there is no need to implement it manually.
+pub trait DynDataProvider<Data: Send + Sync> {
+    /// Try to load data
+    fn load_data_boxed<'a>(&'a self) -> BoxedLoadFuture<'a, Data> where Data: 'a;
+
+    /// Revalidate currently cached data
+    fn revalidate_data_boxed<'a>(&'a self, current: &'a DataLoadResult<Data>) -> BoxedRevalidationFuture<'a, Data> where Data: 'a;
+}
+
+impl <Data: Send + Sync, Provider: DataProvider<Data>> DynDataProvider<Data> for Provider {
+    fn load_data_boxed<'a>(&'a self) -> BoxedLoadFuture<'a, Data> where Data: 'a {
+        Box::pin(self.load_data())
+    }
+
+    fn revalidate_data_boxed<'a>(&'a self, current: &'a DataLoadResult<Data>) -> BoxedRevalidationFuture<'a, Data> where Data: 'a {
+        Box::pin(self.revalidate_data(current))
+    }
+}
+
+/// Type-erased data provider.
+/// Allows to store providers of different types together and to choose provider at runtime.
+/// # Examples
+/// ```
+/// use std::collections::HashMap;
+/// use reqwest::Url;
+/// use remote_config::data_providers::data_provider::BoxedDataProvider;
+/// use remote_config::data_providers::http::HttpDataProvider;
+/// use remote_config::data_providers::http::serde_extractor::SerdeDataExtractor;
+///
+/// type Data = HashMap<String, String>;
+/// let http = HttpDataProvider::new(reqwest::Client::default(), Url::parse("https://www.example.com/cfg").unwrap(), SerdeDataExtractor::<Data>::new());
+/// let providers: Vec<BoxedDataProvider<Data>> = vec![BoxedDataProvider::new(http)];
+/// ```
+pub struct BoxedDataProvider<Data: Send + Sync>(Box<dyn DynDataProvider<Data> + Send>);
+
+impl <Data: Send + Sync> BoxedDataProvider<Data> {
+    /// Wraps data provider
+    pub fn new(provider: impl DataProvider<Data> + Send + 'static) -> Self {
+        Self(Box::new(provider))
+    }
+}
+
+impl <Data: Send + Sync> DataProvider<Data> for BoxedDataProvider<Data> {
+    fn load_data(&self) -> impl Future<Output = Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>>> + Send {
+        self.0.load_data_boxed()
+    }
+
+    fn revalidate_data<'a>(&'a self, current: &'a DataLoadResult<Data>) -> impl Future<Output = Result<Revalidation<Data>, Box<dyn Error + Send + Sync>>> + Send {
+        self.0.revalidate_data_boxed(current)
+    }
+}
+
+impl <Data: Send + Sync> Debug for BoxedDataProvider<Data> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("BoxedDataProvider").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,120 @@
+use std::error::Error;
+use std::marker::PhantomData;
+use std::time::{Instant, SystemTime};
+use hickory_resolver::error::ResolveError;
+use hickory_resolver::TokioAsyncResolver;
+use crate::data_providers::data_provider::{DataLoadResult, DataProvider};
+
+/// This data provider resolves TXT record and parses its content with specified function.
+/// Data is valid until TTL of the record expires.
+///
+/// Character strings of a record are concatenated, and multiple records are sorted and joined with `\n`,
+/// so records like `key=value` can be parsed as lines.
+/// Intended for tiny bootstrap configs, such as endpoint discovery.
+/// # Examples
+/// ```
+/// use std::collections::HashMap;
+/// use remote_config::data_providers::dns::DnsTxtDataProvider;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let data_provider = DnsTxtDataProvider::new("_config.example.com.", |bytes: &[u8]| {
+///     Ok(std::str::from_utf8(bytes)?
+///         .lines()
+///         .filter_map(|line| line.split_once('='))
+///         .map(|(key, value)| (key.to_string(), value.to_string()))
+///         .collect::<HashMap<_, _>>())
+/// })?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct DnsTxtDataProvider<Data: Send + Sync, Parser> {
+    resolver: TokioAsyncResolver,
+    name: String,
+    parser: Parser,
+    data_type: PhantomData<Data>
+}
+
+impl <Data, Parser> DnsTxtDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&[u8]) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Creates data provider that uses system resolver configuration (e.g. `/etc/resolv.conf`)
+    /// # Errors
+    /// If system configuration can't be read
+    pub fn new(name: impl Into<String>, parser: Parser) -> Result<Self, ResolveError> {
+        Ok(Self::with_resolver(TokioAsyncResolver::tokio_from_system_conf()?, name, parser))
+    }
+
+    /// Creates data provider that uses specified resolver
+    pub fn with_resolver(resolver: TokioAsyncResolver, name: impl Into<String>, parser: Parser) -> Self {
+        Self {
+            resolver,
+            name: name.into(),
+            parser,
+            data_type: PhantomData
+        }
+    }
+}
+
+impl <Data, Parser> DataProvider<Data> for DnsTxtDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&[u8]) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Resolves and parses TXT record
+    /// # Errors
+    /// If record can't be resolved or parser returns an error
+    async fn load_data(&self) -> Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>> {
+        let lookup = self.resolver.txt_lookup(self.name.as_str()).await?;
+        let mut records: Vec<Vec<u8>> = lookup.iter().map(|txt| txt.txt_data().concat()).collect();
+        records.sort();
+        let bytes = records.join(&b'\n');
+
+        // Lookup expires together with the record with the smallest TTL
+        let valid_until = SystemTime::now() + lookup.valid_until().saturating_duration_since(Instant::now());
+        let mut result = DataLoadResult::new((self.parser)(&bytes)?, false, valid_until);
+        result.metadata.size = Some(bytes.len() as u64);
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+    use std::time::{Duration, SystemTime};
+    use hickory_resolver::config::{NameServerConfigGroup, ResolverConfig, ResolverOpts};
+    use hickory_resolver::proto::op::{Message, MessageType};
+    use hickory_resolver::proto::rr::{RData, Record};
+    use hickory_resolver::proto::rr::rdata::TXT;
+    use hickory_resolver::TokioAsyncResolver;
+    use tokio::net::UdpSocket;
+    use crate::data_providers::data_provider::DataProvider;
+    use crate::data_providers::dns::DnsTxtDataProvider;
+
+    /// Answers every query with two TXT records
+    async fn serve(socket: UdpSocket) {
+        let mut buf = [0; 512];
+        while let Ok((len, peer)) = socket.recv_from(&mut buf).await {
+            let query = Message::from_vec(&buf[..len]).unwrap();
+            let name = query.queries()[0].name().clone();
+            let mut response = query.clone();
+            response.set_message_type(MessageType::Response).set_recursion_available(true);
+            response.add_answer(Record::from_rdata(name.clone(), 300, RData::TXT(TXT::new(vec!["region=".into(), "eu".into()]))));
+            response.add_answer(Record::from_rdata(name, 60, RData::TXT(TXT::new(vec!["endpoint=https://eu.example.com".into()]))));
+            socket.send_to(&response.to_vec().unwrap(), peer).await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn txt_record() {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let port = socket.local_addr().unwrap().port();
+        tokio::spawn(serve(socket));
+
+        let servers = NameServerConfigGroup::from_ips_clear(&[IpAddr::V4(Ipv4Addr::LOCALHOST)], port, true);
+        let resolver = TokioAsyncResolver::tokio(ResolverConfig::from_parts(None, vec![], servers), ResolverOpts::default());
+        let data_provider = DnsTxtDataProvider::with_resolver(resolver, "_config.example.com.", |bytes: &[u8]| Ok(String::from_utf8(bytes.to_vec())?));
+
+        let result = data_provider.load_data().await.unwrap();
+        assert_eq!(result.data, "endpoint=https://eu.example.com\nregion=eu");
+        let ttl = result.valid_until.duration_since(SystemTime::now()).unwrap();
+        assert!(ttl > Duration::from_secs(50) && ttl <= Duration::from_secs(60));
+    }
+}
@@ -0,0 +1,326 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Debug, Formatter};
+use std::io::ErrorKind;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use reqwest::Url;
+use tokio::task::AbortHandle;
+use crate::data_providers::data_provider::{DataLoadResult, DataProvider, InvalidationToken, Revalidation};
+use crate::duration::parse_duration;
+
+/// Default path of annotations file, as mounted by downward API volume in Kubernetes examples
+pub const DEFAULT_ANNOTATIONS_PATH: &str = "/etc/podinfo/annotations";
+/// Default prefix of hint annotations
+pub const DEFAULT_PREFIX: &str = "remote-config";
+
+/// Refresh hints read from pod annotations
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct RefreshHints {
+    /// Time after which data is loaded again (`{prefix}/poll-interval`, e.g. `30s`, `5m` or `90`)
+    pub poll_interval: Option<Duration>,
+    /// Url that data is loaded from (`{prefix}/url`)
+    pub url: Option<Url>
+}
+
+impl RefreshHints {
+    /// Parses hints from downward API annotations file (lines of `key="value"`).
+    /// Unknown annotations and invalid values are ignored.
+    pub fn parse(annotations: &str, prefix: &str) -> Self {
+        let annotations: HashMap<&str, String> = annotations.lines()
+            .filter_map(|line| line.split_once('='))
+            .map(|(key, value)| (key.trim(), unquote(value.trim())))
+            .collect();
+        let hint = |name: &str| annotations.get(format!("{prefix}/{name}").as_str());
+        Self {
+            poll_interval: hint("poll-interval").and_then(|value| parse_duration(value)),
+            url: hint("url").and_then(|value| Url::parse(value).ok())
+        }
+    }
+}
+
+/// Data provider that can be reconfigured with refresh hints
+pub trait Reconfigure {
+    /// Applies hints. Called on first load and every time hints change.
+    /// Hints that are not supported can be ignored.
+    fn reconfigure(&mut self, hints: &RefreshHints);
+}
+
+/// State shared with watch task
+#[derive(Debug, Default)]
+struct HintState {
+    /// Contents of annotations file that current hints were read from
+    annotations: Mutex<Option<String>>,
+    token: Mutex<InvalidationToken>
+}
+
+/// This data provider wraps another provider and reconfigures it with refresh hints, that are read from pod annotations
+/// exposed with Kubernetes downward API volume. It lets platform teams tune polling of whole fleet without image rebuilds.
+///
+/// Poll interval hint replaces lifetime of loaded data, and url hint is applied with [`Reconfigure`].
+/// Annotations file is checked periodically, and loaded data is invalidated as soon as it changes.
+/// If file does not exist (e.g. outside of cluster), wrapped provider is used as is.
+/// # Examples
+/// ```
+/// # #[cfg(feature = "json")] {
+/// use std::collections::HashMap;
+/// use reqwest::Url;
+/// use remote_config::data_providers::downward_api::HintedDataProvider;
+/// use remote_config::data_providers::http::HttpDataProvider;
+/// use remote_config::data_providers::http::serde_extractor::SerdeDataExtractor;
+///
+/// // Pod annotations:
+/// //   remote-config/poll-interval: 30s
+/// //   remote-config/url: https://config.internal/v2/app.json
+/// let http = HttpDataProvider::new(reqwest::Client::default(), Url::parse("https://config.internal/v1/app.json").unwrap(), SerdeDataExtractor::<HashMap<String, String>>::new());
+/// let data_provider = HintedDataProvider::new(http);
+/// # }
+/// ```
+pub struct HintedDataProvider<Data: Send + Sync, Provider> {
+    inner: tokio::sync::Mutex<Provider>,
+    path: PathBuf,
+    prefix: String,
+    check_interval: Duration,
+    state: Arc<HintState>,
+    /// Watch task, started on first load
+    watcher: Mutex<Option<AbortHandle>>,
+    data_type: PhantomData<Data>
+}
+
+impl <Data, Provider> HintedDataProvider<Data, Provider>
+where Data: Send + Sync, Provider: DataProvider<Data> + Reconfigure + Send
+{
+    /// Wraps data provider, reading hints from [`DEFAULT_ANNOTATIONS_PATH`] with [`DEFAULT_PREFIX`]
+    pub fn new(inner: Provider) -> Self {
+        Self {
+            inner: tokio::sync::Mutex::new(inner),
+            path: PathBuf::from(DEFAULT_ANNOTATIONS_PATH),
+            prefix: DEFAULT_PREFIX.to_string(),
+            check_interval: Duration::from_secs(10),
+            state: Arc::new(HintState::default()),
+            watcher: Mutex::new(None),
+            data_type: PhantomData
+        }
+    }
+
+    /// Path of downward API annotations file
+    pub fn with_path(mut self, path: impl AsRef<Path>) -> Self {
+        self.path = path.as_ref().to_path_buf();
+        self
+    }
+
+    /// Prefix of hint annotations. Default is [`DEFAULT_PREFIX`]
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// How often annotations file is checked for changes. Default is 10 seconds.
+    /// Kubelet updates downward API volumes with a delay anyway.
+    pub fn with_check_interval(mut self, interval: Duration) -> Self {
+        self.check_interval = interval;
+        self
+    }
+
+    /// Starts watch task, if it is not started yet
+    fn start_watching(&self) {
+        let mut watcher = self.watcher.lock().unwrap();
+        if watcher.is_some() {
+            return;
+        }
+        let path = self.path.clone();
+        let interval = self.check_interval;
+        let state = Arc::downgrade(&self.state);
+        let handle = tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                let Some(state) = state.upgrade() else {
+                    return;
+                };
+                match read_annotations(&path).await {
+                    Ok(annotations) if state.annotations.lock().unwrap().as_ref() != Some(&annotations) => {
+                        state.token.lock().unwrap().invalidate();
+                    },
+                    Ok(_) => {},
+                    Err(_err) => {
+                        #[cfg(feature = "tracing")]
+                        tracing::warn!(path = %path.display(), "Failed to read refresh hints: {_err}");
+                    }
+                }
+            }
+        });
+        *watcher = Some(handle.abort_handle());
+    }
+
+    async fn load(&self, current: Option<&DataLoadResult<Data>>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        // Token is replaced before reading, so changes made in between are not missed
+        let token = InvalidationToken::new();
+        *self.state.token.lock().unwrap() = token.clone();
+        let annotations = read_annotations(&self.path).await?;
+        let hints = RefreshHints::parse(&annotations, &self.prefix);
+
+        let mut inner = self.inner.lock().await;
+        let previous = self.state.annotations.lock().unwrap().replace(annotations.clone());
+        let previous = previous.map(|annotations| RefreshHints::parse(&annotations, &self.prefix));
+        let url_changed = previous.as_ref().is_some_and(|previous| previous.url != hints.url);
+        if previous.as_ref() != Some(&hints) {
+            inner.reconfigure(&hints);
+        }
+        let revalidation = match current {
+            // Data from previous url can't be revalidated
+            Some(current) if !url_changed => inner.revalidate_data(current).await?,
+            _ => Revalidation::Modified(inner.load_data().await?)
+        };
+        drop(inner);
+        self.start_watching();
+
+        let valid_until = |valid_until| hints.poll_interval.map_or(valid_until, |interval| SystemTime::now() + interval);
+        let invalidation = |inner: Option<InvalidationToken>| Some(match inner {
+            Some(inner) => InvalidationToken::any([inner, token.clone()]),
+            None => token.clone()
+        });
+        Ok(match revalidation {
+            Revalidation::Modified(mut result) => {
+                result.valid_until = valid_until(result.valid_until);
+                result.metadata.invalidation = invalidation(result.metadata.invalidation.take());
+                Revalidation::Modified(result)
+            },
+            Revalidation::NotModified { must_revalidate, valid_until: until, invalidation: inner, fetched_at } => Revalidation::NotModified {
+                must_revalidate,
+                valid_until: valid_until(until),
+                invalidation: invalidation(inner),
+                fetched_at
+            }
+        })
+    }
+}
+
+impl <Data, Provider> DataProvider<Data> for HintedDataProvider<Data, Provider>
+where Data: Send + Sync, Provider: DataProvider<Data> + Reconfigure + Send
+{
+    /// Applies current hints and loads data with wrapped provider
+    /// # Errors
+    /// If annotations file can't be read, or wrapped provider returns an error
+    async fn load_data(&self) -> Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>> {
+        match self.load(None).await? {
+            Revalidation::Modified(result) => Ok(result),
+            Revalidation::NotModified { .. } => unreachable!("data is loaded without current data")
+        }
+    }
+
+    /// Applies current hints and revalidates data with wrapped provider.
+    /// If url changed, data is loaded again.
+    async fn revalidate_data<'a>(&'a self, current: &'a DataLoadResult<Data>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        self.load(Some(current)).await
+    }
+}
+
+impl <Data: Send + Sync, Provider> Drop for HintedDataProvider<Data, Provider> {
+    fn drop(&mut self) {
+        if let Some(handle) = self.watcher.lock().unwrap().take() {
+            handle.abort();
+        }
+    }
+}
+
+impl <Data: Send + Sync, Provider> Debug for HintedDataProvider<Data, Provider> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HintedDataProvider")
+            .field("path", &self.path)
+            .field("prefix", &self.prefix)
+            .field("check_interval", &self.check_interval)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Reads annotations file. Missing file has no annotations.
+async fn read_annotations(path: &Path) -> Result<String, std::io::Error> {
+    match tokio::fs::read_to_string(path).await {
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(String::new()),
+        result => result
+    }
+}
+
+/// Removes quotes and escapes of downward API value
+fn unquote(value: &str) -> String {
+    let Some(value) = value.strip_prefix('"').and_then(|value| value.strip_suffix('"')) else {
+        return value.to_string();
+    };
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(char) = chars.next() {
+        if char != '\\' {
+            result.push(char);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some(escaped) => result.push(escaped),
+            None => {}
+        }
+    }
+    result
+}
+
+#[cfg(all(test, feature = "json"))]
+mod tests {
+    use std::collections::HashMap;
+    use std::time::{Duration, SystemTime};
+    use reqwest::Url;
+    use crate::data_providers::data_provider::{DataProvider, Revalidation};
+    use crate::data_providers::downward_api::{HintedDataProvider, RefreshHints};
+    use crate::data_providers::http::HttpDataProvider;
+    use crate::data_providers::http::serde_extractor::SerdeDataExtractor;
+
+    #[test]
+    fn annotations_are_parsed() {
+        let annotations = "kubernetes.io/config.seen=\"2026-10-16T10:00:00Z\"\nremote-config/poll-interval=\"5m\"\nremote-config/url=\"https://config.internal/app.json\"";
+        let hints = RefreshHints::parse(annotations, "remote-config");
+        assert_eq!(hints.poll_interval, Some(Duration::from_secs(300)));
+        assert_eq!(hints.url.unwrap().as_str(), "https://config.internal/app.json");
+        assert_eq!(RefreshHints::parse(annotations, "other"), RefreshHints::default());
+    }
+
+    #[tokio::test]
+    async fn hints_reconfigure_provider() {
+        let mut server = mockito::Server::new_async().await;
+        for version in ["v1", "v2"] {
+            server
+                .mock("GET", format!("/{version}").as_str())
+                .with_header("Content-Type", "application/json")
+                .with_header("Cache-Control", "max-age=3600")
+                .with_body(format!(r#"{{"version": "{version}"}}"#))
+                .create_async()
+                .await;
+        }
+        let path = std::env::temp_dir().join(format!("remote-config-annotations-{}", std::process::id()));
+        std::fs::write(&path, format!("remote-config/poll-interval=\"30\"\nremote-config/url=\"{}/v1\"", server.url())).unwrap();
+
+        let http = HttpDataProvider::new(reqwest::Client::default(), Url::parse("http://unused.invalid").unwrap(), SerdeDataExtractor::<HashMap<String, String>>::new());
+        let data_provider = HintedDataProvider::new(http).with_path(&path).with_check_interval(Duration::from_millis(20));
+        let result = data_provider.load_data().await.unwrap();
+        assert_eq!(result.data["version"], "v1");
+        assert!(result.valid_until < SystemTime::now() + Duration::from_secs(31));
+
+        // Changed annotations invalidate data, and new url is used
+        std::fs::write(&path, format!("remote-config/url=\"{}/v2\"", server.url())).unwrap();
+        let token = result.metadata.invalidation.clone().unwrap();
+        for _ in 0..100 {
+            if token.is_invalidated() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert!(token.is_invalidated());
+        let Revalidation::Modified(result) = data_provider.revalidate_data(&result).await.unwrap() else {
+            panic!("Expected data to be loaded from new url");
+        };
+        assert_eq!(result.data["version"], "v2");
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
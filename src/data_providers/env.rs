@@ -0,0 +1,124 @@
+use std::collections::hash_map::DefaultHasher;
+use std::env;
+use std::error::Error;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::time::{Duration, SystemTime};
+use serde::de::DeserializeOwned;
+use crate::data_providers::data_provider::{DataLoadResult, DataProvider, Revalidation};
+
+/// This data provider builds data from environment variables with specified prefix, using
+/// [envy](https://crates.io/crates/envy). Prefix is stripped and remaining names are lowercased,
+/// so `APP_LISTEN_PORT` is deserialized into `listen_port` field with `APP_` prefix.
+///
+/// It allows to use the same [`RemoteConfig`](crate::config::RemoteConfig) interface for locally provided config,
+/// e.g. during development. Variables are read again after max age, and data is replaced only if they changed.
+/// # Examples
+/// ```
+/// use serde::Deserialize;
+/// use remote_config::data_providers::env::EnvDataProvider;
+///
+/// #[derive(Deserialize)]
+/// struct Settings {
+///     listen_port: u16,
+///     #[serde(default)]
+///     debug: bool
+/// }
+///
+/// let data_provider = EnvDataProvider::<Settings>::new("APP_");
+/// ```
+#[derive(Debug)]
+pub struct EnvDataProvider<Data: DeserializeOwned + Send + Sync> {
+    prefix: String,
+    max_age: Duration,
+    data_type: PhantomData<Data>
+}
+
+impl <Data: DeserializeOwned + Send + Sync> EnvDataProvider<Data> {
+    /// Creates data provider for variables with specified prefix
+    pub fn new(prefix: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+            max_age: Duration::from_secs(60),
+            data_type: PhantomData
+        }
+    }
+
+    /// Time after which variables are read again. Default is one minute.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    fn load(&self, current_revision: Option<&str>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        let mut variables: Vec<(String, String)> = env::vars_os()
+            .filter_map(|(name, value)| Some((name.into_string().ok()?, value.into_string().ok()?)))
+            .filter(|(name, _)| name.starts_with(&self.prefix))
+            .collect();
+        variables.sort();
+
+        // Hash is only compared within the same process, so default hasher is enough
+        let mut hasher = DefaultHasher::new();
+        variables.hash(&mut hasher);
+        let revision = format!("{:016x}", hasher.finish());
+        let valid_until = SystemTime::now() + self.max_age;
+        if current_revision == Some(revision.as_str()) {
+            return Ok(Revalidation::NotModified { must_revalidate: false, valid_until, invalidation: None, fetched_at: None });
+        }
+
+        let size = variables.iter().map(|(name, value)| name.len() + value.len()).sum::<usize>();
+        let data = envy::prefixed(self.prefix.as_str()).from_iter(variables)?;
+        let mut result = DataLoadResult::new(data, false, valid_until);
+        result.metadata.size = Some(size as u64);
+        result.metadata.revision = Some(revision);
+        Ok(Revalidation::Modified(result))
+    }
+}
+
+impl <Data: DeserializeOwned + Send + Sync> DataProvider<Data> for EnvDataProvider<Data> {
+    /// Reads and deserializes variables
+    /// # Errors
+    /// If variables can't be deserialized (e.g. required variable is missing)
+    async fn load_data(&self) -> Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>> {
+        Ok(self.load(None)?.into_loaded())
+    }
+
+    /// Reads variables, but deserializes them only if they changed
+    async fn revalidate_data<'a>(&'a self, current: &'a DataLoadResult<Data>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        self.load(current.metadata.revision.as_deref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use serde::Deserialize;
+    use crate::data_providers::data_provider::{DataProvider, Revalidation};
+    use crate::data_providers::env::EnvDataProvider;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Settings {
+        listen_port: u16,
+        hosts: Vec<String>
+    }
+
+    #[tokio::test]
+    async fn variables_are_deserialized() {
+        env::set_var("REMOTE_CONFIG_ENV_TEST_LISTEN_PORT", "8080");
+        env::set_var("REMOTE_CONFIG_ENV_TEST_HOSTS", "a,b");
+        let data_provider = EnvDataProvider::<Settings>::new("REMOTE_CONFIG_ENV_TEST_");
+
+        let result = data_provider.load_data().await.unwrap();
+        assert_eq!(result.data, Settings { listen_port: 8080, hosts: vec!["a".to_string(), "b".to_string()] });
+        assert!(matches!(data_provider.revalidate_data(&result).await.unwrap(), Revalidation::NotModified { .. }));
+
+        env::set_var("REMOTE_CONFIG_ENV_TEST_LISTEN_PORT", "9090");
+        let Revalidation::Modified(result) = data_provider.revalidate_data(&result).await.unwrap() else {
+            panic!("Expected changed variables to be loaded");
+        };
+        assert_eq!(result.data.listen_port, 9090);
+
+        env::remove_var("REMOTE_CONFIG_ENV_TEST_LISTEN_PORT");
+        data_provider.load_data().await.expect_err("Expected missing variable to be reported");
+    }
+}
@@ -0,0 +1,259 @@
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use crate::data_providers::data_provider::{BoxedDataProvider, DataLoadResult, DataProvider};
+
+/// This data provider loads data from primary origin, and fails over to mirrors (in order) when it fails.
+///
+/// While data is served from a mirror, primary is probed at most once per probe interval,
+/// and validity of mirror data is capped by the next probe, so that config with [background updates](crate::config::RemoteConfig::follow_updates)
+/// keeps probing it. Provider fails back once primary loaded data for `failback_threshold` consecutive probes,
+/// so that flapping primary does not move traffic back and forth.
+/// Data loaded by probe before that is served only if all mirrors fail.
+///
+/// Every failover and failback is reported with tracing (if enabled), and current origin is available with [`FailoverProvider::active_origin`].
+/// # Examples
+/// ```
+/// use std::collections::HashMap;
+/// use std::time::Duration;
+/// use reqwest::Url;
+/// use remote_config::data_providers::data_provider::BoxedDataProvider;
+/// use remote_config::data_providers::failover::FailoverProvider;
+/// use remote_config::data_providers::http::HttpDataProvider;
+/// use remote_config::data_providers::http::serde_extractor::SerdeDataExtractor;
+///
+/// type Data = HashMap<String, String>;
+/// let origin = |url: &str| BoxedDataProvider::new(HttpDataProvider::new(reqwest::Client::default(), Url::parse(url).unwrap(), SerdeDataExtractor::<Data>::new()));
+/// let data_provider = FailoverProvider::new(vec![
+///     origin("https://api.example.com/cfg"),
+///     origin("https://mirror.example.com/cfg")
+/// ]).with_probe_interval(Duration::from_secs(30));
+/// ```
+pub struct FailoverProvider<Data: Send + Sync> {
+    origins: Vec<BoxedDataProvider<Data>>,
+    probe_interval: Duration,
+    failback_threshold: u32,
+    state: Mutex<FailoverState>
+}
+
+#[derive(Debug)]
+struct FailoverState {
+    /// Index of origin that data is served from
+    active: usize,
+    /// Number of consecutive successful probes of primary
+    healthy_probes: u32,
+    /// Primary is not probed before this time
+    next_probe: SystemTime
+}
+
+impl <Data: Send + Sync> FailoverProvider<Data> {
+    /// Constructs provider with primary origin followed by mirrors.
+    /// Primary is probed every 60 seconds while failed over, and three successful probes are required to fail back.
+    pub fn new(origins: Vec<BoxedDataProvider<Data>>) -> Self {
+        Self {
+            origins,
+            probe_interval: Duration::from_secs(60),
+            failback_threshold: 3,
+            state: Mutex::new(FailoverState { active: 0, healthy_probes: 0, next_probe: SystemTime::UNIX_EPOCH })
+        }
+    }
+
+    /// Sets how often primary is probed while data is served from a mirror
+    pub fn with_probe_interval(mut self, interval: Duration) -> Self {
+        self.probe_interval = interval;
+        self
+    }
+
+    /// Sets number of consecutive successful probes of primary that are required to fail back to it. Zero is treated as one.
+    pub fn with_failback_threshold(mut self, threshold: u32) -> Self {
+        self.failback_threshold = threshold;
+        self
+    }
+
+    /// Index of origin that data is currently served from. Zero is primary
+    pub fn active_origin(&self) -> usize {
+        self.state.lock().unwrap().active
+    }
+}
+
+/// Records switch to another origin
+fn switch_origin(state: &mut FailoverState, origin: usize) {
+    if state.active == origin {
+        return;
+    }
+    #[cfg(feature = "tracing")]
+    if origin == 0 {
+        tracing::info!(from = state.active, "Failed back to primary origin");
+    } else {
+        tracing::warn!(from = state.active, to = origin, "Failed over to mirror origin");
+    }
+    state.active = origin;
+    state.healthy_probes = 0;
+}
+
+impl <Data: Send + Sync> DataProvider<Data> for FailoverProvider<Data> {
+    /// Loads data from active origin, falling back to other origins if it fails
+    /// # Errors
+    /// If all origins failed to load data
+    fn load_data(&self) -> impl Future<Output = Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>>> + Send {
+        // Futures are created before async block, so that provider itself is not required to be Sync.
+        // They do nothing until polled, so origins that are not tried are not loaded
+        let mut pending: Vec<_> = self.origins.iter().map(|origin| Some(origin.load_data())).collect();
+        let (state, probe_interval, failback_threshold) = (&self.state, self.probe_interval, self.failback_threshold.max(1));
+
+        async move {
+            let now = SystemTime::now();
+            let (active, probe) = {
+                let mut state = state.lock().unwrap();
+                let probe = state.active != 0 && now >= state.next_probe;
+                if probe {
+                    state.next_probe = now + probe_interval;
+                }
+                (state.active, probe)
+            };
+
+            let mut errors: Vec<Box<dyn Error + Send + Sync>> = Vec::new();
+            let mut probed = None;
+            if probe {
+                let result = pending[0].take().expect("primary is not loaded yet").await;
+                let mut state = state.lock().unwrap();
+                match result {
+                    Ok(result) => {
+                        state.healthy_probes += 1;
+                        if state.healthy_probes >= failback_threshold {
+                            switch_origin(&mut state, 0);
+                            return Ok(result);
+                        }
+                        probed = Some(result);
+                    },
+                    Err(err) => {
+                        #[cfg(feature = "tracing")]
+                        tracing::debug!("Primary origin probe failed: {err}");
+                        state.healthy_probes = 0;
+                        errors.push(err);
+                    }
+                }
+            }
+
+            let order = std::iter::once(active).chain((0..pending.len()).filter(|origin| *origin != active));
+            for origin in order {
+                let Some(future) = pending.get_mut(origin).and_then(Option::take) else {
+                    continue;
+                };
+                match future.await {
+                    Ok(mut result) => {
+                        let mut state = state.lock().unwrap();
+                        if origin != 0 {
+                            if state.active == 0 {
+                                state.next_probe = now + probe_interval;
+                            }
+                            result.valid_until = result.valid_until.min(state.next_probe);
+                        }
+                        switch_origin(&mut state, origin);
+                        return Ok(result);
+                    },
+                    Err(err) => {
+                        #[cfg(feature = "tracing")]
+                        tracing::warn!(origin, "Origin failed to load data: {err}");
+                        errors.push(err);
+                    }
+                }
+            }
+            if let Some(mut result) = probed {
+                #[cfg(feature = "tracing")]
+                tracing::warn!("All mirrors failed, serving data loaded by primary origin probe");
+                // Origin is not switched, so validity is capped by the next probe as for mirror data
+                result.valid_until = result.valid_until.min(state.lock().unwrap().next_probe);
+                return Ok(result);
+            }
+            Err(FailoverError { errors }.into())
+        }
+    }
+}
+
+impl <Data: Send + Sync> Debug for FailoverProvider<Data> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FailoverProvider")
+            .field("origins", &self.origins.len())
+            .field("probe_interval", &self.probe_interval)
+            .field("failback_threshold", &self.failback_threshold)
+            .field("state", &self.state)
+            .finish()
+    }
+}
+
+/// All origins of [`FailoverProvider`] failed to load data
+#[derive(Debug)]
+pub struct FailoverError {
+    /// Errors of tried origins, in order they were tried
+    pub errors: Vec<Box<dyn Error + Send + Sync>>
+}
+
+impl Display for FailoverError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "all {} origins failed to load data", self.errors.len())
+    }
+}
+
+impl Error for FailoverError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.errors.last().map(|err| err.as_ref() as &(dyn Error + 'static))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+    use crate::data_providers::data_provider::{BoxedDataProvider, DataProvider};
+    use crate::data_providers::failover::{FailoverError, FailoverProvider};
+    use crate::data_providers::memory::InMemoryDataProvider;
+
+    #[tokio::test]
+    async fn fails_back_after_consecutive_probes() {
+        let primary = InMemoryDataProvider::new("primary").with_max_age(Duration::from_secs(600));
+        let mirror = InMemoryDataProvider::new("mirror").with_max_age(Duration::from_secs(600));
+        let data_provider = FailoverProvider::new(vec![BoxedDataProvider::new(primary.clone()), BoxedDataProvider::new(mirror.clone())])
+            .with_probe_interval(Duration::ZERO)
+            .with_failback_threshold(2);
+        assert_eq!(data_provider.load_data().await.unwrap().data, "primary");
+
+        primary.fail("primary is down");
+        let result = data_provider.load_data().await.unwrap();
+        assert_eq!(result.data, "mirror");
+        assert!(result.valid_until <= SystemTime::now());
+        assert_eq!(data_provider.active_origin(), 1);
+
+        primary.set("primary");
+        assert_eq!(data_provider.load_data().await.unwrap().data, "mirror");
+        assert_eq!(data_provider.load_data().await.unwrap().data, "primary");
+        assert_eq!(data_provider.active_origin(), 0);
+
+        primary.fail("primary is down");
+        mirror.fail("mirror is down");
+        let err = data_provider.load_data().await.unwrap_err().downcast::<FailoverError>().unwrap();
+        assert_eq!(err.errors.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn serves_probed_primary_when_mirrors_fail() {
+        let primary = InMemoryDataProvider::new("primary").with_max_age(Duration::from_secs(600));
+        let mirror = InMemoryDataProvider::new("mirror").with_max_age(Duration::from_secs(600));
+        let data_provider = FailoverProvider::new(vec![BoxedDataProvider::new(primary.clone()), BoxedDataProvider::new(mirror.clone())])
+            .with_probe_interval(Duration::ZERO)
+            .with_failback_threshold(2);
+        primary.fail("primary is down");
+        assert_eq!(data_provider.load_data().await.unwrap().data, "mirror");
+
+        primary.set("primary");
+        mirror.fail("mirror is down");
+        let result = data_provider.load_data().await.unwrap();
+        assert_eq!(result.data, "primary");
+        assert!(result.valid_until <= SystemTime::now());
+        assert_eq!(data_provider.active_origin(), 1);
+
+        assert_eq!(data_provider.load_data().await.unwrap().data, "primary");
+        assert_eq!(data_provider.active_origin(), 0);
+    }
+}
@@ -0,0 +1,286 @@
+use std::collections::hash_map::DefaultHasher;
+use std::error::Error;
+use std::ffi::OsStr;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use crate::data_providers::data_provider::{DataLoadResult, DataProvider, InvalidationToken, Revalidation};
+
+/// Default time after which file is read again, even if no change was detected
+pub const DEFAULT_MAX_AGE: Duration = Duration::from_secs(60 * 60);
+/// Default time without file changes, after which file is read
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(50);
+/// Directory that Kubernetes atomically swaps when ConfigMap or Secret volume is updated
+const KUBERNETES_DATA_DIR: &str = "..data";
+
+/// This data provider reads data from local file and parses it with specified function.
+/// File is watched with [notify](https://crates.io/crates/notify), and loaded data is invalidated as soon as file changes,
+/// so next [`RemoteConfig::load`](crate::config::RemoteConfig::load) call starts revalidation.
+///
+/// Parent directory is watched, so file replaced with atomic rename (as editors and ConfigMap volumes do) is still tracked.
+/// File is read only after it was not changed for debounce period, so bursts of writes are read once.
+/// Hash of content is reported as revision, and data is not parsed again if content did not change.
+/// Data is reported as fetched when file is read. If file caches remote data (e.g. written by sidecar),
+/// its modification time can be reported instead (see [`FileDataProvider::with_modification_time_as_fetch_time`]).
+///
+/// Loaded data can be used while revalidation is in progress (`must_revalidate` is false).
+/// # Examples
+/// ```no_run
+/// # #[cfg(feature = "json")] {
+/// use std::collections::HashMap;
+/// use remote_config::data_providers::file::FileDataProvider;
+///
+/// let data_provider = FileDataProvider::new("/etc/app/config.json", |bytes: &[u8]| {
+///     Ok(serde_json::from_slice::<HashMap<String, String>>(bytes)?)
+/// }).unwrap();
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct FileDataProvider<Data: Send + Sync, Parser> {
+    path: PathBuf,
+    parser: Parser,
+    max_age: Duration,
+    debounce: Duration,
+    /// Report modification time of file as fetch time of data
+    mtime_as_fetch_time: bool,
+    /// Token of last loaded data, invalidated by watcher
+    current: Arc<Mutex<InvalidationToken>>,
+    /// Time of last detected change
+    last_change: Arc<Mutex<Option<Instant>>>,
+    /// Watching stops when watcher is dropped
+    _watcher: Mutex<RecommendedWatcher>,
+    data_type: PhantomData<Data>
+}
+
+impl <Data, Parser> FileDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&[u8]) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Creates data provider and starts watching file
+    /// # Errors
+    /// If file watcher can't be created or file can't be watched (e.g. it or its directory doesn't exist)
+    pub fn new(path: impl AsRef<Path>, parser: Parser) -> notify::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if !path.exists() {
+            return Err(notify::Error::path_not_found().add_path(path));
+        }
+        let directory = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from(".")
+        };
+        let current = Arc::new(Mutex::new(InvalidationToken::new()));
+        let last_change = Arc::new(Mutex::new(None));
+
+        let (watched, changed) = (current.clone(), last_change.clone());
+        let file_name = path.file_name().map(OsStr::to_os_string);
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            let Ok(event) = event else {
+                return;
+            };
+            if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)) {
+                return;
+            }
+            let relevant = event.paths.iter()
+                .filter_map(|path| path.file_name())
+                .any(|name| Some(name) == file_name.as_deref() || name == KUBERNETES_DATA_DIR);
+            if relevant {
+                *changed.lock().unwrap() = Some(Instant::now());
+                watched.lock().unwrap().invalidate();
+            }
+        })?;
+        watcher.watch(&directory, RecursiveMode::NonRecursive)?;
+
+        Ok(Self {
+            path,
+            parser,
+            max_age: DEFAULT_MAX_AGE,
+            debounce: DEFAULT_DEBOUNCE,
+            mtime_as_fetch_time: false,
+            current,
+            last_change,
+            _watcher: Mutex::new(watcher),
+            data_type: PhantomData
+        })
+    }
+
+    /// Time after which file is read again, even if no change was detected.
+    /// Default is [`DEFAULT_MAX_AGE`].
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Time without changes, that must pass after file was changed before it is read.
+    /// Default is [`DEFAULT_DEBOUNCE`].
+    pub fn with_debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
+    /// Report modification time of file as [`LoadMetadata::fetched_at`](crate::data_providers::data_provider::LoadMetadata::fetched_at),
+    /// so file that caches remote data (e.g. written by sidecar) is as old as its last write.
+    /// Don't enable it for static files, that are edited rarely, as they never appear recent
+    /// (e.g. to [`ConfigManager::barrier`](crate::manager::ConfigManager::barrier)).
+    pub fn with_modification_time_as_fetch_time(mut self) -> Self {
+        self.mtime_as_fetch_time = true;
+        self
+    }
+
+    /// Waits until file is not changed for debounce period
+    async fn settle(&self) {
+        loop {
+            let Some(last_change) = *self.last_change.lock().unwrap() else {
+                return;
+            };
+            match (last_change + self.debounce).checked_duration_since(Instant::now()) {
+                Some(wait) if !wait.is_zero() => tokio::time::sleep(wait).await,
+                _ => return
+            }
+        }
+    }
+
+    async fn load(&self, current_revision: Option<&str>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        self.settle().await;
+        // Token is replaced before reading, so changes made during read are not missed
+        let token = InvalidationToken::new();
+        *self.current.lock().unwrap() = token.clone();
+
+        // Modification time is read first, so that data is never reported newer than it is
+        let fetched_at = match self.mtime_as_fetch_time {
+            true => tokio::fs::metadata(&self.path).await?.modified().ok(),
+            false => None
+        };
+        let bytes = tokio::fs::read(&self.path).await?;
+        let mut hasher = DefaultHasher::new();
+        bytes.hash(&mut hasher);
+        let revision = format!("{:016x}", hasher.finish());
+        let valid_until = SystemTime::now() + self.max_age;
+        if current_revision == Some(revision.as_str()) {
+            return Ok(Revalidation::NotModified { must_revalidate: false, valid_until, invalidation: Some(token), fetched_at });
+        }
+
+        let mut result = DataLoadResult::new((self.parser)(&bytes)?, false, valid_until);
+        result.metadata.size = Some(bytes.len() as u64);
+        result.metadata.revision = Some(revision);
+        result.metadata.invalidation = Some(token);
+        result.metadata.fetched_at = fetched_at;
+        Ok(Revalidation::Modified(result))
+    }
+}
+
+impl <Data, Parser> DataProvider<Data> for FileDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&[u8]) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Reads and parses file
+    /// # Errors
+    /// If file can't be read, or parser returns an error
+    async fn load_data(&self) -> Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>> {
+        Ok(self.load(None).await?.into_loaded())
+    }
+
+    /// Reads file, but parses it only if content changed
+    async fn revalidate_data<'a>(&'a self, current: &'a DataLoadResult<Data>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        self.load(current.metadata.revision.as_deref()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use std::time::{Duration, SystemTime};
+    use crate::data_providers::data_provider::{DataProvider, InvalidationToken, Revalidation};
+    use crate::data_providers::file::FileDataProvider;
+
+    fn parse(bytes: &[u8]) -> Result<String, Box<dyn Error + Send + Sync>> {
+        Ok(String::from_utf8(bytes.to_vec())?)
+    }
+
+    #[tokio::test]
+    async fn change_invalidates_data() {
+        let path = std::env::temp_dir().join(format!("remote-config-file-{}", std::process::id()));
+        std::fs::write(&path, "v1").unwrap();
+
+        let data_provider = FileDataProvider::new(&path, parse).unwrap();
+        let first = data_provider.load_data().await.unwrap();
+        assert_eq!(first.data, "v1");
+        assert_eq!(first.metadata.size, Some(2));
+        let token = first.metadata.invalidation.unwrap();
+        assert!(!token.is_invalidated());
+
+        std::fs::write(&path, "v2").unwrap();
+        for _ in 0..100 {
+            if token.is_invalidated() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert!(token.is_invalidated());
+        assert_eq!(data_provider.load_data().await.unwrap().data, "v2");
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    async fn wait_for_invalidation(token: &InvalidationToken) {
+        for _ in 0..100 {
+            if token.is_invalidated() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert!(token.is_invalidated());
+    }
+
+    #[tokio::test]
+    async fn atomic_rename_is_tracked() {
+        let directory = std::env::temp_dir().join(format!("remote-config-rename-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let (path, staged) = (directory.join("config"), directory.join("config.tmp"));
+        std::fs::write(&path, "v1").unwrap();
+
+        let data_provider = FileDataProvider::new(&path, parse).unwrap();
+        let first = data_provider.load_data().await.unwrap();
+
+        // Same content is written again, so data is not parsed again
+        std::fs::write(&staged, "v1").unwrap();
+        std::fs::rename(&staged, &path).unwrap();
+        wait_for_invalidation(first.metadata.invalidation.as_ref().unwrap()).await;
+        let Revalidation::NotModified { invalidation: Some(token), .. } = data_provider.revalidate_data(&first).await.unwrap() else {
+            panic!("Expected unchanged content to be reported as not modified");
+        };
+
+        // Replaced file is still watched
+        std::fs::write(&staged, "v2").unwrap();
+        std::fs::rename(&staged, &path).unwrap();
+        wait_for_invalidation(&token).await;
+        let Revalidation::Modified(second) = data_provider.revalidate_data(&first).await.unwrap() else {
+            panic!("Expected changed content to be loaded");
+        };
+        assert_eq!(second.data, "v2");
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[tokio::test]
+    async fn modification_time_is_fetch_time() {
+        let path = std::env::temp_dir().join(format!("remote-config-mtime-{}", std::process::id()));
+        std::fs::write(&path, "v1").unwrap();
+        let written = SystemTime::now() - Duration::from_secs(24 * 60 * 60);
+        std::fs::File::options().write(true).open(&path).unwrap().set_modified(written).unwrap();
+
+        // File is fetched when it is read by default
+        let data_provider = FileDataProvider::new(&path, parse).unwrap();
+        assert_eq!(data_provider.load_data().await.unwrap().metadata.fetched_at, None);
+
+        let data_provider = FileDataProvider::new(&path, parse).unwrap().with_modification_time_as_fetch_time();
+        let first = data_provider.load_data().await.unwrap();
+        assert_eq!(first.metadata.fetched_at, Some(written));
+        let Revalidation::NotModified { fetched_at, .. } = data_provider.revalidate_data(&first).await.unwrap() else {
+            panic!("Expected unchanged file to be reported as not modified");
+        };
+        assert_eq!(fetched_at, Some(written));
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,230 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::marker::PhantomData;
+use std::time::{Duration, SystemTime};
+use reqwest::header::{AUTHORIZATION, ETAG, IF_NONE_MATCH};
+use reqwest::{StatusCode, Url};
+use serde_json::Value;
+use crate::data_providers::data_provider::{DataLoadResult, DataProvider, Revalidation};
+use crate::data_providers::gcs::{GcsCredentials, TokenSource};
+
+/// Default Firebase Remote Config API endpoint
+pub const DEFAULT_ENDPOINT: &str = "https://firebaseremoteconfig.googleapis.com";
+/// OAuth scope that is requested for service account tokens
+const REMOTE_CONFIG_SCOPE: &str = "https://www.googleapis.com/auth/firebase.remoteconfig";
+
+/// Default values of Remote Config template parameters by parameter name
+pub type FirebaseParameters = BTreeMap<String, String>;
+
+/// Remote Config API returned an error
+#[derive(Debug)]
+pub struct FirebaseError {
+    /// Http status
+    pub status: StatusCode,
+    /// Error message, if reported
+    pub message: Option<String>
+}
+
+impl Display for FirebaseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Remote Config request failed with status {}", self.status)?;
+        if let Some(message) = &self.message {
+            write!(f, " ({message})")?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for FirebaseError {}
+
+/// This data provider loads Remote Config template of Firebase project and maps default values of its parameters
+/// (including parameters in groups) into data with specified function.
+/// Parameters that use in-app default are omitted, and conditional values are ignored.
+///
+/// ETag of template is reported as revision, and template is fetched with `If-None-Match`,
+/// so data is not mapped again if template was not published since last fetch.
+/// Credentials are the same as for [`GcsDataProvider`](crate::data_providers::gcs::GcsDataProvider).
+/// # Examples
+/// ```
+/// use remote_config::data_providers::firebase::{FirebaseParameters, FirebaseRemoteConfigProvider};
+/// use remote_config::data_providers::gcs::GcsCredentials;
+///
+/// struct Flags {
+///     new_checkout: bool
+/// }
+///
+/// let data_provider = FirebaseRemoteConfigProvider::new(reqwest::Client::default(), "my-project", GcsCredentials::metadata_server(), |parameters: &FirebaseParameters| {
+///     Ok(Flags { new_checkout: parameters.get("new_checkout").is_some_and(|value| value == "true") })
+/// });
+/// ```
+pub struct FirebaseRemoteConfigProvider<Data: Send + Sync, Parser> {
+    client: reqwest::Client,
+    endpoint: Url,
+    project: String,
+    tokens: TokenSource,
+    max_age: Duration,
+    parser: Parser,
+    data_type: PhantomData<Data>
+}
+
+impl <Data, Parser> FirebaseRemoteConfigProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&FirebaseParameters) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Creates data provider for template of specified project (id or number)
+    pub fn new(client: reqwest::Client, project: impl Into<String>, credentials: GcsCredentials, parser: Parser) -> Self {
+        Self {
+            client,
+            endpoint: Url::parse(DEFAULT_ENDPOINT).expect("valid url"),
+            project: project.into(),
+            tokens: TokenSource::new(credentials, REMOTE_CONFIG_SCOPE),
+            max_age: Duration::from_secs(60),
+            parser,
+            data_type: PhantomData
+        }
+    }
+
+    /// Remote Config API endpoint. Default is [`DEFAULT_ENDPOINT`]
+    pub fn with_endpoint(mut self, endpoint: Url) -> Self {
+        self.endpoint = endpoint;
+        self
+    }
+
+    /// Time after which template is fetched again. Default is one minute.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    fn template_url(&self) -> Url {
+        let mut url = self.endpoint.clone();
+        url.path_segments_mut()
+            .expect("endpoint is a base url")
+            .pop_if_empty()
+            .extend(["v1", "projects", &self.project, "remoteConfig"]);
+        url
+    }
+
+    async fn load(&self, current_revision: Option<&str>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        let token = self.tokens.access_token(&self.client).await?;
+        let mut request = self.client.get(self.template_url());
+        if !token.is_empty() {
+            request = request.header(AUTHORIZATION, format!("Bearer {token}"));
+        }
+        if let Some(etag) = current_revision {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        let response = request.send().await?;
+        let valid_until = SystemTime::now() + self.max_age;
+        match response.status() {
+            StatusCode::NOT_MODIFIED if current_revision.is_some() => {
+                return Ok(Revalidation::NotModified { must_revalidate: false, valid_until, invalidation: None, fetched_at: None });
+            },
+            status if !status.is_success() => {
+                let body: Value = response.bytes().await.ok()
+                    .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+                    .unwrap_or_default();
+                let message = body["error"]["message"].as_str().map(str::to_string);
+                return Err(FirebaseError { status, message }.into());
+            },
+            _ => {}
+        }
+
+        let revision = response.headers().get(ETAG).and_then(|value| value.to_str().ok()).map(str::to_string);
+        let bytes = response.bytes().await?;
+        let template: Value = serde_json::from_slice(&bytes)?;
+        let groups = template["parameterGroups"].as_object().into_iter().flatten().map(|(_, group)| &group["parameters"]);
+        let parameters: FirebaseParameters = std::iter::once(&template["parameters"])
+            .chain(groups)
+            .filter_map(Value::as_object)
+            .flatten()
+            .filter_map(|(name, parameter)| Some((name.clone(), parameter["defaultValue"]["value"].as_str()?.to_string())))
+            .collect();
+
+        let mut result = DataLoadResult::new((self.parser)(&parameters)?, false, valid_until);
+        result.metadata.size = Some(bytes.len() as u64);
+        result.metadata.revision = revision;
+        Ok(Revalidation::Modified(result))
+    }
+}
+
+impl <Data, Parser> DataProvider<Data> for FirebaseRemoteConfigProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&FirebaseParameters) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Fetches template and maps its parameters into data
+    /// # Errors
+    /// If access token can't be obtained, request fails, API returns an error or parser returns an error
+    async fn load_data(&self) -> Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>> {
+        match self.load(None).await? {
+            Revalidation::Modified(result) => Ok(result),
+            Revalidation::NotModified { .. } => unreachable!("request is not conditional")
+        }
+    }
+
+    /// Fetches template only if its ETag changed
+    async fn revalidate_data<'a>(&'a self, current: &'a DataLoadResult<Data>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        self.load(current.metadata.revision.as_deref()).await
+    }
+}
+
+impl <Data: Send + Sync, Parser> Debug for FirebaseRemoteConfigProvider<Data, Parser> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FirebaseRemoteConfigProvider")
+            .field("endpoint", &self.endpoint)
+            .field("project", &self.project)
+            .field("tokens", &self.tokens)
+            .field("max_age", &self.max_age)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mockito::Matcher;
+    use reqwest::Url;
+    use crate::data_providers::data_provider::{DataProvider, Revalidation};
+    use crate::data_providers::firebase::{FirebaseParameters, FirebaseRemoteConfigProvider};
+    use crate::data_providers::gcs::GcsCredentials;
+
+    #[tokio::test]
+    async fn conditional_fetch() {
+        let mut server = mockito::Server::new_async().await;
+        let template = server
+            .mock("GET", "/v1/projects/my-project/remoteConfig")
+            .match_header("authorization", "Bearer secret")
+            .match_header("if-none-match", Matcher::Missing)
+            .with_header("ETag", "etag-1")
+            .with_body(r#"{
+                "parameters": {
+                    "welcome": {"defaultValue": {"value": "hello"}, "conditionalValues": {"ios": {"value": "hi"}}},
+                    "in_app": {"defaultValue": {"useInAppDefault": true}}
+                },
+                "parameterGroups": {"checkout": {"parameters": {"new_checkout": {"defaultValue": {"value": "true"}}}}},
+                "version": {"versionNumber": "7"}
+            }"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let not_modified = server
+            .mock("GET", "/v1/projects/my-project/remoteConfig")
+            .match_header("if-none-match", "etag-1")
+            .with_status(304)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let data_provider = FirebaseRemoteConfigProvider::new(reqwest::Client::default(), "my-project", GcsCredentials::Token("secret".to_string()), |parameters: &FirebaseParameters| {
+            Ok(parameters.clone())
+        }).with_endpoint(Url::parse(&server.url()).unwrap());
+
+        let result = data_provider.load_data().await.unwrap();
+        assert_eq!(result.data.len(), 2);
+        assert_eq!(result.data["welcome"], "hello");
+        assert_eq!(result.data["new_checkout"], "true");
+        assert_eq!(result.metadata.revision.as_deref(), Some("etag-1"));
+
+        assert!(matches!(data_provider.revalidate_data(&result).await.unwrap(), Revalidation::NotModified { .. }));
+        template.assert_async().await;
+        not_modified.assert_async().await;
+    }
+}
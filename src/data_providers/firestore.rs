@@ -0,0 +1,525 @@
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, SystemTime};
+use reqwest::header::{AUTHORIZATION, CONTENT_TYPE};
+use reqwest::{RequestBuilder, Response, StatusCode, Url};
+use serde_json::{json, Map, Value};
+use tokio::task::AbortHandle;
+use crate::content_hash;
+use crate::data_providers::data_provider::{DataLoadResult, DataProvider, InvalidationToken, Revalidation};
+
+/// Default Firestore API address
+pub const DEFAULT_API_URL: &str = "https://firestore.googleapis.com/v1";
+/// Id of the only target of listen stream
+const TARGET_ID: u32 = 1;
+
+/// This data provider reads Firestore document or all documents of collection with REST API, and parses them with specified function.
+/// Path with even number of segments (e.g. `config/app`) refers to document, and path with odd number (e.g. `flags` or `config/app/flags`) refers to collection.
+///
+/// Parser receives JSON with plain values instead of Firestore typed values: fields of document,
+/// or object that maps ids of collection documents to their fields. Integers are converted to JSON numbers,
+/// timestamps, references and bytes (base64) are kept as strings.
+///
+/// Update time of document is reported as revision. For collection, hash of update times of all documents is reported instead,
+/// so deletion of document changes revision too. Documents are not parsed again if revision did not change.
+///
+/// With [`FirestoreDataProvider::with_listen`], background task keeps listen stream open, and invalidates loaded data as soon as target is modified.
+/// # Examples
+/// ```
+/// # #[cfg(feature = "json")] {
+/// use std::collections::HashMap;
+/// use remote_config::data_providers::firestore::FirestoreDataProvider;
+///
+/// let data_provider = FirestoreDataProvider::new(reqwest::Client::default(), "acme-prod", "config/app", |bytes: &[u8]| {
+///     Ok(serde_json::from_slice::<HashMap<String, String>>(bytes)?)
+/// }).with_access_token("ya29....").with_listen();
+/// # }
+/// ```
+pub struct FirestoreDataProvider<Data: Send + Sync, Parser> {
+    client: reqwest::Client,
+    api_url: Url,
+    project: String,
+    database: String,
+    path: String,
+    access_token: Option<String>,
+    parser: Parser,
+    max_age: Duration,
+    listen: bool,
+    token: Arc<Mutex<InvalidationToken>>,
+    /// Listen task, started on first load
+    listener: Mutex<Option<AbortHandle>>,
+    data_type: PhantomData<Data>
+}
+
+impl <Data, Parser> FirestoreDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&[u8]) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Creates data provider for document or collection at specified path of `(default)` database
+    pub fn new(client: reqwest::Client, project: impl Into<String>, path: impl Into<String>, parser: Parser) -> Self {
+        Self {
+            client,
+            api_url: Url::parse(DEFAULT_API_URL).expect("valid url"),
+            project: project.into(),
+            database: "(default)".to_string(),
+            path: path.into(),
+            access_token: None,
+            parser,
+            max_age: Duration::from_secs(60),
+            listen: false,
+            token: Arc::new(Mutex::new(InvalidationToken::new())),
+            listener: Mutex::new(None),
+            data_type: PhantomData
+        }
+    }
+
+    /// Database id. Default is `(default)`.
+    pub fn with_database(mut self, database: impl Into<String>) -> Self {
+        self.database = database.into();
+        self
+    }
+
+    /// OAuth 2.0 access token that is sent as bearer token. Without it, client must authenticate requests on its own (e.g. with default headers),
+    /// or documents must be readable without authentication.
+    pub fn with_access_token(mut self, token: impl Into<String>) -> Self {
+        self.access_token = Some(token.into());
+        self
+    }
+
+    /// Firestore API address, e.g. `http://localhost:8080/v1` of emulator. Default is [`DEFAULT_API_URL`].
+    pub fn with_api_url(mut self, api_url: Url) -> Self {
+        self.api_url = api_url;
+        self
+    }
+
+    /// Time after which documents are read again. Default is 60 seconds.
+    /// With listen stream it can be much longer, as changes are detected immediately.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Invalidate data when document or collection is modified.
+    /// Listening starts on first data load, and stops when data provider is dropped.
+    pub fn with_listen(mut self) -> Self {
+        self.listen = true;
+        self
+    }
+
+    fn segments(&self) -> impl Iterator<Item = &str> {
+        self.path.split('/').filter(|segment| !segment.is_empty())
+    }
+
+    fn is_collection(&self) -> bool {
+        self.segments().count() % 2 == 1
+    }
+
+    /// Resource name of database documents root
+    fn documents_root(&self) -> String {
+        format!("projects/{}/databases/{}/documents", self.project, self.database)
+    }
+
+    fn documents_url(&self, path: impl IntoIterator<Item = impl AsRef<str>>) -> Url {
+        let mut url = self.api_url.clone();
+        url.path_segments_mut()
+            .expect("api url is a base url")
+            .pop_if_empty()
+            .extend(["projects", &self.project, "databases", &self.database, "documents"])
+            .extend(path);
+        url
+    }
+
+    fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+        match &self.access_token {
+            Some(token) => request.header(AUTHORIZATION, format!("Bearer {token}")),
+            None => request
+        }
+    }
+
+    async fn send(request: RequestBuilder) -> Result<Response, Box<dyn Error + Send + Sync>> {
+        let response = request.send().await?;
+        match response.status() {
+            status if status.is_success() => Ok(response),
+            StatusCode::NOT_FOUND => Err(FirestoreError::NotFound.into()),
+            status => Err(FirestoreError::Status(status).into())
+        }
+    }
+
+    /// Listen request for document or collection
+    fn listen_request(&self) -> Value {
+        let root = self.documents_root();
+        let target = if self.is_collection() {
+            let segments: Vec<_> = self.segments().collect();
+            let (collection, parent) = segments.split_last().expect("collection path is not empty");
+            let parent = parent.iter().fold(root, |parent, segment| format!("{parent}/{segment}"));
+            json!({ "query": { "parent": parent, "structuredQuery": { "from": [{ "collectionId": collection }] } } })
+        } else {
+            json!({ "documents": { "documents": [format!("{root}/{}", self.segments().collect::<Vec<_>>().join("/"))] } })
+        };
+        let mut request = json!({ "addTarget": target });
+        request["addTarget"]["targetId"] = TARGET_ID.into();
+        request
+    }
+
+    /// Starts listen task, if it is enabled and not started yet.
+    /// Changes made before target becomes current may be missed by following read, so data is invalidated once target is current.
+    fn start_listening(&self) {
+        let mut listener = self.listener.lock().unwrap();
+        if !self.listen || listener.is_some() {
+            return;
+        }
+        let mut url = self.api_url.clone();
+        url.path_segments_mut()
+            .expect("api url is a base url")
+            .pop_if_empty()
+            .extend(["projects", &self.project, "databases", &self.database, "documents:listen"]);
+        let mut body = self.listen_request();
+        body["database"] = format!("projects/{}/databases/{}", self.project, self.database).into();
+        let request = self.authorize(self.client.post(url).header(CONTENT_TYPE, "application/json").body(body.to_string()));
+        let token = Arc::downgrade(&self.token);
+        let handle = tokio::spawn(async move {
+            loop {
+                let Some(request) = request.try_clone() else {
+                    return;
+                };
+                if let Err(_err) = listen(request, &token).await {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!("Firestore listen stream failed: {_err}");
+                }
+                // Stream is reopened, and data is invalidated once target is current again
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                if token.strong_count() == 0 {
+                    return;
+                }
+            }
+        });
+        *listener = Some(handle.abort_handle());
+    }
+
+    /// Reads documents and returns their plain fields and revision
+    async fn fetch(&self) -> Result<(Value, String), Box<dyn Error + Send + Sync>> {
+        if !self.is_collection() {
+            let response = Self::send(self.authorize(self.client.get(self.documents_url(self.segments())))).await?;
+            let document: Value = serde_json::from_slice(&response.bytes().await?)?;
+            let revision = document["updateTime"].as_str().unwrap_or_default().to_string();
+            return Ok((plain_fields(&document), revision));
+        }
+
+        let mut documents = Map::new();
+        let mut update_times = Vec::new();
+        let mut page_token: Option<String> = None;
+        loop {
+            let mut url = self.documents_url(self.segments());
+            url.query_pairs_mut().append_pair("pageSize", "300");
+            if let Some(page_token) = &page_token {
+                url.query_pairs_mut().append_pair("pageToken", page_token);
+            }
+            let page: Value = serde_json::from_slice(&Self::send(self.authorize(self.client.get(url))).await?.bytes().await?)?;
+            for document in page["documents"].as_array().into_iter().flatten() {
+                let name = document["name"].as_str().unwrap_or_default();
+                update_times.push(format!("{name}@{}", document["updateTime"].as_str().unwrap_or_default()));
+                let id = name.rsplit('/').next().unwrap_or_default().to_string();
+                documents.insert(id, plain_fields(document));
+            }
+            match page["nextPageToken"].as_str() {
+                Some(next) if !next.is_empty() => page_token = Some(next.to_string()),
+                _ => break
+            }
+        }
+        update_times.sort();
+        let revision = format!("{:016x}", content_hash::fnv1a(update_times.join("\n").as_bytes()));
+        Ok((Value::Object(documents), revision))
+    }
+
+    async fn load(&self, current_revision: Option<&str>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        // Token is replaced before read, so changes made during read are not missed
+        let token = InvalidationToken::new();
+        *self.token.lock().unwrap() = token.clone();
+        self.start_listening();
+
+        let (value, revision) = self.fetch().await?;
+        let valid_until = SystemTime::now() + self.max_age;
+        if current_revision == Some(revision.as_str()) {
+            return Ok(Revalidation::NotModified { must_revalidate: false, valid_until, invalidation: self.listen.then_some(token), fetched_at: None });
+        }
+
+        let bytes = serde_json::to_vec(&value)?;
+        let mut result = DataLoadResult::new((self.parser)(&bytes)?, false, valid_until);
+        result.metadata.size = Some(bytes.len() as u64);
+        result.metadata.revision = Some(revision);
+        result.metadata.invalidation = self.listen.then_some(token);
+        Ok(Revalidation::Modified(result))
+    }
+}
+
+/// Reads listen stream until it ends, invalidating data on changes
+async fn listen(request: RequestBuilder, token: &Weak<Mutex<InvalidationToken>>) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let mut response = request.send().await?;
+    if !response.status().is_success() {
+        return Err(FirestoreError::Status(response.status()).into());
+    }
+    let mut splitter = MessageSplitter::default();
+    let mut current = false;
+    while let Some(chunk) = response.chunk().await? {
+        for message in splitter.push(&chunk) {
+            let message: Value = serde_json::from_slice(&message)?;
+            if !is_change(&message, &mut current) {
+                continue;
+            }
+            let Some(token) = token.upgrade() else {
+                return Ok(());
+            };
+            token.lock().unwrap().invalidate();
+        }
+    }
+    Ok(())
+}
+
+/// Whether listen response means that data may have changed.
+/// Documents sent before target becomes current for the first time are initial snapshot, and are not changes.
+fn is_change(message: &Value, current: &mut bool) -> bool {
+    if let Some(change) = message.get("targetChange") {
+        return match change["targetChangeType"].as_str() {
+            Some("CURRENT") if !*current => {
+                *current = true;
+                true
+            },
+            // Target must be read again from scratch
+            Some("RESET") => {
+                *current = false;
+                false
+            },
+            Some("REMOVE") => true,
+            _ => false
+        };
+    }
+    *current && ["documentChange", "documentDelete", "documentRemove", "filter"].iter().any(|key| message.get(key).is_some())
+}
+
+/// Splits listen stream, which is JSON array of responses sent in arbitrary chunks, into separate responses
+#[derive(Debug, Default)]
+struct MessageSplitter {
+    buffer: Vec<u8>,
+    /// Start of current message in buffer
+    start: usize,
+    depth: usize,
+    in_string: bool,
+    escaped: bool
+}
+
+impl MessageSplitter {
+    fn push(&mut self, chunk: &[u8]) -> Vec<Vec<u8>> {
+        let mut messages = Vec::new();
+        let offset = self.buffer.len();
+        self.buffer.extend_from_slice(chunk);
+        for index in offset..self.buffer.len() {
+            let byte = self.buffer[index];
+            if self.in_string {
+                match byte {
+                    _ if self.escaped => self.escaped = false,
+                    b'\\' => self.escaped = true,
+                    b'"' => self.in_string = false,
+                    _ => {}
+                }
+                continue;
+            }
+            match byte {
+                b'"' if self.depth > 0 => self.in_string = true,
+                b'{' | b'[' if self.depth > 0 => self.depth += 1,
+                b'{' => {
+                    self.depth = 1;
+                    self.start = index;
+                },
+                b'}' | b']' if self.depth > 0 => {
+                    self.depth -= 1;
+                    if self.depth == 0 {
+                        messages.push(self.buffer[self.start..=index].to_vec());
+                    }
+                },
+                // Array brackets, separators and whitespace between messages
+                _ => {}
+            }
+        }
+        if self.depth == 0 {
+            self.buffer.clear();
+        } else {
+            self.buffer.drain(..self.start);
+            self.start = 0;
+        }
+        messages
+    }
+}
+
+/// Fields of document as plain JSON object
+fn plain_fields(document: &Value) -> Value {
+    Value::Object(document["fields"].as_object().into_iter().flatten().map(|(key, value)| (key.clone(), plain(value))).collect())
+}
+
+/// Converts Firestore typed value to plain JSON value
+fn plain(value: &Value) -> Value {
+    let Some((kind, inner)) = value.as_object().and_then(|value| value.iter().next()) else {
+        return Value::Null;
+    };
+    match kind.as_str() {
+        "integerValue" => inner.as_str().and_then(|integer| integer.parse::<i64>().ok()).map_or_else(|| inner.clone(), Value::from),
+        "arrayValue" => Value::Array(inner["values"].as_array().into_iter().flatten().map(plain).collect()),
+        "mapValue" => plain_fields(inner),
+        "nullValue" => Value::Null,
+        // Booleans, doubles, strings, timestamps, references, bytes and geo points
+        _ => inner.clone()
+    }
+}
+
+impl <Data, Parser> DataProvider<Data> for FirestoreDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&[u8]) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Reads and parses documents
+    /// # Errors
+    /// If request fails, document doesn't exist, or parser returns an error
+    async fn load_data(&self) -> Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>> {
+        Ok(self.load(None).await?.into_loaded())
+    }
+
+    /// Reads documents, but parses them only if they changed
+    async fn revalidate_data<'a>(&'a self, current: &'a DataLoadResult<Data>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        self.load(current.metadata.revision.as_deref()).await
+    }
+}
+
+impl <Data: Send + Sync, Parser> Drop for FirestoreDataProvider<Data, Parser> {
+    fn drop(&mut self) {
+        if let Some(handle) = self.listener.lock().unwrap().take() {
+            handle.abort();
+        }
+    }
+}
+
+impl <Data: Send + Sync, Parser> Debug for FirestoreDataProvider<Data, Parser> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FirestoreDataProvider")
+            .field("api_url", &self.api_url)
+            .field("project", &self.project)
+            .field("database", &self.database)
+            .field("path", &self.path)
+            .field("max_age", &self.max_age)
+            .field("listen", &self.listen)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Firestore specific errors
+#[derive(Debug)]
+pub enum FirestoreError {
+    /// Document does not exist
+    NotFound,
+    /// Unexpected http status
+    Status(StatusCode)
+}
+
+impl Display for FirestoreError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotFound => write!(f, "document not found in Firestore"),
+            Self::Status(status) => write!(f, "unexpected response status code: {status}")
+        }
+    }
+}
+
+impl Error for FirestoreError {}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use std::time::Duration;
+    use mockito::Matcher;
+    use reqwest::Url;
+    use serde_json::{json, Value};
+    use crate::data_providers::data_provider::{DataProvider, Revalidation};
+    use crate::data_providers::firestore::{is_change, FirestoreDataProvider, MessageSplitter};
+
+    fn parse(bytes: &[u8]) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+
+    #[tokio::test]
+    async fn document_is_parsed_only_when_updated() {
+        let mut server = mockito::Server::new_async().await;
+        let document = server
+            .mock("GET", "/projects/acme/databases/(default)/documents/config/app")
+            .match_header("Authorization", "Bearer secret")
+            .with_body(r#"{
+                "name": "projects/acme/databases/(default)/documents/config/app",
+                "fields": {
+                    "limit": {"integerValue": "10"},
+                    "regions": {"arrayValue": {"values": [{"stringValue": "eu"}]}},
+                    "limits": {"mapValue": {"fields": {"burst": {"doubleValue": 1.5}, "enabled": {"booleanValue": true}}}}
+                },
+                "updateTime": "2024-05-01T10:00:00.123456Z"
+            }"#)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let data_provider = FirestoreDataProvider::new(reqwest::Client::default(), "acme", "config/app", parse)
+            .with_api_url(Url::parse(&server.url()).unwrap())
+            .with_access_token("secret");
+        let loaded = data_provider.load_data().await.unwrap();
+        assert_eq!(loaded.data, json!({"limit": 10, "regions": ["eu"], "limits": {"burst": 1.5, "enabled": true}}));
+        assert_eq!(loaded.metadata.revision.as_deref(), Some("2024-05-01T10:00:00.123456Z"));
+        assert!(matches!(data_provider.revalidate_data(&loaded).await.unwrap(), Revalidation::NotModified { .. }));
+        document.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn collection_pages_are_merged_and_listened() {
+        let mut server = mockito::Server::new_async().await;
+        let _first = server
+            .mock("GET", "/projects/acme/databases/(default)/documents/flags")
+            .match_query(Matcher::Exact("pageSize=300".into()))
+            .with_body(r#"{"documents": [{"name": "projects/acme/databases/(default)/documents/flags/a", "fields": {"on": {"booleanValue": true}}, "updateTime": "t1"}], "nextPageToken": "p2"}"#)
+            .create_async()
+            .await;
+        let _second = server
+            .mock("GET", "/projects/acme/databases/(default)/documents/flags")
+            .match_query(Matcher::UrlEncoded("pageToken".into(), "p2".into()))
+            .with_body(r#"{"documents": [{"name": "projects/acme/databases/(default)/documents/flags/b", "fields": {}, "updateTime": "t2"}]}"#)
+            .create_async()
+            .await;
+        let listen = server
+            .mock("POST", "/projects/acme/databases/(default)/documents:listen")
+            .match_body(Matcher::PartialJson(json!({"addTarget": {"query": {"parent": "projects/acme/databases/(default)/documents"}}})))
+            .with_body(r#"[{"targetChange": {"targetChangeType": "ADD", "targetIds": [1]}}, {"targetChange": {"targetChangeType": "CURRENT", "targetIds": [1]}}]"#)
+            .expect_at_least(1)
+            .create_async()
+            .await;
+
+        let data_provider = FirestoreDataProvider::new(reqwest::Client::default(), "acme", "flags", parse)
+            .with_api_url(Url::parse(&server.url()).unwrap())
+            .with_listen();
+        let loaded = data_provider.load_data().await.unwrap();
+        assert_eq!(loaded.data, json!({"a": {"on": true}, "b": {}}));
+        let token = loaded.metadata.invalidation.clone().unwrap();
+        tokio::time::timeout(Duration::from_secs(5), token.invalidated()).await.unwrap();
+        listen.assert_async().await;
+    }
+
+    #[test]
+    fn listen_stream_is_split_into_changes() {
+        let stream = br#"[{"targetChange": {"targetChangeType": "ADD"}},
+            {"documentChange": {"document": {"name": "a", "fields": {"text": {"stringValue": "}\"{"}}}}},
+            {"targetChange": {"targetChangeType": "CURRENT"}},
+            {"documentDelete": {"document": "a"}}]"#;
+        let mut splitter = MessageSplitter::default();
+        let messages: Vec<Value> = stream.chunks(7)
+            .flat_map(|chunk| splitter.push(chunk))
+            .map(|message| serde_json::from_slice(&message).unwrap())
+            .collect();
+        assert_eq!(messages.len(), 4);
+        assert_eq!(messages[1]["documentChange"]["document"]["fields"]["text"]["stringValue"], "}\"{");
+
+        let mut current = false;
+        let changes: Vec<bool> = messages.iter().map(|message| is_change(message, &mut current)).collect();
+        assert_eq!(changes, vec![false, false, true, true]);
+    }
+}
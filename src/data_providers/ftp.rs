@@ -0,0 +1,356 @@
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::marker::PhantomData;
+use std::time::{Duration, SystemTime};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio_native_tls::native_tls;
+use crate::data_providers::data_provider::{DataLoadResult, DataProvider, Revalidation};
+
+/// Transport security of FTP connections
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FtpSecurity {
+    /// Plain FTP, default port is 21. Credentials and file are sent unencrypted.
+    Plain,
+    /// FTPS with implicit TLS, default port is 990. Both control and data connections are encrypted,
+    /// and server certificate is verified with platform TLS implementation.
+    ImplicitTls
+}
+
+/// FTP specific errors
+#[derive(Debug)]
+pub enum FtpError {
+    /// Server replied to command with unexpected code
+    UnexpectedReply {
+        /// Command, with arguments omitted
+        command: String,
+        /// Reply code
+        code: u16,
+        /// Reply text
+        message: String
+    },
+    /// Reply can't be parsed
+    InvalidReply(String)
+}
+
+impl Display for FtpError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnexpectedReply { command, code, message } => write!(f, "FTP server replied to {command} with {code} {message}"),
+            Self::InvalidReply(reply) => write!(f, "invalid FTP reply: {reply}")
+        }
+    }
+}
+
+impl Error for FtpError {}
+
+trait Stream: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl <T: AsyncRead + AsyncWrite + Unpin + Send> Stream for T {}
+
+/// Control connection
+struct Control {
+    stream: BufReader<Box<dyn Stream>>
+}
+
+impl Control {
+    /// Reads reply, joining lines of multi-line reply
+    async fn reply(&mut self) -> Result<(u16, String), Box<dyn Error + Send + Sync>> {
+        let mut line = String::new();
+        self.stream.read_line(&mut line).await?;
+        let code: u16 = line.get(..3).and_then(|code| code.parse().ok()).ok_or_else(|| FtpError::InvalidReply(line.clone()))?;
+        let mut message = line[3..].trim_start_matches([' ', '-']).trim_end().to_string();
+        if line.as_bytes().get(3) == Some(&b'-') {
+            // Multi-line reply ends with line that starts with the same code followed by space
+            let end = format!("{code} ");
+            loop {
+                line.clear();
+                if self.stream.read_line(&mut line).await? == 0 {
+                    return Err(FtpError::InvalidReply(message).into());
+                }
+                message.push('\n');
+                message.push_str(line.trim_end().trim_start_matches(end.as_str()));
+                if line.starts_with(&end) {
+                    break;
+                }
+            }
+        }
+        Ok((code, message))
+    }
+
+    /// Sends command and reads reply
+    async fn command(&mut self, command: &str) -> Result<(u16, String), Box<dyn Error + Send + Sync>> {
+        self.stream.get_mut().write_all(format!("{command}\r\n").as_bytes()).await?;
+        self.reply().await
+    }
+
+    /// Sends command and checks that reply has one of expected codes
+    async fn expect(&mut self, command: &str, expected: &[u16]) -> Result<String, Box<dyn Error + Send + Sync>> {
+        let (code, message) = self.command(command).await?;
+        check(command, code, message, expected)
+    }
+}
+
+fn check(command: &str, code: u16, message: String, expected: &[u16]) -> Result<String, Box<dyn Error + Send + Sync>> {
+    if expected.contains(&code) {
+        return Ok(message);
+    }
+    let command = command.split(' ').next().unwrap_or_default().to_string();
+    Err(FtpError::UnexpectedReply { command, code, message }.into())
+}
+
+/// Port from `227 Entering Passive Mode (h1,h2,h3,h4,p1,p2)` reply
+fn passive_port(message: &str) -> Option<u16> {
+    let numbers: Vec<u16> = message.split(['(', ')']).nth(1)?.split(',').map(|number| number.trim().parse().ok()).collect::<Option<_>>()?;
+    match numbers[..] {
+        [_, _, _, _, high, low] => Some(high * 256 + low),
+        _ => None
+    }
+}
+
+/// This data provider downloads file from FTP server (plain or with implicit TLS) and parses it with specified function.
+/// New session is opened for every load, and data is transferred in binary passive mode.
+///
+/// Modification time (`MDTM`) and size (`SIZE`) of file are reported as revision, and file is downloaded again only if they change.
+/// If server doesn't support these commands, file is downloaded on every revalidation.
+/// # Examples
+/// ```
+/// use remote_config::data_providers::ftp::{FtpDataProvider, FtpSecurity};
+///
+/// let data_provider = FtpDataProvider::new("ftp.plant.example", "/config/line-3.ini", |bytes: &[u8]| {
+///     Ok(String::from_utf8(bytes.to_vec())?)
+/// }).with_security(FtpSecurity::ImplicitTls).with_credentials("line3", "secret");
+/// ```
+pub struct FtpDataProvider<Data: Send + Sync, Parser> {
+    host: String,
+    port: Option<u16>,
+    security: FtpSecurity,
+    username: String,
+    password: String,
+    path: String,
+    parser: Parser,
+    timeout: Duration,
+    max_age: Duration,
+    data_type: PhantomData<Data>
+}
+
+impl <Data, Parser> FtpDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&[u8]) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Creates data provider for file at specified path, that logs in anonymously over plain FTP
+    pub fn new(host: impl Into<String>, path: impl Into<String>, parser: Parser) -> Self {
+        Self {
+            host: host.into(),
+            port: None,
+            security: FtpSecurity::Plain,
+            username: "anonymous".to_string(),
+            password: "anonymous@".to_string(),
+            path: path.into(),
+            parser,
+            timeout: Duration::from_secs(30),
+            max_age: Duration::from_secs(60),
+            data_type: PhantomData
+        }
+    }
+
+    /// Transport security. Default is [`FtpSecurity::Plain`].
+    pub fn with_security(mut self, security: FtpSecurity) -> Self {
+        self.security = security;
+        self
+    }
+
+    /// Server port. Default depends on security.
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.port = Some(port);
+        self
+    }
+
+    /// User name and password. Default is anonymous login.
+    pub fn with_credentials(mut self, username: impl Into<String>, password: impl Into<String>) -> Self {
+        self.username = username.into();
+        self.password = password.into();
+        self
+    }
+
+    /// Timeout of whole session. Default is 30 seconds.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Time after which file is checked again. Default is 60 seconds.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Opens connection to specified port of server, wrapping it in TLS if needed
+    async fn connect(&self, port: u16) -> Result<Box<dyn Stream>, Box<dyn Error + Send + Sync>> {
+        let stream = TcpStream::connect((self.host.as_str(), port)).await?;
+        match self.security {
+            FtpSecurity::Plain => Ok(Box::new(stream)),
+            FtpSecurity::ImplicitTls => {
+                let connector = tokio_native_tls::TlsConnector::from(native_tls::TlsConnector::new()?);
+                Ok(Box::new(connector.connect(&self.host, stream).await?))
+            }
+        }
+    }
+
+    /// Logs in and returns revision of file, and its content if revision differs from current one
+    async fn fetch(&self, current_revision: Option<&str>) -> Result<(Option<String>, Option<Vec<u8>>), Box<dyn Error + Send + Sync>> {
+        let port = self.port.unwrap_or(match self.security {
+            FtpSecurity::Plain => 21,
+            FtpSecurity::ImplicitTls => 990
+        });
+        let mut control = Control { stream: BufReader::new(self.connect(port).await?) };
+        let (code, message) = control.reply().await?;
+        check("connect", code, message, &[220])?;
+        let (code, message) = control.command(&format!("USER {}", self.username)).await?;
+        if code == 331 {
+            control.expect(&format!("PASS {}", self.password), &[230, 202]).await?;
+        } else {
+            check("USER", code, message, &[230])?;
+        }
+        if self.security == FtpSecurity::ImplicitTls {
+            control.expect("PBSZ 0", &[200]).await?;
+            control.expect("PROT P", &[200]).await?;
+        }
+        control.expect("TYPE I", &[200]).await?;
+
+        let (mdtm_code, modified) = control.command(&format!("MDTM {}", self.path)).await?;
+        let (size_code, size) = control.command(&format!("SIZE {}", self.path)).await?;
+        let revision = (mdtm_code == 213 && size_code == 213).then(|| format!("{}-{}", modified.trim(), size.trim()));
+        if revision.is_some() && revision.as_deref() == current_revision {
+            let _ = control.command("QUIT").await;
+            return Ok((revision, None));
+        }
+
+        let passive = control.expect("PASV", &[227]).await?;
+        // Address in reply is ignored, as it is often wrong behind NAT
+        let data_port = passive_port(&passive).ok_or(FtpError::InvalidReply(passive))?;
+        let mut data = self.connect(data_port).await?;
+        control.expect(&format!("RETR {}", self.path), &[125, 150]).await?;
+        let mut bytes = Vec::new();
+        data.read_to_end(&mut bytes).await?;
+        drop(data);
+        let (code, message) = control.reply().await?;
+        check("RETR", code, message, &[226, 250])?;
+        let _ = control.command("QUIT").await;
+        Ok((revision, Some(bytes)))
+    }
+
+    async fn load(&self, current_revision: Option<&str>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        let (revision, bytes) = tokio::time::timeout(self.timeout, self.fetch(current_revision)).await??;
+        let valid_until = SystemTime::now() + self.max_age;
+        let Some(bytes) = bytes else {
+            return Ok(Revalidation::NotModified { must_revalidate: false, valid_until, invalidation: None, fetched_at: None });
+        };
+
+        let mut result = DataLoadResult::new((self.parser)(&bytes)?, false, valid_until);
+        result.metadata.size = Some(bytes.len() as u64);
+        result.metadata.revision = revision;
+        Ok(Revalidation::Modified(result))
+    }
+}
+
+impl <Data, Parser> DataProvider<Data> for FtpDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&[u8]) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Downloads and parses file
+    /// # Errors
+    /// If connection fails, server rejects login or download, session times out, or parser returns an error
+    async fn load_data(&self) -> Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>> {
+        Ok(self.load(None).await?.into_loaded())
+    }
+
+    /// Downloads and parses file, if its modification time or size changed
+    async fn revalidate_data<'a>(&'a self, current: &'a DataLoadResult<Data>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        self.load(current.metadata.revision.as_deref()).await
+    }
+}
+
+impl <Data: Send + Sync, Parser> Debug for FtpDataProvider<Data, Parser> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FtpDataProvider")
+            .field("host", &self.host)
+            .field("port", &self.port)
+            .field("security", &self.security)
+            .field("username", &self.username)
+            .field("path", &self.path)
+            .field("max_age", &self.max_age)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+    use crate::data_providers::data_provider::{DataProvider, Revalidation};
+    use crate::data_providers::ftp::{passive_port, FtpDataProvider};
+
+    fn parse(bytes: &[u8]) -> Result<String, Box<dyn Error + Send + Sync>> {
+        Ok(String::from_utf8(bytes.to_vec())?)
+    }
+
+    /// Serves `/cfg.ini` to user `plc` for every session
+    async fn serve(listener: TcpListener) {
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (reader, mut writer) = stream.into_split();
+            let mut lines = BufReader::new(reader).lines();
+            writer.write_all(b"220-Welcome\r\n220 Ready\r\n").await.unwrap();
+            let mut data = None;
+            while let Some(line) = lines.next_line().await.unwrap() {
+                let reply = match line.as_str() {
+                    "USER plc" => "331 Password required".to_string(),
+                    "PASS secret" => "230 Logged in".to_string(),
+                    "TYPE I" => "200 Binary".to_string(),
+                    "MDTM /cfg.ini" => "213 20240101120000".to_string(),
+                    "SIZE /cfg.ini" => "213 5".to_string(),
+                    "PASV" => {
+                        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+                        let port = listener.local_addr().unwrap().port();
+                        data = Some(listener);
+                        format!("227 Entering Passive Mode (10,0,0,1,{},{})", port / 256, port % 256)
+                    },
+                    "RETR /cfg.ini" => {
+                        writer.write_all(b"150 Opening data connection\r\n").await.unwrap();
+                        let (mut stream, _) = data.take().unwrap().accept().await.unwrap();
+                        stream.write_all(b"a=1\r\n").await.unwrap();
+                        drop(stream);
+                        "226 Transfer complete".to_string()
+                    },
+                    "QUIT" => "221 Bye".to_string(),
+                    _ => "502 Not implemented".to_string()
+                };
+                writer.write_all(format!("{reply}\r\n").as_bytes()).await.unwrap();
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn downloads_file_only_when_changed() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(serve(listener));
+
+        let data_provider = FtpDataProvider::new("127.0.0.1", "/cfg.ini", parse)
+            .with_port(port)
+            .with_credentials("plc", "secret");
+        let loaded = data_provider.load_data().await.unwrap();
+        assert_eq!(loaded.data, "a=1\r\n");
+        assert_eq!(loaded.metadata.revision.as_deref(), Some("20240101120000-5"));
+        assert!(matches!(data_provider.revalidate_data(&loaded).await.unwrap(), Revalidation::NotModified { .. }));
+
+        let rejected = FtpDataProvider::new("127.0.0.1", "/cfg.ini", parse).with_port(port);
+        assert_eq!(rejected.load_data().await.unwrap_err().to_string(), "FTP server replied to USER with 502 Not implemented");
+    }
+
+    #[test]
+    fn passive_reply() {
+        assert_eq!(passive_port("Entering Passive Mode (192,168,1,2,19,137)"), Some(5001));
+        assert_eq!(passive_port("Entering Passive Mode"), None);
+    }
+}
@@ -0,0 +1,272 @@
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::marker::PhantomData;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use reqwest::header::AUTHORIZATION;
+use reqwest::Url;
+use serde_json::{json, Value};
+use crate::data_providers::data_provider::{DataLoadResult, DataProvider};
+use crate::data_providers::http::HttpDataExtractor;
+
+/// Default Cloud Storage JSON API endpoint
+pub const DEFAULT_ENDPOINT: &str = "https://storage.googleapis.com";
+/// Default metadata server token URL
+pub const METADATA_TOKEN_URL: &str = "http://metadata.google.internal/computeMetadata/v1/instance/service-accounts/default/token";
+/// OAuth scope that is requested for service account tokens
+const READ_ONLY_SCOPE: &str = "https://www.googleapis.com/auth/devstorage.read_only";
+/// Tokens are refreshed this long before they expire
+const TOKEN_REFRESH_MARGIN: Duration = Duration::from_secs(60);
+
+/// Service account key, as downloaded from Google Cloud console
+#[derive(Clone)]
+pub struct ServiceAccountKey {
+    client_email: String,
+    private_key: String,
+    token_uri: String
+}
+
+impl ServiceAccountKey {
+    /// Parses JSON key file contents
+    /// # Errors
+    /// If JSON is invalid or required fields are missing
+    pub fn from_json(json: &str) -> Result<Self, GcsError> {
+        let value: Value = serde_json::from_str(json).map_err(|err| GcsError::InvalidKey(err.to_string()))?;
+        let field = |name: &str| value[name].as_str()
+            .map(str::to_string)
+            .ok_or_else(|| GcsError::InvalidKey(format!("field '{name}' is missing")));
+        Ok(Self {
+            client_email: field("client_email")?,
+            private_key: field("private_key")?,
+            token_uri: field("token_uri")?
+        })
+    }
+}
+
+impl Debug for ServiceAccountKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ServiceAccountKey").field("client_email", &self.client_email).finish_non_exhaustive()
+    }
+}
+
+/// Source of OAuth access tokens for Cloud Storage requests
+#[derive(Clone)]
+pub enum GcsCredentials {
+    /// Token of attached service account, issued by metadata server at specified URL.
+    /// Works on Compute Engine, Cloud Run and GKE with Workload Identity.
+    MetadataServer(Url),
+    /// Token is exchanged for JWT signed with service account key
+    ServiceAccount(ServiceAccountKey),
+    /// Static access token. Useful for testing and public buckets (empty token is not sent)
+    Token(String)
+}
+
+impl GcsCredentials {
+    /// Metadata server credentials with default URL
+    pub fn metadata_server() -> Self {
+        Self::MetadataServer(Url::parse(METADATA_TOKEN_URL).expect("valid url"))
+    }
+}
+
+impl Debug for GcsCredentials {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MetadataServer(url) => f.debug_tuple("MetadataServer").field(url).finish(),
+            Self::ServiceAccount(key) => f.debug_tuple("ServiceAccount").field(key).finish(),
+            Self::Token(_) => f.debug_tuple("Token").finish_non_exhaustive()
+        }
+    }
+}
+
+/// Cloud Storage specific errors
+#[derive(Debug)]
+pub enum GcsError {
+    /// Service account key is invalid
+    InvalidKey(String),
+    /// Token endpoint returned unexpected response
+    TokenResponse(String)
+}
+
+impl Display for GcsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidKey(reason) => write!(f, "invalid service account key: {reason}"),
+            Self::TokenResponse(reason) => write!(f, "failed to obtain access token: {reason}")
+        }
+    }
+}
+
+impl Error for GcsError {}
+
+/// Access tokens of credentials for specified OAuth scope, cached until they expire
+#[derive(Debug)]
+pub(crate) struct TokenSource {
+    credentials: GcsCredentials,
+    scope: &'static str,
+    token: Mutex<Option<(String, SystemTime)>>
+}
+
+impl TokenSource {
+    pub fn new(credentials: GcsCredentials, scope: &'static str) -> Self {
+        Self {
+            credentials,
+            scope,
+            token: Mutex::new(None)
+        }
+    }
+
+    /// Returns cached access token or requests new one
+    pub async fn access_token(&self, client: &reqwest::Client) -> Result<String, Box<dyn Error + Send + Sync>> {
+        let now = SystemTime::now();
+        if let Some((token, expires)) = self.token.lock().unwrap().as_ref() {
+            if now + TOKEN_REFRESH_MARGIN < *expires {
+                return Ok(token.clone());
+            }
+        }
+
+        let response = match &self.credentials {
+            GcsCredentials::Token(token) => return Ok(token.clone()),
+            GcsCredentials::MetadataServer(url) => {
+                client.get(url.clone()).header("Metadata-Flavor", "Google").send().await?
+            },
+            GcsCredentials::ServiceAccount(key) => {
+                let iat = now.duration_since(UNIX_EPOCH)?.as_secs();
+                let claims = json!({
+                    "iss": key.client_email,
+                    "scope": self.scope,
+                    "aud": key.token_uri,
+                    "iat": iat,
+                    "exp": iat + 3600
+                });
+                let encoding_key = jsonwebtoken::EncodingKey::from_rsa_pem(key.private_key.as_bytes())?;
+                let assertion = jsonwebtoken::encode(&jsonwebtoken::Header::new(jsonwebtoken::Algorithm::RS256), &claims, &encoding_key)?;
+                client.post(&key.token_uri)
+                    .form(&[("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"), ("assertion", &assertion)])
+                    .send()
+                    .await?
+            }
+        };
+
+        if !response.status().is_success() {
+            return Err(GcsError::TokenResponse(format!("status {}", response.status())).into());
+        }
+        let body: Value = serde_json::from_slice(&response.bytes().await?)?;
+        let token = body["access_token"].as_str()
+            .ok_or_else(|| GcsError::TokenResponse("access_token is missing".to_string()))?
+            .to_string();
+        let expires_in = body["expires_in"].as_u64().unwrap_or_default();
+        *self.token.lock().unwrap() = Some((token.clone(), now + Duration::from_secs(expires_in)));
+        Ok(token)
+    }
+}
+
+/// This data provider downloads object from Google Cloud Storage bucket and feeds response into specified data extractor.
+/// Object metadata is translated into response headers by Cloud Storage,
+/// so `cacheControl` and `contentType` of the object control `valid_until`, `must_revalidate` and deserialization
+/// (e.g. with [`SerdeDataExtractor`](crate::data_providers::http::serde_extractor::SerdeDataExtractor)).
+/// # Examples
+/// ```
+/// use std::collections::HashMap;
+/// use remote_config::data_providers::gcs::{GcsCredentials, GcsDataProvider};
+/// use remote_config::data_providers::http::serde_extractor::SerdeDataExtractor;
+///
+/// let extractor = SerdeDataExtractor::<HashMap<String, String>>::new();
+/// let data_provider = GcsDataProvider::new(reqwest::Client::default(), "config-bucket", "app/config.json", GcsCredentials::metadata_server(), extractor);
+/// ```
+pub struct GcsDataProvider<Data: Send + Sync, Extractor: HttpDataExtractor<Data>> {
+    client: reqwest::Client,
+    endpoint: Url,
+    bucket: String,
+    object: String,
+    tokens: TokenSource,
+    extractor: Extractor,
+    phantom_data: PhantomData<Data>
+}
+
+impl <Data: Send + Sync, Extractor: HttpDataExtractor<Data>> GcsDataProvider<Data, Extractor> {
+    /// Constructs new data provider for specified object
+    pub fn new(client: reqwest::Client, bucket: impl Into<String>, object: impl Into<String>, credentials: GcsCredentials, extractor: Extractor) -> Self {
+        Self {
+            client,
+            endpoint: Url::parse(DEFAULT_ENDPOINT).expect("valid url"),
+            bucket: bucket.into(),
+            object: object.into(),
+            tokens: TokenSource::new(credentials, READ_ONLY_SCOPE),
+            extractor,
+            phantom_data: PhantomData
+        }
+    }
+
+    /// Cloud Storage endpoint. Default is [`DEFAULT_ENDPOINT`]
+    pub fn with_endpoint(mut self, endpoint: Url) -> Self {
+        self.endpoint = endpoint;
+        self
+    }
+
+    /// Media download URL of the object
+    fn object_url(&self) -> Url {
+        let mut url = self.endpoint.clone();
+        url.path_segments_mut()
+            .expect("endpoint is a base url")
+            .pop_if_empty()
+            .extend(["storage", "v1", "b", &self.bucket, "o", &self.object]);
+        url.query_pairs_mut().append_pair("alt", "media");
+        url
+    }
+}
+
+impl <Data: Send + Sync, Extractor: HttpDataExtractor<Data> + Sync> DataProvider<Data> for GcsDataProvider<Data, Extractor> {
+    /// Downloads object and passes response to extractor
+    /// # Errors
+    /// If access token can't be obtained, request fails, or data extractor returns an error
+    async fn load_data(&self) -> Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>> {
+        let token = self.tokens.access_token(&self.client).await?;
+        let mut request = self.client.get(self.object_url());
+        if !token.is_empty() {
+            request = request.header(AUTHORIZATION, format!("Bearer {token}"));
+        }
+        self.extractor.extract(request.send().await?).await
+    }
+}
+
+#[cfg(all(test, feature = "json"))]
+mod tests {
+    use std::collections::HashMap;
+    use reqwest::Url;
+    use crate::data_providers::data_provider::DataProvider;
+    use crate::data_providers::gcs::{GcsCredentials, GcsDataProvider};
+    use crate::data_providers::http::serde_extractor::SerdeDataExtractor;
+
+    #[tokio::test]
+    async fn metadata_server_auth() {
+        let mut server = mockito::Server::new_async().await;
+        let token = server
+            .mock("GET", "/token")
+            .match_header("Metadata-Flavor", "Google")
+            .with_body(r#"{"access_token": "secret", "expires_in": 3600, "token_type": "Bearer"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let object = server
+            .mock("GET", "/storage/v1/b/bucket/o/app%2Fconfig.json?alt=media")
+            .match_header("Authorization", "Bearer secret")
+            .with_header("Content-Type", "application/json")
+            .with_header("Cache-Control", "private, max-age=60")
+            .with_body(r#"{"key": "value"}"#)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let credentials = GcsCredentials::MetadataServer(Url::parse(&(server.url() + "/token")).unwrap());
+        let data_provider = GcsDataProvider::new(reqwest::Client::default(), "bucket", "app/config.json", credentials, SerdeDataExtractor::<HashMap<String, String>>::new())
+            .with_endpoint(Url::parse(&server.url()).unwrap());
+
+        for _ in 0..2 {
+            let data = data_provider.load_data().await.unwrap();
+            assert_eq!(data.data["key"], "value");
+        }
+        // Token is cached
+        token.assert_async().await;
+        object.assert_async().await;
+    }
+}
@@ -0,0 +1,281 @@
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::marker::PhantomData;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use reqwest::header::{ACCEPT, AUTHORIZATION, ETAG, IF_NONE_MATCH, USER_AGENT};
+use reqwest::{StatusCode, Url};
+use serde::Deserialize;
+use crate::data_providers::data_provider::{DataLoadResult, DataProvider, Revalidation};
+
+/// Default GitHub REST API address
+pub const DEFAULT_API_URL: &str = "https://api.github.com";
+/// Version of REST API that responses are parsed for
+const API_VERSION: &str = "2022-11-28";
+
+/// File entry returned by contents API
+#[derive(Deserialize)]
+struct Contents {
+    #[serde(rename = "type")]
+    kind: String,
+    sha: String,
+    /// Empty for files larger than 1 MB
+    #[serde(default)]
+    content: String,
+    #[serde(default)]
+    encoding: String
+}
+
+/// This data provider reads single file from GitHub repository with contents API, and parses it with specified function.
+/// Blob SHA of file is reported as revision, and file is not parsed again if it did not change.
+///
+/// Revalidation sends conditional request with `ETag` of previous response, and GitHub doesn't count
+/// `304 Not Modified` responses against rate limit. Files larger than 1 MB are downloaded again with raw media type.
+/// Also works with GitHub Enterprise Server (see [`GitHubDataProvider::with_api_url`]).
+/// # Examples
+/// ```
+/// # #[cfg(feature = "json")] {
+/// use std::collections::HashMap;
+/// use remote_config::data_providers::github::GitHubDataProvider;
+///
+/// let data_provider = GitHubDataProvider::new(reqwest::Client::default(), "acme", "config", "prod/app.json", |bytes: &[u8]| {
+///     Ok(serde_json::from_slice::<HashMap<String, String>>(bytes)?)
+/// }).with_token("github_pat_...").with_ref("main");
+/// # }
+/// ```
+pub struct GitHubDataProvider<Data: Send + Sync, Parser> {
+    client: reqwest::Client,
+    api_url: Url,
+    owner: String,
+    repo: String,
+    path: String,
+    reference: Option<String>,
+    token: Option<String>,
+    parser: Parser,
+    max_age: Duration,
+    /// `ETag` of last response with file contents
+    etag: Mutex<Option<String>>,
+    data_type: PhantomData<Data>
+}
+
+impl <Data, Parser> GitHubDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&[u8]) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Creates data provider for file at specified path of repository default branch
+    pub fn new(client: reqwest::Client, owner: impl Into<String>, repo: impl Into<String>, path: impl Into<String>, parser: Parser) -> Self {
+        Self {
+            client,
+            api_url: Url::parse(DEFAULT_API_URL).expect("valid url"),
+            owner: owner.into(),
+            repo: repo.into(),
+            path: path.into(),
+            reference: None,
+            token: None,
+            parser,
+            max_age: Duration::from_secs(60),
+            etag: Mutex::new(None),
+            data_type: PhantomData
+        }
+    }
+
+    /// Branch, tag or commit SHA to read file from. Default is default branch of repository.
+    pub fn with_ref(mut self, reference: impl Into<String>) -> Self {
+        self.reference = Some(reference.into());
+        self
+    }
+
+    /// Token (personal access token, or GitHub App installation token) that is sent as bearer token.
+    /// It is required for private repositories, and raises rate limit for public ones.
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// REST API address, e.g. `https://github.example.com/api/v3` for GitHub Enterprise Server. Default is [`DEFAULT_API_URL`].
+    pub fn with_api_url(mut self, api_url: Url) -> Self {
+        self.api_url = api_url;
+        self
+    }
+
+    /// Time after which file is checked again. Default is 60 seconds.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    fn contents_url(&self) -> Url {
+        let mut url = self.api_url.clone();
+        url.path_segments_mut()
+            .expect("api url is a base url")
+            .pop_if_empty()
+            .extend(["repos", &self.owner, &self.repo, "contents"])
+            .extend(self.path.split('/'));
+        if let Some(reference) = &self.reference {
+            url.query_pairs_mut().append_pair("ref", reference);
+        }
+        url
+    }
+
+    fn request(&self, accept: &str) -> reqwest::RequestBuilder {
+        let mut request = self.client.get(self.contents_url())
+            .header(ACCEPT, accept)
+            .header("X-GitHub-Api-Version", API_VERSION)
+            // GitHub rejects requests without user agent
+            .header(USER_AGENT, concat!("remote_config/", env!("CARGO_PKG_VERSION")));
+        if let Some(token) = &self.token {
+            request = request.header(AUTHORIZATION, format!("Bearer {token}"));
+        }
+        request
+    }
+
+    async fn load(&self, current_revision: Option<&str>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        let mut request = self.request("application/vnd.github+json");
+        let etag = self.etag.lock().unwrap().clone();
+        if let Some(etag) = etag.filter(|_| current_revision.is_some()) {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        let response = request.send().await?;
+        let valid_until = SystemTime::now() + self.max_age;
+        match response.status() {
+            StatusCode::NOT_MODIFIED if current_revision.is_some() => {
+                return Ok(Revalidation::NotModified { must_revalidate: false, valid_until, invalidation: None, fetched_at: None });
+            },
+            status if status.is_success() => {},
+            StatusCode::NOT_FOUND => return Err(GitHubError::NotFound.into()),
+            status => return Err(GitHubError::Status(status).into())
+        }
+        let etag = response.headers().get(ETAG).and_then(|etag| etag.to_str().ok()).map(str::to_string);
+
+        let contents: Contents = serde_json::from_slice(&response.bytes().await?)?;
+        if contents.kind != "file" {
+            return Err(GitHubError::NotAFile(contents.kind).into());
+        }
+        *self.etag.lock().unwrap() = etag;
+        if current_revision == Some(contents.sha.as_str()) {
+            return Ok(Revalidation::NotModified { must_revalidate: false, valid_until, invalidation: None, fetched_at: None });
+        }
+
+        let bytes = match contents.encoding.as_str() {
+            // Line breaks are inserted every 60 characters
+            "base64" => STANDARD.decode(contents.content.split_ascii_whitespace().collect::<String>())?,
+            // File is too large to be embedded
+            "none" => {
+                let response = self.request("application/vnd.github.raw+json").send().await?;
+                if !response.status().is_success() {
+                    return Err(GitHubError::Status(response.status()).into());
+                }
+                response.bytes().await?.to_vec()
+            },
+            encoding => return Err(GitHubError::UnsupportedEncoding(encoding.to_string()).into())
+        };
+        let mut result = DataLoadResult::new((self.parser)(&bytes)?, false, valid_until);
+        result.metadata.size = Some(bytes.len() as u64);
+        result.metadata.revision = Some(contents.sha);
+        Ok(Revalidation::Modified(result))
+    }
+}
+
+impl <Data, Parser> DataProvider<Data> for GitHubDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&[u8]) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Downloads and parses the file
+    /// # Errors
+    /// If request fails, path is not a file, or parser returns an error
+    async fn load_data(&self) -> Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>> {
+        Ok(self.load(None).await?.into_loaded())
+    }
+
+    /// Checks the file with conditional request, and parses it only if blob SHA changed
+    async fn revalidate_data<'a>(&'a self, current: &'a DataLoadResult<Data>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        self.load(current.metadata.revision.as_deref()).await
+    }
+}
+
+impl <Data: Send + Sync, Parser> Debug for GitHubDataProvider<Data, Parser> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GitHubDataProvider")
+            .field("api_url", &self.api_url)
+            .field("owner", &self.owner)
+            .field("repo", &self.repo)
+            .field("path", &self.path)
+            .field("reference", &self.reference)
+            .field("max_age", &self.max_age)
+            .finish_non_exhaustive()
+    }
+}
+
+/// GitHub specific errors
+#[derive(Debug)]
+pub enum GitHubError {
+    /// Repository or file does not exist, or token has no access to it
+    NotFound,
+    /// Path points to directory, symlink or submodule, type is included
+    NotAFile(String),
+    /// Contents are encoded with unknown encoding
+    UnsupportedEncoding(String),
+    /// Unexpected http status
+    Status(StatusCode)
+}
+
+impl Display for GitHubError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotFound => write!(f, "file not found in GitHub repository"),
+            Self::NotAFile(kind) => write!(f, "path in GitHub repository is {kind}, not a file"),
+            Self::UnsupportedEncoding(encoding) => write!(f, "unsupported encoding of file contents: {encoding}"),
+            Self::Status(status) => write!(f, "unexpected response status code: {status}")
+        }
+    }
+}
+
+impl Error for GitHubError {}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use mockito::Matcher;
+    use reqwest::Url;
+    use crate::data_providers::data_provider::{DataProvider, Revalidation};
+    use crate::data_providers::github::GitHubDataProvider;
+
+    fn parse(bytes: &[u8]) -> Result<String, Box<dyn Error + Send + Sync>> {
+        Ok(String::from_utf8(bytes.to_vec())?)
+    }
+
+    #[tokio::test]
+    async fn decodes_contents_and_revalidates_with_etag() {
+        let mut server = mockito::Server::new_async().await;
+        let conditional = server
+            .mock("GET", "/repos/acme/config/contents/prod/app.txt")
+            .match_query(Matcher::UrlEncoded("ref".into(), "main".into()))
+            .match_header("If-None-Match", "\"abc\"")
+            .with_status(304)
+            .expect(1)
+            .create_async()
+            .await;
+        let contents = server
+            .mock("GET", "/repos/acme/config/contents/prod/app.txt")
+            .match_query(Matcher::UrlEncoded("ref".into(), "main".into()))
+            .match_header("Authorization", "Bearer secret")
+            .match_header("If-None-Match", Matcher::Missing)
+            .with_header("ETag", "\"abc\"")
+            .with_body(r#"{"type": "file", "sha": "3b18e512", "encoding": "base64", "content": "aGVsbG8g\nd29ybGQ=\n"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let data_provider = GitHubDataProvider::new(reqwest::Client::default(), "acme", "config", "prod/app.txt", parse)
+            .with_api_url(Url::parse(&server.url()).unwrap())
+            .with_token("secret")
+            .with_ref("main");
+
+        let loaded = data_provider.load_data().await.unwrap();
+        assert_eq!(loaded.data, "hello world");
+        assert_eq!(loaded.metadata.revision.as_deref(), Some("3b18e512"));
+        assert!(matches!(data_provider.revalidate_data(&loaded).await.unwrap(), Revalidation::NotModified { .. }));
+        contents.assert_async().await;
+        conditional.assert_async().await;
+    }
+}
@@ -0,0 +1,240 @@
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::marker::PhantomData;
+use std::time::{Duration, SystemTime};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use reqwest::{Method, StatusCode, Url};
+use serde::Deserialize;
+use crate::data_providers::data_provider::{DataLoadResult, DataProvider, Revalidation};
+
+/// Default GitLab address
+pub const DEFAULT_URL: &str = "https://gitlab.com";
+/// Header with blob id of file, returned by both GET and HEAD requests
+const BLOB_ID_HEADER: &str = "X-Gitlab-Blob-Id";
+/// Header with access token
+const TOKEN_HEADER: &str = "PRIVATE-TOKEN";
+
+/// File returned by repository files API
+#[derive(Deserialize)]
+struct RepositoryFile {
+    blob_id: String,
+    encoding: String,
+    content: String
+}
+
+/// This data provider reads single file from GitLab repository with repository files API, and parses it with specified function.
+/// Blob id of file is reported as revision.
+///
+/// Revalidation sends `HEAD` request first, which returns blob id without file contents,
+/// so file is downloaded and parsed again only if it changed. Works with self-hosted GitLab (see [`GitLabDataProvider::with_url`]).
+/// # Examples
+/// ```
+/// # #[cfg(feature = "json")] {
+/// use std::collections::HashMap;
+/// use remote_config::data_providers::gitlab::GitLabDataProvider;
+///
+/// let data_provider = GitLabDataProvider::new(reqwest::Client::default(), "acme/platform/config", "prod/app.json", |bytes: &[u8]| {
+///     Ok(serde_json::from_slice::<HashMap<String, String>>(bytes)?)
+/// }).with_private_token("glpat-...").with_ref("main");
+/// # }
+/// ```
+pub struct GitLabDataProvider<Data: Send + Sync, Parser> {
+    client: reqwest::Client,
+    url: Url,
+    project: String,
+    path: String,
+    reference: String,
+    private_token: Option<String>,
+    parser: Parser,
+    max_age: Duration,
+    data_type: PhantomData<Data>
+}
+
+impl <Data, Parser> GitLabDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&[u8]) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Creates data provider for file at specified path of project default branch.
+    /// Project is specified by numeric id, or by full path with namespace (e.g. `group/subgroup/project`).
+    pub fn new(client: reqwest::Client, project: impl Into<String>, path: impl Into<String>, parser: Parser) -> Self {
+        Self {
+            client,
+            url: Url::parse(DEFAULT_URL).expect("valid url"),
+            project: project.into(),
+            path: path.into(),
+            reference: "HEAD".to_string(),
+            private_token: None,
+            parser,
+            max_age: Duration::from_secs(60),
+            data_type: PhantomData
+        }
+    }
+
+    /// Branch, tag or commit SHA to read file from. Default is `HEAD` (default branch of project).
+    pub fn with_ref(mut self, reference: impl Into<String>) -> Self {
+        self.reference = reference.into();
+        self
+    }
+
+    /// Personal, project or group access token with `read_repository` or `read_api` scope, that is sent in `PRIVATE-TOKEN` header.
+    /// It is required for private and internal projects.
+    pub fn with_private_token(mut self, token: impl Into<String>) -> Self {
+        self.private_token = Some(token.into());
+        self
+    }
+
+    /// Address of GitLab instance. Default is [`DEFAULT_URL`].
+    pub fn with_url(mut self, url: Url) -> Self {
+        self.url = url;
+        self
+    }
+
+    /// Time after which file is checked again. Default is 60 seconds.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    fn file_url(&self) -> Url {
+        let mut url = self.url.clone();
+        // Project path and file path are single segments, so slashes in them are encoded
+        url.path_segments_mut()
+            .expect("url is a base url")
+            .pop_if_empty()
+            .extend(["api", "v4", "projects", &self.project, "repository", "files", &self.path]);
+        url.query_pairs_mut().append_pair("ref", &self.reference);
+        url
+    }
+
+    async fn send(&self, method: Method) -> Result<reqwest::Response, Box<dyn Error + Send + Sync>> {
+        let mut request = self.client.request(method, self.file_url());
+        if let Some(token) = &self.private_token {
+            request = request.header(TOKEN_HEADER, token);
+        }
+        let response = request.send().await?;
+        match response.status() {
+            status if status.is_success() => Ok(response),
+            StatusCode::NOT_FOUND => Err(GitLabError::NotFound.into()),
+            status => Err(GitLabError::Status(status).into())
+        }
+    }
+
+    async fn load(&self, current_revision: Option<&str>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        let valid_until = SystemTime::now() + self.max_age;
+        if let Some(current_revision) = current_revision {
+            let response = self.send(Method::HEAD).await?;
+            if response.headers().get(BLOB_ID_HEADER).is_some_and(|blob_id| blob_id == current_revision) {
+                return Ok(Revalidation::NotModified { must_revalidate: false, valid_until, invalidation: None, fetched_at: None });
+            }
+        }
+
+        let file: RepositoryFile = serde_json::from_slice(&self.send(Method::GET).await?.bytes().await?)?;
+        if file.encoding != "base64" {
+            return Err(GitLabError::UnsupportedEncoding(file.encoding).into());
+        }
+        let bytes = STANDARD.decode(file.content)?;
+        let mut result = DataLoadResult::new((self.parser)(&bytes)?, false, valid_until);
+        result.metadata.size = Some(bytes.len() as u64);
+        result.metadata.revision = Some(file.blob_id);
+        Ok(Revalidation::Modified(result))
+    }
+}
+
+impl <Data, Parser> DataProvider<Data> for GitLabDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&[u8]) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Downloads and parses the file
+    /// # Errors
+    /// If request fails, file doesn't exist, or parser returns an error
+    async fn load_data(&self) -> Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>> {
+        Ok(self.load(None).await?.into_loaded())
+    }
+
+    /// Checks blob id of the file, and downloads it only if it changed
+    async fn revalidate_data<'a>(&'a self, current: &'a DataLoadResult<Data>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        self.load(current.metadata.revision.as_deref()).await
+    }
+}
+
+impl <Data: Send + Sync, Parser> Debug for GitLabDataProvider<Data, Parser> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GitLabDataProvider")
+            .field("url", &self.url)
+            .field("project", &self.project)
+            .field("path", &self.path)
+            .field("reference", &self.reference)
+            .field("max_age", &self.max_age)
+            .finish_non_exhaustive()
+    }
+}
+
+/// GitLab specific errors
+#[derive(Debug)]
+pub enum GitLabError {
+    /// Project, ref or file does not exist, or token has no access to it
+    NotFound,
+    /// File contents are encoded with unknown encoding
+    UnsupportedEncoding(String),
+    /// Unexpected http status
+    Status(StatusCode)
+}
+
+impl Display for GitLabError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotFound => write!(f, "file not found in GitLab repository"),
+            Self::UnsupportedEncoding(encoding) => write!(f, "unsupported encoding of file contents: {encoding}"),
+            Self::Status(status) => write!(f, "unexpected response status code: {status}")
+        }
+    }
+}
+
+impl Error for GitLabError {}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use mockito::Matcher;
+    use reqwest::Url;
+    use crate::data_providers::data_provider::{DataProvider, Revalidation};
+    use crate::data_providers::gitlab::GitLabDataProvider;
+
+    fn parse(bytes: &[u8]) -> Result<String, Box<dyn Error + Send + Sync>> {
+        Ok(String::from_utf8(bytes.to_vec())?)
+    }
+
+    #[tokio::test]
+    async fn downloads_file_only_when_blob_changes() {
+        let mut server = mockito::Server::new_async().await;
+        let path = "/api/v4/projects/acme%2Fconfig/repository/files/prod%2Fapp.txt";
+        let head = server
+            .mock("HEAD", path)
+            .match_query(Matcher::UrlEncoded("ref".into(), "main".into()))
+            .match_header("PRIVATE-TOKEN", "secret")
+            .with_header("X-Gitlab-Blob-Id", "79f7bbd2")
+            .expect(1)
+            .create_async()
+            .await;
+        let get = server
+            .mock("GET", path)
+            .match_query(Matcher::UrlEncoded("ref".into(), "main".into()))
+            .match_header("PRIVATE-TOKEN", "secret")
+            .with_header("X-Gitlab-Blob-Id", "79f7bbd2")
+            .with_body(r#"{"file_path": "prod/app.txt", "encoding": "base64", "content": "aGVsbG8=", "blob_id": "79f7bbd2", "ref": "main"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let data_provider = GitLabDataProvider::new(reqwest::Client::default(), "acme/config", "prod/app.txt", parse)
+            .with_url(Url::parse(&server.url()).unwrap())
+            .with_private_token("secret")
+            .with_ref("main");
+
+        let loaded = data_provider.load_data().await.unwrap();
+        assert_eq!(loaded.data, "hello");
+        assert_eq!(loaded.metadata.revision.as_deref(), Some("79f7bbd2"));
+        assert!(matches!(data_provider.revalidate_data(&loaded).await.unwrap(), Revalidation::NotModified { .. }));
+        head.assert_async().await;
+        get.assert_async().await;
+    }
+}
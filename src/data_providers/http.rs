@@ -1,23 +1,81 @@
 use std::error::Error;
-use std::fmt::{Display, Formatter};
+use std::fmt::{Debug, Display, Formatter};
+use std::future::Future;
 use std::marker::PhantomData;
 use std::ops::Deref;
-use cache_control::CacheControl;
-use reqwest::header::{CACHE_CONTROL, HeaderName, HeaderValue};
-use reqwest::{StatusCode, Url};
-use crate::data_providers::data_provider::{DataLoadResult, DataProvider};
-use crate::data_providers::http::DataExtractionError::HeaderParseError;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime};
+use cache_control::{Cachability, CacheControl};
+use reqwest::header::{ACCEPT_LANGUAGE, AGE, CACHE_CONTROL, CONTENT_TYPE, DATE, ETAG, EXPIRES, HeaderMap, HeaderName, HeaderValue, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, LINK, RETRY_AFTER};
+use reqwest::{Method, StatusCode, Url};
+use crate::context::RequestContext;
+use crate::control::Control;
+use crate::data_providers::data_provider::{DataLoadResult, DataProvider, Provenance, RetryHint, Revalidation};
+use crate::data_providers::http::DataExtractionError::{HeaderNotFound, HeaderParseError};
+use crate::data_providers::http::identity::InstanceIdentity;
+use crate::deprecation::EndpointDeprecation;
+use crate::duration::parse_duration;
+use crate::hardened::{HardenedClient, SecurityAudit, SecurityIssue};
+#[cfg(doc)]
+use crate::hardened::Hardened;
+use crate::data_providers::http::validator_store::{StateValidatorStore, StoredResponse, ValidatorStore};
+use crate::data_providers::transport::HttpTransport;
+use crate::state_store::StateStore;
+#[cfg(feature = "downward_api")]
+use crate::data_providers::downward_api::{Reconfigure, RefreshHints};
+
+pub mod validator_store;
+pub mod identity;
+/// Data provider that sends requests over Unix domain socket
+#[cfg(all(feature = "unix_socket", unix))]
+pub mod unix_socket;
+/// Projection of one document into several typed views
+#[cfg(feature = "json")]
+pub mod views;
 
 /// Generic data extractor, that consumes [`reqwest::Response`]
 /// Use this trait to create custom data extractors.
 pub trait HttpDataExtractor<Data: Send + Sync> {
     /// Extract data from HTTP response
     /// # Errors
-    /// Any error can be returned by custom implementation.
-    fn extract(&self, response: reqwest::Response) -> impl std::future::Future<Output = Result<DataLoadResult<Data>, Box<dyn Error>>> + Send;
+    /// Any error that is [`Send`] and [`Sync`] can be returned by custom implementation.
+    fn extract(&self, response: reqwest::Response) -> impl std::future::Future<Output = Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>>> + Send;
+}
+
+/// Verifies signature of response body, e.g. detached signature sent in header.
+/// Use [`HttpDataProvider::with_signature_verifier`] to require valid signature.
+pub trait SignatureVerifier: Debug + Send + Sync {
+    /// Checks signature of response
+    /// # Errors
+    /// If signature is missing or invalid
+    fn verify(&self, headers: &HeaderMap, body: &[u8]) -> Result<(), Box<dyn Error + Send + Sync>>;
 }
 
-/// This data provider uses http client to send GET request to specified URL, then feeds response into specified data extractor
+/// Future returned by [`HeaderProvider::headers`]
+pub type HeadersFuture<'a> = Pin<Box<dyn Future<Output = Result<HeaderMap, Box<dyn Error + Send + Sync>>> + Send + 'a>>;
+
+/// Produces headers that are added to every request, e.g. bearer token that is refreshed before it expires.
+/// Use [`HttpDataProvider::with_header_provider`] to register it.
+pub trait HeaderProvider: Debug + Send + Sync {
+    /// Headers for next request. They replace headers with the same names set by data provider
+    /// # Errors
+    /// If headers can't be produced (e.g. token refresh failed), then request is not sent
+    fn headers(&self) -> HeadersFuture<'_>;
+}
+
+/// This data provider uses http client to send GET request to specified URL, then feeds response into specified data extractor.
+/// Method, body and other details of request can be changed with [`HttpDataProvider::with_method`], [`HttpDataProvider::with_body`]
+/// and [`HttpDataProvider::with_request_customizer`].
+/// Client is reqwest by default, and any other client can be used by implementing [`HttpTransport`] for it
+/// (see [`HttpDataProvider::new_with_transport`]).
+///
+/// If origin reports `ETag` or `Last-Modified`, stale data is revalidated with conditional request, and `304 Not Modified` response
+/// only extends freshness of current data, so it is not downloaded and deserialized again.
+/// Origins that require revalidation before every use (`Cache-Control: no-cache` or `max-age=0, must-revalidate`) are revalidated
+/// the same way. Concurrent loads wait for single revalidation request,
+/// so every [`RemoteConfig::load`](crate::config::RemoteConfig::load) costs at most one `304 Not Modified` round trip.
 /// # Examples
 /// ```
 /// use std::collections::HashMap;
@@ -31,46 +89,500 @@ pub trait HttpDataExtractor<Data: Send + Sync> {
 /// let extractor = SerdeDataExtractor::<HashMap<String, String>>::new();
 /// let data_provider = HttpDataProvider::new(client, Url::parse("https://www.example.com/cfg").unwrap(), extractor);
 /// ```
-pub struct HttpDataProvider<Data: Send + Sync, Extractor: HttpDataExtractor<Data>> {
+pub struct HttpDataProvider<Data: Send + Sync, Extractor: HttpDataExtractor<Data>, Transport: HttpTransport = reqwest::Client> {
     extractor: Extractor,
-    client: reqwest::Client,
+    transport: Transport,
     url: Url,
+    validator_store: Option<Arc<dyn ValidatorStore>>,
+    report_revision: bool,
+    identity_headers: HeaderMap,
+    signature_verifier: Option<Arc<dyn SignatureVerifier>>,
+    captured_headers: Vec<HeaderName>,
+    /// Validators of current data, if origin reported them
+    validators: Mutex<Option<Validators>>,
+    /// Successor URL and how long before sunset to switch to it
+    successor: Option<(Url, Duration)>,
+    /// Set once requests are sent to successor URL
+    migrated: AtomicBool,
+    context: Option<(RequestContext, ContextPlacement)>,
+    tls_enforced: bool,
+    debug_headers: bool,
+    method: Method,
+    /// Request body and its content type
+    body: Option<(HeaderValue, Vec<u8>)>,
+    customizer: Option<Mutex<RequestCustomizer>>,
+    header_provider: Option<Arc<dyn HeaderProvider>>,
     phantom_data: PhantomData<Data>
 }
 
-impl <Data: Send + Sync, Extractor: HttpDataExtractor<Data> + Sync> DataProvider<Data> for HttpDataProvider<Data, Extractor> {
-    /// Loads data by making GET request to specified URL
+/// Hook that adjusts every request before it is sent (see [`HttpDataProvider::with_request_customizer`])
+pub type RequestCustomizer = Box<dyn FnMut(http::request::Builder) -> http::request::Builder + Send>;
+
+impl <Data: Send + Sync, Extractor: HttpDataExtractor<Data> + Sync, Transport: HttpTransport> DataProvider<Data> for HttpDataProvider<Data, Extractor, Transport> {
+    /// Loads data by making request (GET by default) to specified URL
     /// # Errors
-    /// If either reqwest client or data extractor returns an error.
-    async fn load_data(&self) -> Result<DataLoadResult<Data>, Box<dyn Error>> {
-        // Clone because trait is not implemented for reference
-        self.extractor.extract(self.client.get(self.url.clone()).send().await?).await
+    /// If either transport or data extractor returns an error.
+    /// Errors of 429 and 503 responses with `Retry-After` header are wrapped in [`RetryHint`], so config doesn't retry before origin asked.
+    async fn load_data(&self) -> Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>> {
+        self.fetch(None).await
+    }
+
+    /// Loads data again, reporting active revision if enabled (see [`HttpDataProvider::with_active_revision_header`]).
+    /// If origin reported `ETag` or `Last-Modified` of current data, conditional request is sent (with `If-None-Match` or `If-Modified-Since`),
+    /// and `304 Not Modified` only extends freshness of current data.
+    /// If 304 response has neither Cache-Control nor Expires header, cache policy of current data
+    /// (including [default TTL](serde_extractor::SerdeDataExtractor::with_default_ttl)) is applied again.
+    async fn revalidate_data<'a>(&'a self, current: &'a DataLoadResult<Data>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        let revision = current.metadata.revision.as_deref().filter(|_| self.report_revision);
+        // Validator store sends conditional requests on its own
+        let validators = self.validators.lock().unwrap().clone().filter(|_| self.validator_store.is_none());
+        let Some(mut validators) = validators else {
+            return Ok(Revalidation::Modified(self.fetch(revision).await?));
+        };
+
+        let mut request = self.request(revision);
+        if let Some(etag) = &validators.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &validators.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+        let response = self.send(request).await?;
+        if response.status() != StatusCode::NOT_MODIFIED {
+            return Ok(Revalidation::Modified(self.extract_received(response).await?));
+        }
+        validators.refresh(response.headers());
+        let mut policy = validators.policy;
+        if self.debug_directives(response.headers()).force_stale {
+            policy.max_age = Duration::ZERO;
+        }
+        *self.validators.lock().unwrap() = Some(validators);
+        Ok(Revalidation::NotModified {
+            must_revalidate: policy.must_revalidate,
+            valid_until: policy.valid_until(SystemTime::now()),
+            invalidation: None,
+            fetched_at: fetched_at(response.headers())
+        })
+    }
+}
+
+/// Validators of current data and its cache policy, used for conditional revalidation
+#[derive(Debug, Clone)]
+struct Validators {
+    etag: Option<HeaderValue>,
+    last_modified: Option<HeaderValue>,
+    /// Effective policy of current data, as applied by extractor
+    policy: CachePolicy
+}
+
+impl Validators {
+    /// Reads validators of response, that was extracted with `policy`. Returns `None` if there are none
+    fn from_headers(headers: &HeaderMap, policy: CachePolicy) -> Option<Self> {
+        let etag = headers.get(ETAG).cloned();
+        let last_modified = headers.get(LAST_MODIFIED).cloned();
+        (etag.is_some() || last_modified.is_some()).then(|| Self { etag, last_modified, policy })
+    }
+
+    /// Updates validators with headers of `304 Not Modified` response.
+    /// Origin may omit them, or freshness headers, then current ones are kept.
+    /// Freshness lifetime of current policy replaces missing `max-age` and Expires, as default TTL does for full responses
+    fn refresh(&mut self, headers: &HeaderMap) {
+        if let Some(etag) = headers.get(ETAG) {
+            self.etag = Some(etag.clone());
+        }
+        if let Some(last_modified) = headers.get(LAST_MODIFIED) {
+            self.last_modified = Some(last_modified.clone());
+        }
+        if !headers.contains_key(CACHE_CONTROL) && !headers.contains_key(EXPIRES) {
+            return;
+        }
+        if let Ok(policy) = CachePolicy::from_headers_with_default_ttl(headers, Some(self.policy.max_age)) {
+            self.policy = policy;
+        }
+    }
+}
+
+impl <Data: Send + Sync, Extractor: HttpDataExtractor<Data> + Sync, Transport: HttpTransport> HttpDataProvider<Data, Extractor, Transport> {
+    fn request(&self, active_revision: Option<&str>) -> http::request::Builder {
+        let mut url = self.url().clone();
+        let mut headers = self.identity_headers.clone();
+        match &self.context {
+            Some((context, ContextPlacement::Query)) => {
+                url.query_pairs_mut().extend_pairs(context.attributes());
+            },
+            Some((context, ContextPlacement::Headers)) => headers.extend(context_headers(context)),
+            None => {}
+        }
+        if let Some((content_type, _)) = &self.body {
+            headers.insert(CONTENT_TYPE, content_type.clone());
+        }
+        let mut request = http::Request::builder().method(self.method.clone()).uri(url.as_str());
+        if let Some(request_headers) = request.headers_mut() {
+            request_headers.extend(headers);
+        }
+        if let Some(revision) = active_revision {
+            request = request.header(ACTIVE_REVISION_HEADER, revision);
+        }
+        match &self.customizer {
+            Some(customizer) => (customizer.lock().unwrap())(request),
+            None => request
+        }
+    }
+
+    /// Sends request with transport, and wraps response for extractors.
+    /// Response is held back for delay requested in debug header, if they are enabled
+    async fn send(&self, mut request: http::request::Builder) -> Result<reqwest::Response, Box<dyn Error + Send + Sync>> {
+        if let Some(header_provider) = &self.header_provider {
+            let headers = header_provider.headers().await?;
+            if let Some(request_headers) = request.headers_mut() {
+                request_headers.extend(headers);
+            }
+        }
+        let body = self.body.as_ref().map(|(_, body)| body.clone()).unwrap_or_default();
+        let response: reqwest::Response = self.transport.send(request.body(body)?).await?.into();
+        if let Some(delay) = self.debug_directives(response.headers()).delay {
+            #[cfg(feature = "tracing")]
+            tracing::info!(url = %self.url(), ?delay, "Delaying response as requested by debug header");
+            tokio::time::sleep(delay).await;
+        }
+        Ok(response)
+    }
+
+    /// Directives of debug header, or defaults if debug headers are not enabled
+    fn debug_directives(&self, headers: &HeaderMap) -> DebugDirectives {
+        match headers.get(DEBUG_HEADER) {
+            Some(value) if self.debug_headers => parse_debug(value),
+            _ => DebugDirectives::default()
+        }
+    }
+
+    /// Extracts data from response that was received from origin, and expires it if debug header forces data to be stale
+    async fn extract_received(&self, response: reqwest::Response) -> Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>> {
+        let force_stale = self.debug_directives(response.headers()).force_stale;
+        let fetched_at = fetched_at(response.headers());
+        let mut result = self.extract(response).await?;
+        result.metadata.fetched_at = fetched_at;
+        if force_stale {
+            #[cfg(feature = "tracing")]
+            tracing::info!(url = %self.url(), "Data is forced to be stale by debug header");
+            result.valid_until = SystemTime::now();
+        }
+        Ok(result)
+    }
+
+    /// Key of stored response. Context is appended as query, so variants of different contexts are stored separately.
+    fn store_key(&self) -> String {
+        let mut url = self.url().clone();
+        if let Some((context, _)) = &self.context {
+            url.query_pairs_mut().extend_pairs(context.attributes());
+        }
+        url.into()
+    }
+
+    async fn fetch(&self, active_revision: Option<&str>) -> Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>> {
+        let mut request = self.request(active_revision);
+        let Some(store) = &self.validator_store else {
+            return self.extract_received(self.send(request).await?).await;
+        };
+
+        let stored = store.load(&self.store_key());
+        if let Some(stored) = &stored {
+            if let Some(etag) = stored.headers.get(ETAG) {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = stored.headers.get(LAST_MODIFIED) {
+                request = request.header(IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+
+        let response = self.send(request).await?;
+        let force_stale = self.debug_directives(response.headers()).force_stale;
+        // Stored response is used only after origin confirmed it, so it is as old as confirmation
+        let fetched_at = fetched_at(response.headers());
+        let stored = match stored {
+            Some(mut stored) if response.status() == StatusCode::NOT_MODIFIED => {
+                stored.refresh(response.headers());
+                stored
+            },
+            _ if response.status().is_success() => StoredResponse::read(response).await?,
+            _ => return self.extract_received(response).await
+        };
+
+        if stored.has_validators() {
+            if let Err(_err) = store.save(&self.store_key(), &stored) {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(url = %self.url(), "Failed to save response to validator store: {_err}");
+            }
+        }
+        let mut result = self.extract(stored.to_response()).await?;
+        result.metadata.fetched_at = fetched_at;
+        if force_stale {
+            result.valid_until = SystemTime::now();
+        }
+        Ok(result)
+    }
+
+    /// Captures allowlisted headers and endpoint deprecation, and adds them to metadata of extracted data.
+    /// Errors of 429 and 503 responses with `Retry-After` header are wrapped in [`RetryHint`]
+    async fn extract(&self, response: reqwest::Response) -> Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>> {
+        let captured: Vec<_> = self.captured_headers.iter()
+            .flat_map(|name| response.headers().get_all(name).iter().map(move |value| (name, value)))
+            .filter_map(|(name, value)| Some((name.as_str().to_string(), value.to_str().ok()?.to_string())))
+            .collect();
+        let deprecation = parse_deprecation(response.headers());
+        let headers = response.headers().clone();
+        let success = response.status().is_success();
+        let retry_after = parse_retry_after(&response);
+        let mut result = match (self.verify_and_extract(response).await, retry_after) {
+            (Err(err), Some(retry_after)) => return Err(RetryHint::new(retry_after, err).into()),
+            (result, _) => result?
+        };
+        // Policy is taken from extracted data, so that defaults of extractor apply to revalidated data too
+        let policy = CachePolicy {
+            max_age: result.valid_until.duration_since(SystemTime::now()).unwrap_or_default(),
+            must_revalidate: result.must_revalidate
+        };
+        *self.validators.lock().unwrap() = Validators::from_headers(&headers, policy).filter(|_| success);
+        result.metadata.headers.extend(captured);
+        if let Some(deprecation) = deprecation {
+            self.migrate_before_sunset(&deprecation);
+            result.metadata.deprecation = Some(deprecation);
+        }
+        Ok(result)
+    }
+
+    /// Switches to successor URL, if it is configured and sunset of current URL is close enough
+    fn migrate_before_sunset(&self, deprecation: &EndpointDeprecation) {
+        let Some((_successor, lead)) = &self.successor else {
+            return;
+        };
+        let Some(remaining) = deprecation.time_to_sunset(SystemTime::now()) else {
+            return;
+        };
+        if remaining <= *lead && !self.migrated.swap(true, Ordering::Relaxed) {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(url = %self.url, successor = %_successor, "Config endpoint sunset is near, switching to successor URL");
+        }
+    }
+
+    /// Verifies signature of successful response, if verifier is set, and passes response to extractor
+    async fn verify_and_extract(&self, response: reqwest::Response) -> Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>> {
+        let Some(verifier) = self.signature_verifier.as_ref().filter(|_| response.status().is_success()) else {
+            return self.extractor.extract(response).await;
+        };
+        let stored = StoredResponse::read(response).await?;
+        verifier.verify(&stored.headers, &stored.body)?;
+        self.extractor.extract(stored.to_response()).await
     }
 }
 
 impl <Data: Send + Sync, Extractor: HttpDataExtractor<Data>> HttpDataProvider<Data, Extractor> {
     /// Construct new [`HttpDataExtractor`] from reqwest client, url and data extractor
     pub fn new(client: reqwest::Client, url: Url, extractor: Extractor) -> Self {
+        Self::new_with_transport(client, url, extractor)
+    }
+
+    /// Construct new [`HttpDataExtractor`] from client that enforces TLS, so it can be used in [`Hardened`] mode
+    pub fn new_hardened(client: HardenedClient, url: Url, extractor: Extractor) -> Self {
+        let mut provider = Self::new(client.into_inner(), url, extractor);
+        provider.tls_enforced = true;
+        provider
+    }
+}
+
+impl <Data: Send + Sync, Extractor: HttpDataExtractor<Data>, Transport: HttpTransport> HttpDataProvider<Data, Extractor, Transport> {
+    /// Construct new [`HttpDataExtractor`] that sends requests with custom transport (e.g. hyper or in-house client).
+    /// Responses are fully read by transport, and passed to extractor as [`reqwest::Response`].
+    pub fn new_with_transport(transport: Transport, url: Url, extractor: Extractor) -> Self {
         Self {
-            client,
+            transport,
             url,
             extractor,
+            validator_store: None,
+            report_revision: false,
+            identity_headers: HeaderMap::new(),
+            signature_verifier: None,
+            captured_headers: Vec::new(),
+            validators: Mutex::new(None),
+            successor: None,
+            migrated: AtomicBool::new(false),
+            context: None,
+            tls_enforced: false,
+            debug_headers: false,
+            method: Method::GET,
+            body: None,
+            customizer: None,
+            header_provider: None,
             phantom_data: PhantomData
         }
     }
+
+    /// Send revision of active data (e.g. `ETag` reported by [`serde_extractor::SerdeDataExtractor`])
+    /// in [`ACTIVE_REVISION_HEADER`] when revalidating, so origin can track rollout progress.
+    pub fn with_active_revision_header(mut self) -> Self {
+        self.report_revision = true;
+        self
+    }
+
+    /// Honor [`DEBUG_HEADER`] of origin, so that staging origin can drive cache behavior of clients in end-to-end tests.
+    /// Directives are `force-stale` (data is stale as soon as it is received) and `delay=<duration>` (e.g. `delay=5s`, response is held back).
+    /// Debug headers are reported as [`SecurityIssue::DebugHeaders`], so they must not be enabled in production.
+    pub fn with_debug_headers(mut self) -> Self {
+        self.debug_headers = true;
+        self
+    }
+
+    /// Await header provider before every request, and add produced headers to it.
+    /// Unlike default headers of client, they can change between requests, e.g. when auth token expires before data does.
+    pub fn with_header_provider(mut self, provider: impl HeaderProvider + 'static) -> Self {
+        self.header_provider = Some(Arc::new(provider));
+        self
+    }
+
+    /// Verify signature of every successful response before it is passed to extractor
+    pub fn with_signature_verifier(mut self, verifier: impl SignatureVerifier + 'static) -> Self {
+        self.signature_verifier = Some(Arc::new(verifier));
+        self
+    }
+
+    /// Copy values of specified response headers into [`LoadMetadata::headers`](crate::data_providers::data_provider::LoadMetadata::headers),
+    /// regardless of extractor. Non-ASCII values are ignored.
+    pub fn with_captured_headers(mut self, names: impl IntoIterator<Item = HeaderName>) -> Self {
+        self.captured_headers.extend(names);
+        self
+    }
+
+    /// Switch to successor URL once origin announces sunset (see [`parse_deprecation`]) that is closer than `lead`.
+    /// With zero lead, URL is switched only after sunset passes. Switch is permanent, and it is traced as warning.
+    pub fn with_successor_url(mut self, successor: Url, lead: Duration) -> Self {
+        self.successor = Some((successor, lead));
+        self
+    }
+
+    /// URL that requests are sent to
+    pub fn url(&self) -> &Url {
+        match &self.successor {
+            Some((successor, _)) if self.migrated.load(Ordering::Relaxed) => successor,
+            _ => &self.url
+        }
+    }
+
+    /// Send requests with specified method instead of GET, e.g. for config APIs that take query in POST body
+    pub fn with_method(mut self, method: Method) -> Self {
+        self.method = method;
+        self
+    }
+
+    /// Send body of specified content type with every request (see [`HttpDataProvider::with_method`])
+    pub fn with_body(mut self, content_type: HeaderValue, body: impl Into<Vec<u8>>) -> Self {
+        self.body = Some((content_type, body.into()));
+        self
+    }
+
+    /// Adjust every request before it is sent, e.g. to construct query or sign request.
+    /// Customizer is called after all other headers are set, except conditional ones (`If-None-Match` and `If-Modified-Since`).
+    /// # Examples
+    /// ```
+    /// use std::collections::HashMap;
+    /// use reqwest::{Method, Url};
+    /// use reqwest::header::HeaderValue;
+    /// use remote_config::data_providers::http::HttpDataProvider;
+    /// use remote_config::data_providers::http::serde_extractor::SerdeDataExtractor;
+    ///
+    /// let mut attempt = 0;
+    /// let data_provider = HttpDataProvider::new(reqwest::Client::default(), Url::parse("https://www.example.com/cfg").unwrap(), SerdeDataExtractor::<HashMap<String, String>>::new())
+    ///     .with_method(Method::POST)
+    ///     .with_body(HeaderValue::from_static("application/json"), r#"{"keys": ["db", "cache"]}"#)
+    ///     .with_request_customizer(move |request| {
+    ///         attempt += 1;
+    ///         request.header("X-Attempt", attempt)
+    ///     });
+    /// ```
+    pub fn with_request_customizer(mut self, customizer: impl FnMut(http::request::Builder) -> http::request::Builder + Send + 'static) -> Self {
+        self.customizer = Some(Mutex::new(Box::new(customizer)));
+        self
+    }
+
+    /// Send attributes of request context with every request, so origin can serve variant of config for it.
+    /// Use one data provider per context, e.g. in factory of [`ContextualConfig`](crate::context::ContextualConfig).
+    pub fn with_request_context(mut self, context: RequestContext, placement: ContextPlacement) -> Self {
+        self.context = Some((context, placement));
+        self
+    }
+
+    /// Attach instance identity headers to every request (see [`InstanceIdentity`])
+    pub fn with_instance_identity(mut self, identity: &InstanceIdentity) -> Self {
+        self.identity_headers = identity.headers();
+        self
+    }
+
+    /// Persist validators (`ETag` and `Last-Modified`) and body of last successful response in specified store.
+    /// Stored validators are sent with every request, including the first one after restart,
+    /// and stored response is passed to extractor when origin replies with `304 Not Modified`.
+    pub fn with_validator_store(mut self, store: impl ValidatorStore + 'static) -> Self {
+        self.validator_store = Some(Arc::new(store));
+        self
+    }
+
+    /// Persist last successful response in [`StateStore`], like [`HttpDataProvider::with_validator_store`].
+    /// Response body is saved as snapshot, and headers as validators.
+    pub fn with_state_store(self, store: impl StateStore + 'static) -> Self {
+        self.with_validator_store(StateValidatorStore(store))
+    }
+}
+
+/// Url hint replaces url
+#[cfg(feature = "downward_api")]
+impl <Data: Send + Sync, Extractor: HttpDataExtractor<Data>, Transport: HttpTransport> Reconfigure for HttpDataProvider<Data, Extractor, Transport> {
+    fn reconfigure(&mut self, hints: &RefreshHints) {
+        if let Some(url) = &hints.url {
+            self.url = url.clone();
+            *self.migrated.get_mut() = false;
+        }
+    }
+}
+
+impl <Data: Send + Sync, Extractor: HttpDataExtractor<Data>, Transport: HttpTransport> SecurityAudit for HttpDataProvider<Data, Extractor, Transport> {
+    fn security_issues(&self) -> Vec<SecurityIssue> {
+        let mut issues = Vec::new();
+        let successor = self.successor.as_ref().map(|(successor, _)| successor);
+        for url in std::iter::once(&self.url).chain(successor) {
+            if url.scheme() != "https" {
+                issues.push(SecurityIssue::PlaintextTransport(url.to_string()));
+            }
+        }
+        if !self.tls_enforced {
+            issues.push(SecurityIssue::TlsNotEnforced);
+        }
+        if self.signature_verifier.is_none() {
+            issues.push(SecurityIssue::MissingSignatureVerification);
+        }
+        if self.debug_headers {
+            issues.push(SecurityIssue::DebugHeaders);
+        }
+        issues
+    }
 }
 
 // Test both serde extractor and http data provider
 #[cfg(all(test, feature = "serde"))]
 mod tests {
-    use std::time::SystemTime;
-    use mockito::ServerGuard;
-    use reqwest::{Url};
+    use std::time::{Duration, SystemTime};
+    use mockito::{Matcher, ServerGuard};
+    use reqwest::{Method, StatusCode, Url};
     use serde::{Deserialize, Serialize};
     use serde_json::json;
-    use crate::data_providers::data_provider::DataProvider;
-    use crate::data_providers::http::{DataExtractionError, HttpDataProvider};
+    use crate::data_providers::data_provider::{DataProvider, RetryHint, Revalidation};
+    use crate::config::DataProviderError;
+    use std::error::Error;
+    use reqwest::header::{CACHE_CONTROL, HeaderMap, HeaderName, HeaderValue};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use reqwest::header::AUTHORIZATION;
+    use crate::data_providers::http::{CachePolicy, DataExtractionError, HeaderProvider, HeadersFuture, HttpDataProvider, SignatureVerifier};
     use crate::data_providers::http::serde_extractor::SerdeDataExtractor;
+    use crate::data_providers::overlay::{OverlayProvider, Overrides};
+    use crate::data_providers::transport::HttpTransport;
+    use crate::hardened::{Hardened, HardenedClient, SecurityAudit, SecurityIssue};
 
     #[derive(Deserialize, Serialize, Debug, Eq, PartialEq)]
     struct TestData {
@@ -88,6 +600,7 @@ mod tests {
             .mock("GET", "/valid-allow-stale")
             .with_header("Content-Type", content_type)
             .with_header("Cache-Control", "public, max-age=10")
+            .with_header("X-Config-Source-Commit", "0123abc")
             .with_body(valid.clone())
             .create_async()
             .await;
@@ -96,6 +609,7 @@ mod tests {
             .mock("GET", "/valid-must-revalidate")
             .with_header("Content-Type", content_type)
             .with_header("Cache-Control", "public, max-age=10, must-revalidate")
+            .with_header("X-Config-Control", "min-poll-interval=300, unknown")
             .with_body(valid.clone())
             .create_async()
             .await;
@@ -156,12 +670,19 @@ mod tests {
                 assert_eq!(data.must_revalidate, false);
                 assert_eq!(data.data, TEST_DATA);
                 assert!(data.valid_until > SystemTime::now());
+                let provenance = data.metadata.provenance.unwrap();
+                assert_eq!(provenance.source_commit.as_deref(), Some("0123abc"));
+                assert_eq!(provenance.publisher, None);
             }
 
             {
                 let data_provider = get_data_provider(server.url() + "/valid-must-revalidate");
                 let data = data_provider.load_data().await.unwrap();
                 assert_eq!(data.must_revalidate, true);
+                assert!(data.metadata.provenance.is_none());
+                let control = data.metadata.control.unwrap();
+                assert_eq!(control.min_poll_interval, Some(std::time::Duration::from_secs(300)));
+                assert!(!control.force_refresh_now);
                 assert_eq!(data.data, TEST_DATA);
                 assert!(data.valid_until > SystemTime::now());
             }
@@ -217,6 +738,599 @@ mod tests {
         test_content_type!(serde_xml_rs::to_string(&TEST_DATA).unwrap(), "application/xml");
     }
 
+    #[tokio::test]
+    #[cfg(feature = "json")]
+    async fn stored_validators_survive_restart() {
+        use crate::data_providers::http::validator_store::FileValidatorStore;
+
+        let dir = std::env::temp_dir().join(format!("remote-config-validators-{}", std::process::id()));
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url() + "/cfg";
+
+        let full = server
+            .mock("GET", "/cfg")
+            .with_header("Content-Type", "application/json")
+            .with_header("Cache-Control", "public, max-age=10")
+            .with_header("ETag", "\"v1\"")
+            .with_body(serde_json::to_string(&TEST_DATA).unwrap())
+            .expect(1)
+            .create_async()
+            .await;
+
+        let data_provider = get_data_provider(url.clone()).with_validator_store(FileValidatorStore::new(&dir));
+        assert_eq!(data_provider.load_data().await.unwrap().data, TEST_DATA);
+        full.assert_async().await;
+        full.remove_async().await;
+
+        // Origin only replies to conditional request, with refreshed freshness
+        let not_modified = server
+            .mock("GET", "/cfg")
+            .match_header("If-None-Match", "\"v1\"")
+            .with_status(304)
+            .with_header("Cache-Control", "public, max-age=10, must-revalidate")
+            .expect(1)
+            .create_async()
+            .await;
+
+        // New provider simulates restarted process
+        let data_provider = get_data_provider(url).with_validator_store(FileValidatorStore::new(&dir));
+        let data = data_provider.load_data().await.unwrap();
+        assert_eq!(data.data, TEST_DATA);
+        assert!(data.must_revalidate);
+        not_modified.assert_async().await;
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "json")]
+    async fn instance_identity_headers() {
+        use crate::data_providers::http::identity::InstanceIdentity;
+
+        let mut server = mockito::Server::new_async().await;
+        let identity = InstanceIdentity::new("web-7").with_shard("eu");
+        let mock = server
+            .mock("GET", "/cfg")
+            .match_header("X-Config-Instance-Id", "web-7")
+            .match_header("X-Config-Shard", "eu")
+            .match_header("X-Config-Instance-Bucket", identity.bucket().to_string().as_str())
+            .match_header("X-Config-Pod-Name", mockito::Matcher::Missing)
+            .with_header("Content-Type", "application/json")
+            .with_header("Cache-Control", "public, max-age=10")
+            .with_body(serde_json::to_string(&TEST_DATA).unwrap())
+            .create_async()
+            .await;
+
+        let data_provider = get_data_provider(server.url() + "/cfg").with_instance_identity(&identity);
+        assert_eq!(data_provider.load_data().await.unwrap().data, TEST_DATA);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "json")]
+    async fn request_context_selects_variant() {
+        use crate::config::RemoteConfig;
+        use crate::context::{ContextualConfig, RequestContext};
+        use crate::data_providers::http::ContextPlacement;
+
+        let mut server = mockito::Server::new_async().await;
+        let mut variant = |tenant: &str, test_number: i64| server
+            .mock("GET", "/cfg")
+            .match_header("X-Config-Tenant", tenant)
+            .match_header("Accept-Language", "de-CH")
+            .with_header("Content-Type", "application/json")
+            .with_header("Cache-Control", "public, max-age=10")
+            .with_body(serde_json::to_string(&TestData { test_number }).unwrap())
+            .expect(1)
+            .create();
+        let acme = variant("acme", 1);
+        let globex = variant("globex", 2);
+        let query = server
+            .mock("GET", "/cfg")
+            .match_query("tenant=acme&region=eu")
+            .with_header("Content-Type", "application/json")
+            .with_header("Cache-Control", "public, max-age=10")
+            .with_body(serde_json::to_string(&TEST_DATA).unwrap())
+            .create_async()
+            .await;
+
+        let url = server.url() + "/cfg";
+        let config = ContextualConfig::new(move |context| {
+            let data_provider = get_data_provider(url.clone()).with_request_context(context.clone(), ContextPlacement::Headers);
+            #[cfg(feature = "tracing")]
+            return RemoteConfig::builder(format!("{context:?}"), data_provider);
+            #[cfg(not (feature = "tracing"))]
+            return RemoteConfig::builder(data_provider);
+        });
+        let acme_context = RequestContext::new().with_tenant("acme").with_locale("de-CH");
+        let globex_context = RequestContext::new().with_tenant("globex").with_locale("de-CH");
+        assert_eq!(config.load(&acme_context).await.unwrap().test_number, 1);
+        assert_eq!(config.load(&globex_context).await.unwrap().test_number, 2);
+        // Both variants are cached
+        assert_eq!(config.load(&acme_context).await.unwrap().test_number, 1);
+        assert_eq!(config.contexts().len(), 2);
+        acme.assert_async().await;
+        globex.assert_async().await;
+
+        let context = RequestContext::new().with_tenant("acme").with_region("eu");
+        let data_provider = get_data_provider(server.url() + "/cfg").with_request_context(context, ContextPlacement::Query);
+        assert_eq!(data_provider.load_data().await.unwrap().data, TEST_DATA);
+        query.assert_async().await;
+    }
+
+    /// Transport that serves single document without network
+    #[derive(Debug)]
+    struct StaticTransport;
+
+    impl HttpTransport for StaticTransport {
+        async fn send(&self, request: http::Request<Vec<u8>>) -> Result<http::Response<Vec<u8>>, Box<dyn Error + Send + Sync>> {
+            assert_eq!(request.uri(), "https://config.local/cfg");
+            Ok(http::Response::builder()
+                .header("Content-Type", "application/json")
+                .header("Cache-Control", "max-age=10")
+                .body(serde_json::to_vec(&TEST_DATA)?)?)
+        }
+    }
+
+    #[tokio::test]
+    async fn custom_transport() {
+        let data_provider = HttpDataProvider::new_with_transport(StaticTransport, Url::parse("https://config.local/cfg").unwrap(), SerdeDataExtractor::<TestData>::new());
+        let result = data_provider.load_data().await.unwrap();
+        assert_eq!(result.data, TEST_DATA);
+        assert!(result.valid_until > SystemTime::now() + Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn captured_headers() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("GET", "/cfg")
+            .with_header("Content-Type", "application/json")
+            .with_header("Cache-Control", "public, max-age=10")
+            .with_header("X-Config-Generation", "17")
+            .with_header("Sunset", "Sat, 31 Dec 2033 23:59:59 GMT")
+            .with_header("X-Not-Captured", "1")
+            .with_body(serde_json::to_string(&TEST_DATA).unwrap())
+            .create_async()
+            .await;
+
+        let data_provider = get_data_provider(server.url() + "/cfg")
+            .with_captured_headers([HeaderName::from_static("x-config-generation"), HeaderName::from_static("sunset")]);
+        let result = data_provider.load_data().await.unwrap();
+        assert_eq!(result.metadata.header("X-Config-Generation"), Some("17"));
+        assert_eq!(result.metadata.header("sunset"), Some("Sat, 31 Dec 2033 23:59:59 GMT"));
+        assert_eq!(result.metadata.header("x-not-captured"), None);
+        assert_eq!(result.metadata.headers.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn switches_to_successor_after_sunset() {
+        let mut server = mockito::Server::new_async().await;
+        let successor = server.url() + "/v2";
+        let _old = server
+            .mock("GET", "/v1")
+            .with_header("Content-Type", "application/json")
+            .with_header("Cache-Control", "max-age=10")
+            .with_header("Deprecation", "@1688169599")
+            .with_header("Sunset", "Sun, 30 Jun 2024 23:59:59 GMT")
+            .with_header("Link", &format!("<{successor}>; rel=\"successor-version\", <https://docs.example.com/migration>; rel=\"deprecation\""))
+            .with_body(serde_json::to_string(&TEST_DATA).unwrap())
+            .expect(1)
+            .create_async()
+            .await;
+        let _new = server
+            .mock("GET", "/v2")
+            .with_header("Content-Type", "application/json")
+            .with_header("Cache-Control", "max-age=10")
+            .with_body(serde_json::to_string(&TEST_DATA).unwrap())
+            .create_async()
+            .await;
+
+        let data_provider = get_data_provider(server.url() + "/v1")
+            .with_successor_url(Url::parse(&successor).unwrap(), Duration::ZERO);
+        let first = data_provider.load_data().await.unwrap();
+        let deprecation = first.metadata.deprecation.unwrap();
+        assert!(deprecation.deprecated);
+        assert_eq!(deprecation.deprecated_since, Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1688169599)));
+        assert_eq!(deprecation.time_to_sunset(SystemTime::now()), Some(Duration::ZERO));
+        assert_eq!(deprecation.successor, Some(successor.clone()));
+        assert_eq!(deprecation.documentation.as_deref(), Some("https://docs.example.com/migration"));
+        assert_eq!(data_provider.url().as_str(), successor);
+
+        let second = data_provider.load_data().await.unwrap();
+        assert_eq!(second.metadata.deprecation, None);
+    }
+
+    #[derive(Debug)]
+    struct SharedSecret;
+
+    impl SignatureVerifier for SharedSecret {
+        fn verify(&self, headers: &HeaderMap, body: &[u8]) -> Result<(), Box<dyn Error + Send + Sync>> {
+            // Not a real signature, just enough to see that verifier gets headers and body
+            let expected = format!("{}:{}", body.len(), "secret");
+            match headers.get("x-signature") {
+                Some(signature) if signature.as_bytes() == expected.as_bytes() => Ok(()),
+                _ => Err("invalid signature".into())
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn signature_verification() {
+        let body = serde_json::to_string(&TEST_DATA).unwrap();
+        let mut server = mockito::Server::new_async().await;
+        for (path, signature) in [("/signed", format!("{}:secret", body.len())), ("/forged", "0:secret".to_string())] {
+            server
+                .mock("GET", path)
+                .with_header("Content-Type", "application/json")
+                .with_header("Cache-Control", "public, max-age=10")
+                .with_header("X-Signature", &signature)
+                .with_body(&body)
+                .create_async()
+                .await;
+        }
+
+        let signed = get_data_provider(server.url() + "/signed").with_signature_verifier(SharedSecret);
+        assert_eq!(signed.load_data().await.unwrap().data, TEST_DATA);
+        let forged = get_data_provider(server.url() + "/forged").with_signature_verifier(SharedSecret);
+        assert_eq!(forged.load_data().await.unwrap_err().to_string(), "invalid signature");
+
+        assert_eq!(signed.security_issues(), vec![SecurityIssue::PlaintextTransport(server.url() + "/signed"), SecurityIssue::TlsNotEnforced]);
+        let hardened = || HttpDataProvider::new_hardened(
+            HardenedClient::new(reqwest::Client::builder()).unwrap(),
+            Url::parse("https://example.com/cfg").unwrap(),
+            SerdeDataExtractor::<TestData>::new()
+        ).with_signature_verifier(SharedSecret);
+        assert!(Hardened::new(hardened()).is_ok());
+        let overlay = OverlayProvider::new(hardened(), Overrides::new().with("test_number", "1"));
+        assert_eq!(overlay.security_issues(), vec![SecurityIssue::LocalOverrides]);
+    }
+
+    #[tokio::test]
+    async fn overloaded_origin_hints_retry() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server.mock("GET", "/cfg")
+            .with_status(503)
+            .with_header("Retry-After", "120")
+            .create_async()
+            .await;
+        let err = get_data_provider(server.url() + "/cfg").load_data().await.unwrap_err().downcast::<RetryHint>().unwrap();
+        assert_eq!(err.retry_after, Duration::from_secs(120));
+        assert!(matches!(err.source().unwrap().downcast_ref::<DataExtractionError>(), Some(DataExtractionError::StatusError(StatusCode::SERVICE_UNAVAILABLE))));
+        assert_eq!(DataProviderError::from(err as Box<dyn Error + Send + Sync>).retry_after(), Some(Duration::from_secs(120)));
+
+        let _mock = server.mock("GET", "/not-found")
+            .with_status(404)
+            .with_header("Retry-After", "120")
+            .create_async()
+            .await;
+        let err = get_data_provider(server.url() + "/not-found").load_data().await.unwrap_err();
+        assert!(err.downcast_ref::<RetryHint>().is_none());
+    }
+
+    #[tokio::test]
+    async fn debug_headers_drive_cache_behavior() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server.mock("GET", "/cfg")
+            .with_header("Content-Type", "application/json")
+            .with_header("Cache-Control", "max-age=600")
+            .with_header("X-Config-Debug", "force-stale, delay=50ms")
+            .with_body(json!(TEST_DATA).to_string())
+            .create_async()
+            .await;
+
+        let result = get_data_provider(server.url() + "/cfg").load_data().await.unwrap();
+        assert!(result.valid_until > SystemTime::now() + Duration::from_secs(500));
+
+        let data_provider = get_data_provider(server.url() + "/cfg").with_debug_headers();
+        assert!(data_provider.security_issues().contains(&SecurityIssue::DebugHeaders));
+        let started = std::time::Instant::now();
+        let result = data_provider.load_data().await.unwrap();
+        assert!(started.elapsed() >= Duration::from_millis(50));
+        assert_eq!(result.data, TEST_DATA);
+        assert!(result.valid_until <= SystemTime::now());
+    }
+
+    #[tokio::test]
+    async fn custom_method_body_and_query() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server.mock("POST", "/cfg")
+            .match_query(Matcher::UrlEncoded("attempt".to_string(), "1".to_string()))
+            .match_header("content-type", "application/json")
+            .match_body(Matcher::JsonString(r#"{"keys": ["test_number"]}"#.to_string()))
+            .with_header("Content-Type", "application/json")
+            .with_header("Cache-Control", "max-age=10")
+            .with_body(json!(TEST_DATA).to_string())
+            .create_async()
+            .await;
+
+        let mut attempt = 0;
+        let data_provider = get_data_provider(server.url() + "/cfg")
+            .with_method(Method::POST)
+            .with_body(HeaderValue::from_static("application/json"), r#"{"keys": ["test_number"]}"#)
+            .with_request_customizer(move |request| {
+                attempt += 1;
+                let uri = format!("{}?attempt={attempt}", request.uri_ref().unwrap());
+                request.uri(uri)
+            });
+        assert_eq!(data_provider.load_data().await.unwrap().data, TEST_DATA);
+        assert!(data_provider.load_data().await.is_err());
+    }
+
+    /// Issues new token for every request
+    #[derive(Debug, Default)]
+    struct RotatingToken(AtomicUsize);
+
+    impl HeaderProvider for RotatingToken {
+        fn headers(&self) -> HeadersFuture<'_> {
+            Box::pin(async move {
+                let token = self.0.fetch_add(1, Ordering::Relaxed);
+                Ok(HeaderMap::from_iter([(AUTHORIZATION, format!("Bearer token-{token}").parse()?)]))
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn header_provider_is_awaited_before_every_request() {
+        let mut server = mockito::Server::new_async().await;
+        let mut mock = |token: &str| server.mock("GET", "/cfg")
+            .match_header("authorization", format!("Bearer {token}").as_str())
+            .with_header("Content-Type", "application/json")
+            .with_header("Cache-Control", "max-age=10")
+            .with_body(json!(TEST_DATA).to_string())
+            .expect(1)
+            .create();
+        let first = mock("token-0");
+        let second = mock("token-1");
+
+        let data_provider = get_data_provider(server.url() + "/cfg").with_header_provider(RotatingToken::default());
+        let result = data_provider.load_data().await.unwrap();
+        assert!(matches!(data_provider.revalidate_data(&result).await.unwrap(), Revalidation::Modified(_)));
+        first.assert_async().await;
+        second.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn cached_response_reports_fetch_time() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server.mock("GET", "/cfg")
+            .with_header("Content-Type", "application/json")
+            .with_header("Cache-Control", "max-age=600")
+            .with_header("Age", "86400")
+            .with_body(json!(TEST_DATA).to_string())
+            .create_async()
+            .await;
+        let _fresh = server.mock("GET", "/fresh")
+            .with_header("Content-Type", "application/json")
+            .with_header("Cache-Control", "max-age=600")
+            .with_body(json!(TEST_DATA).to_string())
+            .create_async()
+            .await;
+
+        let fetched_at = get_data_provider(server.url() + "/cfg").load_data().await.unwrap().metadata.fetched_at.unwrap();
+        let age = SystemTime::now().duration_since(fetched_at).unwrap();
+        assert!((Duration::from_secs(86400)..Duration::from_secs(86410)).contains(&age));
+        assert_eq!(get_data_provider(server.url() + "/fresh").load_data().await.unwrap().metadata.fetched_at, None);
+    }
+
+    #[test]
+    fn cache_policy_clamps_max_age() {
+        let mut headers = HeaderMap::new();
+        assert!(matches!(CachePolicy::from_headers(&headers), Err(DataExtractionError::HeaderNotFound(_))));
+
+        headers.insert(CACHE_CONTROL, HeaderValue::from_static("max-age=99999999999"));
+        let policy = CachePolicy::from_headers(&headers).unwrap();
+        assert_eq!(policy.max_age, Duration::from_secs(1 << 31));
+        assert!(!policy.must_revalidate);
+
+        headers.insert(CACHE_CONTROL, HeaderValue::from_static("no-cache, max-age=60"));
+        let policy = CachePolicy::from_headers(&headers).unwrap();
+        assert_eq!(policy.max_age, Duration::ZERO);
+        assert!(policy.must_revalidate);
+    }
+
+    #[tokio::test]
+    async fn expires_and_default_ttl_replace_missing_max_age() {
+        let mut headers = HeaderMap::new();
+        headers.insert("date", HeaderValue::from_static("Tue, 01 Sep 2026 10:00:00 GMT"));
+        headers.insert("expires", HeaderValue::from_static("Tue, 01 Sep 2026 10:05:00 GMT"));
+        assert_eq!(CachePolicy::from_headers(&headers).unwrap().max_age, Duration::from_secs(300));
+
+        headers.insert(CACHE_CONTROL, HeaderValue::from_static("public, must-revalidate"));
+        let policy = CachePolicy::from_headers(&headers).unwrap();
+        assert_eq!(policy.max_age, Duration::from_secs(300));
+        assert!(policy.must_revalidate);
+
+        headers.insert(CACHE_CONTROL, HeaderValue::from_static("max-age=60"));
+        assert_eq!(CachePolicy::from_headers(&headers).unwrap().max_age, Duration::from_secs(60));
+
+        headers.insert(CACHE_CONTROL, HeaderValue::from_static("max-age=60, s-maxage=120"));
+        headers.insert("age", HeaderValue::from_static("100"));
+        assert_eq!(CachePolicy::from_headers(&headers).unwrap().max_age, Duration::from_secs(20));
+        headers.insert("age", HeaderValue::from_static("1000"));
+        assert_eq!(CachePolicy::from_headers(&headers).unwrap().max_age, Duration::ZERO);
+        headers.insert("age", HeaderValue::from_static("soon"));
+        assert_eq!(CachePolicy::from_headers(&headers).unwrap().max_age, Duration::from_secs(120));
+
+        headers.remove(CACHE_CONTROL);
+        headers.remove("age");
+        headers.insert("expires", HeaderValue::from_static("0"));
+        assert_eq!(CachePolicy::from_headers(&headers).unwrap().max_age, Duration::ZERO);
+
+        // Default TTL applies when neither max-age nor Expires is specified
+        let default_ttl = Some(Duration::from_secs(30));
+        assert_eq!(CachePolicy::from_headers_with_default_ttl(&headers, default_ttl).unwrap().max_age, Duration::ZERO);
+        headers.remove("expires");
+        headers.insert(CACHE_CONTROL, HeaderValue::from_static("public"));
+        assert_eq!(CachePolicy::from_headers(&headers).unwrap().max_age, Duration::ZERO);
+        assert_eq!(CachePolicy::from_headers_with_default_ttl(&headers, default_ttl).unwrap().max_age, Duration::from_secs(30));
+        headers.insert(CACHE_CONTROL, HeaderValue::from_static("public, max-age=10"));
+        assert_eq!(CachePolicy::from_headers_with_default_ttl(&headers, default_ttl).unwrap().max_age, Duration::from_secs(10));
+
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server.mock("GET", "/cfg")
+            .with_header("Content-Type", "application/json")
+            .with_body(serde_json::to_string(&TEST_DATA).unwrap())
+            .create_async()
+            .await;
+        let data_provider = HttpDataProvider::new(
+            reqwest::Client::default(),
+            Url::parse(&(server.url() + "/cfg")).unwrap(),
+            SerdeDataExtractor::<TestData>::new().with_default_ttl(Duration::from_secs(30))
+        );
+        let data = data_provider.load_data().await.unwrap();
+        assert_eq!(data.data, TEST_DATA);
+        assert!(!data.must_revalidate);
+        assert!(data.valid_until > SystemTime::now() + Duration::from_secs(20));
+
+        let strict = HttpDataProvider::new(
+            reqwest::Client::default(),
+            Url::parse(&(server.url() + "/cfg")).unwrap(),
+            SerdeDataExtractor::<TestData>::new().with_default_ttl(Duration::from_secs(30)).require_cache_control(true)
+        );
+        let err = strict.load_data().await.unwrap_err().downcast::<DataExtractionError>().unwrap();
+        assert!(matches!(*err, DataExtractionError::HeaderNotFound(CACHE_CONTROL)));
+    }
+
+    #[tokio::test]
+    async fn default_ttl_survives_revalidation() {
+        // Default TTL applies to responses without Cache-Control, and to ones without max-age
+        for cache_control in [None, Some("public")] {
+            let mut server = mockito::Server::new_async().await;
+            let mut full = server
+                .mock("GET", "/cfg")
+                .with_header("Content-Type", "application/json")
+                .with_header("ETag", "\"v1\"")
+                .with_body(serde_json::to_string(&TEST_DATA).unwrap());
+            if let Some(cache_control) = cache_control {
+                full = full.with_header("Cache-Control", cache_control);
+            }
+            let _full = full.create_async().await;
+            let data_provider = HttpDataProvider::new(
+                reqwest::Client::default(),
+                Url::parse(&(server.url() + "/cfg")).unwrap(),
+                SerdeDataExtractor::<TestData>::new().with_default_ttl(Duration::from_secs(30))
+            );
+            let first = data_provider.load_data().await.unwrap();
+            assert!(first.valid_until > SystemTime::now() + Duration::from_secs(20));
+
+            // 304 responses without freshness headers keep default TTL
+            let not_modified = server
+                .mock("GET", "/cfg")
+                .match_header("If-None-Match", "\"v1\"")
+                .with_status(304)
+                .expect(2)
+                .create_async()
+                .await;
+            for _ in 0..2 {
+                let Revalidation::NotModified { valid_until, .. } = data_provider.revalidate_data(&first).await.unwrap() else {
+                    panic!("expected not modified data");
+                };
+                assert!(valid_until > SystemTime::now() + Duration::from_secs(20));
+            }
+            not_modified.assert_async().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn always_revalidate_with_conditional_requests() {
+        let mut server = mockito::Server::new_async().await;
+        let _full = server
+            .mock("GET", "/cfg")
+            .with_header("Content-Type", "application/json")
+            .with_header("Cache-Control", "no-cache")
+            .with_header("ETag", "\"v1\"")
+            .with_body(serde_json::to_string(&TEST_DATA).unwrap())
+            .expect(1)
+            .create_async()
+            .await;
+        let data_provider = get_data_provider(server.url() + "/cfg");
+        let first = data_provider.load_data().await.unwrap();
+        assert!(first.must_revalidate);
+        assert!(first.valid_until <= SystemTime::now());
+
+        let not_modified = server
+            .mock("GET", "/cfg")
+            .match_header("If-None-Match", "\"v1\"")
+            .with_status(304)
+            .with_header("Cache-Control", "max-age=0, must-revalidate")
+            .expect(2)
+            .create_async()
+            .await;
+        for _ in 0..2 {
+            let Revalidation::NotModified { must_revalidate, .. } = data_provider.revalidate_data(&first).await.unwrap() else {
+                panic!("expected not modified data");
+            };
+            assert!(must_revalidate);
+        }
+        not_modified.assert_async().await;
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "json")]
+    async fn stale_data_is_revalidated_with_etag() {
+        let mut server = mockito::Server::new_async().await;
+        let full = server
+            .mock("GET", "/cfg")
+            .with_header("Content-Type", "application/json")
+            .with_header("Cache-Control", "max-age=60")
+            .with_header("ETag", "\"v1\"")
+            .with_body(serde_json::to_string(&TEST_DATA).unwrap())
+            .expect(1)
+            .create_async()
+            .await;
+        let data_provider = get_data_provider(server.url() + "/cfg");
+        let first = data_provider.load_data().await.unwrap();
+        full.assert_async().await;
+
+        // 304 response without Cache-Control gets policy of current data
+        let not_modified = server
+            .mock("GET", "/cfg")
+            .match_header("If-None-Match", "\"v1\"")
+            .with_status(304)
+            .expect(1)
+            .create_async()
+            .await;
+        let Revalidation::NotModified { must_revalidate, valid_until, .. } = data_provider.revalidate_data(&first).await.unwrap() else {
+            panic!("expected not modified data");
+        };
+        assert!(!must_revalidate);
+        assert!(valid_until > SystemTime::now() + Duration::from_secs(50));
+        not_modified.assert_async().await;
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "json")]
+    async fn stale_data_is_revalidated_with_last_modified() {
+        let mut server = mockito::Server::new_async().await;
+        let _full = server
+            .mock("GET", "/cfg")
+            .with_header("Content-Type", "application/json")
+            .with_header("Cache-Control", "max-age=0")
+            .with_header("Last-Modified", "Wed, 21 Oct 2015 07:28:00 GMT")
+            .with_body(serde_json::to_string(&TEST_DATA).unwrap())
+            .expect(1)
+            .create_async()
+            .await;
+        let data_provider = get_data_provider(server.url() + "/cfg");
+        let first = data_provider.load_data().await.unwrap();
+
+        let not_modified = server
+            .mock("GET", "/cfg")
+            .match_header("If-Modified-Since", "Wed, 21 Oct 2015 07:28:00 GMT")
+            .match_header("If-None-Match", Matcher::Missing)
+            .with_status(304)
+            .with_header("Cache-Control", "max-age=60")
+            .expect(1)
+            .create_async()
+            .await;
+        let Revalidation::NotModified { valid_until, .. } = data_provider.revalidate_data(&first).await.unwrap() else {
+            panic!("expected not modified data");
+        };
+        assert!(valid_until > SystemTime::now() + Duration::from_secs(50));
+        not_modified.assert_async().await;
+    }
+
     #[tokio::test]
     async fn http_error() {
         {
@@ -237,7 +1351,7 @@ pub enum DataExtractionError {
     /// If there is feature that enables support for this content type, feature name is included
     UnsupportedContentType(String, Option<&'static str>), // Optional feature name can be provided
     /// Response body could not be parsed
-    ContentParseError(String, Box<dyn Error>),
+    ContentParseError(String, Box<dyn Error + Send + Sync>),
     /// Unexpected http status
     StatusError(StatusCode)
 }
@@ -278,21 +1392,303 @@ pub fn parse_cache_control(h: &HeaderValue) -> Result<CacheControl, DataExtracti
     CacheControl::from_value(s).ok_or(HeaderParseError(CACHE_CONTROL, s.to_string()))
 }
 
+/// Greatest freshness lifetime that can be represented by Cache-Control header
+const MAX_DELTA_SECONDS: Duration = Duration::from_secs(1 << 31);
+
+/// Freshness policy of HTTP response, derived from its headers.
+/// Exported so that custom extractors compute freshness the same way as built-in ones.
+/// # Examples
+/// ```
+/// use std::time::{Duration, SystemTime};
+/// use reqwest::header::{CACHE_CONTROL, HeaderMap, HeaderValue};
+/// use remote_config::data_providers::http::CachePolicy;
+///
+/// let mut headers = HeaderMap::new();
+/// headers.insert(CACHE_CONTROL, HeaderValue::from_static("max-age=60, must-revalidate"));
+/// let policy = CachePolicy::from_headers(&headers).unwrap();
+/// assert_eq!(policy.max_age, Duration::from_secs(60));
+/// assert!(policy.must_revalidate);
+/// assert_eq!(policy.valid_until(SystemTime::UNIX_EPOCH), SystemTime::UNIX_EPOCH + Duration::from_secs(60));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CachePolicy {
+    /// Remaining freshness lifetime of response, with its `Age` subtracted.
+    /// Zero if neither `max-age`, `Expires` nor default TTL is specified
+    pub max_age: Duration,
+    /// Stale response must not be used until revalidated
+    pub must_revalidate: bool
+}
+
+impl CachePolicy {
+    /// Reads policy from Cache-Control header.
+    /// `no-cache` is treated as `max-age=0, must-revalidate`, so response must be revalidated before every use.
+    /// `s-maxage` takes precedence over `max-age`, as config is usually served through shared caches (CDNs).
+    /// If neither is specified, freshness lifetime is computed from Expires and Date headers (RFC 9111, section 4.2.1).
+    /// Age header is subtracted from freshness lifetime, so that response cached by CDN doesn't appear fresher than it is.
+    /// # Errors
+    /// If neither Cache-Control nor Expires header is present, or Cache-Control can't be parsed
+    pub fn from_headers(headers: &HeaderMap) -> Result<Self, DataExtractionError> {
+        Self::from_headers_with_default_ttl(headers, None)
+    }
+
+    /// Reads policy from headers like [`CachePolicy::from_headers`], and uses `default_ttl` as freshness lifetime
+    /// if neither `max-age` nor Expires is specified (e.g. `Cache-Control: public`, or no Cache-Control at all).
+    /// # Errors
+    /// If Cache-Control can't be parsed, or neither Cache-Control nor Expires header is present and default TTL is not set
+    pub fn from_headers_with_default_ttl(headers: &HeaderMap, default_ttl: Option<Duration>) -> Result<Self, DataExtractionError> {
+        let cache_control = match headers.get(CACHE_CONTROL) {
+            Some(value) => parse_cache_control(value)?,
+            None if headers.contains_key(EXPIRES) || default_ttl.is_some() => CacheControl::default(),
+            None => return Err(HeaderNotFound(CACHE_CONTROL))
+        };
+        let no_cache = cache_control.cachability == Some(Cachability::NoCache);
+        let s_max_age = headers.get(CACHE_CONTROL).and_then(parse_s_maxage);
+        let max_age = match s_max_age.or(cache_control.max_age) {
+            _ if no_cache => Duration::ZERO,
+            Some(max_age) => max_age,
+            None => expires_lifetime(headers).or(default_ttl).unwrap_or_default()
+        };
+        let age = parse_age(headers).unwrap_or_default();
+        Ok(Self {
+            // RFC 9111 (section 1.2.2): delta-seconds greater than 2^31 are treated as 2^31
+            max_age: max_age.min(MAX_DELTA_SECONDS).saturating_sub(age),
+            must_revalidate: cache_control.must_revalidate || no_cache
+        })
+    }
+
+    /// Time when response, received at `now`, becomes stale
+    pub fn valid_until(&self, now: SystemTime) -> SystemTime {
+        now + self.max_age
+    }
+}
+
+/// Value of Age header, set by caches that served response. Invalid Age is ignored (RFC 9111, section 5.1)
+fn parse_age(headers: &HeaderMap) -> Option<Duration> {
+    headers.get(AGE)?.to_str().ok()?.trim().parse().ok().map(Duration::from_secs)
+}
+
+/// Time when response was fetched from origin, if it was served by cache (see [`LoadMetadata::fetched_at`](crate::data_providers::data_provider::LoadMetadata::fetched_at)).
+/// Age is used instead of Date header, so clock skew of origin doesn't matter
+fn fetched_at(headers: &HeaderMap) -> Option<SystemTime> {
+    SystemTime::now().checked_sub(parse_age(headers)?)
+}
+
+/// Delay from `Retry-After` header (in seconds or as HTTP date) of 429 and 503 responses
+fn parse_retry_after(response: &reqwest::Response) -> Option<Duration> {
+    if !matches!(response.status(), StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE) {
+        return None;
+    }
+    let value = response.headers().get(RETRY_AFTER)?.to_str().ok()?.trim();
+    match value.parse() {
+        Ok(seconds) => Some(Duration::from_secs(seconds)),
+        Err(_) => Some(httpdate::parse_http_date(value).ok()?.duration_since(SystemTime::now()).unwrap_or_default())
+    }
+}
+
+/// `s-maxage` directive of Cache-Control header, that is not parsed by `cache_control` crate
+fn parse_s_maxage(value: &HeaderValue) -> Option<Duration> {
+    value.to_str().ok()?
+        .split(',')
+        .filter_map(|directive| directive.split_once('='))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("s-maxage"))
+        .and_then(|(_, seconds)| seconds.trim().trim_matches('"').parse().ok())
+        .map(Duration::from_secs)
+}
+
+/// Freshness lifetime from Expires header, relative to Date header (or current time, if origin doesn't send it).
+/// Invalid Expires value means that response is already expired (RFC 9111, section 5.3).
+fn expires_lifetime(headers: &HeaderMap) -> Option<Duration> {
+    let date = |name| headers.get(name).and_then(|value| value.to_str().ok()).and_then(|value| httpdate::parse_http_date(value).ok());
+    headers.get(EXPIRES)?;
+    let Some(expires) = date(EXPIRES) else {
+        return Some(Duration::ZERO);
+    };
+    let now = date(DATE).unwrap_or_else(SystemTime::now);
+    Some(expires.duration_since(now).unwrap_or_default())
+}
+
+/// Header with publisher of config version
+pub const PUBLISHER_HEADER: &str = "x-config-publisher";
+/// Header with URL of pipeline run that published config version
+pub const PIPELINE_RUN_HEADER: &str = "x-config-pipeline-run";
+/// Header with subject of config version signature
+pub const SIGNATURE_SUBJECT_HEADER: &str = "x-config-signature-subject";
+/// Header with source commit of config version
+pub const SOURCE_COMMIT_HEADER: &str = "x-config-source-commit";
+
+/// Utility function to parse provenance headers.
+/// Returns `None` if none of them is present. Non-ASCII values are ignored.
+/// Exported so that it can be used in custom extractors.
+pub fn parse_provenance(headers: &HeaderMap) -> Option<Provenance> {
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok()).map(str::to_string);
+    let provenance = Provenance {
+        publisher: header(PUBLISHER_HEADER),
+        pipeline_run: header(PIPELINE_RUN_HEADER),
+        signature_subject: header(SIGNATURE_SUBJECT_HEADER),
+        source_commit: header(SOURCE_COMMIT_HEADER)
+    };
+    (!provenance.is_empty()).then_some(provenance)
+}
+
+/// Header that announces deprecation of endpoint (RFC 9745)
+pub const DEPRECATION_HEADER: &str = "deprecation";
+/// Header with time after which endpoint may stop responding (RFC 8594)
+pub const SUNSET_HEADER: &str = "sunset";
+
+/// Utility function to parse `Deprecation` and `Sunset` headers, and related `Link` relations
+/// (`successor-version`, `deprecation` and `sunset`).
+/// Returns `None` if neither deprecation nor sunset is announced.
+/// Deprecation time is accepted both as structured date (`@1688169599`) and HTTP date, other values only mark endpoint as deprecated.
+/// Exported so that it can be used in custom extractors.
+/// # Examples
+/// ```
+/// use reqwest::header::{HeaderMap, HeaderValue, LINK};
+/// use remote_config::data_providers::http::{parse_deprecation, DEPRECATION_HEADER, SUNSET_HEADER};
+///
+/// let mut headers = HeaderMap::new();
+/// headers.insert(DEPRECATION_HEADER, HeaderValue::from_static("@1688169599"));
+/// headers.insert(SUNSET_HEADER, HeaderValue::from_static("Sun, 30 Jun 2030 23:59:59 GMT"));
+/// headers.insert(LINK, HeaderValue::from_static(r#"<https://config.example.com/v2>; rel="successor-version""#));
+/// let deprecation = parse_deprecation(&headers).unwrap();
+/// assert!(deprecation.deprecated);
+/// assert_eq!(deprecation.successor.as_deref(), Some("https://config.example.com/v2"));
+/// ```
+pub fn parse_deprecation(headers: &HeaderMap) -> Option<EndpointDeprecation> {
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok()).map(str::trim);
+    let deprecation_value = header(DEPRECATION_HEADER);
+    let deprecated_since = deprecation_value.and_then(|value| match value.strip_prefix('@') {
+        Some(timestamp) => timestamp.parse().ok().map(|seconds| SystemTime::UNIX_EPOCH + Duration::from_secs(seconds)),
+        None => httpdate::parse_http_date(value).ok()
+    });
+    let mut deprecation = EndpointDeprecation {
+        deprecated: deprecation_value.is_some_and(|value| value != "false"),
+        deprecated_since,
+        sunset: header(SUNSET_HEADER).and_then(|value| httpdate::parse_http_date(value).ok()),
+        ..EndpointDeprecation::default()
+    };
+    if deprecation.is_empty() {
+        return None;
+    }
+
+    let links = headers.get_all(LINK).iter().filter_map(|value| value.to_str().ok()).flat_map(|value| value.split(','));
+    for link in links {
+        let mut parts = link.split(';');
+        let Some(target) = parts.next().and_then(|target| target.trim().strip_prefix('<')?.strip_suffix('>')) else {
+            continue;
+        };
+        let relations = parts
+            .filter_map(|param| param.trim().strip_prefix("rel="))
+            .flat_map(|relations| relations.trim_matches('"').split_ascii_whitespace());
+        for relation in relations {
+            match relation {
+                "successor-version" => deprecation.successor.get_or_insert_with(|| target.to_string()),
+                "deprecation" | "sunset" => deprecation.documentation.get_or_insert_with(|| target.to_string()),
+                _ => continue
+            };
+        }
+    }
+    Some(deprecation)
+}
+
+/// Header with revision of data that is currently active on client
+pub const ACTIVE_REVISION_HEADER: &str = "x-config-active-revision";
+
+/// Header with tenant of request context
+pub const TENANT_HEADER: &str = "x-config-tenant";
+
+/// Header with region of request context
+pub const REGION_HEADER: &str = "x-config-region";
+
+/// How attributes of [`RequestContext`] are sent to origin
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContextPlacement {
+    /// Tenant and region are sent in [`TENANT_HEADER`] and [`REGION_HEADER`], locale in `Accept-Language`
+    Headers,
+    /// Attributes are appended to URL as `tenant`, `locale` and `region` query parameters
+    Query
+}
+
+/// Headers of request context. Values that are not valid header values are skipped.
+fn context_headers(context: &RequestContext) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    let values = [
+        (HeaderName::from_static(TENANT_HEADER), context.tenant()),
+        (ACCEPT_LANGUAGE, context.locale()),
+        (HeaderName::from_static(REGION_HEADER), context.region())
+    ];
+    for (name, value) in values {
+        if let Some(value) = value.and_then(|value| HeaderValue::from_str(value).ok()) {
+            headers.insert(name, value);
+        }
+    }
+    headers
+}
+
+/// Header with origin directives, e.g. `min-poll-interval=300, pause-until=1718000000, force-refresh`
+pub const CONTROL_HEADER: &str = "x-config-control";
+
+/// Header with debug directives of staging origin, e.g. `force-stale, delay=5s` (see [`HttpDataProvider::with_debug_headers`])
+pub const DEBUG_HEADER: &str = "x-config-debug";
+
+/// Directives of [`DEBUG_HEADER`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct DebugDirectives {
+    force_stale: bool,
+    delay: Option<Duration>
+}
+
+/// Parses debug header. Unknown and malformed directives are ignored
+fn parse_debug(h: &HeaderValue) -> DebugDirectives {
+    let mut directives = DebugDirectives::default();
+    for directive in h.to_str().unwrap_or_default().split(',') {
+        match directive.split_once('=').map(|(name, value)| (name.trim(), value.trim())) {
+            Some(("delay", value)) => directives.delay = parse_duration(value).or(directives.delay),
+            None if directive.trim() == "force-stale" => directives.force_stale = true,
+            _ => {}
+        }
+    }
+    directives
+}
+
+/// Utility function to parse control header (see [`CONTROL_HEADER`]).
+/// Unknown and malformed directives are ignored.
+/// Exported so that it can be used in custom extractors.
+pub fn parse_control(h: &HeaderValue) -> Control {
+    let mut control = Control::default();
+    for directive in h.to_str().unwrap_or_default().split(',') {
+        let (name, value) = match directive.split_once('=') {
+            Some((name, value)) => (name.trim(), Some(value.trim())),
+            None => (directive.trim(), None)
+        };
+        let seconds = value.and_then(|value| value.parse().ok()).map(Duration::from_secs);
+        match name {
+            "min-poll-interval" => control.min_poll_interval = seconds,
+            "pause-until" => control.pause_updates_until = seconds.map(|elapsed| SystemTime::UNIX_EPOCH + elapsed),
+            "force-refresh" => control.force_refresh_now = true,
+            _ => {}
+        }
+    }
+    control
+}
+
 /// Automatic HTTP response deserialization with serde
 #[cfg(feature = "serde")]
 pub mod serde_extractor {
     use std::error::Error;
     use std::marker::PhantomData;
     use std::time::{Duration, SystemTime};
-    use reqwest::header::{CACHE_CONTROL, CONTENT_TYPE};
+    use reqwest::header::{CACHE_CONTROL, CONTENT_TYPE, ETAG};
     use reqwest::Response;
     use serde::de::DeserializeOwned;
     use crate::data_providers::data_provider::DataLoadResult;
-    use crate::data_providers::http::{HttpDataExtractor, parse_cache_control};
+    use crate::control::ControlSection;
+    use crate::data_providers::http::{CachePolicy, CONTROL_HEADER, HttpDataExtractor, parse_control, parse_provenance};
     use crate::data_providers::http::DataExtractionError::{ContentParseError, HeaderNotFound, StatusError, UnsupportedContentType};
 
     /// This data extractor automatically deserializes response if its Content-Type is supported.
-    /// Cache-Control header is used to determine max age and revalidation policy.
+    /// Cache-Control header is used to determine max age and revalidation policy, falling back to Expires header
+    /// and then to [default TTL](SerdeDataExtractor::with_default_ttl) (see [`CachePolicy`]).
     /// See list of features and MIME types that they provide support for.
     ///
     /// | Feature | Content-Type            |
@@ -304,6 +1700,10 @@ pub mod serde_extractor {
     ///
     /// [^note]: As of 21.06.2024  there is no official MIME type for TOML, so `application/toml` is used
     pub struct SerdeDataExtractor<Data: DeserializeOwned>{
+        control_section: bool,
+        content_type: Option<&'static str>,
+        default_ttl: Option<Duration>,
+        require_cache_control: bool,
         phantom_data: PhantomData<Data>
     }
 
@@ -311,25 +1711,39 @@ pub mod serde_extractor {
         /// Extracts data from provided response.
         /// # Errors
         /// Return an error in one the following cases:
-        /// - Cache-Control header is not present or can't be parsed
+        /// - Neither Cache-Control nor Expires header is present and default TTL is not set,
+        ///   or Cache-Control header is not present and it is [required](SerdeDataExtractor::require_cache_control)
+        /// - Cache-Control header can't be parsed
         /// - Content-Type header is not present
         /// - MIME type specified in Content-Type header is not supported
         /// - Body cannot be deserialized into `Data` struct
-        async fn extract(&self, response: Response) -> Result<DataLoadResult<Data>, Box<dyn Error>> {
+        ///
+        /// Provenance headers are included in metadata (see [`parse_provenance`]), and `ETag` is reported as revision.
+        async fn extract(&self, response: Response) -> Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>> {
             if !response.status().is_success() {
                 return Err(StatusError(response.status()).into())
             }
 
-            let cache_control = parse_cache_control(response.headers().get(CACHE_CONTROL).ok_or(HeaderNotFound(CACHE_CONTROL))?)?;
-            let content_type = response.headers().get(CONTENT_TYPE).ok_or(HeaderNotFound(CACHE_CONTROL))?;
+            if self.require_cache_control && !response.headers().contains_key(CACHE_CONTROL) {
+                return Err(HeaderNotFound(CACHE_CONTROL).into());
+            }
+            let cache_policy = CachePolicy::from_headers_with_default_ttl(response.headers(), self.default_ttl)?;
+            let content_type = match self.content_type {
+                Some(content_type) => content_type,
+                None => response.headers().get(CONTENT_TYPE).ok_or(HeaderNotFound(CONTENT_TYPE))?.to_str()?
+            };
+            let provenance = parse_provenance(response.headers());
+            let revision = response.headers().get(ETAG).and_then(|etag| etag.to_str().ok()).map(str::to_string);
+            let header_control = response.headers().get(CONTROL_HEADER).map(parse_control);
 
-            let data: Data = match content_type.to_str()? {
+            let (data, size, section): (Data, usize, Option<ControlSection>) = match content_type {
                 "application/json" => {
-                    #[cfg(not (feature = "json"))] return Err(UnsupportedContentType("application/json".to_string(), Some("json"))).into();
+                    #[cfg(not (feature = "json"))] return Err(Box::new(UnsupportedContentType("application/json".to_string(), Some("json"))));
 
                     #[cfg(feature = "json")] {
                         let bytes = response.bytes().await.map_err(|e| ContentParseError("application/json".to_owned(), Box::new(e)))?;
-                        serde_json::de::from_slice::<Data>(&bytes).map_err(|e| ContentParseError("application/json".to_owned(), Box::new(e)))?
+                        let section = self.control_section.then(|| serde_json::de::from_slice(&bytes).ok()).flatten();
+                        (serde_json::de::from_slice::<Data>(&bytes).map_err(|e| ContentParseError("application/json".to_owned(), Box::new(e)))?, bytes.len(), section)
                     }
                 },
                 // NOTE: as of 21.06.2024 no MIME type for TOML is registered officially
@@ -338,7 +1752,8 @@ pub mod serde_extractor {
 
                     #[cfg(feature = "toml")] {
                         let txt = response.text().await.map_err(|e| ContentParseError("application/toml".to_string(), Box::new(e)))?;
-                        toml::from_str::<Data>(&txt).map_err(|e| ContentParseError("application/toml".to_string(), Box::new(e)))?
+                        let section = self.control_section.then(|| toml::from_str(&txt).ok()).flatten();
+                        (toml::from_str::<Data>(&txt).map_err(|e| ContentParseError("application/toml".to_string(), Box::new(e)))?, txt.len(), section)
                     }
                 },
                 "application/yaml" => {
@@ -346,36 +1761,83 @@ pub mod serde_extractor {
 
                     #[cfg(feature = "yaml")] {
                         let bytes = response.bytes().await.map_err(|e| ContentParseError("application/yaml".to_owned(), Box::new(e)))?;
-                        serde_yaml::from_slice::<Data>(&bytes).map_err(|e| ContentParseError("application/yaml".to_owned(), Box::new(e)))?
+                        let section = self.control_section.then(|| serde_yaml::from_slice(&bytes).ok()).flatten();
+                        (serde_yaml::from_slice::<Data>(&bytes).map_err(|e| ContentParseError("application/yaml".to_owned(), Box::new(e)))?, bytes.len(), section)
                     }
                 },
                 "application/xml" => {
-                    #[cfg(not (feature = "xml"))] return Err(Box::new(UnsupportedContentType("application/yaml".to_string(), Some("xml"))));
+                    #[cfg(not (feature = "xml"))] return Err(Box::new(UnsupportedContentType("application/xml".to_string(), Some("xml"))));
 
                     #[cfg(feature = "xml")] {
                         let txt = response.text().await.map_err(|e| ContentParseError("application/xml".to_string(), Box::new(e)))?;
-                        serde_xml_rs::from_str::<Data>(&txt).map_err(|e| ContentParseError("application/xml".to_string(), Box::new(e)))?
+                        let section = self.control_section.then(|| serde_xml_rs::from_str(&txt).ok()).flatten();
+                        (serde_xml_rs::from_str::<Data>(&txt).map_err(|e| ContentParseError("application/xml".to_string(), Box::new(e)))?, txt.len(), section)
                     }
                 }
                 other => {
                     return Err(Box::new(UnsupportedContentType(other.to_string(), None)));
                 }
             };
-            Ok(DataLoadResult {
-                data,
-                must_revalidate: cache_control.must_revalidate,
-                valid_until: SystemTime::now() + cache_control.max_age.unwrap_or(Duration::default())
-            })
+            let mut result = DataLoadResult::new(data, cache_policy.must_revalidate, cache_policy.valid_until(SystemTime::now()));
+            result.metadata.size = Some(size as u64);
+            result.metadata.provenance = provenance;
+            result.metadata.revision = revision;
+            result.metadata.control = section.and_then(|section| section.control).or(header_control);
+            Ok(result)
         }
     }
 
     impl <Data: DeserializeOwned> SerdeDataExtractor<Data> {
         /// Constructs new extractor instance
         pub fn new() -> Self {
-            SerdeDataExtractor{phantom_data: PhantomData}
+            SerdeDataExtractor{control_section: false, content_type: None, default_ttl: None, require_cache_control: false, phantom_data: PhantomData}
+        }
+
+        /// Freshness lifetime of responses that specify neither `max-age` nor Expires header (see [`CachePolicy::from_headers_with_default_ttl`]).
+        /// By default responses without Cache-Control and Expires headers are rejected with [`HeaderNotFound`],
+        /// and responses with Cache-Control header without `max-age` are stale immediately.
+        pub fn with_default_ttl(mut self, ttl: Duration) -> Self {
+            self.default_ttl = Some(ttl);
+            self
+        }
+
+        /// Reject responses without Cache-Control header, instead of falling back to Expires header and default TTL.
+        /// Useful when origin is expected to control freshness explicitly, so misconfigured proxy that strips the header is noticed.
+        pub fn require_cache_control(mut self, required: bool) -> Self {
+            self.require_cache_control = required;
+            self
+        }
+
+        /// Deserialize every response as specified MIME type from the table above, ignoring its Content-Type.
+        /// Useful for origins that serve documents as `text/plain` or `application/octet-stream`.
+        pub fn with_content_type(mut self, content_type: &'static str) -> Self {
+            self.content_type = Some(content_type);
+            self
+        }
+
+        /// Read origin directives from reserved `__control` document section (see [`Control`](crate::control::Control)).
+        /// Document is deserialized twice, so it is disabled by default.
+        /// Directives from [`CONTROL_HEADER`] are always read, and section takes precedence over them.
+        pub fn with_control_section(mut self) -> Self {
+            self.control_section = true;
+            self
         }
     }
     
+    /// MIME type of format name (`json`, `yaml`, `toml` or `xml`), or `None` for `auto` (selected by Content-Type).
+    /// Used by bindings for other languages, that receive format as string.
+    #[cfg(any(feature = "ffi", feature = "python", feature = "manifest"))]
+    pub(crate) fn format_content_type(format: &str) -> Result<Option<&'static str>, String> {
+        match format {
+            "auto" => Ok(None),
+            "json" => Ok(Some("application/json")),
+            "yaml" => Ok(Some("application/yaml")),
+            "toml" => Ok(Some("application/toml")),
+            "xml" => Ok(Some("application/xml")),
+            other => Err(format!("unsupported format: {other}"))
+        }
+    }
+
     impl<Data: DeserializeOwned> Default for SerdeDataExtractor<Data>{
         fn default() -> Self {
             SerdeDataExtractor::new()
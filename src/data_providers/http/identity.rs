@@ -0,0 +1,104 @@
+use std::env;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+
+/// Header with instance id
+pub const INSTANCE_ID_HEADER: &str = "x-config-instance-id";
+/// Header with pod name
+pub const POD_NAME_HEADER: &str = "x-config-pod-name";
+/// Header with shard
+pub const SHARD_HEADER: &str = "x-config-shard";
+/// Header with rollout bucket of instance (see [`InstanceIdentity::bucket`])
+pub const BUCKET_HEADER: &str = "x-config-instance-bucket";
+
+/// Stable identity of client instance, that is sent with every fetch,
+/// so origin can target config versions to percentage of instances or to specific canaries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstanceIdentity {
+    instance_id: String,
+    pod_name: Option<String>,
+    shard: Option<String>
+}
+
+impl InstanceIdentity {
+    /// Creates identity with specified instance id
+    pub fn new(instance_id: impl Into<String>) -> Self {
+        Self {
+            instance_id: instance_id.into(),
+            pod_name: None,
+            shard: None
+        }
+    }
+
+    /// Reads identity from environment.
+    /// Instance id is taken from `REMOTE_CONFIG_INSTANCE_ID` or `HOSTNAME`, pod name from `POD_NAME`
+    /// and shard from `REMOTE_CONFIG_SHARD`. Returns `None` if instance id is not set.
+    pub fn from_env() -> Option<Self> {
+        let var = |name| env::var(name).ok().filter(|value: &String| !value.is_empty());
+        let instance_id = var("REMOTE_CONFIG_INSTANCE_ID").or_else(|| var("HOSTNAME"))?;
+        Some(Self {
+            instance_id,
+            pod_name: var("POD_NAME"),
+            shard: var("REMOTE_CONFIG_SHARD")
+        })
+    }
+
+    /// Kubernetes pod name
+    pub fn with_pod_name(mut self, pod_name: impl Into<String>) -> Self {
+        self.pod_name = Some(pod_name.into());
+        self
+    }
+
+    /// Shard that instance belongs to
+    pub fn with_shard(mut self, shard: impl Into<String>) -> Self {
+        self.shard = Some(shard.into());
+        self
+    }
+
+    /// Instance id
+    pub fn instance_id(&self) -> &str {
+        &self.instance_id
+    }
+
+    /// Rollout bucket in `0..100`, computed as 64-bit FNV-1a hash of instance id modulo 100.
+    /// Origin can compute the same bucket to serve config version to percentage of instances.
+    pub fn bucket(&self) -> u8 {
+        let hash = self.instance_id.bytes().fold(0xcbf29ce484222325_u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        });
+        (hash % 100) as u8
+    }
+
+    /// Identity headers. Values that are not valid header values are skipped.
+    pub(crate) fn headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        let bucket = self.bucket().to_string();
+        let values = [
+            (INSTANCE_ID_HEADER, Some(self.instance_id.as_str())),
+            (POD_NAME_HEADER, self.pod_name.as_deref()),
+            (SHARD_HEADER, self.shard.as_deref()),
+            (BUCKET_HEADER, Some(bucket.as_str()))
+        ];
+        for (name, value) in values {
+            if let Some(value) = value.and_then(|value| HeaderValue::from_str(value).ok()) {
+                headers.insert(HeaderName::from_static(name), value);
+            }
+        }
+        headers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data_providers::http::identity::InstanceIdentity;
+
+    #[test]
+    fn bucket_is_stable() {
+        // FNV-1a of "a" is 0xaf63dc4c8601ec8c
+        assert_eq!(InstanceIdentity::new("a").bucket(), (0xaf63dc4c8601ec8c_u64 % 100) as u8);
+        assert_eq!(InstanceIdentity::new("web-7").bucket(), InstanceIdentity::new("web-7").with_shard("eu").bucket());
+
+        let headers = InstanceIdentity::new("web-7").with_pod_name("web-7-abc").headers();
+        assert_eq!(headers["x-config-pod-name"], "web-7-abc");
+        assert!(!headers.contains_key("x-config-shard"));
+    }
+}
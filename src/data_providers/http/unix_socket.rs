@@ -0,0 +1,139 @@
+use std::error::Error;
+use std::fmt::{Debug, Formatter};
+use std::marker::PhantomData;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+use reqwest::header::IF_NONE_MATCH;
+use reqwest::StatusCode;
+use http::Uri;
+use crate::data_providers::data_provider::{DataLoadResult, DataProvider, Revalidation};
+use crate::data_providers::http::{CachePolicy, HttpDataExtractor};
+use crate::data_providers::transport::{HttpTransport, UnixSocketTransport};
+
+/// This data provider sends GET requests to HTTP server listening on Unix domain socket (e.g. sidecar agent,
+/// that fetches and decrypts config for application), then feeds response into specified data extractor,
+/// the same as [`HttpDataProvider`](crate::data_providers::http::HttpDataProvider) does.
+///
+/// Revalidation sends conditional request, if current data has revision (`ETag` reported by extractor),
+/// and `304 Not Modified` only extends freshness of current data.
+/// # Examples
+/// ```
+/// use std::collections::HashMap;
+/// use remote_config::data_providers::http::unix_socket::UnixSocketHttpDataProvider;
+/// use remote_config::data_providers::http::serde_extractor::SerdeDataExtractor;
+///
+/// let extractor = SerdeDataExtractor::<HashMap<String, String>>::new();
+/// let data_provider = UnixSocketHttpDataProvider::new("/run/config-agent.sock", "http://agent/v1/config".parse().unwrap(), extractor);
+/// ```
+pub struct UnixSocketHttpDataProvider<Data: Send + Sync, Extractor: HttpDataExtractor<Data>> {
+    transport: UnixSocketTransport,
+    uri: Uri,
+    extractor: Extractor,
+    data_type: PhantomData<Data>
+}
+
+impl <Data: Send + Sync, Extractor: HttpDataExtractor<Data> + Sync> UnixSocketHttpDataProvider<Data, Extractor> {
+    /// Creates data provider, that connects to socket at specified path.
+    /// Path and query of URI are requested, and its authority is sent as Host header.
+    pub fn new(socket: impl Into<PathBuf>, uri: Uri, extractor: Extractor) -> Self {
+        Self {
+            transport: UnixSocketTransport::new(socket),
+            uri,
+            extractor,
+            data_type: PhantomData
+        }
+    }
+
+    /// Requested URI
+    pub fn uri(&self) -> &Uri {
+        &self.uri
+    }
+
+    async fn send(&self, etag: Option<&str>) -> Result<http::Response<Vec<u8>>, Box<dyn Error + Send + Sync>> {
+        let mut request = http::Request::get(self.uri.clone());
+        if let Some(etag) = etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        self.transport.send(request.body(Vec::new())?).await
+    }
+}
+
+impl <Data: Send + Sync, Extractor: HttpDataExtractor<Data> + Sync> DataProvider<Data> for UnixSocketHttpDataProvider<Data, Extractor> {
+    /// Loads data by making GET request over socket
+    /// # Errors
+    /// If socket can't be connected, request fails, or data extractor returns an error
+    async fn load_data(&self) -> Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>> {
+        self.extractor.extract(self.send(None).await?.into()).await
+    }
+
+    /// Loads data again, with conditional request if current data has revision
+    async fn revalidate_data<'a>(&'a self, current: &'a DataLoadResult<Data>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        let response = self.send(current.metadata.revision.as_deref()).await?;
+        if response.status() != StatusCode::NOT_MODIFIED || current.metadata.revision.is_none() {
+            return Ok(Revalidation::Modified(self.extractor.extract(response.into()).await?));
+        }
+        // Server may omit Cache-Control in 304 response, then policy of current data is kept
+        let policy = CachePolicy::from_headers(response.headers())
+            .unwrap_or(CachePolicy { max_age: Duration::ZERO, must_revalidate: current.must_revalidate });
+        Ok(Revalidation::NotModified {
+            must_revalidate: policy.must_revalidate,
+            valid_until: policy.valid_until(SystemTime::now()),
+            invalidation: None,
+            fetched_at: None
+        })
+    }
+}
+
+impl <Data: Send + Sync, Extractor: HttpDataExtractor<Data>> Debug for UnixSocketHttpDataProvider<Data, Extractor> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UnixSocketHttpDataProvider")
+            .field("socket", &self.transport.path())
+            .field("uri", &self.uri)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::UnixListener;
+    use crate::data_providers::data_provider::{DataProvider, Revalidation};
+    use crate::data_providers::http::serde_extractor::SerdeDataExtractor;
+    use crate::data_providers::http::unix_socket::UnixSocketHttpDataProvider;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct AgentConfig {
+        level: String
+    }
+
+    #[tokio::test]
+    async fn loads_and_revalidates_over_socket() {
+        let path = std::env::temp_dir().join(format!("remote_config_agent_{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path).unwrap();
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = vec![0; 4096];
+                let read = stream.read(&mut request).await.unwrap();
+                let request = String::from_utf8_lossy(&request[..read]).to_lowercase();
+                assert!(request.starts_with("get /v1/config http/1.1\r\n"), "{request}");
+                assert!(request.contains("host: agent\r\n"), "{request}");
+                let response = if request.contains("if-none-match: \"1\"") {
+                    "HTTP/1.1 304 Not Modified\r\ncache-control: max-age=30\r\ncontent-length: 0\r\n\r\n".to_string()
+                } else {
+                    let body = r#"{"level": "debug"}"#;
+                    format!("HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncache-control: max-age=30\r\netag: \"1\"\r\ncontent-length: {}\r\n\r\n{body}", body.len())
+                };
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+
+        let data_provider = UnixSocketHttpDataProvider::new(&path, "http://agent/v1/config".parse().unwrap(), SerdeDataExtractor::<AgentConfig>::new());
+        let loaded = data_provider.load_data().await.unwrap();
+        assert_eq!(loaded.data, AgentConfig { level: "debug".to_string() });
+        assert!(matches!(data_provider.revalidate_data(&loaded).await.unwrap(), Revalidation::NotModified { .. }));
+        std::fs::remove_file(path).unwrap();
+    }
+}
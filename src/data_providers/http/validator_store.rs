@@ -0,0 +1,167 @@
+use std::error::Error;
+use std::fmt::Debug;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+use reqwest::header::{ETAG, HeaderMap, HeaderName, HeaderValue, LAST_MODIFIED, SET_COOKIE};
+use reqwest::Response;
+use tokio::task::AbortHandle;
+use crate::state_store::{compact_dir, escape_file_name, spawn_gc, write_atomically, RetentionPolicy, StateStore};
+
+/// Successful response that is stored to issue conditional requests and to restore response on `304 Not Modified`
+#[derive(Debug, Clone, Default)]
+pub struct StoredResponse {
+    /// Response headers, except `Set-Cookie`
+    pub headers: HeaderMap,
+    /// Response body
+    pub body: Vec<u8>
+}
+
+impl StoredResponse {
+    /// Reads whole response
+    pub(crate) async fn read(response: Response) -> reqwest::Result<Self> {
+        let mut headers = response.headers().clone();
+        headers.remove(SET_COOKIE);
+        let body = response.bytes().await?.to_vec();
+        Ok(Self { headers, body })
+    }
+
+    /// Checks if response has `ETag` or `Last-Modified` header
+    pub(crate) fn has_validators(&self) -> bool {
+        self.headers.contains_key(ETAG) || self.headers.contains_key(LAST_MODIFIED)
+    }
+
+    /// Updates stored headers with headers of `304 Not Modified` response (RFC 9111, section 4.3.4)
+    pub(crate) fn refresh(&mut self, headers: &HeaderMap) {
+        for name in headers.keys() {
+            if name == SET_COOKIE {
+                continue;
+            }
+            self.headers.remove(name);
+            for value in headers.get_all(name) {
+                self.headers.append(name.clone(), value.clone());
+            }
+        }
+    }
+
+    /// Restores response, so it can be passed to data extractor
+    pub(crate) fn to_response(&self) -> Response {
+        let mut response = http::Response::new(self.body.clone());
+        *response.headers_mut() = self.headers.clone();
+        Response::from(response)
+    }
+
+    /// Serializes response as header lines, followed by empty line and body
+    fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.body.len() + 256);
+        for (name, value) in &self.headers {
+            out.extend_from_slice(name.as_str().as_bytes());
+            out.extend_from_slice(b": ");
+            out.extend_from_slice(value.as_bytes());
+            out.push(b'\n');
+        }
+        out.push(b'\n');
+        out.extend_from_slice(&self.body);
+        out
+    }
+
+    fn decode(bytes: &[u8]) -> Option<Self> {
+        let mut headers = HeaderMap::new();
+        let mut rest = bytes;
+        loop {
+            let end = rest.iter().position(|b| *b == b'\n')?;
+            let line = &rest[..end];
+            rest = &rest[end + 1..];
+            if line.is_empty() {
+                break;
+            }
+            let separator = line.iter().position(|b| *b == b':')?;
+            let name = HeaderName::from_bytes(&line[..separator]).ok()?;
+            let value = HeaderValue::from_bytes(line[separator + 1..].trim_ascii_start()).ok()?;
+            headers.append(name, value);
+        }
+        Some(Self { headers, body: rest.to_vec() })
+    }
+}
+
+/// Storage for last successful responses, shared between processes (or between restarts of one process).
+/// Stored validators allow even the first request after restart to be conditional.
+/// # Errors
+/// Any error can be returned by [`ValidatorStore::save`]. It is reported with tracing (if enabled), but does not fail data load.
+pub trait ValidatorStore: Debug + Send + Sync {
+    /// Loads stored response for specified URL
+    fn load(&self, url: &str) -> Option<StoredResponse>;
+    /// Saves response for specified URL
+    fn save(&self, url: &str, response: &StoredResponse) -> Result<(), Box<dyn Error + Send + Sync>>;
+}
+
+/// Stores responses in files inside specified directory, one file per URL.
+/// Files are replaced atomically, so the directory can be shared by several processes.
+///
+/// Response body is stored as is, so directory permissions should match sensitivity of config data.
+/// Use [`FileValidatorStore::with_retention`] to limit accumulated responses of URLs that are no longer used.
+#[derive(Debug, Clone)]
+pub struct FileValidatorStore {
+    dir: PathBuf,
+    retention: RetentionPolicy
+}
+
+impl FileValidatorStore {
+    /// Creates store in specified directory. Directory is created on first save if it doesn't exist.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into(), retention: RetentionPolicy::default() }
+    }
+
+    /// Retention policy enforced by [`FileValidatorStore::compact`]
+    pub fn with_retention(mut self, policy: RetentionPolicy) -> Self {
+        self.retention = policy;
+        self
+    }
+
+    /// Removes responses that exceed retention policy, and temporary files left by interrupted saves.
+    /// Returns number of removed responses.
+    /// # Errors
+    /// If directory can't be read or file can't be removed
+    pub fn compact(&self) -> std::io::Result<usize> {
+        compact_dir(&self.dir, &self.retention, |file_name| file_name)
+    }
+
+    /// Starts task, that compacts store with specified interval (first time immediately) until it is aborted
+    pub fn spawn_gc(&self, interval: Duration) -> AbortHandle {
+        let store = self.clone();
+        spawn_gc(move || store.compact(), interval)
+    }
+
+    fn path(&self, url: &str) -> PathBuf {
+        self.dir.join(escape_file_name(url))
+    }
+}
+
+impl ValidatorStore for FileValidatorStore {
+    fn load(&self, url: &str) -> Option<StoredResponse> {
+        StoredResponse::decode(&fs::read(self.path(url)).ok()?)
+    }
+
+    fn save(&self, url: &str, response: &StoredResponse) -> Result<(), Box<dyn Error + Send + Sync>> {
+        fs::create_dir_all(&self.dir)?;
+        write_atomically(&self.path(url), &response.encode())?;
+        Ok(())
+    }
+}
+
+/// Stores responses in [`StateStore`]: body as snapshot, and headers as validators
+#[derive(Debug)]
+pub(crate) struct StateValidatorStore<Store>(pub(crate) Store);
+
+impl <Store: StateStore> ValidatorStore for StateValidatorStore<Store> {
+    fn load(&self, url: &str) -> Option<StoredResponse> {
+        let headers = StoredResponse::decode(&self.0.load_validators(url)?)?.headers;
+        Some(StoredResponse { headers, body: self.0.load_snapshot(url)? })
+    }
+
+    fn save(&self, url: &str, response: &StoredResponse) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let headers = StoredResponse { headers: response.headers.clone(), body: Vec::new() };
+        self.0.save_snapshot(url, &response.body)?;
+        self.0.save_validators(url, &headers.encode())
+    }
+}
@@ -0,0 +1,229 @@
+use std::any::{type_name, Any, TypeId};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::sync::Arc;
+use reqwest::Response;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use tokio::sync::watch;
+use crate::config::RemoteConfig;
+use crate::content_hash;
+use crate::data_providers::data_provider::{DataLoadResult, DataProvider};
+use crate::data_providers::http::HttpDataExtractor;
+use crate::data_providers::http::serde_extractor::SerdeDataExtractor;
+
+type Projector = fn(&Value) -> Result<(Arc<dyn Any + Send + Sync>, u64), serde_json::Error>;
+
+/// Registered view type
+struct Projection {
+    type_id: TypeId,
+    name: &'static str,
+    project: Projector
+}
+
+fn project<View: DeserializeOwned + Serialize + Send + Sync + 'static>(document: &Value) -> Result<(Arc<dyn Any + Send + Sync>, u64), serde_json::Error> {
+    let view = View::deserialize(document)?;
+    let hash = content_hash::serialized(&view)?;
+    Ok((Arc::new(view), hash))
+}
+
+/// Typed views over single document, loaded with [`ViewsExtractor`]
+#[derive(Clone)]
+pub struct Views {
+    views: HashMap<TypeId, (Arc<dyn Any + Send + Sync>, u64)>
+}
+
+impl Views {
+    /// View of specified type, if it is registered
+    pub fn get<View: 'static>(&self) -> Option<&View> {
+        self.views.get(&TypeId::of::<View>()).and_then(|(view, _)| view.downcast_ref())
+    }
+
+    /// Content hash of view, that changes only when projection changes
+    pub fn content_hash<View: 'static>(&self) -> Option<u64> {
+        self.views.get(&TypeId::of::<View>()).map(|(_, hash)| *hash)
+    }
+}
+
+impl Debug for Views {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Views").field("count", &self.views.len()).finish_non_exhaustive()
+    }
+}
+
+/// View could not be projected from loaded document
+#[derive(Debug)]
+pub struct ViewError {
+    /// Type name of view
+    pub view: &'static str,
+    /// Deserialization error
+    pub source: serde_json::Error
+}
+
+impl Display for ViewError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "failed to project view {}", self.view)
+    }
+}
+
+impl Error for ViewError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// This data extractor deserializes response once with [`SerdeDataExtractor`] (so all its content types are supported),
+/// and projects document into every registered view type. Each view only has to declare fields that it uses.
+///
+/// Application can subscribe to changes of single view with [`RemoteConfig::subscribe_view`].
+/// Subscribers are notified only when projection of their view changes, i.e. when its [`Views::content_hash`] changes.
+/// # Examples
+/// ```
+/// use serde::{Deserialize, Serialize};
+/// use remote_config::data_providers::http::views::ViewsExtractor;
+///
+/// #[derive(Deserialize, Serialize)]
+/// struct Database {
+///     pool_size: u32
+/// }
+///
+/// #[derive(Deserialize, Serialize)]
+/// struct Features {
+///     new_checkout: bool
+/// }
+///
+/// let extractor = ViewsExtractor::new().with_view::<Database>().with_view::<Features>();
+/// // Pass extractor to HttpDataProvider, and read views with `config.load().await?.get::<Features>()`.
+/// // `config.subscribe_view::<Features>()` returns receiver, that is notified only when activated Features differ from previous ones.
+/// ```
+#[derive(Default)]
+pub struct ViewsExtractor {
+    document: SerdeDataExtractor<Value>,
+    projections: Vec<Projection>
+}
+
+impl ViewsExtractor {
+    /// Constructs extractor without views
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers view type. View is deserialized from whole document, and serialized to compute its content hash.
+    pub fn with_view<View: DeserializeOwned + Serialize + Send + Sync + 'static>(mut self) -> Self {
+        let type_id = TypeId::of::<View>();
+        if self.projections.iter().all(|projection| projection.type_id != type_id) {
+            self.projections.push(Projection {
+                type_id,
+                name: type_name::<View>(),
+                project: project::<View>
+            });
+        }
+        self
+    }
+}
+
+impl HttpDataExtractor<Views> for ViewsExtractor {
+    /// Deserializes document and projects it into all views
+    /// # Errors
+    /// If document can't be extracted by [`SerdeDataExtractor`], or any view can't be deserialized from it
+    async fn extract(&self, response: Response) -> Result<DataLoadResult<Views>, Box<dyn Error + Send + Sync>> {
+        let document = self.document.extract(response).await?;
+        let mut views = HashMap::with_capacity(self.projections.len());
+        for projection in &self.projections {
+            let view = (projection.project)(&document.data).map_err(|source| ViewError { view: projection.name, source })?;
+            views.insert(projection.type_id, view);
+        }
+        let mut result = DataLoadResult::new(Views { views }, document.must_revalidate, document.valid_until);
+        result.metadata = document.metadata;
+        Ok(result)
+    }
+}
+
+impl <Provider: DataProvider<Views> + Send + 'static> RemoteConfig<Views, Provider> {
+    /// Receiver of content hash of view, that is updated only when activated version changes projection of the view.
+    /// Value is `None` while view is not loaded, e.g. before first successful load or if view type is not registered in [`ViewsExtractor`].
+    ///
+    /// Must be called within tokio runtime, that runs task following config.
+    /// Subscribers are notified after version is activated, so [`RemoteConfig::load`] returns new view when receiver sees the change.
+    /// Task revalidates data as soon as it becomes stale, and stops when all receivers are dropped.
+    pub fn subscribe_view<View: 'static>(&'static self) -> watch::Receiver<Option<u64>> {
+        let (sender, receiver) = watch::channel(None);
+        tokio::spawn(Self::follow_versions(self, move |views| {
+            let hash = views.content_hash::<View>();
+            sender.send_if_modified(|current| std::mem::replace(current, hash) != hash);
+            !sender.is_closed()
+        }));
+        receiver
+    }
+}
+
+impl Debug for ViewsExtractor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ViewsExtractor")
+            .field("views", &self.projections.iter().map(|projection| projection.name).collect::<Vec<_>>())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::Url;
+    use serde::{Deserialize, Serialize};
+    use crate::config::RemoteConfig;
+    use crate::data_providers::http::HttpDataProvider;
+    use crate::data_providers::http::views::ViewsExtractor;
+
+    #[derive(Debug, Deserialize, Serialize, PartialEq)]
+    struct Database {
+        pool_size: u32
+    }
+
+    #[derive(Debug, Deserialize, Serialize, PartialEq)]
+    struct Features {
+        new_checkout: bool
+    }
+
+    #[tokio::test]
+    async fn only_changed_views_are_notified() {
+        let mut server = mockito::Server::new_async().await;
+        let url = Url::parse(&server.url()).unwrap();
+        let mut mock = |body: &'static str| server
+            .mock("GET", "/")
+            .with_header("Cache-Control", "max-age=60")
+            .with_header("Content-Type", "application/json")
+            .with_body(body)
+            .create();
+        let _first = mock(r#"{"pool_size": 4, "new_checkout": false}"#);
+
+        let extractor = ViewsExtractor::new().with_view::<Database>().with_view::<Features>();
+        let data_provider = HttpDataProvider::new(reqwest::Client::default(), url, extractor);
+        #[cfg(feature = "tracing")]
+        let config = RemoteConfig::builder("Views".to_string(), data_provider).build().await.unwrap();
+        #[cfg(not(feature = "tracing"))]
+        let config = RemoteConfig::builder(data_provider).build().await.unwrap();
+        let config = Box::leak(Box::new(config));
+
+        let mut database_changed = config.subscribe_view::<Database>();
+        let mut features_changed = config.subscribe_view::<Features>();
+        let first = config.load().await.unwrap();
+        assert_eq!(first.get::<Database>(), Some(&Database { pool_size: 4 }));
+        assert_eq!(first.get::<Features>(), Some(&Features { new_checkout: false }));
+        assert!(database_changed.has_changed().unwrap());
+        assert!(features_changed.has_changed().unwrap());
+        database_changed.mark_unchanged();
+        features_changed.mark_unchanged();
+
+        // Only features section changed
+        let _second = mock(r#"{"pool_size": 4, "new_checkout": true}"#);
+        config.refresh().await.unwrap();
+        features_changed.changed().await.unwrap();
+        // Subscriber sees activated version
+        let second = config.load().await.unwrap();
+        assert_eq!(second.get::<Features>(), Some(&Features { new_checkout: true }));
+        assert_eq!(*features_changed.borrow(), (*second).content_hash::<Features>());
+        assert_eq!((*second).content_hash::<Database>(), (*first).content_hash::<Database>());
+        assert!(!database_changed.has_changed().unwrap());
+    }
+}
@@ -0,0 +1,264 @@
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::marker::PhantomData;
+use std::time::{Duration, SystemTime};
+use reqwest::{Response, StatusCode, Url};
+use crate::data_providers::data_provider::{DataLoadResult, DataProvider, Revalidation};
+
+/// Header with immutable path of content served by gateway
+const IPFS_PATH_HEADER: &str = "X-Ipfs-Path";
+
+/// Where content is loaded from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IpfsEndpoint {
+    /// HTTP gateway, e.g. `https://ipfs.io` or gateway of local node at `http://127.0.0.1:8080`.
+    /// IPNS names are resolved with `HEAD` request, that returns `X-Ipfs-Path` header.
+    Gateway(Url),
+    /// RPC API of local node (e.g. Kubo), usually `http://127.0.0.1:5001`.
+    /// IPNS names are resolved with `name/resolve`, and content is read with `cat`.
+    Node(Url)
+}
+
+/// This data provider loads document from IPFS by immutable path (`/ipfs/<cid>`) or IPNS name (`/ipns/<name>`),
+/// optionally followed by path inside directory, and parses it with specified function.
+///
+/// CID of root (that IPNS name resolves to) is reported as revision. Document is downloaded only when CID changes,
+/// so checking IPNS name costs one resolve request, and content of `/ipfs/` path is never downloaded again.
+/// # Examples
+/// ```
+/// # #[cfg(feature = "json")] {
+/// use std::collections::HashMap;
+/// use reqwest::Url;
+/// use remote_config::data_providers::ipfs::{IpfsDataProvider, IpfsEndpoint};
+///
+/// let gateway = IpfsEndpoint::Gateway(Url::parse("https://ipfs.io").unwrap());
+/// let data_provider = IpfsDataProvider::new(reqwest::Client::default(), gateway, "/ipns/config.example.com/app.json", |bytes: &[u8]| {
+///     Ok(serde_json::from_slice::<HashMap<String, String>>(bytes)?)
+/// });
+/// # }
+/// ```
+pub struct IpfsDataProvider<Data: Send + Sync, Parser> {
+    client: reqwest::Client,
+    endpoint: IpfsEndpoint,
+    path: String,
+    parser: Parser,
+    max_age: Duration,
+    data_type: PhantomData<Data>
+}
+
+impl <Data, Parser> IpfsDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&[u8]) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Creates data provider for `/ipfs/` or `/ipns/` path
+    pub fn new(client: reqwest::Client, endpoint: IpfsEndpoint, path: impl Into<String>, parser: Parser) -> Self {
+        Self {
+            client,
+            endpoint,
+            path: path.into(),
+            parser,
+            max_age: Duration::from_secs(60),
+            data_type: PhantomData
+        }
+    }
+
+    /// Time after which IPNS name is resolved again. Default is 60 seconds.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Splits path into namespace, root and path inside root (empty or starting with `/`)
+    fn split_path(&self) -> Result<(&str, &str, &str), IpfsError> {
+        let invalid = || IpfsError::InvalidPath(self.path.clone());
+        let (namespace, rest) = self.path.strip_prefix('/').ok_or_else(invalid)?.split_once('/').ok_or_else(invalid)?;
+        let (root, inner) = rest.find('/').map_or((rest, ""), |index| rest.split_at(index));
+        match namespace {
+            "ipfs" | "ipns" if !root.is_empty() => Ok((namespace, root, inner)),
+            _ => Err(invalid())
+        }
+    }
+
+    fn endpoint_url(base: &Url, segments: &[&str]) -> Url {
+        let mut url = base.clone();
+        url.path_segments_mut().expect("endpoint is a base url").pop_if_empty().extend(segments);
+        url
+    }
+
+    async fn check(response: Response) -> Result<Response, Box<dyn Error + Send + Sync>> {
+        match response.status() {
+            status if status.is_success() => Ok(response),
+            status => Err(IpfsError::Status(status).into())
+        }
+    }
+
+    /// Resolves IPNS name to CID
+    async fn resolve(&self, name: &str) -> Result<String, Box<dyn Error + Send + Sync>> {
+        let resolved = match &self.endpoint {
+            IpfsEndpoint::Gateway(gateway) => {
+                let response = Self::check(self.client.head(Self::endpoint_url(gateway, &["ipns", name])).send().await?).await?;
+                response.headers().get(IPFS_PATH_HEADER).and_then(|path| path.to_str().ok()).map(str::to_string)
+            },
+            IpfsEndpoint::Node(node) => {
+                let mut url = Self::endpoint_url(node, &["api", "v0", "name", "resolve"]);
+                url.query_pairs_mut().append_pair("arg", &format!("/ipns/{name}"));
+                let response = Self::check(self.client.post(url).send().await?).await?;
+                let body: serde_json::Value = serde_json::from_slice(&response.bytes().await?)?;
+                body["Path"].as_str().map(str::to_string)
+            }
+        };
+        resolved.as_deref()
+            .and_then(|path| path.strip_prefix("/ipfs/"))
+            .and_then(|path| path.split('/').next())
+            .filter(|cid| !cid.is_empty())
+            .map(str::to_string)
+            .ok_or_else(|| IpfsError::Unresolved(name.to_string()).into())
+    }
+
+    async fn fetch(&self, cid: &str, inner: &str) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>> {
+        let inner_segments = inner.split('/').filter(|segment| !segment.is_empty());
+        let request = match &self.endpoint {
+            IpfsEndpoint::Gateway(gateway) => {
+                let segments: Vec<_> = ["ipfs", cid].into_iter().chain(inner_segments).collect();
+                self.client.get(Self::endpoint_url(gateway, &segments))
+            },
+            IpfsEndpoint::Node(node) => {
+                let mut url = Self::endpoint_url(node, &["api", "v0", "cat"]);
+                url.query_pairs_mut().append_pair("arg", &format!("/ipfs/{cid}{inner}"));
+                self.client.post(url)
+            }
+        };
+        Ok(Self::check(request.send().await?).await?.bytes().await?.to_vec())
+    }
+
+    async fn load(&self, current_revision: Option<&str>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        let (namespace, root, inner) = self.split_path()?;
+        let cid = match namespace {
+            "ipns" => self.resolve(root).await?,
+            _ => root.to_string()
+        };
+        let valid_until = SystemTime::now() + self.max_age;
+        if current_revision == Some(cid.as_str()) {
+            return Ok(Revalidation::NotModified { must_revalidate: false, valid_until, invalidation: None, fetched_at: None });
+        }
+
+        let bytes = self.fetch(&cid, inner).await?;
+        let mut result = DataLoadResult::new((self.parser)(&bytes)?, false, valid_until);
+        result.metadata.size = Some(bytes.len() as u64);
+        result.metadata.revision = Some(cid);
+        Ok(Revalidation::Modified(result))
+    }
+}
+
+impl <Data, Parser> DataProvider<Data> for IpfsDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&[u8]) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Resolves path and loads document
+    /// # Errors
+    /// If path is invalid, IPNS name can't be resolved, request fails or parser returns an error
+    async fn load_data(&self) -> Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>> {
+        Ok(self.load(None).await?.into_loaded())
+    }
+
+    /// Resolves IPNS name again, and loads document only if CID changed
+    async fn revalidate_data<'a>(&'a self, current: &'a DataLoadResult<Data>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        self.load(current.metadata.revision.as_deref()).await
+    }
+}
+
+impl <Data: Send + Sync, Parser> Debug for IpfsDataProvider<Data, Parser> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IpfsDataProvider")
+            .field("endpoint", &self.endpoint)
+            .field("path", &self.path)
+            .field("max_age", &self.max_age)
+            .finish_non_exhaustive()
+    }
+}
+
+/// IPFS specific errors
+#[derive(Debug)]
+pub enum IpfsError {
+    /// Path doesn't start with `/ipfs/<cid>` or `/ipns/<name>`
+    InvalidPath(String),
+    /// IPNS name was not resolved to `/ipfs/` path
+    Unresolved(String),
+    /// Unexpected http status
+    Status(StatusCode)
+}
+
+impl Display for IpfsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidPath(path) => write!(f, "invalid IPFS path: {path}"),
+            Self::Unresolved(name) => write!(f, "IPNS name {name} was not resolved to IPFS path"),
+            Self::Status(status) => write!(f, "unexpected response status code: {status}")
+        }
+    }
+}
+
+impl Error for IpfsError {}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use mockito::Matcher;
+    use reqwest::Url;
+    use crate::data_providers::data_provider::{DataProvider, Revalidation};
+    use crate::data_providers::ipfs::{IpfsDataProvider, IpfsEndpoint};
+
+    fn parse(bytes: &[u8]) -> Result<String, Box<dyn Error + Send + Sync>> {
+        Ok(String::from_utf8(bytes.to_vec())?)
+    }
+
+    #[tokio::test]
+    async fn node_resolves_name_and_loads_changed_cid() {
+        let mut server = mockito::Server::new_async().await;
+        let resolve = server
+            .mock("POST", "/api/v0/name/resolve")
+            .match_query(Matcher::UrlEncoded("arg".into(), "/ipns/k51qzi5u".into()))
+            .with_body(r#"{"Path": "/ipfs/bafybeigdyr"}"#)
+            .expect(2)
+            .create_async()
+            .await;
+        let cat = server
+            .mock("POST", "/api/v0/cat")
+            .match_query(Matcher::UrlEncoded("arg".into(), "/ipfs/bafybeigdyr/app.txt".into()))
+            .with_body("hello")
+            .expect(1)
+            .create_async()
+            .await;
+
+        let endpoint = IpfsEndpoint::Node(Url::parse(&server.url()).unwrap());
+        let data_provider = IpfsDataProvider::new(reqwest::Client::default(), endpoint, "/ipns/k51qzi5u/app.txt", parse);
+        let loaded = data_provider.load_data().await.unwrap();
+        assert_eq!(loaded.data, "hello");
+        assert_eq!(loaded.metadata.revision.as_deref(), Some("bafybeigdyr"));
+        assert!(matches!(data_provider.revalidate_data(&loaded).await.unwrap(), Revalidation::NotModified { .. }));
+        resolve.assert_async().await;
+        cat.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn gateway_resolves_name_from_header() {
+        let mut server = mockito::Server::new_async().await;
+        let _resolve = server
+            .mock("HEAD", "/ipns/config.example.com")
+            .with_header("X-Ipfs-Path", "/ipfs/bafkreiabc")
+            .create_async()
+            .await;
+        let _content = server
+            .mock("GET", "/ipfs/bafkreiabc")
+            .with_body("v1")
+            .create_async()
+            .await;
+
+        let endpoint = IpfsEndpoint::Gateway(Url::parse(&server.url()).unwrap());
+        let data_provider = IpfsDataProvider::new(reqwest::Client::default(), endpoint, "/ipns/config.example.com", parse);
+        let loaded = data_provider.load_data().await.unwrap();
+        assert_eq!(loaded.data, "v1");
+        assert_eq!(loaded.metadata.revision.as_deref(), Some("bafkreiabc"));
+
+        let invalid = IpfsDataProvider::new(reqwest::Client::default(), IpfsEndpoint::Gateway(Url::parse(&server.url()).unwrap()), "ipfs/", parse);
+        assert!(invalid.load_data().await.is_err());
+    }
+}
@@ -0,0 +1,318 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use rdkafka::{Message, Offset, TopicPartitionList};
+use rdkafka::config::ClientConfig;
+use rdkafka::consumer::{Consumer, StreamConsumer};
+use rdkafka::error::{KafkaError, KafkaResult};
+use tokio::task::AbortHandle;
+use crate::data_providers::data_provider::{DataLoadResult, DataProvider, Revalidation};
+use crate::data_providers::push::{PushedPayloads, Publisher};
+
+/// Topic was not consumed up to its end before timeout
+#[derive(Debug)]
+pub struct CatchUpTimeout {
+    /// Consumed topic
+    pub topic: String
+}
+
+impl Display for CatchUpTimeout {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "topic {} was not consumed up to its end before timeout", self.topic)
+    }
+}
+
+impl Error for CatchUpTimeout {}
+
+/// Applies consumed records, and produces snapshot every time all partitions are consumed up to their end
+#[derive(Debug)]
+struct Materializer {
+    records: BTreeMap<String, Vec<u8>>,
+    /// Partitions with records that were consumed after partition last reached its end
+    behind: BTreeSet<i32>
+}
+
+impl Materializer {
+    /// Every partition is behind, until it reaches its end for the first time
+    fn new(partitions: impl IntoIterator<Item = i32>) -> Self {
+        Self {
+            records: BTreeMap::new(),
+            behind: partitions.into_iter().collect()
+        }
+    }
+
+    /// Replaces record of key. Tombstone (record without payload) removes key, and records without key are ignored.
+    fn apply(&mut self, partition: i32, key: Option<&[u8]>, payload: Option<&[u8]>) {
+        self.behind.insert(partition);
+        let Some(key) = key else {
+            return;
+        };
+        let key = String::from_utf8_lossy(key).into_owned();
+        match payload {
+            Some(payload) => self.records.insert(key, payload.to_vec()),
+            None => self.records.remove(&key)
+        };
+    }
+
+    /// Returns latest records of all keys, if partition was behind and now all partitions are consumed up to their end
+    fn reached_end(&mut self, partition: i32) -> Option<BTreeMap<String, Vec<u8>>> {
+        if !self.behind.remove(&partition) || !self.behind.is_empty() {
+            return None;
+        }
+        Some(self.records.clone())
+    }
+}
+
+/// Assigns all partitions of topic from the beginning, and returns them
+async fn assign(consumer: &Arc<StreamConsumer>, topic: &str) -> KafkaResult<Vec<i32>> {
+    let metadata = {
+        let consumer = consumer.clone();
+        let topic = topic.to_string();
+        // Metadata request blocks
+        tokio::task::spawn_blocking(move || consumer.fetch_metadata(Some(&topic), Duration::from_secs(10)))
+            .await
+            .expect("metadata request does not panic")?
+    };
+    let partitions: Vec<_> = metadata.topics().iter()
+        .filter(|metadata| metadata.name() == topic)
+        .flat_map(|metadata| metadata.partitions().iter().map(|partition| partition.id()))
+        .collect();
+    if partitions.is_empty() {
+        return Err(KafkaError::Subscription(format!("topic {topic} has no partitions")));
+    }
+    let mut assignment = TopicPartitionList::new();
+    for partition in &partitions {
+        assignment.add_partition_offset(topic, *partition, Offset::Beginning)?;
+    }
+    consumer.assign(&assignment)?;
+    Ok(partitions)
+}
+
+/// Consumes topic until task is aborted, and publishes snapshots
+async fn consume(consumer: Arc<StreamConsumer>, topic: String, snapshots: Publisher<BTreeMap<String, Vec<u8>>>) {
+    let partitions = loop {
+        match assign(&consumer, &topic).await {
+            Ok(partitions) => break partitions,
+            Err(_err) => {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(topic, "Failed to assign Kafka topic partitions: {_err}");
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        }
+    };
+    let mut materializer = Materializer::new(partitions);
+    loop {
+        let snapshot = match consumer.recv().await {
+            Ok(message) => {
+                materializer.apply(message.partition(), message.key(), message.payload());
+                continue;
+            },
+            Err(KafkaError::PartitionEOF(partition)) => materializer.reached_end(partition),
+            Err(_err) => {
+                // Client reconnects on its own
+                #[cfg(feature = "tracing")]
+                tracing::warn!(topic, "Failed to consume Kafka topic: {_err}");
+                continue;
+            }
+        };
+        if snapshot.is_some_and(|snapshot| !snapshots.publish(snapshot)) {
+            return;
+        }
+    }
+}
+
+/// This data provider consumes compacted Kafka topic from the beginning, and materializes the latest record of every key
+/// into map, that is parsed with specified function. Tombstones remove keys.
+///
+/// Consumer is started on first load, that waits until all partitions are consumed up to their end.
+/// Then it keeps consuming in background, and every time it catches up with new records, loaded data is invalidated,
+/// and [`DataProvider::load_data`] returns new snapshot. Use [`RemoteConfig::follow_updates`](crate::config::RemoteConfig::follow_updates)
+/// to apply updates immediately. Partitions are assigned directly, so offsets are not committed,
+/// and partitions that are added to topic later are consumed only after restart.
+/// # Examples
+/// ```
+/// use rdkafka::config::ClientConfig;
+/// use remote_config::data_providers::kafka::KafkaDataProvider;
+///
+/// let mut client_config = ClientConfig::new();
+/// client_config.set("bootstrap.servers", "kafka-0.example.com:9092");
+/// let data_provider = KafkaDataProvider::new(client_config, "feature-flags", |records| {
+///     Ok(records.iter().map(|(key, value)| (key.clone(), value == b"on")).collect::<Vec<_>>())
+/// });
+/// ```
+pub struct KafkaDataProvider<Data: Send + Sync, Parser> {
+    client_config: ClientConfig,
+    topic: String,
+    parser: Parser,
+    max_age: Duration,
+    catch_up_timeout: Duration,
+    snapshots: PushedPayloads<BTreeMap<String, Vec<u8>>>,
+    /// Consumer task, started on first load
+    consumer: Mutex<Option<AbortHandle>>,
+    data_type: PhantomData<Data>
+}
+
+impl <Data, Parser> KafkaDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&BTreeMap<String, Vec<u8>>) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Creates data provider, that consumes topic with specified client config (at least `bootstrap.servers` must be set).
+    /// Partition EOF events are enabled, and offset commits are disabled.
+    pub fn new(mut client_config: ClientConfig, topic: impl Into<String>, parser: Parser) -> Self {
+        client_config.set("enable.partition.eof", "true");
+        client_config.set("enable.auto.commit", "false");
+        if client_config.get("group.id").is_none() {
+            client_config.set("group.id", "remote-config");
+        }
+        Self {
+            client_config,
+            topic: topic.into(),
+            parser,
+            max_age: Duration::from_secs(60),
+            catch_up_timeout: Duration::from_secs(30),
+            snapshots: PushedPayloads::new(),
+            consumer: Mutex::new(None),
+            data_type: PhantomData
+        }
+    }
+
+    /// Time after which latest snapshot is checked again. Default is 60 seconds.
+    /// Consumed records invalidate data regardless of it.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Time that first load waits until topic is consumed up to its end. Default is 30 seconds.
+    pub fn with_catch_up_timeout(mut self, timeout: Duration) -> Self {
+        self.catch_up_timeout = timeout;
+        self
+    }
+
+    /// Starts consumer task, if it is not started yet
+    fn start_consuming(&self) -> KafkaResult<()> {
+        let mut consumer = self.consumer.lock().unwrap();
+        if consumer.is_some() {
+            return Ok(());
+        }
+        let stream: StreamConsumer = self.client_config.create()?;
+        let topic = self.topic.clone();
+        let handle = tokio::spawn(consume(Arc::new(stream), topic, self.snapshots.publisher()));
+        *consumer = Some(handle.abort_handle());
+        Ok(())
+    }
+
+    async fn load(&self, current_revision: Option<&str>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        self.start_consuming()?;
+        let Some((snapshot, token)) = self.snapshots.latest(self.catch_up_timeout).await else {
+            return Err(CatchUpTimeout { topic: self.topic.clone() }.into());
+        };
+        snapshot.revalidate(current_revision, token, self.max_age, |records| {
+            Ok(((self.parser)(records)?, records.values().map(|value| value.len() as u64).sum()))
+        })
+    }
+}
+
+impl <Data, Parser> DataProvider<Data> for KafkaDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&BTreeMap<String, Vec<u8>>) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Parses latest snapshot. First load starts consumer and waits until topic is consumed up to its end.
+    /// # Errors
+    /// If consumer can't be created with client config, topic is not consumed before timeout, or parser returns an error
+    async fn load_data(&self) -> Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>> {
+        Ok(self.load(None).await?.into_loaded())
+    }
+
+    /// Parses latest snapshot, if it was not parsed yet
+    async fn revalidate_data<'a>(&'a self, current: &'a DataLoadResult<Data>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        self.load(current.metadata.revision.as_deref()).await
+    }
+}
+
+impl <Data: Send + Sync, Parser> Drop for KafkaDataProvider<Data, Parser> {
+    fn drop(&mut self) {
+        if let Some(handle) = self.consumer.lock().unwrap().take() {
+            handle.abort();
+        }
+    }
+}
+
+impl <Data: Send + Sync, Parser> Debug for KafkaDataProvider<Data, Parser> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KafkaDataProvider")
+            .field("bootstrap_servers", &self.client_config.get("bootstrap.servers"))
+            .field("topic", &self.topic)
+            .field("max_age", &self.max_age)
+            .field("catch_up_timeout", &self.catch_up_timeout)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::time::Duration;
+    use rdkafka::config::ClientConfig;
+    use rdkafka::producer::{FutureProducer, FutureRecord};
+    use crate::data_providers::data_provider::{DataProvider, Revalidation};
+    use crate::data_providers::kafka::{KafkaDataProvider, Materializer};
+
+    #[test]
+    fn snapshot_is_produced_when_all_partitions_caught_up() {
+        let mut materializer = Materializer::new([0, 1]);
+        materializer.apply(0, Some(b"a"), Some(b"1"));
+        materializer.apply(0, Some(b"b"), Some(b"2"));
+        materializer.apply(0, None, Some(b"ignored"));
+        assert!(materializer.reached_end(0).is_none());
+
+        let snapshot = materializer.reached_end(1).unwrap();
+        assert_eq!(snapshot.len(), 2);
+
+        // Tombstone removes key
+        materializer.apply(1, Some(b"a"), None);
+        assert!(materializer.reached_end(0).is_none());
+        let snapshot = materializer.reached_end(1).unwrap();
+        assert_eq!(snapshot, BTreeMap::from([("b".to_string(), b"2".to_vec())]));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Kafka broker at KAFKA_BROKERS"]
+    async fn compacted_topic_is_materialized() {
+        let mut client_config = ClientConfig::new();
+        client_config.set("bootstrap.servers", std::env::var("KAFKA_BROKERS").unwrap());
+        let topic = format!("remote-config-test-{}", std::process::id());
+        let producer: FutureProducer = client_config.clone().set("allow.auto.create.topics", "true").create().unwrap();
+        let send = |key: &'static str, payload: Option<&'static str>| {
+            let mut record = FutureRecord::<str, str>::to(&topic).key(key);
+            record.payload = payload;
+            producer.send(record, Duration::from_secs(10))
+        };
+        send("rps", Some("10")).await.unwrap();
+        send("burst", Some("20")).await.unwrap();
+
+        let data_provider = KafkaDataProvider::new(client_config, topic.clone(), |records| {
+            Ok(records.iter().map(|(key, value)| (key.clone(), String::from_utf8(value.clone()).unwrap())).collect::<BTreeMap<_, _>>())
+        });
+        let first = data_provider.load_data().await.unwrap();
+        assert_eq!(first.data["rps"], "10");
+
+        send("rps", Some("15")).await.unwrap();
+        send("burst", None).await.unwrap();
+        let token = first.metadata.invalidation.clone().unwrap();
+        tokio::time::timeout(Duration::from_secs(10), token.invalidated()).await.unwrap();
+        let Revalidation::Modified(mut second) = data_provider.revalidate_data(&first).await.unwrap() else {
+            panic!("expected modified data");
+        };
+        // Consumer may catch up between records
+        for _ in 0..100 {
+            if second.data.len() == 1 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            second = data_provider.load_data().await.unwrap();
+        }
+        assert_eq!(second.data, BTreeMap::from([("rps".to_string(), "15".to_string())]));
+    }
+}
@@ -0,0 +1,348 @@
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use futures_util::StreamExt;
+use k8s_openapi::NamespaceResourceScope;
+use k8s_openapi::api::core::v1::{ConfigMap, Secret};
+use kube::Api;
+use kube::api::{WatchEvent, WatchParams};
+use kube::core::Resource;
+use serde::de::DeserializeOwned;
+use tokio::task::AbortHandle;
+use crate::data_providers::data_provider::{DataLoadResult, DataProvider, InvalidationToken, Revalidation};
+
+/// Server side timeout of single watch request, in seconds
+const WATCH_TIMEOUT: u32 = 290;
+
+/// State shared with watch task
+#[derive(Debug, Default)]
+struct WatchState {
+    resource_version: Mutex<Option<String>>,
+    token: Mutex<InvalidationToken>
+}
+
+/// Namespaced resource that stores values by key
+pub trait KeyedResource: Resource<Scope = NamespaceResourceScope, DynamicType = ()> + Clone + DeserializeOwned + Debug + Send + Sync + 'static {
+    /// Value of the key, if it is present
+    fn value(&self, key: &str) -> Option<&[u8]>;
+}
+
+/// Both `data` and `binaryData` keys are supported
+impl KeyedResource for ConfigMap {
+    fn value(&self, key: &str) -> Option<&[u8]> {
+        self.data.as_ref().and_then(|data| data.get(key)).map(|value| value.as_bytes())
+            .or_else(|| self.binary_data.as_ref().and_then(|data| data.get(key)).map(|value| value.0.as_slice()))
+    }
+}
+
+/// Values are base64-decoded
+impl KeyedResource for Secret {
+    fn value(&self, key: &str) -> Option<&[u8]> {
+        self.data.as_ref().and_then(|data| data.get(key)).map(|value| value.0.as_slice())
+    }
+}
+
+/// Data provider that reads ConfigMap key, see [`K8sDataProvider`]
+pub type K8sConfigMapProvider<Data, Parser> = K8sDataProvider<ConfigMap, Data, Parser>;
+
+/// Data provider that reads Secret key, see [`K8sDataProvider`]
+pub type K8sSecretProvider<Data, Parser> = K8sDataProvider<Secret, Data, Parser>;
+
+/// This data provider reads single key of a ConfigMap or Secret via Kubernetes API server and parses it with specified function.
+/// Resource version is reported as revision, and data is not parsed again if it did not change.
+///
+/// With [`K8sDataProvider::with_watch`], background task watches the resource,
+/// and invalidates loaded data as soon as it is modified or deleted.
+/// # Examples
+/// ```no_run
+/// # #[cfg(feature = "json")]
+/// # async fn example() -> Result<(), kube::Error> {
+/// use std::collections::HashMap;
+/// use remote_config::data_providers::kubernetes::K8sConfigMapProvider;
+///
+/// let client = kube::Client::try_default().await?;
+/// let data_provider = K8sConfigMapProvider::new(client, "default", "app-config", "config.json", |bytes: &[u8]| {
+///     Ok(serde_json::from_slice::<HashMap<String, String>>(bytes)?)
+/// }).with_watch();
+/// # Ok(())
+/// # }
+/// ```
+pub struct K8sDataProvider<K: KeyedResource, Data: Send + Sync, Parser> {
+    api: Api<K>,
+    name: String,
+    key: String,
+    parser: Parser,
+    max_age: Duration,
+    watch: bool,
+    state: Arc<WatchState>,
+    /// Watch task, started on first load
+    watcher: Mutex<Option<AbortHandle>>,
+    data_type: PhantomData<Data>
+}
+
+impl <K, Data, Parser> K8sDataProvider<K, Data, Parser>
+where K: KeyedResource, Data: Send + Sync, Parser: Fn(&[u8]) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Creates data provider for key of resource in specified namespace
+    pub fn new(client: kube::Client, namespace: &str, name: impl Into<String>, key: impl Into<String>, parser: Parser) -> Self {
+        Self {
+            api: Api::namespaced(client, namespace),
+            name: name.into(),
+            key: key.into(),
+            parser,
+            max_age: Duration::from_secs(60),
+            watch: false,
+            state: Arc::new(WatchState::default()),
+            watcher: Mutex::new(None),
+            data_type: PhantomData
+        }
+    }
+
+    /// Time after which resource is read again. Default is 60 seconds.
+    /// With watch it can be much longer, as changes are detected immediately.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Watch resource for changes.
+    /// Watching starts on first data load, and stops when data provider is dropped.
+    pub fn with_watch(mut self) -> Self {
+        self.watch = true;
+        self
+    }
+
+    /// Starts watch task, if it is enabled and not started yet
+    fn start_watching(&self) {
+        if !self.watch {
+            return;
+        }
+        let mut watcher = self.watcher.lock().unwrap();
+        if watcher.is_some() {
+            return;
+        }
+
+        let api = self.api.clone();
+        let params = WatchParams::default()
+            .fields(&format!("metadata.name={}", self.name))
+            .timeout(WATCH_TIMEOUT);
+        let state = Arc::downgrade(&self.state);
+        let handle = tokio::spawn(async move {
+            loop {
+                let Some(version) = state.upgrade().and_then(|state| state.resource_version.lock().unwrap().clone()) else {
+                    if state.strong_count() == 0 {
+                        return;
+                    }
+                    // Data is being reloaded
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                    continue;
+                };
+
+                let mut events = match api.watch(&params, &version).await {
+                    Ok(events) => events.boxed(),
+                    Err(_err) => {
+                        #[cfg(feature = "tracing")]
+                        tracing::warn!(kind = %K::kind(&()), "Kubernetes watch failed: {_err}");
+                        tokio::time::sleep(Duration::from_secs(1)).await;
+                        continue;
+                    }
+                };
+                while let Some(event) = events.next().await {
+                    let Some(state) = state.upgrade() else {
+                        return;
+                    };
+                    let new_version = match event {
+                        Ok(WatchEvent::Added(resource) | WatchEvent::Modified(resource) | WatchEvent::Deleted(resource)) => {
+                            state.token.lock().unwrap().invalidate();
+                            resource.meta().resource_version.clone()
+                        },
+                        Ok(WatchEvent::Bookmark(bookmark)) => Some(bookmark.metadata.resource_version),
+                        // Resource version is too old, data must be reloaded to get new one
+                        Ok(WatchEvent::Error(_)) => {
+                            state.token.lock().unwrap().invalidate();
+                            None
+                        },
+                        Err(_err) => {
+                            #[cfg(feature = "tracing")]
+                            tracing::warn!(kind = %K::kind(&()), "Kubernetes watch failed: {_err}");
+                            break;
+                        }
+                    };
+                    *state.resource_version.lock().unwrap() = new_version;
+                }
+            }
+        });
+        *watcher = Some(handle.abort_handle());
+    }
+
+    async fn load(&self, current_revision: Option<&str>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        // Token is replaced before request, so changes made during request are not missed
+        let token = InvalidationToken::new();
+        *self.state.token.lock().unwrap() = token.clone();
+
+        let resource = self.api.get(&self.name).await?;
+        let revision = resource.meta().resource_version.clone();
+        *self.state.resource_version.lock().unwrap() = revision.clone();
+        self.start_watching();
+
+        let valid_until = SystemTime::now() + self.max_age;
+        if revision.is_some() && current_revision == revision.as_deref() {
+            return Ok(Revalidation::NotModified { must_revalidate: false, valid_until, invalidation: Some(token), fetched_at: None });
+        }
+
+        let bytes = resource.value(&self.key).ok_or_else(|| K8sError::KeyNotFound(self.key.clone()))?;
+        let mut result = DataLoadResult::new((self.parser)(bytes)?, false, valid_until);
+        result.metadata.size = Some(bytes.len() as u64);
+        result.metadata.revision = revision;
+        result.metadata.invalidation = Some(token);
+        Ok(Revalidation::Modified(result))
+    }
+}
+
+impl <K, Data, Parser> DataProvider<Data> for K8sDataProvider<K, Data, Parser>
+where K: KeyedResource, Data: Send + Sync, Parser: Fn(&[u8]) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Reads resource and parses value of the key
+    /// # Errors
+    /// If request fails, resource or key doesn't exist or parser returns an error
+    async fn load_data(&self) -> Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>> {
+        Ok(self.load(None).await?.into_loaded())
+    }
+
+    /// Reads resource, but parses value of the key only if resource version changed
+    async fn revalidate_data<'a>(&'a self, current: &'a DataLoadResult<Data>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        self.load(current.metadata.revision.as_deref()).await
+    }
+}
+
+impl <K: KeyedResource, Data: Send + Sync, Parser> Drop for K8sDataProvider<K, Data, Parser> {
+    fn drop(&mut self) {
+        if let Some(handle) = self.watcher.lock().unwrap().take() {
+            handle.abort();
+        }
+    }
+}
+
+impl <K: KeyedResource, Data: Send + Sync, Parser> Debug for K8sDataProvider<K, Data, Parser> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("K8sDataProvider")
+            .field("kind", &K::kind(&()))
+            .field("name", &self.name)
+            .field("key", &self.key)
+            .field("max_age", &self.max_age)
+            .field("watch", &self.watch)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Kubernetes specific errors
+#[derive(Debug)]
+pub enum K8sError {
+    /// Key is not present in resource
+    KeyNotFound(String)
+}
+
+impl Display for K8sError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::KeyNotFound(key) => write!(f, "key '{key}' not found in Kubernetes resource")
+        }
+    }
+}
+
+impl Error for K8sError {}
+
+#[cfg(all(test, feature = "json"))]
+mod tests {
+    use std::error::Error;
+    use std::time::Duration;
+    use mockito::Matcher;
+    use serde_json::json;
+    use crate::data_providers::data_provider::{DataProvider, Revalidation};
+    use crate::data_providers::kubernetes::{K8sConfigMapProvider, K8sSecretProvider};
+
+    fn parse(bytes: &[u8]) -> Result<String, Box<dyn Error + Send + Sync>> {
+        Ok(String::from_utf8(bytes.to_vec())?)
+    }
+
+    fn config_map(version: &str, value: &str) -> serde_json::Value {
+        json!({
+            "apiVersion": "v1",
+            "kind": "ConfigMap",
+            "metadata": {"name": "app", "namespace": "default", "resourceVersion": version},
+            "data": {"config": value}
+        })
+    }
+
+    #[tokio::test]
+    async fn revision_and_watch() {
+        let mut server = mockito::Server::new_async().await;
+        let read = server
+            .mock("GET", "/api/v1/namespaces/default/configmaps/app")
+            .with_header("Content-Type", "application/json")
+            .with_body(config_map("7", "v1").to_string())
+            .expect(2)
+            .create_async()
+            .await;
+        let watch = server
+            .mock("GET", "/api/v1/namespaces/default/configmaps")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("watch".into(), "true".into()),
+                Matcher::UrlEncoded("resourceVersion".into(), "7".into()),
+                Matcher::UrlEncoded("fieldSelector".into(), "metadata.name=app".into())
+            ]))
+            .with_header("Content-Type", "application/json")
+            .with_body(json!({"type": "MODIFIED", "object": config_map("8", "v2")}).to_string() + "\n")
+            .expect_at_least(1)
+            .create_async()
+            .await;
+
+        let client = kube::Client::try_from(kube::Config::new(server.url().parse().unwrap())).unwrap();
+        let data_provider = K8sConfigMapProvider::new(client, "default", "app", "config", parse).with_watch();
+
+        let first = data_provider.load_data().await.unwrap();
+        assert_eq!(first.data, "v1");
+        assert_eq!(first.metadata.revision.as_deref(), Some("7"));
+
+        // ConfigMap was modified according to watch
+        let token = first.metadata.invalidation.clone().unwrap();
+        for _ in 0..100 {
+            if token.is_invalidated() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(token.is_invalidated());
+        watch.assert_async().await;
+
+        // Same version is returned by read, so data is not parsed again
+        assert!(matches!(data_provider.revalidate_data(&first).await.unwrap(), Revalidation::NotModified { .. }));
+        read.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn secret_values_are_decoded() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/api/v1/namespaces/default/secrets/app")
+            .with_header("Content-Type", "application/json")
+            .with_body(json!({
+                "apiVersion": "v1",
+                "kind": "Secret",
+                "metadata": {"name": "app", "namespace": "default", "resourceVersion": "3"},
+                "data": {"token": "c2VjcmV0"}
+            }).to_string())
+            .create_async()
+            .await;
+
+        let client = kube::Client::try_from(kube::Config::new(server.url().parse().unwrap())).unwrap();
+        let result = K8sSecretProvider::new(client.clone(), "default", "app", "token", parse).load_data().await.unwrap();
+        assert_eq!(result.data, "secret");
+        assert_eq!(result.metadata.revision.as_deref(), Some("3"));
+
+        let missing = K8sSecretProvider::new(client, "default", "app", "password", parse).load_data().await;
+        assert!(missing.is_err());
+    }
+}
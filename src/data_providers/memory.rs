@@ -0,0 +1,198 @@
+use std::error::Error;
+use std::fmt::{Debug, Formatter};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use crate::data_providers::data_provider::{DataLoadResult, DataProvider, InvalidationToken, Revalidation};
+
+/// Data provider that always returns the same data.
+/// Useful in unit tests and local development, where config must be constructed without remote source.
+/// # Examples
+/// ```
+/// use std::collections::HashMap;
+/// use remote_config::data_providers::memory::StaticDataProvider;
+///
+/// let data_provider = StaticDataProvider::new(HashMap::from([("new_ui".to_string(), "true".to_string())]));
+/// ```
+#[derive(Debug, Clone)]
+pub struct StaticDataProvider<Data> {
+    data: Data,
+    max_age: Duration
+}
+
+impl <Data: Clone + Send + Sync> StaticDataProvider<Data> {
+    /// Creates data provider. Data is revalidated every 60 seconds, which never changes it
+    pub fn new(data: Data) -> Self {
+        Self { data, max_age: Duration::from_secs(60) }
+    }
+
+    /// Sets how long data is fresh
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+}
+
+impl <Data: Clone + Send + Sync> DataProvider<Data> for StaticDataProvider<Data> {
+    /// Returns copy of data
+    async fn load_data(&self) -> Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>> {
+        Ok(DataLoadResult::new(self.data.clone(), false, SystemTime::now() + self.max_age))
+    }
+
+    /// Data never changes, so it is always not modified
+    async fn revalidate_data<'a>(&'a self, _current: &'a DataLoadResult<Data>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        Ok(Revalidation::NotModified { must_revalidate: false, valid_until: SystemTime::now() + self.max_age, invalidation: None, fetched_at: None })
+    }
+}
+
+/// Data provider that returns data set by [`InMemoryDataProvider::set`].
+/// Clones share data, so a clone can be kept to change data of config that owns the provider.
+/// Loaded data must be revalidated, and it is invalidated as soon as it is changed, so next load returns new data.
+///
+/// Intended for tests, that can also make loads fail with [`InMemoryDataProvider::fail`].
+/// # Examples
+/// ```
+/// use remote_config::data_providers::memory::InMemoryDataProvider;
+///
+/// let data_provider = InMemoryDataProvider::new(1u32);
+/// let handle = data_provider.clone();
+/// // Build config with `data_provider`, then change data with handle
+/// handle.set(2);
+/// ```
+#[derive(Clone)]
+pub struct InMemoryDataProvider<Data> {
+    shared: Arc<Shared<Data>>,
+    max_age: Duration
+}
+
+struct Shared<Data> {
+    state: Mutex<State<Data>>,
+    /// Token of last loaded data
+    token: Mutex<InvalidationToken>
+}
+
+struct State<Data> {
+    data: Data,
+    /// Message of error that loads fail with, until data is set again
+    error: Option<String>,
+    /// Incremented every time data is set
+    version: u64
+}
+
+impl <Data: Clone + Send + Sync> InMemoryDataProvider<Data> {
+    /// Creates data provider with initial data. Data is fresh for 60 seconds, unless it is changed
+    pub fn new(data: Data) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                state: Mutex::new(State { data, error: None, version: 0 }),
+                token: Mutex::new(InvalidationToken::new())
+            }),
+            max_age: Duration::from_secs(60)
+        }
+    }
+
+    /// Sets how long data is fresh, if it is not changed
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Replaces data, and makes loads succeed again if they were failing
+    pub fn set(&self, data: Data) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.data = data;
+        state.error = None;
+        state.version += 1;
+        drop(state);
+        self.shared.token.lock().unwrap().invalidate();
+    }
+
+    /// Makes following loads fail with specified message, until data is set again
+    pub fn fail(&self, message: impl Into<String>) {
+        self.shared.state.lock().unwrap().error = Some(message.into());
+        self.shared.token.lock().unwrap().invalidate();
+    }
+
+    /// Returns copy of current data
+    pub fn get(&self) -> Data {
+        self.shared.state.lock().unwrap().data.clone()
+    }
+
+    fn load(&self, current_revision: Option<&str>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        // Token is replaced before reading, so changes made during read are not missed
+        let token = InvalidationToken::new();
+        *self.shared.token.lock().unwrap() = token.clone();
+
+        let state = self.shared.state.lock().unwrap();
+        if let Some(message) = &state.error {
+            return Err(message.clone().into());
+        }
+        let revision = state.version.to_string();
+        let valid_until = SystemTime::now() + self.max_age;
+        if current_revision == Some(revision.as_str()) {
+            return Ok(Revalidation::NotModified { must_revalidate: true, valid_until, invalidation: Some(token), fetched_at: None });
+        }
+
+        let mut result = DataLoadResult::new(state.data.clone(), true, valid_until);
+        result.metadata.revision = Some(revision);
+        result.metadata.invalidation = Some(token);
+        Ok(Revalidation::Modified(result))
+    }
+}
+
+impl <Data: Clone + Send + Sync> DataProvider<Data> for InMemoryDataProvider<Data> {
+    /// Returns copy of current data
+    /// # Errors
+    /// If loads were made to fail with [`InMemoryDataProvider::fail`]
+    async fn load_data(&self) -> Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>> {
+        Ok(self.load(None)?.into_loaded())
+    }
+
+    /// Returns copy of current data, if it was changed since last load
+    async fn revalidate_data<'a>(&'a self, current: &'a DataLoadResult<Data>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        self.load(current.metadata.revision.as_deref())
+    }
+}
+
+impl <Data: Debug> Debug for InMemoryDataProvider<Data> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InMemoryDataProvider")
+            .field("data", &self.shared.state.lock().unwrap().data)
+            .field("max_age", &self.max_age)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use crate::config::RemoteConfig;
+    use crate::data_providers::data_provider::{DataProvider, Revalidation};
+    use crate::data_providers::memory::{InMemoryDataProvider, StaticDataProvider};
+
+    #[tokio::test]
+    async fn static_data_is_never_modified() {
+        let data_provider = StaticDataProvider::new(vec![1, 2]);
+        let first = data_provider.load_data().await.unwrap();
+        assert_eq!(first.data, vec![1, 2]);
+        assert!(matches!(data_provider.revalidate_data(&first).await.unwrap(), Revalidation::NotModified { .. }));
+    }
+
+    #[tokio::test]
+    async fn changes_are_loaded_immediately() {
+        let data_provider = InMemoryDataProvider::new("first".to_string());
+        let handle = data_provider.clone();
+        #[cfg(feature = "tracing")]
+        let config = RemoteConfig::builder("InMemory".to_string(), data_provider).build().await.unwrap();
+        #[cfg(not(feature = "tracing"))]
+        let config = RemoteConfig::builder(data_provider).build().await.unwrap();
+        let config = Box::leak(Box::new(config));
+        assert_eq!(*config.load().await.unwrap(), "first");
+
+        handle.set("second".to_string());
+        assert_eq!(*config.load().await.unwrap(), "second");
+        assert_eq!(config.status().revision.as_deref(), Some("1"));
+
+        handle.fail("origin is down");
+        assert_eq!(config.refresh().await.unwrap_err().source().unwrap().to_string(), "origin is down");
+    }
+}
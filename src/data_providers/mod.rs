@@ -1,6 +1,140 @@
 /// Common data provider code and public trait for creating custom implementations
 pub mod data_provider;
 
+/// Data provider that cross-checks data loaded from several sources
+pub mod verifying;
+
+/// Data provider that fails over from primary origin to mirrors, and fails back when primary recovers
+pub mod failover;
+
+/// Data provider that applies local overrides on top of loaded data
+pub mod overlay;
+
+/// Data providers that return static or in-memory data, for tests and local development
+pub mod memory;
+
+/// Data provider that resolves secret references in loaded data
+#[cfg(feature = "secrets")]
+pub mod secrets;
+
 /// Data providers and extractors that use reqwest HTTP client to load data from remote source
 #[cfg(feature = "http")]
 pub mod http;
+
+/// Minimal HTTP transport trait, that can be implemented with any HTTP client
+#[cfg(any(feature = "slim", feature = "http"))]
+pub mod transport;
+
+/// Data provider that loads data with minimal HTTP transport
+#[cfg(feature = "slim")]
+pub mod slim;
+
+/// Data provider that reads data from local file and watches it for changes
+#[cfg(feature = "file")]
+pub mod file;
+
+/// Latest payload of data providers, that keep connection to origin open and receive pushed updates
+#[cfg(any(feature = "kafka", feature = "mqtt", feature = "sse", feature = "websocket"))]
+mod push;
+
+/// Data provider that materializes compacted Kafka topic
+#[cfg(feature = "kafka")]
+pub mod kafka;
+/// Data provider that subscribes to retained MQTT topic
+#[cfg(feature = "mqtt")]
+pub mod mqtt;
+/// Data provider that subscribes to server-sent events
+#[cfg(feature = "sse")]
+pub mod sse;
+
+/// Data provider that receives config snapshots pushed over WebSocket
+#[cfg(feature = "websocket")]
+pub mod websocket;
+
+/// Data provider that downloads remote file over SFTP
+#[cfg(feature = "sftp")]
+pub mod sftp;
+
+/// Data provider that downloads file from FTP server
+#[cfg(feature = "ftp")]
+pub mod ftp;
+
+/// Data provider that builds data from environment variables
+#[cfg(feature = "env")]
+pub mod env;
+
+/// Data provider that loads objects from Google Cloud Storage
+#[cfg(feature = "gcs")]
+pub mod gcs;
+
+/// Data provider that loads Firebase Remote Config templates
+#[cfg(feature = "firebase")]
+pub mod firebase;
+
+/// Data providers for AWS services
+#[cfg(feature = "aws")]
+pub mod aws;
+
+/// Data provider that loads key-values from Azure App Configuration
+#[cfg(feature = "azure")]
+pub mod azure;
+
+/// Data provider that reads files from GitHub repositories
+#[cfg(feature = "github")]
+pub mod github;
+
+/// Data provider that reads files from GitLab repositories
+#[cfg(feature = "gitlab")]
+pub mod gitlab;
+
+/// Data provider that reads values from Cloudflare Workers KV
+#[cfg(feature = "cloudflare")]
+pub mod cloudflare;
+
+/// Data provider that reads Firestore documents
+#[cfg(feature = "firestore")]
+pub mod firestore;
+
+/// Data provider that loads documents from IPFS
+#[cfg(feature = "ipfs")]
+pub mod ipfs;
+
+/// Data provider that reads values from Consul KV store
+#[cfg(feature = "consul")]
+pub mod consul;
+
+/// Data provider that reads znodes from ZooKeeper and watches them for changes
+#[cfg(feature = "zookeeper")]
+pub mod zookeeper;
+
+/// Data provider that reads secrets from HashiCorp Vault
+#[cfg(feature = "vault")]
+pub mod vault;
+
+/// Data providers that read ConfigMaps and Secrets from Kubernetes API server
+#[cfg(feature = "kubernetes")]
+pub mod kubernetes;
+
+/// Data provider wrapper that applies refresh hints from Kubernetes downward API
+#[cfg(feature = "downward_api")]
+pub mod downward_api;
+
+/// Data provider that runs queries against PostgreSQL database and listens for change notifications
+#[cfg(feature = "postgres")]
+pub mod postgres;
+
+/// Data provider that runs queries against MySQL database
+#[cfg(feature = "mysql")]
+pub mod mysql;
+
+/// Data provider that runs queries against local SQLite database file
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+/// Data provider that loads document from MongoDB collection and watches it with change streams
+#[cfg(feature = "mongodb")]
+pub mod mongodb;
+
+/// Data provider that resolves DNS TXT records
+#[cfg(feature = "dns")]
+pub mod dns;
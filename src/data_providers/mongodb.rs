@@ -0,0 +1,253 @@
+use std::collections::hash_map::DefaultHasher;
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use futures_util::StreamExt;
+use mongodb::bson::{doc, Document};
+use mongodb::Collection;
+use tokio::task::AbortHandle;
+use crate::data_providers::data_provider::{DataLoadResult, DataProvider, InvalidationToken, Revalidation};
+
+/// No document matches filter of [`MongoDataProvider`]
+#[derive(Debug)]
+pub struct DocumentNotFound {
+    /// Filter of data provider
+    pub filter: Document
+}
+
+impl Display for DocumentNotFound {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "no document matches filter {}", self.filter)
+    }
+}
+
+impl Error for DocumentNotFound {}
+
+/// This data provider loads single document from MongoDB collection and maps it into data with specified function.
+/// Hash of document is reported as revision, so document is not mapped again if it did not change.
+///
+/// With [`MongoDataProvider::with_change_stream`], background task watches collection with change stream,
+/// and invalidates loaded data as soon as collection is modified. If filter contains `_id`,
+/// only changes of that document are watched. Change streams are available on replica sets and sharded clusters only.
+/// # Examples
+/// ```
+/// use mongodb::bson::{doc, Document};
+/// use mongodb::Collection;
+/// use remote_config::data_providers::mongodb::MongoDataProvider;
+///
+/// struct Limits {
+///     requests_per_second: i64
+/// }
+///
+/// # fn example(collection: Collection<Document>) {
+/// let data_provider = MongoDataProvider::new(collection, doc! { "_id": "limits" }, |document: &Document| {
+///     Ok(Limits { requests_per_second: document.get_i64("requests_per_second")? })
+/// }).with_change_stream();
+/// # }
+/// ```
+pub struct MongoDataProvider<Data: Send + Sync, Parser> {
+    collection: Collection<Document>,
+    filter: Document,
+    parser: Parser,
+    max_age: Duration,
+    change_stream: bool,
+    token: Arc<Mutex<InvalidationToken>>,
+    /// Change stream task, started on first load
+    watcher: Mutex<Option<AbortHandle>>,
+    data_type: PhantomData<Data>
+}
+
+impl <Data, Parser> MongoDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&Document) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Creates data provider, that loads first document matching filter
+    pub fn new(collection: Collection<Document>, filter: Document, parser: Parser) -> Self {
+        Self {
+            collection,
+            filter,
+            parser,
+            max_age: Duration::from_secs(60),
+            change_stream: false,
+            token: Arc::new(Mutex::new(InvalidationToken::new())),
+            watcher: Mutex::new(None),
+            data_type: PhantomData
+        }
+    }
+
+    /// Time after which document is loaded again. Default is 60 seconds.
+    /// With change stream it can be much longer, as changes are detected immediately.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Invalidate data when collection is modified.
+    /// Watching starts on first data load, and stops when data provider is dropped.
+    pub fn with_change_stream(mut self) -> Self {
+        self.change_stream = true;
+        self
+    }
+
+    /// Starts change stream task, if it is enabled and not started yet.
+    /// Stream is opened before returning, so changes made during following read are not missed.
+    async fn start_watching(&self) -> mongodb::error::Result<()> {
+        if !self.change_stream || self.watcher.lock().unwrap().is_some() {
+            return Ok(());
+        }
+        let pipeline = match self.filter.get("_id") {
+            Some(id) => vec![doc! { "$match": { "documentKey._id": id } }],
+            None => Vec::new()
+        };
+        let mut stream = self.collection.watch().pipeline(pipeline.clone()).await?;
+
+        let mut watcher = self.watcher.lock().unwrap();
+        if watcher.is_some() {
+            return Ok(());
+        }
+        let collection = self.collection.clone();
+        let token = Arc::downgrade(&self.token);
+        let handle = tokio::spawn(async move {
+            loop {
+                while let Some(event) = stream.next().await {
+                    let Some(token) = token.upgrade() else {
+                        return;
+                    };
+                    token.lock().unwrap().invalidate();
+                    if let Err(_err) = event {
+                        #[cfg(feature = "tracing")]
+                        tracing::warn!("MongoDB change stream failed: {_err}");
+                        break;
+                    }
+                }
+                // Stream failed or was closed (e.g. collection was dropped), so it is reopened.
+                // Data is invalidated after every attempt, as changes may be missed until stream is open again.
+                loop {
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                    let reopened = collection.watch().pipeline(pipeline.clone()).await;
+                    let Some(token) = token.upgrade() else {
+                        return;
+                    };
+                    token.lock().unwrap().invalidate();
+                    match reopened {
+                        Ok(reopened) => {
+                            stream = reopened;
+                            break;
+                        },
+                        Err(_err) => {
+                            #[cfg(feature = "tracing")]
+                            tracing::warn!("Failed to reopen MongoDB change stream: {_err}");
+                        }
+                    }
+                }
+            }
+        });
+        *watcher = Some(handle.abort_handle());
+        Ok(())
+    }
+
+    async fn load(&self, current_revision: Option<&str>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        // Token is replaced before read, so changes made during read are not missed
+        let token = InvalidationToken::new();
+        *self.token.lock().unwrap() = token.clone();
+        self.start_watching().await?;
+
+        let document = self.collection.find_one(self.filter.clone()).await?
+            .ok_or_else(|| DocumentNotFound { filter: self.filter.clone() })?;
+        let valid_until = SystemTime::now() + self.max_age;
+        let mut bytes = Vec::new();
+        document.to_writer(&mut bytes)?;
+        let mut hasher = DefaultHasher::new();
+        bytes.hash(&mut hasher);
+        let revision = format!("{:016x}", hasher.finish());
+        if current_revision == Some(revision.as_str()) {
+            return Ok(Revalidation::NotModified { must_revalidate: false, valid_until, invalidation: Some(token), fetched_at: None });
+        }
+
+        let mut result = DataLoadResult::new((self.parser)(&document)?, false, valid_until);
+        result.metadata.size = Some(bytes.len() as u64);
+        result.metadata.revision = Some(revision);
+        result.metadata.invalidation = Some(token);
+        Ok(Revalidation::Modified(result))
+    }
+}
+
+impl <Data, Parser> DataProvider<Data> for MongoDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&Document) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Loads and maps document
+    /// # Errors
+    /// If change stream can't be opened, read fails, document doesn't exist or parser returns an error
+    async fn load_data(&self) -> Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>> {
+        Ok(self.load(None).await?.into_loaded())
+    }
+
+    /// Loads document, but maps it only if it changed
+    async fn revalidate_data<'a>(&'a self, current: &'a DataLoadResult<Data>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        self.load(current.metadata.revision.as_deref()).await
+    }
+}
+
+impl <Data: Send + Sync, Parser> Drop for MongoDataProvider<Data, Parser> {
+    fn drop(&mut self) {
+        if let Some(handle) = self.watcher.lock().unwrap().take() {
+            handle.abort();
+        }
+    }
+}
+
+impl <Data: Send + Sync, Parser> Debug for MongoDataProvider<Data, Parser> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MongoDataProvider")
+            .field("namespace", &self.collection.namespace())
+            .field("filter", &self.filter)
+            .field("max_age", &self.max_age)
+            .field("change_stream", &self.change_stream)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use mongodb::bson::{doc, Document};
+    use mongodb::Client;
+    use crate::data_providers::data_provider::{DataProvider, Revalidation};
+    use crate::data_providers::mongodb::MongoDataProvider;
+
+    #[tokio::test]
+    #[ignore = "requires MongoDB replica set at MONGODB_URL"]
+    async fn change_stream_invalidates_data() {
+        let client = Client::with_uri_str(std::env::var("MONGODB_URL").unwrap()).await.unwrap();
+        let collection = client.database("remote_config_test").collection::<Document>("config");
+        collection.drop().await.unwrap();
+        collection.insert_one(doc! { "_id": "limits", "rps": 10 }).await.unwrap();
+
+        let data_provider = MongoDataProvider::new(collection.clone(), doc! { "_id": "limits" }, |document: &Document| {
+            Ok(document.get_i32("rps")?)
+        }).with_change_stream();
+        let first = data_provider.load_data().await.unwrap();
+        assert_eq!(first.data, 10);
+
+        // Nothing changed
+        let Revalidation::NotModified { invalidation: Some(token), .. } = data_provider.revalidate_data(&first).await.unwrap() else {
+            panic!("expected not modified data with new invalidation token");
+        };
+
+        collection.update_one(doc! { "_id": "limits" }, doc! { "$set": { "rps": 20 } }).await.unwrap();
+        for _ in 0..100 {
+            if token.is_invalidated() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(token.is_invalidated());
+
+        let Revalidation::Modified(second) = data_provider.revalidate_data(&first).await.unwrap() else {
+            panic!("expected modified data");
+        };
+        assert_eq!(second.data, 20);
+    }
+}
@@ -0,0 +1,250 @@
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::marker::PhantomData;
+use std::sync::Mutex;
+use std::time::Duration;
+use rumqttc::{AsyncClient, Event, EventLoop, MqttOptions, Packet, QoS};
+use tokio::task::AbortHandle;
+use crate::data_providers::data_provider::{DataLoadResult, DataProvider, Revalidation};
+use crate::data_providers::push::{PushedPayloads, Publisher};
+
+/// No retained message was received from topic before timeout
+#[derive(Debug)]
+pub struct NoRetainedMessage {
+    /// Subscribed topic
+    pub topic: String
+}
+
+impl Display for NoRetainedMessage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "no retained config message was received from topic {}", self.topic)
+    }
+}
+
+impl Error for NoRetainedMessage {}
+
+/// This data provider subscribes to MQTT topic, that holds current config as retained message, and parses it with specified function.
+/// Broker delivers retained message right after subscription, and every message published to topic later replaces it.
+///
+/// Connection is opened on first load, and reopened after it fails, until data provider is dropped.
+/// Every received message invalidates loaded data, and [`DataProvider::load_data`] returns the latest received message.
+/// Empty messages (that clear retained message on broker) are ignored, so the last config is kept.
+/// Use [`RemoteConfig::follow_updates`](crate::config::RemoteConfig::follow_updates) to apply published updates immediately.
+/// # Examples
+/// ```
+/// use rumqttc::MqttOptions;
+/// use remote_config::data_providers::mqtt::MqttDataProvider;
+///
+/// let options = MqttOptions::new("sensor-17", "broker.example.com", 1883);
+/// let data_provider = MqttDataProvider::new(options, "fleet/sensors/config", |payload: &[u8]| {
+///     Ok(String::from_utf8(payload.to_vec())?)
+/// });
+/// ```
+pub struct MqttDataProvider<Data: Send + Sync, Parser> {
+    options: MqttOptions,
+    topic: String,
+    qos: QoS,
+    parser: Parser,
+    max_age: Duration,
+    message_timeout: Duration,
+    messages: PushedPayloads<Vec<u8>>,
+    /// Event loop task, started on first load
+    event_loop: Mutex<Option<AbortHandle>>,
+    data_type: PhantomData<Data>
+}
+
+impl <Data, Parser> MqttDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&[u8]) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Creates data provider, that connects to broker with specified options and subscribes to topic
+    pub fn new(options: MqttOptions, topic: impl Into<String>, parser: Parser) -> Self {
+        Self {
+            options,
+            topic: topic.into(),
+            qos: QoS::AtLeastOnce,
+            parser,
+            max_age: Duration::from_secs(60),
+            message_timeout: Duration::from_secs(10),
+            messages: PushedPayloads::new(),
+            event_loop: Mutex::new(None),
+            data_type: PhantomData
+        }
+    }
+
+    /// Quality of service of subscription. Default is [`QoS::AtLeastOnce`].
+    pub fn with_qos(mut self, qos: QoS) -> Self {
+        self.qos = qos;
+        self
+    }
+
+    /// Time after which latest message is checked again. Default is 60 seconds.
+    /// Published messages invalidate data regardless of it.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Time that first load waits for retained message. Default is 10 seconds.
+    pub fn with_message_timeout(mut self, timeout: Duration) -> Self {
+        self.message_timeout = timeout;
+        self
+    }
+
+    /// Starts event loop task, if it is not started yet
+    fn start_event_loop(&self) {
+        let mut event_loop = self.event_loop.lock().unwrap();
+        if event_loop.is_some() {
+            return;
+        }
+        let (client, connection) = AsyncClient::new(self.options.clone(), 10);
+        let topic = self.topic.clone();
+        let qos = self.qos;
+        let messages = self.messages.publisher();
+        let handle = tokio::spawn(async move {
+            Self::run(client, connection, topic, qos, messages).await
+        });
+        *event_loop = Some(handle.abort_handle());
+    }
+
+    /// Polls event loop (that reconnects on its own), and subscribes to topic after every connect
+    async fn run(
+        client: AsyncClient,
+        mut connection: EventLoop,
+        topic: String,
+        qos: QoS,
+        messages: Publisher<Vec<u8>>
+    ) {
+        loop {
+            match connection.poll().await {
+                Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                    if let Err(_err) = client.try_subscribe(topic.as_str(), qos) {
+                        #[cfg(feature = "tracing")]
+                        tracing::warn!(topic, "Failed to subscribe to MQTT topic: {_err}");
+                    }
+                },
+                Ok(Event::Incoming(Packet::Publish(publish))) if publish.topic == topic && !publish.payload.is_empty() => {
+                    if !messages.publish(publish.payload.to_vec()) {
+                        return;
+                    }
+                },
+                Ok(_) => {},
+                Err(_err) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(topic, "MQTT connection failed: {_err}");
+                    // Latest message is kept until broker delivers retained message again after reconnect
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                }
+            }
+        }
+    }
+
+    async fn load(&self, current_revision: Option<&str>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        self.start_event_loop();
+        let Some((message, token)) = self.messages.latest(self.message_timeout).await else {
+            return Err(NoRetainedMessage { topic: self.topic.clone() }.into());
+        };
+        message.revalidate(current_revision, token, self.max_age, |payload| Ok(((self.parser)(payload)?, payload.len() as u64)))
+    }
+}
+
+impl <Data, Parser> DataProvider<Data> for MqttDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&[u8]) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Parses latest received message. First load connects to broker and waits for retained message.
+    /// # Errors
+    /// If no message is received before timeout, or parser returns an error
+    async fn load_data(&self) -> Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>> {
+        Ok(self.load(None).await?.into_loaded())
+    }
+
+    /// Parses latest received message, if it was not parsed yet
+    async fn revalidate_data<'a>(&'a self, current: &'a DataLoadResult<Data>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        self.load(current.metadata.revision.as_deref()).await
+    }
+}
+
+impl <Data: Send + Sync, Parser> Drop for MqttDataProvider<Data, Parser> {
+    fn drop(&mut self) {
+        if let Some(handle) = self.event_loop.lock().unwrap().take() {
+            handle.abort();
+        }
+    }
+}
+
+impl <Data: Send + Sync, Parser> Debug for MqttDataProvider<Data, Parser> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MqttDataProvider")
+            .field("broker", &self.options.broker_address())
+            .field("topic", &self.topic)
+            .field("qos", &self.qos)
+            .field("max_age", &self.max_age)
+            .field("message_timeout", &self.message_timeout)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use std::time::Duration;
+    use rumqttc::{MqttOptions, QoS};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio::sync::mpsc;
+    use crate::data_providers::data_provider::{DataProvider, Revalidation};
+    use crate::data_providers::mqtt::MqttDataProvider;
+
+    fn parse(payload: &[u8]) -> Result<String, Box<dyn Error + Send + Sync>> {
+        Ok(String::from_utf8(payload.to_vec())?)
+    }
+
+    /// MQTT 3.1.1 PUBLISH packet with QoS 0
+    fn publish(topic: &str, payload: &str, retain: bool) -> Vec<u8> {
+        let mut packet = vec![0x30 | retain as u8, (2 + topic.len() + payload.len()) as u8];
+        packet.extend_from_slice(&(topic.len() as u16).to_be_bytes());
+        packet.extend_from_slice(topic.as_bytes());
+        packet.extend_from_slice(payload.as_bytes());
+        packet
+    }
+
+    #[tokio::test]
+    async fn retained_and_published_messages_are_applied() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (push, mut pushed) = mpsc::unbounded_channel::<&'static str>();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            // Packets are short, so remaining length is single byte
+            let mut header = [0; 2];
+            stream.read_exact(&mut header).await.unwrap();
+            assert_eq!(header[0], 0x10, "CONNECT is expected");
+            stream.read_exact(&mut vec![0; header[1] as usize]).await.unwrap();
+            stream.write_all(&[0x20, 0x02, 0x00, 0x00]).await.unwrap();
+            // SUBSCRIBE is acknowledged with its packet id
+            stream.read_exact(&mut header).await.unwrap();
+            assert_eq!(header[0], 0x82, "SUBSCRIBE is expected");
+            let mut subscribe = vec![0; header[1] as usize];
+            stream.read_exact(&mut subscribe).await.unwrap();
+            stream.write_all(&[0x90, 0x03, subscribe[0], subscribe[1], 0x00]).await.unwrap();
+            stream.write_all(&publish("fleet/config", "first", true)).await.unwrap();
+            while let Some(payload) = pushed.recv().await {
+                stream.write_all(&publish("fleet/other", "ignored", false)).await.unwrap();
+                stream.write_all(&publish("fleet/config", payload, false)).await.unwrap();
+            }
+        });
+
+        let options = MqttOptions::new("test", "127.0.0.1", port);
+        let data_provider = MqttDataProvider::new(options, "fleet/config", parse).with_qos(QoS::AtMostOnce);
+        let first = data_provider.load_data().await.unwrap();
+        assert_eq!(first.data, "first");
+
+        push.send("second").unwrap();
+        let token = first.metadata.invalidation.clone().unwrap();
+        tokio::time::timeout(Duration::from_secs(5), token.invalidated()).await.unwrap();
+        let Revalidation::Modified(second) = data_provider.revalidate_data(&first).await.unwrap() else {
+            panic!("expected modified data");
+        };
+        assert_eq!(second.data, "second");
+        assert_ne!(second.metadata.revision, first.metadata.revision);
+    }
+}
@@ -0,0 +1,185 @@
+use std::collections::hash_map::DefaultHasher;
+use std::error::Error;
+use std::fmt::{Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::time::{Duration, SystemTime};
+use sqlx::mysql::{MySqlPool, MySqlRow};
+use sqlx::Row;
+use crate::data_providers::data_provider::{DataLoadResult, DataProvider, Revalidation};
+
+/// This data provider runs query against MySQL database and maps returned rows into data with specified function,
+/// e.g. [`deserialize_rows`].
+/// Hash of returned values is reported as revision, so rows are not mapped again if query result did not change.
+/// # Examples
+/// ```no_run
+/// # #[cfg(feature = "json")] {
+/// use serde::Deserialize;
+/// use sqlx::mysql::MySqlPool;
+/// use remote_config::data_providers::mysql::{deserialize_rows, MySqlDataProvider};
+///
+/// #[derive(Deserialize)]
+/// struct Setting {
+///     name: String,
+///     value: String,
+///     enabled: bool
+/// }
+///
+/// # async fn example() -> Result<(), sqlx::Error> {
+/// let pool = MySqlPool::connect("mysql://app@localhost/app").await?;
+/// let data_provider = MySqlDataProvider::new(pool, "SELECT name, value, enabled FROM settings", deserialize_rows::<Vec<Setting>>);
+/// # Ok(())
+/// # }
+/// # }
+/// ```
+pub struct MySqlDataProvider<Data: Send + Sync, Parser> {
+    pool: MySqlPool,
+    query: String,
+    parser: Parser,
+    max_age: Duration,
+    data_type: PhantomData<Data>
+}
+
+impl <Data, Parser> MySqlDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&[MySqlRow]) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Creates data provider, that runs specified query with connections from pool
+    pub fn new(pool: MySqlPool, query: impl Into<String>, parser: Parser) -> Self {
+        Self {
+            pool,
+            query: query.into(),
+            parser,
+            max_age: Duration::from_secs(60),
+            data_type: PhantomData
+        }
+    }
+
+    /// Time after which query is run again. Default is 60 seconds.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    async fn load(&self, current_revision: Option<&str>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        let rows = sqlx::query(&self.query).fetch_all(&self.pool).await?;
+        let valid_until = SystemTime::now() + self.max_age;
+        let revision = revision(&rows);
+        if current_revision == Some(revision.as_str()) {
+            return Ok(Revalidation::NotModified { must_revalidate: false, valid_until, invalidation: None, fetched_at: None });
+        }
+
+        let mut result = DataLoadResult::new((self.parser)(&rows)?, false, valid_until);
+        result.metadata.revision = Some(revision);
+        Ok(Revalidation::Modified(result))
+    }
+}
+
+/// Hash of raw values of all rows
+fn revision(rows: &[MySqlRow]) -> String {
+    let mut hasher = DefaultHasher::new();
+    for row in rows {
+        for index in 0..row.len() {
+            row.try_get_unchecked::<Option<&[u8]>, _>(index).ok().flatten().hash(&mut hasher);
+        }
+        // Row boundary
+        u8::MAX.hash(&mut hasher);
+    }
+    format!("{:016x}", hasher.finish())
+}
+
+/// Deserializes rows as a sequence of maps from column name to value, so it can be used as parser of [`MySqlDataProvider`].
+///
+/// Integer, floating point and string columns are supported, and `BOOLEAN` (`TINYINT(1)`) columns are mapped to booleans.
+/// Other columns (e.g. `DECIMAL`, `DATETIME` or `JSON`) should be cast to one of supported types in query.
+/// # Errors
+/// If column type is not supported, or rows can't be deserialized into data
+#[cfg(feature = "json")]
+pub fn deserialize_rows<Data: serde::de::DeserializeOwned>(rows: &[MySqlRow]) -> Result<Data, Box<dyn Error + Send + Sync>> {
+    use serde_json::{Map, Value};
+    use sqlx::{Column, TypeInfo};
+
+    let mut values = Vec::with_capacity(rows.len());
+    for row in rows {
+        let mut object = Map::with_capacity(row.len());
+        for column in row.columns() {
+            let index = column.ordinal();
+            let type_name = column.type_info().name();
+            let value = if type_name == "BOOLEAN" {
+                row.try_get::<Option<bool>, _>(index).map(Value::from)
+            } else {
+                row.try_get::<Option<i64>, _>(index).map(Value::from)
+                    .or_else(|_| row.try_get::<Option<u64>, _>(index).map(Value::from))
+                    .or_else(|_| row.try_get::<Option<f64>, _>(index).map(Value::from))
+                    .or_else(|_| row.try_get::<Option<String>, _>(index).map(Value::from))
+            };
+            let value = value.map_err(|_| format!("column '{}' has unsupported type {type_name}", column.name()))?;
+            object.insert(column.name().to_string(), value);
+        }
+        values.push(Value::Object(object));
+    }
+    Ok(serde_json::from_value(Value::Array(values))?)
+}
+
+impl <Data, Parser> DataProvider<Data> for MySqlDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&[MySqlRow]) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Runs query and maps returned rows
+    /// # Errors
+    /// If query fails or parser returns an error
+    async fn load_data(&self) -> Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>> {
+        Ok(self.load(None).await?.into_loaded())
+    }
+
+    /// Runs query, but maps rows only if returned values changed
+    async fn revalidate_data<'a>(&'a self, current: &'a DataLoadResult<Data>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        self.load(current.metadata.revision.as_deref()).await
+    }
+}
+
+impl <Data: Send + Sync, Parser> Debug for MySqlDataProvider<Data, Parser> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MySqlDataProvider")
+            .field("query", &self.query)
+            .field("max_age", &self.max_age)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(all(test, feature = "json"))]
+mod tests {
+    use std::collections::HashMap;
+    use serde::Deserialize;
+    use sqlx::mysql::MySqlPool;
+    use crate::data_providers::data_provider::{DataProvider, Revalidation};
+    use crate::data_providers::mysql::{deserialize_rows, MySqlDataProvider};
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Setting {
+        value: String,
+        enabled: bool,
+        weight: Option<f64>
+    }
+
+    #[tokio::test]
+    #[ignore = "requires MySQL server at DATABASE_URL"]
+    async fn rows_are_deserialized() {
+        let pool = MySqlPool::connect(&std::env::var("DATABASE_URL").unwrap()).await.unwrap();
+        sqlx::query("DROP TABLE IF EXISTS remote_config_test").execute(&pool).await.unwrap();
+        sqlx::query("CREATE TABLE remote_config_test (name VARCHAR(64) PRIMARY KEY, value TEXT, enabled BOOLEAN, weight DOUBLE)").execute(&pool).await.unwrap();
+        sqlx::query("INSERT INTO remote_config_test VALUES ('a', 'x', TRUE, 0.5), ('b', 'y', FALSE, NULL)").execute(&pool).await.unwrap();
+
+        let data_provider = MySqlDataProvider::new(pool.clone(), "SELECT name, value, enabled, weight FROM remote_config_test", deserialize_rows::<Vec<HashMap<String, serde_json::Value>>>);
+        let first = data_provider.load_data().await.unwrap();
+        assert_eq!(first.data.len(), 2);
+        assert_eq!(first.data[0]["enabled"], true);
+
+        // Nothing changed
+        assert!(matches!(data_provider.revalidate_data(&first).await.unwrap(), Revalidation::NotModified { .. }));
+
+        sqlx::query("UPDATE remote_config_test SET value = 'z' WHERE name = 'b'").execute(&pool).await.unwrap();
+        let data_provider = MySqlDataProvider::new(pool, "SELECT value, enabled, weight FROM remote_config_test ORDER BY name", deserialize_rows::<Vec<Setting>>);
+        let second = data_provider.load_data().await.unwrap();
+        assert_eq!(second.data[1], Setting { value: "z".to_string(), enabled: false, weight: None });
+        assert_ne!(second.metadata.revision, first.metadata.revision);
+    }
+}
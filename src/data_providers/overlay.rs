@@ -0,0 +1,459 @@
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::future::Future;
+use std::marker::PhantomData;
+#[cfg(feature = "file")]
+use std::path::Path;
+#[cfg(feature = "file")]
+use std::sync::Mutex;
+#[cfg(feature = "file")]
+use std::time::SystemTime;
+#[cfg(feature = "file")]
+use crate::data_providers::data_provider::InvalidationToken;
+#[cfg(feature = "file")]
+use crate::data_providers::file::FileDataProvider;
+use crate::data_providers::data_provider::{DataLoadResult, DataProvider, Revalidation};
+use crate::hardened::{SecurityAudit, SecurityIssue};
+
+/// Data that local overrides can be applied to
+pub trait Overridable {
+    /// Sets value of the key
+    /// # Errors
+    /// If key or value is not valid for this data
+    fn apply_override(&mut self, key: &str, value: &str) -> Result<(), Box<dyn Error + Send + Sync>>;
+}
+
+impl Overridable for HashMap<String, String> {
+    fn apply_override(&mut self, key: &str, value: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+}
+
+impl Overridable for BTreeMap<String, String> {
+    fn apply_override(&mut self, key: &str, value: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+}
+
+/// Key is a dot separated path, missing objects are created.
+/// Value is parsed as JSON, and used as string if it is not valid JSON.
+#[cfg(feature = "json")]
+impl Overridable for serde_json::Value {
+    fn apply_override(&mut self, key: &str, value: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let mut target = self;
+        for segment in key.split('.') {
+            if target.is_null() {
+                *target = serde_json::Value::Object(Default::default());
+            }
+            target = target.as_object_mut()
+                .ok_or_else(|| OverrideError::NotAnObject(key.to_string()))?
+                .entry(segment)
+                .or_insert(serde_json::Value::Null);
+        }
+        *target = serde_json::from_str(value).unwrap_or_else(|_| serde_json::Value::String(value.to_string()));
+        Ok(())
+    }
+}
+
+/// Ordered list of local overrides
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Overrides(Vec<(String, String)>);
+
+impl Overrides {
+    /// Creates empty list
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds override. Later overrides of the same key take precedence.
+    pub fn with(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.0.push((key.into(), value.into()));
+        self
+    }
+
+    /// Parses `key=value` items, e.g. values of repeated command-line flag
+    /// # Errors
+    /// If item does not contain `=`
+    pub fn parse<Item: AsRef<str>>(items: impl IntoIterator<Item = Item>) -> Result<Self, OverrideError> {
+        items.into_iter()
+            .map(|item| {
+                let item = item.as_ref();
+                item.split_once('=')
+                    .map(|(key, value)| (key.trim().to_string(), value.to_string()))
+                    .ok_or_else(|| OverrideError::Malformed(item.to_string()))
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+
+    /// Parses `key=value` lines. Empty lines and lines starting with `#` are ignored.
+    /// # Errors
+    /// If line does not contain `=`
+    pub fn parse_lines(text: &str) -> Result<Self, OverrideError> {
+        Self::parse(text.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#')))
+    }
+
+    /// Reads environment variables that start with prefix.
+    /// Key is the rest of variable name in lowercase, with `__` replaced by `.`,
+    /// so with prefix `APP_CFG_`, `APP_CFG_FEATURES__NEW_UI=true` overrides `features.new_ui`.
+    pub fn from_env(prefix: &str) -> Self {
+        let mut overrides: Vec<_> = std::env::vars()
+            .filter_map(|(name, value)| {
+                let key = name.strip_prefix(prefix)?.to_lowercase().replace("__", ".");
+                (!key.is_empty()).then_some((key, value))
+            })
+            .collect();
+        overrides.sort();
+        Self(overrides)
+    }
+
+    /// Checks if there are no overrides
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Applies all overrides and returns overridden keys
+    fn apply(&self, data: &mut impl Overridable) -> Result<Vec<String>, Box<dyn Error + Send + Sync>> {
+        let mut keys = Vec::with_capacity(self.0.len());
+        for (key, value) in &self.0 {
+            data.apply_override(key, value)?;
+            if !keys.contains(key) {
+                keys.push(key.clone());
+            }
+        }
+        Ok(keys)
+    }
+}
+
+/// This data provider applies local overrides on top of every version loaded by inner provider, before it is activated.
+/// Overridden keys are reported in [`crate::data_providers::data_provider::LoadMetadata::overrides`].
+///
+/// Intended for developers that need to force a value locally without touching the shared origin.
+/// With [`OverlayProvider::with_override_file`], overrides are also read from local file, that is watched for changes.
+/// # Examples
+/// ```
+/// use std::collections::HashMap;
+/// use reqwest::Url;
+/// use remote_config::data_providers::http::HttpDataProvider;
+/// use remote_config::data_providers::http::serde_extractor::SerdeDataExtractor;
+/// use remote_config::data_providers::overlay::{OverlayProvider, Overrides};
+///
+/// let source = HttpDataProvider::new(reqwest::Client::default(), Url::parse("https://example.com/cfg").unwrap(), SerdeDataExtractor::<HashMap<String, String>>::new());
+/// let overrides = Overrides::parse(["new_ui=true"]).unwrap();
+/// let data_provider = OverlayProvider::new(source, overrides);
+/// ```
+#[derive(Debug)]
+pub struct OverlayProvider<Data: Send + Sync, Provider> {
+    inner: Provider,
+    overrides: Overrides,
+    #[cfg(feature = "file")]
+    file: Option<OverrideFile<Data>>,
+    data_type: PhantomData<Data>
+}
+
+impl <Data: Send + Sync, Provider: DataProvider<Data>> OverlayProvider<Data, Provider> {
+    /// Wraps provider
+    pub fn new(inner: Provider, overrides: Overrides) -> Self {
+        Self {
+            inner,
+            overrides,
+            #[cfg(feature = "file")]
+            file: None,
+            data_type: PhantomData
+        }
+    }
+
+    /// Also apply overrides from local file, that contains `key=value` lines (empty lines and lines starting with `#` are ignored).
+    /// File overrides take precedence, and are re-applied to last loaded version as soon as file is edited,
+    /// without loading it from inner provider again.
+    /// # Errors
+    /// If file can't be watched (e.g. it doesn't exist)
+    #[cfg(feature = "file")]
+    pub fn with_override_file(mut self, path: impl AsRef<Path>) -> notify::Result<Self> {
+        let parse: OverridesParser = |bytes| Ok(Overrides::parse_lines(std::str::from_utf8(bytes)?)?);
+        self.file = Some(OverrideFile {
+            provider: FileDataProvider::new(path, parse)?,
+            loaded: Mutex::new(None),
+            base: Mutex::new(None)
+        });
+        Ok(self)
+    }
+}
+
+impl <Data: Send + Sync, Provider: SecurityAudit> SecurityAudit for OverlayProvider<Data, Provider> {
+    fn security_issues(&self) -> Vec<SecurityIssue> {
+        let mut issues = self.inner.security_issues();
+        #[cfg(feature = "file")]
+        let has_file = self.file.is_some();
+        #[cfg(not(feature = "file"))]
+        let has_file = false;
+        if !self.overrides.is_empty() || has_file {
+            issues.push(SecurityIssue::LocalOverrides);
+        }
+        issues
+    }
+}
+
+#[cfg(feature = "file")]
+type OverridesParser = fn(&[u8]) -> Result<Overrides, Box<dyn Error + Send + Sync>>;
+
+/// Watched override file, and last version loaded by inner provider
+#[cfg(feature = "file")]
+#[derive(Debug)]
+struct OverrideFile<Data: Send + Sync> {
+    provider: FileDataProvider<Overrides, OverridesParser>,
+    loaded: Mutex<Option<(Overrides, InvalidationToken)>>,
+    base: Mutex<Option<DataLoadResult<Data>>>
+}
+
+#[cfg(feature = "file")]
+impl <Data: Overridable + Clone + Send + Sync> OverrideFile<Data> {
+    fn is_changed(&self) -> bool {
+        self.loaded.lock().unwrap().as_ref().is_none_or(|(_, token)| token.is_invalidated())
+    }
+
+    /// Copy of last loaded version
+    fn base_copy(&self) -> Option<DataLoadResult<Data>> {
+        let base = self.base.lock().unwrap();
+        let base = base.as_ref()?;
+        let mut copy = DataLoadResult::new(base.data.clone(), base.must_revalidate, base.valid_until);
+        copy.metadata = base.metadata.clone();
+        Some(copy)
+    }
+
+    /// Copy of last loaded version, if it is still fresh
+    fn fresh_base(&self) -> Option<DataLoadResult<Data>> {
+        self.base_copy()
+            .filter(|base| base.valid_until > SystemTime::now())
+            .filter(|base| !base.metadata.invalidation.as_ref().is_some_and(InvalidationToken::is_invalidated))
+    }
+
+    /// Applies overrides and file overrides on top of base version, which is kept for later
+    async fn activate(&self, overrides: &Overrides, base: DataLoadResult<Data>) -> Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>> {
+        if self.is_changed() {
+            let loaded = self.provider.load_data().await?;
+            let token = loaded.metadata.invalidation.unwrap_or_default();
+            *self.loaded.lock().unwrap() = Some((loaded.data, token));
+        }
+        let (file_overrides, file_token) = self.loaded.lock().unwrap().clone().expect("override file is loaded");
+
+        let mut result = DataLoadResult::new(base.data.clone(), base.must_revalidate, base.valid_until);
+        result.metadata = base.metadata.clone();
+        result.metadata.overrides = Overrides(overrides.0.iter().chain(&file_overrides.0).cloned().collect()).apply(&mut result.data)?;
+        result.metadata.invalidation = Some(InvalidationToken::any(base.metadata.invalidation.iter().cloned().chain([file_token])));
+        *self.base.lock().unwrap() = Some(base);
+        Ok(result)
+    }
+}
+
+impl <Data, Provider> DataProvider<Data> for OverlayProvider<Data, Provider>
+where Data: Overridable + Clone + Send + Sync, Provider: DataProvider<Data>
+{
+    /// Loads data with inner provider and applies overrides
+    /// # Errors
+    /// If inner provider returns an error, override file can't be read, or override can't be applied
+    fn load_data(&self) -> impl Future<Output = Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>>> + Send {
+        // Future is created before async block, so that inner provider is not required to be Sync
+        let load = self.inner.load_data();
+        let overrides = &self.overrides;
+        #[cfg(feature = "file")]
+        let file = self.file.as_ref();
+        async move {
+            let mut result = load.await?;
+            #[cfg(feature = "file")]
+            if let Some(file) = file {
+                return file.activate(overrides, result).await;
+            }
+            result.metadata.overrides = overrides.apply(&mut result.data)?;
+            Ok(result)
+        }
+    }
+
+    /// Revalidates data with inner provider, and applies overrides if it was modified.
+    /// If only override file was changed, overrides are applied to last loaded version instead.
+    fn revalidate_data<'a>(&'a self, current: &'a DataLoadResult<Data>) -> impl Future<Output = Result<Revalidation<Data>, Box<dyn Error + Send + Sync>>> + Send {
+        let revalidation = self.inner.revalidate_data(current);
+        // Futures do nothing until polled, so data is loaded only if base version is not kept
+        #[cfg(feature = "file")]
+        let load = self.inner.load_data();
+        let overrides = &self.overrides;
+        #[cfg(feature = "file")]
+        let file = self.file.as_ref();
+        async move {
+            #[cfg(feature = "file")]
+            if let Some(file) = file {
+                if file.is_changed() {
+                    if let Some(base) = file.fresh_base() {
+                        return Ok(Revalidation::Modified(file.activate(overrides, base).await?));
+                    }
+                }
+                return match revalidation.await? {
+                    Revalidation::Modified(base) => Ok(Revalidation::Modified(file.activate(overrides, base).await?)),
+                    Revalidation::NotModified { must_revalidate, valid_until, invalidation, fetched_at } => {
+                        let base_token = file.base.lock().unwrap().as_mut().map(|base| {
+                            base.must_revalidate = must_revalidate;
+                            base.valid_until = valid_until;
+                            base.metadata.invalidation = invalidation.or(base.metadata.invalidation.take());
+                            base.metadata.fetched_at = fetched_at;
+                            base.metadata.invalidation.clone()
+                        });
+                        // Current data was not loaded by this provider (e.g. it replaced another one), so base version is loaded
+                        let Some(base_token) = base_token else {
+                            return Ok(Revalidation::Modified(file.activate(overrides, load.await?).await?));
+                        };
+                        if file.is_changed() {
+                            let base = file.base_copy().expect("base version is loaded");
+                            return Ok(Revalidation::Modified(file.activate(overrides, base).await?));
+                        }
+                        let file_token = file.loaded.lock().unwrap().as_ref().map(|(_, token)| token.clone());
+                        let invalidation = InvalidationToken::any(base_token.into_iter().chain(file_token));
+                        Ok(Revalidation::NotModified { must_revalidate, valid_until, invalidation: Some(invalidation), fetched_at })
+                    }
+                };
+            }
+            match revalidation.await? {
+                Revalidation::Modified(mut result) => {
+                    result.metadata.overrides = overrides.apply(&mut result.data)?;
+                    Ok(Revalidation::Modified(result))
+                },
+                not_modified => Ok(not_modified)
+            }
+        }
+    }
+}
+
+/// Override errors
+#[derive(Debug)]
+pub enum OverrideError {
+    /// Override is not in `key=value` format
+    Malformed(String),
+    /// Path of override goes through value that is not an object
+    NotAnObject(String)
+}
+
+impl Display for OverrideError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Malformed(item) => write!(f, "override '{item}' is not in key=value format"),
+            Self::NotAnObject(key) => write!(f, "override '{key}' can't be applied: path goes through value that is not an object")
+        }
+    }
+}
+
+impl Error for OverrideError {}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::error::Error;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::{Duration, SystemTime};
+    use crate::data_providers::data_provider::{DataLoadResult, DataProvider, Revalidation};
+    use crate::data_providers::overlay::{OverlayProvider, OverrideError, Overrides};
+
+    #[derive(Default)]
+    struct Source {
+        loads: AtomicUsize,
+        /// Report that data was not modified on revalidation
+        unchanged: bool
+    }
+
+    impl DataProvider<HashMap<String, String>> for Source {
+        async fn load_data(&self) -> Result<DataLoadResult<HashMap<String, String>>, Box<dyn Error + Send + Sync>> {
+            self.loads.fetch_add(1, Ordering::Relaxed);
+            let data = HashMap::from([("new_ui".to_string(), "false".to_string()), ("limit".to_string(), "10".to_string())]);
+            Ok(DataLoadResult::new(data, false, SystemTime::now() + Duration::from_secs(60)))
+        }
+
+        async fn revalidate_data<'a>(&'a self, _current: &'a DataLoadResult<HashMap<String, String>>) -> Result<Revalidation<HashMap<String, String>>, Box<dyn Error + Send + Sync>> {
+            if self.unchanged {
+                let valid_until = SystemTime::now() + Duration::from_secs(60);
+                return Ok(Revalidation::NotModified { must_revalidate: false, valid_until, invalidation: None, fetched_at: None });
+            }
+            Ok(Revalidation::Modified(self.load_data().await?))
+        }
+    }
+
+    #[tokio::test]
+    async fn overrides_are_applied() {
+        let overrides = Overrides::parse(["new_ui=true", "theme = dark=blue"]).unwrap();
+        let result = OverlayProvider::new(Source::default(), overrides).load_data().await.unwrap();
+        assert_eq!(result.data["new_ui"], "true");
+        assert_eq!(result.data["limit"], "10");
+        assert_eq!(result.data["theme"], " dark=blue");
+        assert_eq!(result.metadata.overrides, vec!["new_ui", "theme"]);
+
+        assert!(matches!(Overrides::parse(["new_ui"]), Err(OverrideError::Malformed(_))));
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn json_paths() {
+        use crate::data_providers::overlay::Overridable;
+
+        let mut data = serde_json::json!({"features": {"new_ui": false}, "name": "app"});
+        data.apply_override("features.new_ui", "true").unwrap();
+        data.apply_override("limits.requests", "100").unwrap();
+        data.apply_override("name", "dev build").unwrap();
+        assert_eq!(data, serde_json::json!({"features": {"new_ui": true}, "limits": {"requests": 100}, "name": "dev build"}));
+        assert!(data.apply_override("name.first", "x").is_err());
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "file")]
+    async fn override_file_is_reapplied() {
+        let path = std::env::temp_dir().join(format!("remote-config-overrides-{}", std::process::id()));
+        std::fs::write(&path, "# local overrides\nnew_ui=true\n").unwrap();
+        let data_provider = OverlayProvider::new(Source::default(), Overrides::new().with("limit", "5"))
+            .with_override_file(&path)
+            .unwrap();
+
+        let first = data_provider.load_data().await.unwrap();
+        assert_eq!(first.data["new_ui"], "true");
+        assert_eq!(first.data["limit"], "5");
+        assert_eq!(first.metadata.overrides, vec!["limit", "new_ui"]);
+
+        std::fs::write(&path, "limit=20\n").unwrap();
+        let token = first.metadata.invalidation.clone().unwrap();
+        for _ in 0..100 {
+            if token.is_invalidated() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(token.is_invalidated());
+
+        // Overrides are applied to kept version, which is not loaded again
+        let Revalidation::Modified(second) = data_provider.revalidate_data(&first).await.unwrap() else {
+            panic!("expected modified data");
+        };
+        assert_eq!(second.data["new_ui"], "false");
+        assert_eq!(second.data["limit"], "20");
+        assert_eq!(data_provider.inner.loads.load(Ordering::Relaxed), 1);
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "file")]
+    async fn unchanged_data_of_replaced_provider_is_loaded() {
+        let path = std::env::temp_dir().join(format!("remote-config-replaced-overrides-{}", std::process::id()));
+        std::fs::write(&path, "new_ui=true\n").unwrap();
+        let current = Source::default().load_data().await.unwrap();
+
+        // Provider that didn't load current data has no base version to apply overrides to
+        let data_provider = OverlayProvider::new(Source { unchanged: true, ..Source::default() }, Overrides::new())
+            .with_override_file(&path)
+            .unwrap();
+        let Revalidation::Modified(result) = data_provider.revalidate_data(&current).await.unwrap() else {
+            panic!("expected modified data");
+        };
+        assert_eq!(result.data["new_ui"], "true");
+        assert_eq!(data_provider.inner.loads.load(Ordering::Relaxed), 1);
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
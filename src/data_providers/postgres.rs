@@ -0,0 +1,243 @@
+use std::collections::hash_map::DefaultHasher;
+use std::error::Error;
+use std::fmt::{Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use sqlx::postgres::{PgListener, PgPool, PgRow};
+use sqlx::{Row, ValueRef};
+use tokio::task::AbortHandle;
+use crate::data_providers::data_provider::{DataLoadResult, DataProvider, InvalidationToken, Revalidation};
+
+/// This data provider runs query against PostgreSQL database and maps returned rows into data with specified function.
+/// Hash of returned values is reported as revision, so rows are not mapped again if query result did not change.
+///
+/// With [`PostgresDataProvider::with_notifications`], background task executes `LISTEN` on specified channel,
+/// and invalidates loaded data as soon as notification is received, e.g. from trigger on config table:
+/// ```sql
+/// CREATE FUNCTION notify_config_changed() RETURNS trigger AS $$
+/// BEGIN
+///     PERFORM pg_notify('config_changed', '');
+///     RETURN NULL;
+/// END;
+/// $$ LANGUAGE plpgsql;
+///
+/// CREATE TRIGGER config_changed AFTER INSERT OR UPDATE OR DELETE OR TRUNCATE ON config
+///     FOR EACH STATEMENT EXECUTE FUNCTION notify_config_changed();
+/// ```
+/// # Examples
+/// ```no_run
+/// use std::collections::HashMap;
+/// use sqlx::Row;
+/// use sqlx::postgres::{PgPool, PgRow};
+/// use remote_config::data_providers::postgres::PostgresDataProvider;
+///
+/// # async fn example() -> Result<(), sqlx::Error> {
+/// let pool = PgPool::connect("postgres://app@localhost/app").await?;
+/// let data_provider = PostgresDataProvider::new(pool, "SELECT key, value FROM config", |rows: &[PgRow]| {
+///     let mut data = HashMap::new();
+///     for row in rows {
+///         data.insert(row.try_get::<String, _>("key")?, row.try_get::<String, _>("value")?);
+///     }
+///     Ok(data)
+/// }).with_notifications("config_changed");
+/// # Ok(())
+/// # }
+/// ```
+pub struct PostgresDataProvider<Data: Send + Sync, Parser> {
+    pool: PgPool,
+    query: String,
+    parser: Parser,
+    max_age: Duration,
+    channel: Option<String>,
+    token: Arc<Mutex<InvalidationToken>>,
+    /// Notification listener task, started on first load
+    watcher: Mutex<Option<AbortHandle>>,
+    data_type: PhantomData<Data>
+}
+
+impl <Data, Parser> PostgresDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&[PgRow]) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Creates data provider, that runs specified query with connections from pool
+    pub fn new(pool: PgPool, query: impl Into<String>, parser: Parser) -> Self {
+        Self {
+            pool,
+            query: query.into(),
+            parser,
+            max_age: Duration::from_secs(60),
+            channel: None,
+            token: Arc::new(Mutex::new(InvalidationToken::new())),
+            watcher: Mutex::new(None),
+            data_type: PhantomData
+        }
+    }
+
+    /// Time after which query is run again. Default is 60 seconds.
+    /// With notifications it can be much longer, as changes are detected immediately.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Invalidate data when notification is sent to specified channel.
+    /// Listening starts on first data load, and stops when data provider is dropped.
+    pub fn with_notifications(mut self, channel: impl Into<String>) -> Self {
+        self.channel = Some(channel.into());
+        self
+    }
+
+    /// Starts notification listener task, if it is enabled and not started yet.
+    /// Listener is connected before returning, so notifications sent during following query are not missed.
+    async fn start_listening(&self) -> Result<(), sqlx::Error> {
+        let Some(channel) = &self.channel else {
+            return Ok(());
+        };
+        if self.watcher.lock().unwrap().is_some() {
+            return Ok(());
+        }
+        let mut listener = PgListener::connect_with(&self.pool).await?;
+        listener.listen(channel).await?;
+
+        let mut watcher = self.watcher.lock().unwrap();
+        if watcher.is_some() {
+            return Ok(());
+        }
+        let token = Arc::downgrade(&self.token);
+        let handle = tokio::spawn(async move {
+            loop {
+                let received = listener.try_recv().await;
+                let Some(token) = token.upgrade() else {
+                    return;
+                };
+                // Notifications may be missed while connection is lost, so data is invalidated in that case too
+                token.lock().unwrap().invalidate();
+                match received {
+                    Ok(_) => {},
+                    Err(sqlx::Error::PoolClosed) => return,
+                    Err(_err) => {
+                        #[cfg(feature = "tracing")]
+                        tracing::warn!("PostgreSQL notification listener failed: {_err}");
+                        tokio::time::sleep(Duration::from_secs(1)).await;
+                    }
+                }
+            }
+        });
+        *watcher = Some(handle.abort_handle());
+        Ok(())
+    }
+
+    async fn load(&self, current_revision: Option<&str>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        // Token is replaced before query, so changes made during query are not missed
+        let token = InvalidationToken::new();
+        *self.token.lock().unwrap() = token.clone();
+        self.start_listening().await?;
+
+        let rows = sqlx::query(&self.query).fetch_all(&self.pool).await?;
+        let valid_until = SystemTime::now() + self.max_age;
+        let revision = revision(&rows);
+        if current_revision == Some(revision.as_str()) {
+            return Ok(Revalidation::NotModified { must_revalidate: false, valid_until, invalidation: Some(token), fetched_at: None });
+        }
+
+        let mut result = DataLoadResult::new((self.parser)(&rows)?, false, valid_until);
+        result.metadata.revision = Some(revision);
+        result.metadata.invalidation = Some(token);
+        Ok(Revalidation::Modified(result))
+    }
+}
+
+/// Hash of raw values of all rows
+fn revision(rows: &[PgRow]) -> String {
+    let mut hasher = DefaultHasher::new();
+    for row in rows {
+        for index in 0..row.len() {
+            let value = row.try_get_raw(index).ok().filter(|value| !value.is_null());
+            value.and_then(|value| value.as_bytes().ok()).hash(&mut hasher);
+        }
+        // Row boundary
+        u8::MAX.hash(&mut hasher);
+    }
+    format!("{:016x}", hasher.finish())
+}
+
+impl <Data, Parser> DataProvider<Data> for PostgresDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&[PgRow]) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Runs query and maps returned rows
+    /// # Errors
+    /// If listener can't be started, query fails or parser returns an error
+    async fn load_data(&self) -> Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>> {
+        Ok(self.load(None).await?.into_loaded())
+    }
+
+    /// Runs query, but maps rows only if returned values changed
+    async fn revalidate_data<'a>(&'a self, current: &'a DataLoadResult<Data>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        self.load(current.metadata.revision.as_deref()).await
+    }
+}
+
+impl <Data: Send + Sync, Parser> Drop for PostgresDataProvider<Data, Parser> {
+    fn drop(&mut self) {
+        if let Some(handle) = self.watcher.lock().unwrap().take() {
+            handle.abort();
+        }
+    }
+}
+
+impl <Data: Send + Sync, Parser> Debug for PostgresDataProvider<Data, Parser> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PostgresDataProvider")
+            .field("query", &self.query)
+            .field("max_age", &self.max_age)
+            .field("channel", &self.channel)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use sqlx::postgres::{PgPool, PgRow};
+    use sqlx::Row;
+    use crate::data_providers::data_provider::{DataProvider, Revalidation};
+    use crate::data_providers::postgres::PostgresDataProvider;
+
+    #[tokio::test]
+    #[ignore = "requires PostgreSQL server at DATABASE_URL"]
+    async fn notifications_invalidate_data() {
+        let pool = PgPool::connect(&std::env::var("DATABASE_URL").unwrap()).await.unwrap();
+        sqlx::query("DROP TABLE IF EXISTS remote_config_test").execute(&pool).await.unwrap();
+        sqlx::query("CREATE TABLE remote_config_test (key TEXT PRIMARY KEY, value TEXT)").execute(&pool).await.unwrap();
+        sqlx::query("INSERT INTO remote_config_test VALUES ('mode', 'a')").execute(&pool).await.unwrap();
+
+        let data_provider = PostgresDataProvider::new(pool.clone(), "SELECT value FROM remote_config_test WHERE key = 'mode'", |rows: &[PgRow]| {
+            Ok(rows[0].try_get::<String, _>("value")?)
+        }).with_notifications("config_changed");
+
+        let first = data_provider.load_data().await.unwrap();
+        assert_eq!(first.data, "a");
+
+        // Nothing changed
+        let Revalidation::NotModified { invalidation: Some(token), .. } = data_provider.revalidate_data(&first).await.unwrap() else {
+            panic!("expected not modified data with new invalidation token");
+        };
+
+        sqlx::query("UPDATE remote_config_test SET value = 'b'").execute(&pool).await.unwrap();
+        sqlx::query("SELECT pg_notify('config_changed', '')").execute(&pool).await.unwrap();
+        for _ in 0..100 {
+            if token.is_invalidated() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(token.is_invalidated());
+
+        let Revalidation::Modified(second) = data_provider.revalidate_data(&first).await.unwrap() else {
+            panic!("expected modified data");
+        };
+        assert_eq!(second.data, "b");
+        assert_ne!(second.metadata.revision, first.metadata.revision);
+    }
+}
@@ -0,0 +1,140 @@
+use std::error::Error;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, SystemTime};
+use tokio::sync::watch;
+use crate::data_providers::data_provider::{DataLoadResult, InvalidationToken, Revalidation};
+
+/// Revision of received payload, unique within process.
+/// Payload received by data provider, that replaced another one, is never mistaken for data loaded by replaced provider.
+fn next_revision() -> String {
+    static NEXT: AtomicU64 = AtomicU64::new(1);
+    NEXT.fetch_add(1, Ordering::Relaxed).to_string()
+}
+
+/// Payload received from origin
+#[derive(Debug)]
+pub(crate) struct Pushed<Payload> {
+    pub(crate) revision: String,
+    pub(crate) payload: Payload
+}
+
+impl <Payload> Pushed<Payload> {
+    /// Not modified, if current revision is revision of this payload, otherwise payload parsed into data and its size.
+    /// Loaded data is invalidated with token.
+    pub(crate) fn revalidate<Data>(
+        &self,
+        current_revision: Option<&str>,
+        token: InvalidationToken,
+        max_age: Duration,
+        parse: impl FnOnce(&Payload) -> Result<(Data, u64), Box<dyn Error + Send + Sync>>
+    ) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        let valid_until = SystemTime::now() + max_age;
+        if current_revision == Some(self.revision.as_str()) {
+            return Ok(Revalidation::NotModified { must_revalidate: false, valid_until, invalidation: Some(token), fetched_at: None });
+        }
+
+        let (data, size) = parse(&self.payload)?;
+        let mut result = DataLoadResult::new(data, false, valid_until);
+        result.metadata.size = Some(size);
+        result.metadata.revision = Some(self.revision.clone());
+        result.metadata.invalidation = Some(token);
+        Ok(Revalidation::Modified(result))
+    }
+}
+
+/// Latest payload received by background task of data provider, that keeps connection to origin open,
+/// and token of loaded data, that is invalidated when next payload is received
+pub(crate) struct PushedPayloads<Payload> {
+    token: Arc<Mutex<InvalidationToken>>,
+    latest: watch::Sender<Option<Arc<Pushed<Payload>>>>
+}
+
+impl <Payload> PushedPayloads<Payload> {
+    pub(crate) fn new() -> Self {
+        Self {
+            token: Arc::new(Mutex::new(InvalidationToken::new())),
+            latest: watch::Sender::new(None)
+        }
+    }
+
+    /// Publisher for background task, that stops publishing when data provider is dropped
+    pub(crate) fn publisher(&self) -> Publisher<Payload> {
+        Publisher { latest: self.latest.clone(), token: Arc::downgrade(&self.token) }
+    }
+
+    /// Waits for first payload, and returns the latest one with new token of loaded data.
+    /// Returns `None` if no payload is received before timeout.
+    pub(crate) async fn latest(&self, timeout: Duration) -> Option<(Arc<Pushed<Payload>>, InvalidationToken)> {
+        let mut latest = self.latest.subscribe();
+        tokio::time::timeout(timeout, latest.wait_for(Option::is_some)).await.ok()?.ok()?;
+        // Token is replaced before payload is read, so payloads received after read are not missed
+        let token = InvalidationToken::new();
+        *self.token.lock().unwrap() = token.clone();
+        let pushed = latest.borrow().clone().expect("payload is received");
+        Some((pushed, token))
+    }
+}
+
+/// Publishes payloads received by background task
+pub(crate) struct Publisher<Payload> {
+    latest: watch::Sender<Option<Arc<Pushed<Payload>>>>,
+    token: Weak<Mutex<InvalidationToken>>
+}
+
+impl <Payload> Publisher<Payload> {
+    /// Replaces latest payload and invalidates loaded data. Returns `false` if data provider was dropped.
+    pub(crate) fn publish(&self, payload: Payload) -> bool {
+        let Some(token) = self.token.upgrade() else {
+            return false;
+        };
+        self.latest.send_replace(Some(Arc::new(Pushed { revision: next_revision(), payload })));
+        token.lock().unwrap().invalidate();
+        true
+    }
+
+    /// Data provider was dropped
+    pub(crate) fn is_closed(&self) -> bool {
+        self.token.strong_count() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use crate::data_providers::data_provider::Revalidation;
+    use crate::data_providers::push::PushedPayloads;
+
+    #[tokio::test]
+    async fn payload_of_replaced_provider_is_not_mistaken_for_current_one() {
+        let parse = |payload: &&str| Ok((payload.to_string(), payload.len() as u64));
+        let replaced = PushedPayloads::new();
+        assert!(replaced.publisher().publish("first"));
+        let (pushed, token) = replaced.latest(Duration::from_secs(1)).await.unwrap();
+        let Revalidation::Modified(current) = pushed.revalidate(None, token, Duration::from_secs(60), parse).unwrap() else {
+            panic!("expected modified data");
+        };
+
+        // The same payload is not modified
+        let (pushed, token) = replaced.latest(Duration::from_secs(1)).await.unwrap();
+        assert!(matches!(pushed.revalidate(current.metadata.revision.as_deref(), token, Duration::from_secs(60), parse).unwrap(), Revalidation::NotModified { .. }));
+
+        // The first payload of another provider is new
+        let payloads = PushedPayloads::new();
+        assert!(payloads.latest(Duration::from_millis(10)).await.is_none());
+        let publisher = payloads.publisher();
+        assert!(publisher.publish("second"));
+        let (pushed, token) = payloads.latest(Duration::from_secs(1)).await.unwrap();
+        let Revalidation::Modified(second) = pushed.revalidate(current.metadata.revision.as_deref(), token.clone(), Duration::from_secs(60), parse).unwrap() else {
+            panic!("expected modified data");
+        };
+        assert_eq!(second.data, "second");
+
+        // Publishing invalidates loaded data, and stops when provider is dropped
+        assert!(publisher.publish("third"));
+        assert!(token.is_invalidated());
+        drop(payloads);
+        assert!(publisher.is_closed());
+        assert!(!publisher.publish("fourth"));
+    }
+}
@@ -0,0 +1,375 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+#[cfg(feature = "vault")]
+use reqwest::{Method, Url};
+use serde_json::Value;
+use crate::data_providers::data_provider::{DataLoadResult, DataProvider, Revalidation};
+use crate::hardened::{SecurityAudit, SecurityIssue};
+
+/// Scheme of secret references
+pub const SECRET_REF_SCHEME: &str = "secretref://";
+
+/// Reference to secret in config value, written as `secretref://{resolver}/{path}#{key}` (key is optional),
+/// e.g. `secretref://vault/secret/payments#api_key` or `secretref://env/DATABASE_PASSWORD`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SecretRef {
+    /// Name of resolver, that the secret is resolved with
+    pub resolver: String,
+    /// Path of secret, meaning of which depends on resolver
+    pub path: String,
+    /// Key inside secret, if secret has several values
+    pub key: Option<String>
+}
+
+impl SecretRef {
+    /// Parses reference. Returns `None` if value is not a secret reference
+    pub fn parse(value: &str) -> Option<Self> {
+        let reference = value.strip_prefix(SECRET_REF_SCHEME)?;
+        let (reference, key) = match reference.split_once('#') {
+            Some((reference, key)) => (reference, Some(key.to_string())),
+            None => (reference, None)
+        };
+        let (resolver, path) = reference.split_once('/')?;
+        (!resolver.is_empty() && !path.is_empty()).then(|| Self { resolver: resolver.to_string(), path: path.to_string(), key })
+    }
+}
+
+impl Display for SecretRef {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{SECRET_REF_SCHEME}{}/{}", self.resolver, self.path)?;
+        match &self.key {
+            Some(key) => write!(f, "#{key}"),
+            None => Ok(())
+        }
+    }
+}
+
+/// Future returned by [`SecretResolver::resolve`]
+pub type ResolveFuture<'a> = Pin<Box<dyn Future<Output = Result<String, Box<dyn Error + Send + Sync>>> + Send + 'a>>;
+
+/// Plugin that reads secret values from secret store, registered with [`SecretResolvingProvider::with_resolver`]
+pub trait SecretResolver: Debug + Send + Sync {
+    /// Reads secret value
+    /// # Errors
+    /// If secret doesn't exist or can't be read
+    fn resolve<'a>(&'a self, reference: &'a SecretRef) -> ResolveFuture<'a>;
+}
+
+/// Resolves `secretref://env/{NAME}` references to values of environment variables
+#[derive(Debug, Clone, Default)]
+pub struct EnvSecretResolver;
+
+impl SecretResolver for EnvSecretResolver {
+    fn resolve<'a>(&'a self, reference: &'a SecretRef) -> ResolveFuture<'a> {
+        Box::pin(async move {
+            std::env::var(&reference.path).map_err(|_| SecretError::NotFound(reference.clone()).into())
+        })
+    }
+}
+
+/// Picks key from secret that is JSON object, or returns the whole secret if no key is referenced
+#[cfg(any(feature = "vault", feature = "ssm"))]
+fn select_key(reference: &SecretRef, secret: &Value) -> Result<String, Box<dyn Error + Send + Sync>> {
+    let value = match &reference.key {
+        Some(key) => secret.get(key).ok_or_else(|| SecretError::NotFound(reference.clone()))?,
+        None => secret
+    };
+    Ok(match value {
+        Value::String(value) => value.clone(),
+        value => value.to_string()
+    })
+}
+
+/// Resolves references to KV v2 secrets in HashiCorp Vault, written as `secretref://vault/{mount}/{path}#{key}`
+#[cfg(feature = "vault")]
+#[derive(Clone)]
+pub struct VaultSecretResolver {
+    client: reqwest::Client,
+    address: Url,
+    token: String,
+    namespace: Option<String>
+}
+
+#[cfg(feature = "vault")]
+impl VaultSecretResolver {
+    /// Creates resolver, that authenticates with specified token
+    pub fn new(client: reqwest::Client, address: Url, token: impl Into<String>) -> Self {
+        Self { client, address, token: token.into(), namespace: None }
+    }
+
+    /// Vault Enterprise namespace
+    pub fn with_namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.namespace = Some(namespace.into());
+        self
+    }
+}
+
+#[cfg(feature = "vault")]
+impl SecretResolver for VaultSecretResolver {
+    fn resolve<'a>(&'a self, reference: &'a SecretRef) -> ResolveFuture<'a> {
+        Box::pin(async move {
+            let (mount, path) = reference.path.split_once('/').ok_or_else(|| SecretError::NotFound(reference.clone()))?;
+            let request = crate::data_providers::vault::request(&self.client, &self.address, &self.token, self.namespace.as_deref(), Method::GET, &format!("{mount}/data/{path}"));
+            let response: Value = serde_json::from_slice(&crate::data_providers::vault::send(request).await?)?;
+            select_key(reference, &response["data"]["data"])
+        })
+    }
+}
+
+#[cfg(feature = "vault")]
+impl Debug for VaultSecretResolver {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VaultSecretResolver")
+            .field("address", &self.address)
+            .field("namespace", &self.namespace)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Resolves references to parameters in AWS Systems Manager Parameter Store, written as `secretref://ssm/{name}#{key}`.
+/// Leading `/` is added to parameter name, and `SecureString` values are decrypted.
+/// If key is referenced, parameter value is parsed as JSON object.
+#[cfg(feature = "ssm")]
+#[derive(Debug)]
+pub struct SsmSecretResolver {
+    service: crate::data_providers::aws::AwsService
+}
+
+#[cfg(feature = "ssm")]
+impl SsmSecretResolver {
+    /// Creates resolver for parameters in specified region
+    pub fn new(client: reqwest::Client, region: impl Into<String>, credentials: crate::data_providers::aws::AwsCredentials) -> Self {
+        Self { service: crate::data_providers::aws::AwsService::new(client, "ssm", region.into(), credentials, "ssm") }
+    }
+
+    /// Systems Manager endpoint. Default is regional endpoint
+    pub fn with_endpoint(mut self, endpoint: reqwest::Url) -> Self {
+        self.service.endpoint = endpoint;
+        self
+    }
+}
+
+#[cfg(feature = "ssm")]
+impl SecretResolver for SsmSecretResolver {
+    fn resolve<'a>(&'a self, reference: &'a SecretRef) -> ResolveFuture<'a> {
+        Box::pin(async move {
+            let name = format!("/{}", reference.path.trim_start_matches('/'));
+            let response = self.service.call("AmazonSSM.GetParameter", &serde_json::json!({"Name": name, "WithDecryption": true})).await?;
+            let value = response["Parameter"]["Value"].as_str().ok_or("parameter value is missing in response")?;
+            match reference.key {
+                Some(_) => select_key(reference, &serde_json::from_str(value)?),
+                None => Ok(value.to_string())
+            }
+        })
+    }
+}
+
+/// Secret resolution errors
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum SecretError {
+    /// No resolver is registered under reference's resolver name
+    UnknownResolver(SecretRef),
+    /// Secret or its key doesn't exist
+    NotFound(SecretRef),
+    /// Resolver failed
+    Resolver {
+        /// Reference that failed to resolve
+        reference: SecretRef,
+        /// Resolver error
+        source: Box<dyn Error + Send + Sync>
+    }
+}
+
+impl Display for SecretError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownResolver(reference) => write!(f, "no resolver is registered for {reference}"),
+            Self::NotFound(reference) => write!(f, "secret {reference} not found"),
+            Self::Resolver { reference, .. } => write!(f, "secret {reference} can't be resolved")
+        }
+    }
+}
+
+impl Error for SecretError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Resolver { source, .. } => Some(source.as_ref()),
+            _ => None
+        }
+    }
+}
+
+/// This data provider replaces secret references (see [`SecretRef`]) in string values of JSON data, loaded by inner provider,
+/// with secret values read by registered resolvers, before data is activated.
+/// So config origin stores only references, and application receives ready-to-use values.
+///
+/// Secrets are resolved every time inner provider returns modified data, and each distinct reference is read once per version.
+/// If any reference can't be resolved, load fails and current data stays active.
+/// [`EnvSecretResolver`] is registered as `env` by default.
+/// # Examples
+/// ```
+/// use reqwest::Url;
+/// use serde_json::Value;
+/// use remote_config::data_providers::http::HttpDataProvider;
+/// use remote_config::data_providers::http::serde_extractor::SerdeDataExtractor;
+/// use remote_config::data_providers::secrets::SecretResolvingProvider;
+///
+/// let source = HttpDataProvider::new(reqwest::Client::default(), Url::parse("https://example.com/cfg").unwrap(), SerdeDataExtractor::<Value>::new());
+/// // {"db": {"password": "secretref://env/DB_PASSWORD"}} is loaded with actual password
+/// let data_provider = SecretResolvingProvider::new(source);
+/// ```
+#[derive(Debug)]
+pub struct SecretResolvingProvider<Provider> {
+    inner: Provider,
+    resolvers: HashMap<String, Arc<dyn SecretResolver>>
+}
+
+impl <Provider: DataProvider<Value>> SecretResolvingProvider<Provider> {
+    /// Wraps provider
+    pub fn new(inner: Provider) -> Self {
+        Self {
+            inner,
+            resolvers: HashMap::from([("env".to_string(), Arc::new(EnvSecretResolver) as Arc<dyn SecretResolver>)])
+        }
+    }
+
+    /// Registers resolver for references with specified resolver name, replacing resolver registered under the same name
+    pub fn with_resolver(mut self, name: impl Into<String>, resolver: impl SecretResolver + 'static) -> Self {
+        self.resolvers.insert(name.into(), Arc::new(resolver));
+        self
+    }
+
+    /// Replaces all references in data
+    async fn resolve(&self, data: &mut Value) -> Result<(), SecretError> {
+        let mut references = Vec::new();
+        collect_references(data, &mut references);
+        let mut resolved = HashMap::new();
+        for reference in references {
+            if resolved.contains_key(&reference) {
+                continue;
+            }
+            let resolver = self.resolvers.get(&reference.resolver).ok_or_else(|| SecretError::UnknownResolver(reference.clone()))?;
+            let value = match resolver.resolve(&reference).await {
+                Ok(value) => value,
+                Err(err) => return Err(match err.downcast::<SecretError>() {
+                    Ok(err) => *err,
+                    Err(source) => SecretError::Resolver { reference, source }
+                })
+            };
+            resolved.insert(reference, value);
+        }
+        replace_references(data, &resolved);
+        Ok(())
+    }
+}
+
+fn collect_references(value: &Value, references: &mut Vec<SecretRef>) {
+    match value {
+        Value::String(string) => references.extend(SecretRef::parse(string)),
+        Value::Array(items) => items.iter().for_each(|item| collect_references(item, references)),
+        Value::Object(object) => object.values().for_each(|item| collect_references(item, references)),
+        _ => {}
+    }
+}
+
+fn replace_references(value: &mut Value, resolved: &HashMap<SecretRef, String>) {
+    match value {
+        Value::String(string) => {
+            if let Some(secret) = SecretRef::parse(string).and_then(|reference| resolved.get(&reference)) {
+                *string = secret.clone();
+            }
+        },
+        Value::Array(items) => items.iter_mut().for_each(|item| replace_references(item, resolved)),
+        Value::Object(object) => object.values_mut().for_each(|item| replace_references(item, resolved)),
+        _ => {}
+    }
+}
+
+impl <Provider: DataProvider<Value> + Sync> DataProvider<Value> for SecretResolvingProvider<Provider> {
+    /// Loads data with inner provider and resolves secrets
+    /// # Errors
+    /// If inner provider fails, or any secret can't be resolved
+    async fn load_data(&self) -> Result<DataLoadResult<Value>, Box<dyn Error + Send + Sync>> {
+        let mut result = self.inner.load_data().await?;
+        self.resolve(&mut result.data).await?;
+        Ok(result)
+    }
+
+    /// Revalidates data with inner provider, and resolves secrets if data was modified
+    async fn revalidate_data<'a>(&'a self, current: &'a DataLoadResult<Value>) -> Result<Revalidation<Value>, Box<dyn Error + Send + Sync>> {
+        match self.inner.revalidate_data(current).await? {
+            Revalidation::Modified(mut result) => {
+                self.resolve(&mut result.data).await?;
+                Ok(Revalidation::Modified(result))
+            },
+            not_modified => Ok(not_modified)
+        }
+    }
+}
+
+impl <Provider: SecurityAudit> SecurityAudit for SecretResolvingProvider<Provider> {
+    fn security_issues(&self) -> Vec<SecurityIssue> {
+        self.inner.security_issues()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::SystemTime;
+    use serde_json::json;
+    use crate::data_providers::data_provider::{DataLoadResult, DataProvider};
+    use crate::data_providers::memory::StaticDataProvider;
+    use crate::data_providers::secrets::{SecretRef, SecretResolvingProvider};
+
+    #[test]
+    fn reference_parsing() {
+        assert_eq!(SecretRef::parse("secretref://vault/secret/payments#api_key"), Some(SecretRef {
+            resolver: "vault".to_string(),
+            path: "secret/payments".to_string(),
+            key: Some("api_key".to_string())
+        }));
+        assert_eq!(SecretRef::parse("secretref://env/TOKEN").unwrap().to_string(), "secretref://env/TOKEN");
+        assert_eq!(SecretRef::parse("secretref://env"), None);
+        assert_eq!(SecretRef::parse("https://example.com"), None);
+    }
+
+    #[tokio::test]
+    async fn references_are_replaced() {
+        std::env::set_var("REMOTE_CONFIG_TEST_SECRET", "hunter2");
+        let data = json!({"db": {"password": "secretref://env/REMOTE_CONFIG_TEST_SECRET", "hosts": ["db.local"]}, "replicas": ["secretref://env/REMOTE_CONFIG_TEST_SECRET"]});
+        let data_provider = SecretResolvingProvider::new(StaticDataProvider::new(data));
+        let result = data_provider.load_data().await.unwrap();
+        assert_eq!(result.data, json!({"db": {"password": "hunter2", "hosts": ["db.local"]}, "replicas": ["hunter2"]}));
+
+        let data_provider = SecretResolvingProvider::new(StaticDataProvider::new(json!({"key": "secretref://gcp/projects/key"})));
+        let err = data_provider.load_data().await.unwrap_err();
+        assert_eq!(err.to_string(), "no resolver is registered for secretref://gcp/projects/key");
+
+        // Not modified data is not resolved again
+        let current = DataLoadResult::new(json!({}), false, SystemTime::now());
+        assert!(data_provider.revalidate_data(&current).await.is_ok());
+    }
+
+    #[cfg(feature = "vault")]
+    #[tokio::test]
+    async fn vault_kv_secret_key() {
+        use reqwest::Url;
+        use crate::data_providers::secrets::VaultSecretResolver;
+
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/v1/secret/data/payments")
+            .match_header("X-Vault-Token", "token")
+            .with_body(r#"{"data": {"data": {"api_key": "sk_live"}, "metadata": {"version": 2}}}"#)
+            .create_async()
+            .await;
+        let resolver = VaultSecretResolver::new(reqwest::Client::new(), Url::parse(&server.url()).unwrap(), "token");
+        let data_provider = SecretResolvingProvider::new(StaticDataProvider::new(json!({"api_key": "secretref://vault/secret/payments#api_key"})))
+            .with_resolver("vault", resolver);
+        assert_eq!(data_provider.load_data().await.unwrap().data, json!({"api_key": "sk_live"}));
+    }
+}
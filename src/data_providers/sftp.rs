@@ -0,0 +1,249 @@
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::io::Read;
+use std::marker::PhantomData;
+use std::net::TcpStream;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use ssh2::{CheckResult, KnownHostFileKind, Session};
+use crate::data_providers::data_provider::{DataLoadResult, DataProvider, Revalidation};
+
+/// SSH authentication method
+#[derive(Clone)]
+pub enum SftpAuth {
+    /// Password authentication
+    Password(String),
+    /// Public key authentication with private key file (e.g. `~/.ssh/id_ed25519`)
+    PrivateKey {
+        /// Path to private key file
+        path: PathBuf,
+        /// Passphrase of encrypted private key
+        passphrase: Option<String>
+    }
+}
+
+impl Debug for SftpAuth {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Password(_) => f.write_str("Password"),
+            Self::PrivateKey { path, .. } => f.debug_struct("PrivateKey").field("path", path).finish_non_exhaustive()
+        }
+    }
+}
+
+/// SFTP specific errors
+#[derive(Debug)]
+pub enum SftpError {
+    /// Host key of server is not listed in known hosts file, or does not match it
+    UnknownHostKey(String),
+    /// Server did not accept credentials
+    AuthenticationFailed
+}
+
+impl Display for SftpError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownHostKey(host) => write!(f, "host key of {host} does not match known hosts"),
+            Self::AuthenticationFailed => write!(f, "SSH authentication failed")
+        }
+    }
+}
+
+impl Error for SftpError {}
+
+/// Revision of remote file, and its content if it was downloaded
+type Fetched = (String, Option<Vec<u8>>);
+
+/// Connection settings, shared with blocking download task
+#[derive(Debug)]
+struct Settings {
+    host: String,
+    port: u16,
+    username: String,
+    auth: SftpAuth,
+    path: PathBuf,
+    known_hosts: Option<PathBuf>,
+    timeout: Duration
+}
+
+impl Settings {
+    /// Connects to server and returns revision of file, and its content if revision differs from current one
+    fn fetch(&self, current_revision: Option<&str>) -> Result<Fetched, Box<dyn Error + Send + Sync>> {
+        let stream = TcpStream::connect((self.host.as_str(), self.port))?;
+        let mut session = Session::new()?;
+        session.set_tcp_stream(stream);
+        session.set_timeout(self.timeout.as_millis().try_into().unwrap_or(u32::MAX));
+        session.handshake()?;
+
+        if let Some(known_hosts) = &self.known_hosts {
+            let mut hosts = session.known_hosts()?;
+            hosts.read_file(known_hosts, KnownHostFileKind::OpenSSH)?;
+            let (key, _) = session.host_key().ok_or_else(|| SftpError::UnknownHostKey(self.host.clone()))?;
+            if !matches!(hosts.check_port(&self.host, self.port, key), CheckResult::Match) {
+                return Err(SftpError::UnknownHostKey(self.host.clone()).into());
+            }
+        }
+        match &self.auth {
+            SftpAuth::Password(password) => session.userauth_password(&self.username, password)?,
+            SftpAuth::PrivateKey { path, passphrase } => session.userauth_pubkey_file(&self.username, None, path, passphrase.as_deref())?
+        }
+        if !session.authenticated() {
+            return Err(SftpError::AuthenticationFailed.into());
+        }
+
+        let sftp = session.sftp()?;
+        let stat = sftp.stat(&self.path)?;
+        let revision = format!("{}-{}", stat.mtime.unwrap_or_default(), stat.size.unwrap_or_default());
+        if current_revision == Some(revision.as_str()) {
+            return Ok((revision, None));
+        }
+        let mut bytes = Vec::new();
+        sftp.open(&self.path)?.read_to_end(&mut bytes)?;
+        Ok((revision, Some(bytes)))
+    }
+}
+
+/// This data provider downloads remote file over SFTP and parses it with specified function.
+/// SSH session runs in blocking task, and new session is opened for every load.
+///
+/// Modification time and size of file are reported as revision, and file is downloaded and parsed again only if they change.
+/// As modification time has one second resolution, file that is overwritten with the same size
+/// within a second after download is picked up only after next change.
+///
+/// Host key is verified only if known hosts file is set with [`SftpDataProvider::with_known_hosts`].
+/// # Examples
+/// ```
+/// use remote_config::data_providers::sftp::{SftpAuth, SftpDataProvider};
+///
+/// let auth = SftpAuth::PrivateKey { path: "/etc/app/id_ed25519".into(), passphrase: None };
+/// let data_provider = SftpDataProvider::new("sftp.partner.example", "app", auth, "/outgoing/config.json", |bytes: &[u8]| {
+///     Ok(String::from_utf8(bytes.to_vec())?)
+/// }).with_known_hosts("/etc/app/known_hosts");
+/// ```
+pub struct SftpDataProvider<Data: Send + Sync, Parser> {
+    settings: Arc<Settings>,
+    max_age: Duration,
+    parser: Parser,
+    data_type: PhantomData<Data>
+}
+
+impl <Data, Parser> SftpDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&[u8]) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Creates data provider for file at specified path on server, that listens on port 22
+    pub fn new(host: impl Into<String>, username: impl Into<String>, auth: SftpAuth, path: impl Into<PathBuf>, parser: Parser) -> Self {
+        Self {
+            settings: Arc::new(Settings {
+                host: host.into(),
+                port: 22,
+                username: username.into(),
+                auth,
+                path: path.into(),
+                known_hosts: None,
+                timeout: Duration::from_secs(30)
+            }),
+            max_age: Duration::from_secs(60),
+            parser,
+            data_type: PhantomData
+        }
+    }
+
+    fn settings(&mut self) -> &mut Settings {
+        Arc::get_mut(&mut self.settings).expect("settings are not shared before first load")
+    }
+
+    /// SSH server port. Default is 22.
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.settings().port = port;
+        self
+    }
+
+    /// Verify host key against OpenSSH known hosts file
+    pub fn with_known_hosts(mut self, path: impl Into<PathBuf>) -> Self {
+        self.settings().known_hosts = Some(path.into());
+        self
+    }
+
+    /// Timeout of blocking SSH operations. Default is 30 seconds.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.settings().timeout = timeout;
+        self
+    }
+
+    /// Time after which file is checked again. Default is 60 seconds.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    async fn load(&self, current_revision: Option<&str>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        let settings = self.settings.clone();
+        let current = current_revision.map(str::to_string);
+        let (revision, bytes) = tokio::task::spawn_blocking(move || settings.fetch(current.as_deref())).await??;
+        let valid_until = SystemTime::now() + self.max_age;
+        let Some(bytes) = bytes else {
+            return Ok(Revalidation::NotModified { must_revalidate: false, valid_until, invalidation: None, fetched_at: None });
+        };
+
+        let mut result = DataLoadResult::new((self.parser)(&bytes)?, false, valid_until);
+        result.metadata.size = Some(bytes.len() as u64);
+        result.metadata.revision = Some(revision);
+        Ok(Revalidation::Modified(result))
+    }
+}
+
+impl <Data, Parser> DataProvider<Data> for SftpDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&[u8]) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Downloads and parses file
+    /// # Errors
+    /// If connection, host key verification, authentication or download fails, or parser returns an error
+    async fn load_data(&self) -> Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>> {
+        Ok(self.load(None).await?.into_loaded())
+    }
+
+    /// Downloads and parses file only if its modification time or size changed
+    async fn revalidate_data<'a>(&'a self, current: &'a DataLoadResult<Data>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        self.load(current.metadata.revision.as_deref()).await
+    }
+}
+
+impl <Data: Send + Sync, Parser> Debug for SftpDataProvider<Data, Parser> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SftpDataProvider")
+            .field("settings", &self.settings)
+            .field("max_age", &self.max_age)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use std::time::Duration;
+    use tokio::io::AsyncWriteExt;
+    use tokio::net::TcpListener;
+    use crate::data_providers::data_provider::DataProvider;
+    use crate::data_providers::sftp::{SftpAuth, SftpDataProvider};
+
+    fn parse(bytes: &[u8]) -> Result<String, Box<dyn Error + Send + Sync>> {
+        Ok(String::from_utf8(bytes.to_vec())?)
+    }
+
+    #[tokio::test]
+    async fn handshake_failure_is_reported() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            stream.write_all(b"HTTP/1.1 400 Bad Request\r\n\r\n").await.unwrap();
+        });
+
+        let data_provider = SftpDataProvider::new("127.0.0.1", "app", SftpAuth::Password("secret".to_string()), "/config.json", parse)
+            .with_port(port)
+            .with_timeout(Duration::from_secs(5));
+        assert!(data_provider.load_data().await.is_err());
+        server.await.unwrap();
+    }
+}
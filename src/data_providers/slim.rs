@@ -0,0 +1,180 @@
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::marker::PhantomData;
+use std::time::{Duration, SystemTime};
+use cache_control::{Cachability, CacheControl};
+use http::header::{CACHE_CONTROL, ETAG, IF_NONE_MATCH};
+use http::{HeaderMap, Request, StatusCode, Uri};
+use crate::data_providers::data_provider::{DataLoadResult, DataProvider, Revalidation};
+use crate::data_providers::transport::HttpTransport;
+
+/// Origin responded with status other than success or `304 Not Modified`
+#[derive(Debug)]
+pub struct UnexpectedStatus {
+    /// Response status
+    pub status: StatusCode
+}
+
+impl Display for UnexpectedStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unexpected response status: {}", self.status)
+    }
+}
+
+impl Error for UnexpectedStatus {}
+
+/// Freshness lifetime and `must-revalidate` from Cache-Control header, if it is present and valid
+fn cache_policy(headers: &HeaderMap) -> Option<(Duration, bool)> {
+    let cache_control = CacheControl::from_value(headers.get(CACHE_CONTROL)?.to_str().ok()?)?;
+    if cache_control.cachability == Some(Cachability::NoCache) {
+        return Some((Duration::ZERO, true));
+    }
+    Some((cache_control.max_age?, cache_control.must_revalidate))
+}
+
+/// This data provider makes GET requests with any [`HttpTransport`], and parses response body with specified function.
+/// It is the only data provider of `slim` feature, intended for size-constrained binaries (e.g. edge agents),
+/// that can't afford reqwest, hyper and multi-threaded tokio runtime.
+///
+/// Freshness is taken from `max-age` and `no-cache` directives of Cache-Control header, and default max age is used
+/// when it is absent. `ETag` is used as revision, and revalidation sends conditional request with it.
+/// # Examples
+/// ```ignore
+/// use remote_config::data_providers::slim::SlimHttpDataProvider;
+/// use remote_config::data_providers::transport::UreqTransport;
+///
+/// let data_provider = SlimHttpDataProvider::new(UreqTransport::default(), "http://config.local/agent".parse().unwrap(), |body: &[u8]| {
+///     Ok(String::from_utf8(body.to_vec())?)
+/// });
+/// ```
+pub struct SlimHttpDataProvider<Data: Send + Sync, Transport, Parser> {
+    transport: Transport,
+    uri: Uri,
+    parser: Parser,
+    default_max_age: Duration,
+    data_type: PhantomData<Data>
+}
+
+impl <Data, Transport, Parser> SlimHttpDataProvider<Data, Transport, Parser>
+where Data: Send + Sync, Transport: HttpTransport, Parser: Fn(&[u8]) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Creates data provider, that loads data from specified URI
+    pub fn new(transport: Transport, uri: Uri, parser: Parser) -> Self {
+        Self {
+            transport,
+            uri,
+            parser,
+            default_max_age: Duration::from_secs(60),
+            data_type: PhantomData
+        }
+    }
+
+    /// Freshness lifetime of responses without Cache-Control header. Default is 60 seconds.
+    pub fn with_default_max_age(mut self, max_age: Duration) -> Self {
+        self.default_max_age = max_age;
+        self
+    }
+
+    /// URI that data is loaded from
+    pub fn uri(&self) -> &Uri {
+        &self.uri
+    }
+
+    async fn load(&self, current_revision: Option<&str>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        let mut request = Request::get(self.uri.clone());
+        if let Some(etag) = current_revision {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        let response = self.transport.send(request.body(Vec::new())?).await?;
+        let (max_age, must_revalidate) = cache_policy(response.headers()).unwrap_or((self.default_max_age, false));
+        let valid_until = SystemTime::now() + max_age;
+        if current_revision.is_some() && response.status() == StatusCode::NOT_MODIFIED {
+            return Ok(Revalidation::NotModified { must_revalidate, valid_until, invalidation: None, fetched_at: None });
+        }
+        if !response.status().is_success() {
+            return Err(UnexpectedStatus { status: response.status() }.into());
+        }
+
+        let mut result = DataLoadResult::new((self.parser)(response.body())?, must_revalidate, valid_until);
+        result.metadata.size = Some(response.body().len() as u64);
+        result.metadata.revision = response.headers().get(ETAG).and_then(|etag| etag.to_str().ok()).map(str::to_string);
+        Ok(Revalidation::Modified(result))
+    }
+}
+
+impl <Data, Transport, Parser> DataProvider<Data> for SlimHttpDataProvider<Data, Transport, Parser>
+where Data: Send + Sync, Transport: HttpTransport, Parser: Fn(&[u8]) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Loads data by making GET request
+    /// # Errors
+    /// If transport fails, response status is not successful, or parser returns an error
+    async fn load_data(&self) -> Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>> {
+        Ok(self.load(None).await?.into_loaded())
+    }
+
+    /// Makes conditional request with `ETag` of current data, if it had one
+    async fn revalidate_data<'a>(&'a self, current: &'a DataLoadResult<Data>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        self.load(current.metadata.revision.as_deref()).await
+    }
+}
+
+impl <Data: Send + Sync, Transport: Debug, Parser> Debug for SlimHttpDataProvider<Data, Transport, Parser> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SlimHttpDataProvider")
+            .field("transport", &self.transport)
+            .field("uri", &self.uri)
+            .field("default_max_age", &self.default_max_age)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use http::header::{HeaderMap, HeaderValue, CACHE_CONTROL};
+    use crate::data_providers::slim::cache_policy;
+
+    #[test]
+    fn cache_policy_from_headers() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(cache_policy(&headers), None);
+        headers.insert(CACHE_CONTROL, HeaderValue::from_static("max-age=30, must-revalidate"));
+        assert_eq!(cache_policy(&headers), Some((Duration::from_secs(30), true)));
+        headers.insert(CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+        assert_eq!(cache_policy(&headers), Some((Duration::ZERO, true)));
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "ureq")]
+    async fn revalidates_with_etag() {
+        use crate::data_providers::data_provider::{DataProvider, Revalidation};
+        use crate::data_providers::slim::SlimHttpDataProvider;
+        use crate::data_providers::transport::UreqTransport;
+
+        let mut server = mockito::Server::new_async().await;
+        let conditional = server.mock("GET", "/cfg")
+            .match_header("if-none-match", "\"v1\"")
+            .with_status(304)
+            .with_header("cache-control", "max-age=5")
+            .create_async().await;
+        let unconditional = server.mock("GET", "/cfg")
+            .match_header("if-none-match", mockito::Matcher::Missing)
+            .with_header("etag", "\"v1\"")
+            .with_body("hello")
+            .create_async().await;
+
+        let uri = format!("{}/cfg", server.url()).parse().unwrap();
+        let data_provider = SlimHttpDataProvider::new(UreqTransport::default(), uri, |body: &[u8]| {
+            Ok(String::from_utf8(body.to_vec())?)
+        });
+        let loaded = data_provider.load_data().await.unwrap();
+        assert_eq!(loaded.data, "hello");
+        assert_eq!(loaded.metadata.revision.as_deref(), Some("\"v1\""));
+        let Revalidation::NotModified { must_revalidate, .. } = data_provider.revalidate_data(&loaded).await.unwrap() else {
+            panic!("expected not modified");
+        };
+        assert!(!must_revalidate);
+        unconditional.assert_async().await;
+        conditional.assert_async().await;
+    }
+}
@@ -0,0 +1,166 @@
+use std::error::Error;
+use std::ffi::OsString;
+use std::fmt::{Debug, Formatter};
+use std::io;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use sqlx::sqlite::{SqliteConnectOptions, SqliteConnection, SqliteRow};
+use sqlx::Connection;
+use crate::data_providers::data_provider::{DataLoadResult, DataProvider, Revalidation};
+
+/// This data provider runs query against local SQLite database file and maps returned rows into data with specified function.
+///
+/// On revalidation, query is run again only if modification time of database file (or its write-ahead log)
+/// or `PRAGMA user_version` changed, and combination of both is reported as revision.
+/// New connection is opened for every check, so database file replaced with atomic rename (e.g. by sidecar) is picked up.
+/// # Examples
+/// ```
+/// use std::collections::HashMap;
+/// use sqlx::Row;
+/// use sqlx::sqlite::SqliteRow;
+/// use remote_config::data_providers::sqlite::SqliteDataProvider;
+///
+/// let data_provider = SqliteDataProvider::new("/etc/app/config.db", "SELECT key, value FROM config", |rows: &[SqliteRow]| {
+///     let mut data = HashMap::new();
+///     for row in rows {
+///         data.insert(row.try_get::<String, _>("key")?, row.try_get::<String, _>("value")?);
+///     }
+///     Ok(data)
+/// });
+/// ```
+pub struct SqliteDataProvider<Data: Send + Sync, Parser> {
+    path: PathBuf,
+    options: SqliteConnectOptions,
+    query: String,
+    parser: Parser,
+    check_interval: Duration,
+    data_type: PhantomData<Data>
+}
+
+impl <Data, Parser> SqliteDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&[SqliteRow]) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Creates data provider, that runs specified query against database file. Database is opened read-only.
+    pub fn new(path: impl Into<PathBuf>, query: impl Into<String>, parser: Parser) -> Self {
+        let path = path.into();
+        Self {
+            options: SqliteConnectOptions::new().filename(&path).read_only(true),
+            path,
+            query: query.into(),
+            parser,
+            check_interval: Duration::from_secs(5),
+            data_type: PhantomData
+        }
+    }
+
+    /// Time after which database file is checked for changes. Default is 5 seconds.
+    pub fn with_check_interval(mut self, interval: Duration) -> Self {
+        self.check_interval = interval;
+        self
+    }
+
+    /// Latest modification time of database file and its write-ahead log
+    async fn modified(&self) -> io::Result<SystemTime> {
+        let modified = tokio::fs::metadata(&self.path).await?.modified()?;
+        let mut wal = OsString::from(self.path.as_os_str());
+        wal.push("-wal");
+        match tokio::fs::metadata(Path::new(&wal)).await {
+            Ok(metadata) => Ok(modified.max(metadata.modified()?)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(modified),
+            Err(err) => Err(err)
+        }
+    }
+
+    async fn load(&self, current_revision: Option<&str>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        // File is checked before it is opened, so changes made after check are detected next time
+        let modified = self.modified().await?;
+        let mut connection = SqliteConnection::connect_with(&self.options).await?;
+        let user_version: i64 = sqlx::query_scalar("PRAGMA user_version").fetch_one(&mut connection).await?;
+        let modified = modified.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_nanos();
+        let revision = format!("{user_version}@{modified}");
+        let valid_until = SystemTime::now() + self.check_interval;
+        if current_revision == Some(revision.as_str()) {
+            return Ok(Revalidation::NotModified { must_revalidate: false, valid_until, invalidation: None, fetched_at: None });
+        }
+
+        let rows = sqlx::query(&self.query).fetch_all(&mut connection).await?;
+        let mut result = DataLoadResult::new((self.parser)(&rows)?, false, valid_until);
+        result.metadata.revision = Some(revision);
+        Ok(Revalidation::Modified(result))
+    }
+}
+
+impl <Data, Parser> DataProvider<Data> for SqliteDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&[SqliteRow]) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Runs query and maps returned rows
+    /// # Errors
+    /// If database file can't be opened, query fails or parser returns an error
+    async fn load_data(&self) -> Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>> {
+        Ok(self.load(None).await?.into_loaded())
+    }
+
+    /// Runs query only if database file or its user version changed
+    async fn revalidate_data<'a>(&'a self, current: &'a DataLoadResult<Data>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        self.load(current.metadata.revision.as_deref()).await
+    }
+}
+
+impl <Data: Send + Sync, Parser> Debug for SqliteDataProvider<Data, Parser> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SqliteDataProvider")
+            .field("path", &self.path)
+            .field("query", &self.query)
+            .field("check_interval", &self.check_interval)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use sqlx::{Connection, Row};
+    use sqlx::sqlite::{SqliteConnectOptions, SqliteConnection, SqliteRow};
+    use crate::data_providers::data_provider::{DataProvider, Revalidation};
+    use crate::data_providers::sqlite::SqliteDataProvider;
+
+    fn parse(rows: &[SqliteRow]) -> Result<String, Box<dyn Error + Send + Sync>> {
+        Ok(rows[0].try_get("value")?)
+    }
+
+    #[tokio::test]
+    async fn changes_and_user_version_are_detected() {
+        let path = std::env::temp_dir().join(format!("remote-config-sqlite-{}.db", std::process::id()));
+        let options = SqliteConnectOptions::new().filename(&path).create_if_missing(true);
+        let mut writer = SqliteConnection::connect_with(&options).await.unwrap();
+        sqlx::query("CREATE TABLE config (key TEXT PRIMARY KEY, value TEXT)").execute(&mut writer).await.unwrap();
+        sqlx::query("INSERT INTO config VALUES ('mode', 'a')").execute(&mut writer).await.unwrap();
+
+        let data_provider = SqliteDataProvider::new(&path, "SELECT value FROM config WHERE key = 'mode'", parse);
+        let first = data_provider.load_data().await.unwrap();
+        assert_eq!(first.data, "a");
+        assert!(matches!(data_provider.revalidate_data(&first).await.unwrap(), Revalidation::NotModified { .. }));
+
+        // Row is updated
+        sqlx::query("UPDATE config SET value = 'b'").execute(&mut writer).await.unwrap();
+        let Revalidation::Modified(second) = data_provider.revalidate_data(&first).await.unwrap() else {
+            panic!("expected modified data");
+        };
+        assert_eq!(second.data, "b");
+
+        // User version is bumped
+        sqlx::query("PRAGMA user_version = 2").execute(&mut writer).await.unwrap();
+        let Revalidation::Modified(third) = data_provider.revalidate_data(&second).await.unwrap() else {
+            panic!("expected modified data");
+        };
+        assert!(third.metadata.revision.unwrap().starts_with("2@"));
+
+        writer.close().await.unwrap();
+        for suffix in ["", "-wal", "-shm"] {
+            let mut file = path.clone().into_os_string();
+            file.push(suffix);
+            std::fs::remove_file(file).ok();
+        }
+    }
+}
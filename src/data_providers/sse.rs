@@ -0,0 +1,347 @@
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::marker::PhantomData;
+use std::sync::Mutex;
+use std::time::Duration;
+use reqwest::header::{ACCEPT, CONTENT_TYPE, HeaderValue};
+use reqwest::{StatusCode, Url};
+use tokio::task::AbortHandle;
+use crate::data_providers::data_provider::{DataLoadResult, DataProvider, Revalidation};
+use crate::data_providers::push::{PushedPayloads, Publisher};
+
+/// Header with id of last received event, sent on reconnect
+const LAST_EVENT_ID_HEADER: &str = "last-event-id";
+
+/// No config event was received before timeout
+#[derive(Debug)]
+pub struct NoEvent {
+    /// URL of event stream
+    pub url: Url
+}
+
+impl Display for NoEvent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "no config event was received from {}", self.url)
+    }
+}
+
+impl Error for NoEvent {}
+
+/// Dispatched server-sent event
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Event {
+    event_type: String,
+    data: String
+}
+
+/// Incremental parser of `text/event-stream` (see [HTML Living Standard](https://html.spec.whatwg.org/multipage/server-sent-events.html#event-stream-interpretation)).
+/// Lines may end with LF or CRLF.
+#[derive(Debug, Default)]
+struct EventStreamParser {
+    /// Incomplete line from previous chunk
+    buffer: Vec<u8>,
+    event_type: String,
+    data: Option<String>,
+    /// Id of last event, kept between events
+    last_event_id: Option<String>,
+    /// Reconnection time requested by server
+    retry: Option<Duration>
+}
+
+impl EventStreamParser {
+    /// Feeds received chunk, and returns events that were dispatched by it
+    fn feed(&mut self, chunk: &[u8]) -> Vec<Event> {
+        self.buffer.extend_from_slice(chunk);
+        let mut events = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|byte| *byte == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches('\n').trim_end_matches('\r');
+            if line.is_empty() {
+                if let Some(data) = self.data.take() {
+                    events.push(Event {
+                        event_type: std::mem::take(&mut self.event_type),
+                        data
+                    });
+                }
+                self.event_type.clear();
+                continue;
+            }
+            let (field, value) = line.split_once(':').unwrap_or((line, ""));
+            let value = value.strip_prefix(' ').unwrap_or(value);
+            match field {
+                "event" => self.event_type = value.to_string(),
+                "data" => match &mut self.data {
+                    Some(data) => {
+                        data.push('\n');
+                        data.push_str(value);
+                    },
+                    None => self.data = Some(value.to_string())
+                },
+                "id" if !value.contains('\0') => self.last_event_id = Some(value.to_string()),
+                "retry" => self.retry = value.parse().ok().map(Duration::from_millis).or(self.retry),
+                // Comments and unknown fields are ignored
+                _ => {}
+            }
+        }
+        events
+    }
+}
+
+/// Connection settings, cloned into subscription task
+#[derive(Debug, Clone)]
+struct Subscription {
+    client: reqwest::Client,
+    url: Url,
+    event_type: Option<String>,
+    reconnect_delay: Duration
+}
+
+impl Subscription {
+    /// Keeps event stream open, reconnecting after it fails or ends
+    async fn run(self, events: Publisher<Event>) {
+        let mut parser = EventStreamParser::default();
+        loop {
+            let mut request = self.client.get(self.url.clone()).header(ACCEPT, "text/event-stream");
+            if let Some(id) = parser.last_event_id.as_deref().and_then(|id| HeaderValue::from_str(id).ok()) {
+                request = request.header(LAST_EVENT_ID_HEADER, id);
+            }
+            match request.send().await {
+                Ok(mut response) if response.status() == StatusCode::OK && is_event_stream(response.headers().get(CONTENT_TYPE)) => {
+                    // Incomplete event of previous connection is discarded
+                    parser.buffer.clear();
+                    parser.data = None;
+                    loop {
+                        let chunk = match response.chunk().await {
+                            Ok(Some(chunk)) => chunk,
+                            Ok(None) => break,
+                            Err(_err) => {
+                                #[cfg(feature = "tracing")]
+                                tracing::warn!(url = %self.url, "Server-sent event stream failed: {_err}");
+                                break;
+                            }
+                        };
+                        for event in parser.feed(&chunk) {
+                            if self.event_type.as_ref().is_some_and(|event_type| *event_type != event.event_type) {
+                                continue;
+                            }
+                            if !events.publish(event) {
+                                return;
+                            }
+                        }
+                    }
+                },
+                Ok(_response) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(url = %self.url, status = %_response.status(), "Server-sent event stream was not opened");
+                },
+                Err(_err) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(url = %self.url, "Failed to connect to server-sent event stream: {_err}");
+                }
+            }
+            if events.is_closed() {
+                return;
+            }
+            tokio::time::sleep(parser.retry.unwrap_or(self.reconnect_delay)).await;
+        }
+    }
+}
+
+fn is_event_stream(content_type: Option<&HeaderValue>) -> bool {
+    content_type.and_then(|value| value.to_str().ok()).is_some_and(|value| value.trim_start().starts_with("text/event-stream"))
+}
+
+/// This data provider subscribes to server-sent event stream, where every event carries full config document
+/// that is parsed with specified function.
+///
+/// Stream is opened on first load, and reopened after it fails or ends, until data provider is dropped.
+/// On reconnect, id of last received event is sent in `Last-Event-ID` header, so server can resume the stream,
+/// and reconnection time requested by server (`retry` field) is respected.
+/// Every received event invalidates loaded data, and [`DataProvider::load_data`] returns the latest received document.
+/// Use [`RemoteConfig::follow_updates`](crate::config::RemoteConfig::follow_updates) to apply received documents immediately.
+/// # Examples
+/// ```
+/// # #[cfg(feature = "json")] {
+/// use std::collections::HashMap;
+/// use reqwest::Url;
+/// use remote_config::data_providers::sse::SseDataProvider;
+///
+/// let url = Url::parse("https://config.example.com/events").unwrap();
+/// let data_provider = SseDataProvider::new(reqwest::Client::default(), url, |data: &str| {
+///     Ok(serde_json::from_str::<HashMap<String, String>>(data)?)
+/// }).with_event_type("config");
+/// # }
+/// ```
+pub struct SseDataProvider<Data: Send + Sync, Parser> {
+    subscription: Subscription,
+    parser: Parser,
+    max_age: Duration,
+    event_timeout: Duration,
+    events: PushedPayloads<Event>,
+    /// Subscription task, started on first load
+    listener: Mutex<Option<AbortHandle>>,
+    data_type: PhantomData<Data>
+}
+
+impl <Data, Parser> SseDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&str) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Creates data provider, that subscribes to event stream at specified URL
+    pub fn new(client: reqwest::Client, url: Url, parser: Parser) -> Self {
+        Self {
+            subscription: Subscription {
+                client,
+                url,
+                event_type: None,
+                reconnect_delay: Duration::from_secs(3)
+            },
+            parser,
+            max_age: Duration::from_secs(60),
+            event_timeout: Duration::from_secs(10),
+            events: PushedPayloads::new(),
+            listener: Mutex::new(None),
+            data_type: PhantomData
+        }
+    }
+
+    /// Use only events of specified type (`event` field), e.g. when stream multiplexes several kinds of events.
+    /// By default, all events are used.
+    pub fn with_event_type(mut self, event_type: impl Into<String>) -> Self {
+        self.subscription.event_type = Some(event_type.into());
+        self
+    }
+
+    /// Time to wait before reconnect, unless server requested another one. Default is 3 seconds.
+    pub fn with_reconnect_delay(mut self, delay: Duration) -> Self {
+        self.subscription.reconnect_delay = delay;
+        self
+    }
+
+    /// Time after which latest event is checked again. Default is 60 seconds.
+    /// Received events invalidate data regardless of it.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Time that first load waits for event. Default is 10 seconds.
+    pub fn with_event_timeout(mut self, timeout: Duration) -> Self {
+        self.event_timeout = timeout;
+        self
+    }
+
+    /// Starts subscription task, if it is not started yet
+    fn start_listening(&self) {
+        let mut listener = self.listener.lock().unwrap();
+        if listener.is_some() {
+            return;
+        }
+        let handle = tokio::spawn(self.subscription.clone().run(self.events.publisher()));
+        *listener = Some(handle.abort_handle());
+    }
+
+    async fn load(&self, current_revision: Option<&str>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        self.start_listening();
+        let Some((received, token)) = self.events.latest(self.event_timeout).await else {
+            return Err(NoEvent { url: self.subscription.url.clone() }.into());
+        };
+        received.revalidate(current_revision, token, self.max_age, |event| Ok(((self.parser)(&event.data)?, event.data.len() as u64)))
+    }
+}
+
+impl <Data, Parser> DataProvider<Data> for SseDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&str) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Parses latest received document. First load subscribes to event stream and waits for event.
+    /// # Errors
+    /// If no event is received before timeout, or parser returns an error
+    async fn load_data(&self) -> Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>> {
+        Ok(self.load(None).await?.into_loaded())
+    }
+
+    /// Parses latest received document, if it was not parsed yet
+    async fn revalidate_data<'a>(&'a self, current: &'a DataLoadResult<Data>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        self.load(current.metadata.revision.as_deref()).await
+    }
+}
+
+impl <Data: Send + Sync, Parser> Drop for SseDataProvider<Data, Parser> {
+    fn drop(&mut self) {
+        if let Some(handle) = self.listener.lock().unwrap().take() {
+            handle.abort();
+        }
+    }
+}
+
+impl <Data: Send + Sync, Parser> Debug for SseDataProvider<Data, Parser> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SseDataProvider")
+            .field("url", &self.subscription.url)
+            .field("event_type", &self.subscription.event_type)
+            .field("max_age", &self.max_age)
+            .field("event_timeout", &self.event_timeout)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use std::time::Duration;
+    use mockito::Matcher;
+    use reqwest::Url;
+    use crate::data_providers::data_provider::{DataProvider, Revalidation};
+    use crate::data_providers::sse::{Event, EventStreamParser, SseDataProvider};
+
+    #[test]
+    fn events_are_parsed_across_chunks() {
+        let mut parser = EventStreamParser::default();
+        assert!(parser.feed(b": comment\r\nretry: 250\r\nevent: con").is_empty());
+        let events = parser.feed(b"fig\r\nid: 7\r\ndata: {\"a\":\r\ndata: 1}\r\n\r\ndata: second\n\n");
+        assert_eq!(events, vec![
+            Event { event_type: "config".to_string(), data: "{\"a\":\n1}".to_string() },
+            Event { event_type: String::new(), data: "second".to_string() }
+        ]);
+        assert_eq!(parser.last_event_id.as_deref(), Some("7"));
+        assert_eq!(parser.retry, Some(Duration::from_millis(250)));
+    }
+
+    fn parse(data: &str) -> Result<String, Box<dyn Error + Send + Sync>> {
+        Ok(data.to_string())
+    }
+
+    #[tokio::test]
+    async fn stream_is_resumed_after_reconnect() {
+        let mut server = mockito::Server::new_async().await;
+        let _first = server
+            .mock("GET", "/events")
+            .match_header("last-event-id", Matcher::Missing)
+            .with_header("Content-Type", "text/event-stream")
+            .with_body("retry: 300\nid: 1\nevent: config\ndata: first\n\nevent: heartbeat\ndata: ping\n\n")
+            .create_async()
+            .await;
+        let resumed = server
+            .mock("GET", "/events")
+            .match_header("last-event-id", "1")
+            .with_header("Content-Type", "text/event-stream")
+            .with_body("id: 2\nevent: config\ndata: sec\ndata: ond\n\n")
+            .expect_at_least(1)
+            .create_async()
+            .await;
+
+        let url = Url::parse(&(server.url() + "/events")).unwrap();
+        let data_provider = SseDataProvider::new(reqwest::Client::default(), url, parse).with_event_type("config");
+        let first = data_provider.load_data().await.unwrap();
+        assert_eq!(first.data, "first");
+
+        let token = first.metadata.invalidation.clone().unwrap();
+        tokio::time::timeout(Duration::from_secs(5), token.invalidated()).await.unwrap();
+        let Revalidation::Modified(second) = data_provider.revalidate_data(&first).await.unwrap() else {
+            panic!("expected modified data");
+        };
+        assert_eq!(second.data, "sec\nond");
+        assert_ne!(second.metadata.revision, first.metadata.revision);
+        resumed.assert_async().await;
+    }
+}
@@ -0,0 +1,110 @@
+use std::error::Error;
+use std::fmt::Debug;
+use std::future::Future;
+
+/// Minimal HTTP client: sends request and returns response with whole body read.
+///
+/// Implement this trait to load data with any HTTP client (e.g. hyper, isahc, or one provided by embedded platform).
+/// `HttpDataProvider` uses it to reuse extractors with such clients, and `SlimHttpDataProvider` to avoid pulling reqwest into the binary.
+/// Built-in implementations are [`reqwest::Client`] (`http` feature), `UreqTransport` (`ureq` feature)
+/// and `UnixSocketTransport` (`unix_socket` feature).
+/// # Errors
+/// Only when response was not received. Responses with error status are returned as `Ok`.
+pub trait HttpTransport: Debug + Send + Sync {
+    /// Sends request
+    fn send(&self, request: http::Request<Vec<u8>>) -> impl Future<Output = Result<http::Response<Vec<u8>>, Box<dyn Error + Send + Sync>>> + Send;
+}
+
+/// Sends request with reqwest client, and reads whole response body
+#[cfg(feature = "http")]
+impl HttpTransport for reqwest::Client {
+    async fn send(&self, request: http::Request<Vec<u8>>) -> Result<http::Response<Vec<u8>>, Box<dyn Error + Send + Sync>> {
+        let response = self.execute(request.try_into()?).await?;
+        let mut builder = http::Response::builder().status(response.status()).version(response.version());
+        if let Some(headers) = builder.headers_mut() {
+            *headers = response.headers().clone();
+        }
+        Ok(builder.body(response.bytes().await?.to_vec())?)
+    }
+}
+
+/// Transport that uses blocking [ureq](https://crates.io/crates/ureq) agent on tokio blocking thread pool
+#[cfg(feature = "ureq")]
+#[derive(Debug, Clone)]
+pub struct UreqTransport {
+    agent: ureq::Agent
+}
+
+#[cfg(feature = "ureq")]
+impl UreqTransport {
+    /// Creates transport with specified agent.
+    /// Agent must be configured with `http_status_as_error(false)`, otherwise `304 Not Modified` responses are errors.
+    pub fn new(agent: ureq::Agent) -> Self {
+        Self { agent }
+    }
+}
+
+#[cfg(feature = "ureq")]
+impl Default for UreqTransport {
+    /// Agent with default config, except that error statuses are returned as responses
+    fn default() -> Self {
+        Self::new(ureq::Agent::config_builder().http_status_as_error(false).build().into())
+    }
+}
+
+#[cfg(feature = "ureq")]
+impl HttpTransport for UreqTransport {
+    async fn send(&self, request: http::Request<Vec<u8>>) -> Result<http::Response<Vec<u8>>, Box<dyn Error + Send + Sync>> {
+        let agent = self.agent.clone();
+        tokio::task::spawn_blocking(move || {
+            // Body size is limited by ureq (10 MB)
+            let (parts, mut body) = agent.run(request)?.into_parts();
+            Ok(http::Response::from_parts(parts, body.read_to_vec()?))
+        }).await?
+    }
+}
+
+/// Transport that connects to HTTP server listening on Unix domain socket (e.g. sidecar agent), with [hyper](https://crates.io/crates/hyper).
+/// Every request is sent over new connection.
+///
+/// Request URI is sent in origin form, and its authority (`localhost` if absent) is sent as Host header.
+/// Besides `UnixSocketHttpDataProvider`, it can be used with `HttpDataProvider::new_with_transport`.
+#[cfg(all(feature = "unix_socket", unix))]
+#[derive(Debug, Clone)]
+pub struct UnixSocketTransport {
+    path: std::path::PathBuf
+}
+
+#[cfg(all(feature = "unix_socket", unix))]
+impl UnixSocketTransport {
+    /// Creates transport, that connects to socket at specified path
+    pub fn new(path: impl Into<std::path::PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Path of socket
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+}
+
+#[cfg(all(feature = "unix_socket", unix))]
+impl HttpTransport for UnixSocketTransport {
+    async fn send(&self, request: http::Request<Vec<u8>>) -> Result<http::Response<Vec<u8>>, Box<dyn Error + Send + Sync>> {
+        use http_body_util::{BodyExt, Full};
+
+        let (mut parts, body) = request.into_parts();
+        if !parts.headers.contains_key(http::header::HOST) {
+            let host = parts.uri.authority().map_or("localhost", http::uri::Authority::as_str);
+            parts.headers.insert(http::header::HOST, host.parse()?);
+        }
+        parts.uri = parts.uri.path_and_query().map_or("/", http::uri::PathAndQuery::as_str).parse()?;
+
+        let stream = tokio::net::UnixStream::connect(&self.path).await?;
+        let (mut sender, connection) = hyper::client::conn::http1::handshake(hyper_util::rt::TokioIo::new(stream)).await?;
+        // Connection is closed when sender is dropped, errors are reported by request
+        tokio::spawn(connection);
+        let (parts, body) = sender.send_request(http::Request::from_parts(parts, Full::new(std::io::Cursor::new(body)))).await?.into_parts();
+        Ok(http::Response::from_parts(parts, body.collect().await?.to_bytes().to_vec()))
+    }
+}
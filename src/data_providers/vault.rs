@@ -0,0 +1,380 @@
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::marker::PhantomData;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use reqwest::{Method, RequestBuilder, StatusCode, Url};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use tokio::task::AbortHandle;
+use crate::data_providers::data_provider::{DataLoadResult, DataProvider, Revalidation};
+
+/// Header with Vault token
+const TOKEN_HEADER: &str = "X-Vault-Token";
+/// Header with Vault Enterprise namespace
+const NAMESPACE_HEADER: &str = "X-Vault-Namespace";
+
+/// Secret that is read by [`VaultDataProvider`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VaultSecret {
+    /// Latest version of KV v2 secret
+    KvV2 {
+        /// Mount path of secrets engine, e.g. `secret`
+        mount: String,
+        /// Path of secret inside the mount
+        path: String
+    },
+    /// Dynamic secret with lease, e.g. `database/creds/app`
+    Dynamic {
+        /// Full path of secret
+        path: String
+    }
+}
+
+#[derive(Deserialize)]
+struct SecretResponse<T> {
+    #[serde(default)]
+    lease_id: String,
+    #[serde(default)]
+    lease_duration: u64,
+    #[serde(default)]
+    renewable: bool,
+    data: T
+}
+
+#[derive(Deserialize)]
+struct KvData {
+    data: serde_json::Value,
+    metadata: KvMetadata
+}
+
+#[derive(Deserialize)]
+struct KvMetadata {
+    version: u64
+}
+
+#[derive(Deserialize)]
+struct TokenLookup {
+    ttl: u64,
+    renewable: bool
+}
+
+/// This data provider reads secret from HashiCorp Vault and deserializes its data.
+///
+/// For KV v2 secrets, secret version is reported as revision, and data is not deserialized again if it did not change.
+/// For dynamic secrets, lease duration is used as `valid_until`, and data must be revalidated once lease expires.
+/// Lease id is reported as revision, and renewable leases are renewed instead of reading new secret.
+///
+/// With [`VaultDataProvider::with_token_renewal`], background task renews the token before its TTL expires.
+/// # Examples
+/// ```
+/// use std::collections::HashMap;
+/// use reqwest::Url;
+/// use remote_config::data_providers::vault::{VaultDataProvider, VaultSecret};
+///
+/// let secret = VaultSecret::KvV2 { mount: "secret".to_string(), path: "app/config".to_string() };
+/// let data_provider = VaultDataProvider::<HashMap<String, String>>::new(reqwest::Client::default(), Url::parse("https://vault.example.com:8200").unwrap(), "token", secret)
+///     .with_token_renewal();
+/// ```
+pub struct VaultDataProvider<Data: Send + Sync> {
+    client: reqwest::Client,
+    address: Url,
+    token: String,
+    namespace: Option<String>,
+    secret: VaultSecret,
+    max_age: Duration,
+    renew_token: bool,
+    /// Token renewal task, started on first load
+    renewal: Mutex<Option<AbortHandle>>,
+    data_type: PhantomData<Data>
+}
+
+impl <Data: DeserializeOwned + Send + Sync> VaultDataProvider<Data> {
+    /// Creates data provider for secret, that authenticates with specified token
+    pub fn new(client: reqwest::Client, address: Url, token: impl Into<String>, secret: VaultSecret) -> Self {
+        Self {
+            client,
+            address,
+            token: token.into(),
+            namespace: None,
+            secret,
+            max_age: Duration::from_secs(5 * 60),
+            renew_token: false,
+            renewal: Mutex::new(None),
+            data_type: PhantomData
+        }
+    }
+
+    /// Vault Enterprise namespace
+    pub fn with_namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.namespace = Some(namespace.into());
+        self
+    }
+
+    /// Time after which secret without lease (e.g. KV v2 secret) is read again. Default is 5 minutes.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Renew token in background when two thirds of its TTL have passed.
+    /// Renewal starts on first data load, and stops when data provider is dropped, or token can't be renewed anymore.
+    pub fn with_token_renewal(mut self) -> Self {
+        self.renew_token = true;
+        self
+    }
+
+    /// Starts token renewal task, if it is enabled and not started yet
+    fn start_renewal(&self) {
+        if !self.renew_token {
+            return;
+        }
+        let mut renewal = self.renewal.lock().unwrap();
+        if renewal.is_some() {
+            return;
+        }
+
+        let client = self.client.clone();
+        let address = self.address.clone();
+        let token = self.token.clone();
+        let namespace = self.namespace.clone();
+        let handle = tokio::spawn(async move {
+            let api = |method, path| request(&client, &address, &token, namespace.as_deref(), method, path);
+            loop {
+                let lookup = match send(api(Method::GET, "auth/token/lookup-self")).await
+                    .and_then(|body| Ok(serde_json::from_slice::<SecretResponse<TokenLookup>>(&body)?))
+                {
+                    Ok(lookup) => lookup.data,
+                    Err(_err) => {
+                        #[cfg(feature = "tracing")]
+                        tracing::warn!("Vault token lookup failed: {_err}");
+                        tokio::time::sleep(Duration::from_secs(10)).await;
+                        continue;
+                    }
+                };
+                // Tokens without TTL (e.g. root tokens) never expire
+                if !lookup.renewable || lookup.ttl == 0 {
+                    return;
+                }
+                tokio::time::sleep(Duration::from_secs(lookup.ttl) * 2 / 3).await;
+                if let Err(_err) = send(api(Method::POST, "auth/token/renew-self")).await {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!("Vault token renewal failed: {_err}");
+                }
+            }
+        });
+        *renewal = Some(handle.abort_handle());
+    }
+
+    fn api(&self, method: Method, path: &str) -> RequestBuilder {
+        request(&self.client, &self.address, &self.token, self.namespace.as_deref(), method, path)
+    }
+
+    async fn load(&self, current_revision: Option<&str>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        self.start_renewal();
+        match &self.secret {
+            VaultSecret::KvV2 { mount, path } => {
+                let body = send(self.api(Method::GET, &format!("{mount}/data/{path}"))).await?;
+                let response: SecretResponse<KvData> = serde_json::from_slice(&body)?;
+                let valid_until = SystemTime::now() + self.max_age;
+                let revision = response.data.metadata.version.to_string();
+                if current_revision == Some(revision.as_str()) {
+                    return Ok(Revalidation::NotModified { must_revalidate: false, valid_until, invalidation: None, fetched_at: None });
+                }
+
+                let mut result = DataLoadResult::new(serde_json::from_value(response.data.data)?, false, valid_until);
+                result.metadata.size = Some(body.len() as u64);
+                result.metadata.revision = Some(revision);
+                Ok(Revalidation::Modified(result))
+            },
+            VaultSecret::Dynamic { path } => {
+                if let Some(lease_id) = current_revision.filter(|lease_id| !lease_id.is_empty()) {
+                    let renewal = self.api(Method::PUT, "sys/leases/renew").body(serde_json::json!({"lease_id": lease_id}).to_string());
+                    match send(renewal).await.and_then(|body| Ok(serde_json::from_slice::<SecretResponse<Option<serde_json::Value>>>(&body)?)) {
+                        Ok(renewed) if renewed.lease_duration > 0 => {
+                            let valid_until = SystemTime::now() + Duration::from_secs(renewed.lease_duration);
+                            return Ok(Revalidation::NotModified { must_revalidate: true, valid_until, invalidation: None, fetched_at: None });
+                        },
+                        // Lease can't be renewed, new secret is read
+                        _ => {}
+                    }
+                }
+
+                let body = send(self.api(Method::GET, path)).await?;
+                let response: SecretResponse<Data> = serde_json::from_slice(&body)?;
+                let (valid_until, must_revalidate) = match response.lease_duration {
+                    0 => (SystemTime::now() + self.max_age, false),
+                    lease_duration => (SystemTime::now() + Duration::from_secs(lease_duration), true)
+                };
+                let mut result = DataLoadResult::new(response.data, must_revalidate, valid_until);
+                result.metadata.size = Some(body.len() as u64);
+                result.metadata.revision = response.renewable.then_some(response.lease_id);
+                Ok(Revalidation::Modified(result))
+            }
+        }
+    }
+}
+
+impl <Data: DeserializeOwned + Send + Sync> DataProvider<Data> for VaultDataProvider<Data> {
+    /// Reads and deserializes secret data
+    /// # Errors
+    /// If request fails, secret doesn't exist or data can't be deserialized
+    async fn load_data(&self) -> Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>> {
+        Ok(self.load(None).await?.into_loaded())
+    }
+
+    /// Reads secret, but deserializes it only if version changed. Renewable leases are renewed instead.
+    async fn revalidate_data<'a>(&'a self, current: &'a DataLoadResult<Data>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        self.load(current.metadata.revision.as_deref()).await
+    }
+}
+
+impl <Data: Send + Sync> Drop for VaultDataProvider<Data> {
+    fn drop(&mut self) {
+        if let Some(handle) = self.renewal.lock().unwrap().take() {
+            handle.abort();
+        }
+    }
+}
+
+impl <Data: Send + Sync> Debug for VaultDataProvider<Data> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VaultDataProvider")
+            .field("address", &self.address)
+            .field("namespace", &self.namespace)
+            .field("secret", &self.secret)
+            .field("max_age", &self.max_age)
+            .field("renew_token", &self.renew_token)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Builds request to Vault API path
+pub(crate) fn request(client: &reqwest::Client, address: &Url, token: &str, namespace: Option<&str>, method: Method, path: &str) -> RequestBuilder {
+    let mut url = address.clone();
+    url.path_segments_mut()
+        .expect("address is a base url")
+        .pop_if_empty()
+        .push("v1")
+        .extend(path.split('/').filter(|segment| !segment.is_empty()));
+    let mut request = client.request(method, url).header(TOKEN_HEADER, token);
+    if let Some(namespace) = namespace {
+        request = request.header(NAMESPACE_HEADER, namespace);
+    }
+    request
+}
+
+/// Sends request and returns response body
+pub(crate) async fn send(request: RequestBuilder) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>> {
+    let response = request.send().await?;
+    match response.status() {
+        status if status.is_success() => Ok(response.bytes().await?.to_vec()),
+        StatusCode::NOT_FOUND => Err(VaultError::NotFound.into()),
+        status => Err(VaultError::Status(status).into())
+    }
+}
+
+/// Vault specific errors
+#[derive(Debug)]
+pub enum VaultError {
+    /// Secret does not exist
+    NotFound,
+    /// Unexpected http status
+    Status(StatusCode)
+}
+
+impl Display for VaultError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotFound => write!(f, "secret not found in Vault"),
+            Self::Status(status) => write!(f, "unexpected response status code: {status}")
+        }
+    }
+}
+
+impl Error for VaultError {}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::time::{Duration, SystemTime};
+    use mockito::Matcher;
+    use reqwest::Url;
+    use serde_json::json;
+    use crate::data_providers::data_provider::{DataProvider, Revalidation};
+    use crate::data_providers::vault::{VaultDataProvider, VaultSecret};
+
+    type Data = HashMap<String, String>;
+
+    #[tokio::test]
+    async fn kv_version_is_revision() {
+        let mut server = mockito::Server::new_async().await;
+        let read = server
+            .mock("GET", "/v1/secret/data/app/config")
+            .match_header("X-Vault-Token", "t0ken")
+            .with_body(json!({"data": {"data": {"key": "value"}, "metadata": {"version": 3}}}).to_string())
+            .expect(2)
+            .create_async()
+            .await;
+
+        let secret = VaultSecret::KvV2 { mount: "secret".to_string(), path: "app/config".to_string() };
+        let data_provider = VaultDataProvider::<Data>::new(reqwest::Client::default(), Url::parse(&server.url()).unwrap(), "t0ken", secret);
+        let first = data_provider.load_data().await.unwrap();
+        assert_eq!(first.data["key"], "value");
+        assert_eq!(first.metadata.revision.as_deref(), Some("3"));
+        assert!(!first.must_revalidate);
+
+        assert!(matches!(data_provider.revalidate_data(&first).await.unwrap(), Revalidation::NotModified { .. }));
+        read.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn lease_is_renewed_with_token() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/v1/database/creds/app")
+            .with_body(json!({"lease_id": "database/creds/app/abc", "lease_duration": 60, "renewable": true, "data": {"username": "u", "password": "p"}}).to_string())
+            .create_async()
+            .await;
+        let renew_lease = server
+            .mock("PUT", "/v1/sys/leases/renew")
+            .match_body(Matcher::Json(json!({"lease_id": "database/creds/app/abc"})))
+            .with_body(json!({"lease_id": "database/creds/app/abc", "lease_duration": 120, "renewable": true, "data": null}).to_string())
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/v1/auth/token/lookup-self")
+            .with_body(json!({"data": {"ttl": 1, "renewable": true}}).to_string())
+            .create_async()
+            .await;
+        let renew_token = server
+            .mock("POST", "/v1/auth/token/renew-self")
+            .with_body(json!({"auth": {}}).to_string())
+            .expect_at_least(1)
+            .create_async()
+            .await;
+
+        let secret = VaultSecret::Dynamic { path: "database/creds/app".to_string() };
+        let data_provider = VaultDataProvider::<Data>::new(reqwest::Client::default(), Url::parse(&server.url()).unwrap(), "t0ken", secret)
+            .with_token_renewal();
+        let first = data_provider.load_data().await.unwrap();
+        assert_eq!(first.data["username"], "u");
+        assert!(first.must_revalidate);
+        assert!(first.valid_until <= SystemTime::now() + Duration::from_secs(60));
+
+        let Revalidation::NotModified { valid_until, must_revalidate, .. } = data_provider.revalidate_data(&first).await.unwrap() else {
+            panic!("expected renewed lease");
+        };
+        assert!(must_revalidate);
+        assert!(valid_until > SystemTime::now() + Duration::from_secs(60));
+        renew_lease.assert_async().await;
+
+        for _ in 0..200 {
+            if renew_token.matched_async().await {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        renew_token.assert_async().await;
+    }
+}
@@ -0,0 +1,197 @@
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::future::{poll_fn, Future};
+use std::pin::Pin;
+use std::task::Poll;
+use crate::data_providers::data_provider::{BoxedDataProvider, DataLoadResult, DataProvider};
+
+/// This data provider loads data from several independent sources concurrently,
+/// and returns it only if at least `quorum` sources returned equal data.
+/// If quorum is reached by several distinct results (e.g. 2 of 4 sources return one version, and 2 return another),
+/// none of them is trusted and load fails.
+/// Any divergence between sources is reported with tracing (if enabled), even when quorum is reached.
+///
+/// Returned result has the earliest `valid_until` of agreeing results,
+/// and `must_revalidate` is set if any of them requires it.
+/// Metadata of the first agreeing result is used, with sizes summed up.
+/// # Examples
+/// ```
+/// use std::collections::HashMap;
+/// use reqwest::Url;
+/// use remote_config::data_providers::data_provider::BoxedDataProvider;
+/// use remote_config::data_providers::http::HttpDataProvider;
+/// use remote_config::data_providers::http::serde_extractor::SerdeDataExtractor;
+/// use remote_config::data_providers::verifying::VerifyingProvider;
+///
+/// type Data = HashMap<String, String>;
+/// let source = |url: &str| BoxedDataProvider::new(HttpDataProvider::new(reqwest::Client::default(), Url::parse(url).unwrap(), SerdeDataExtractor::<Data>::new()));
+/// let data_provider = VerifyingProvider::new(vec![
+///     source("https://api.example.com/cfg"),
+///     source("https://mirror.example.com/cfg")
+/// ]);
+/// ```
+#[derive(Debug)]
+pub struct VerifyingProvider<Data: Send + Sync> {
+    sources: Vec<BoxedDataProvider<Data>>,
+    quorum: usize
+}
+
+impl <Data: Send + Sync> VerifyingProvider<Data> {
+    /// Constructs provider that requires all sources to agree
+    pub fn new(sources: Vec<BoxedDataProvider<Data>>) -> Self {
+        let quorum = sources.len();
+        Self {
+            sources,
+            quorum
+        }
+    }
+
+    /// Number of sources that must return equal data. Default is number of sources.
+    /// Quorum that is not a majority of sources can be reached by several distinct results, and then load fails.
+    pub fn with_quorum(mut self, quorum: usize) -> Self {
+        self.quorum = quorum;
+        self
+    }
+}
+
+impl <Data: Send + Sync + PartialEq> DataProvider<Data> for VerifyingProvider<Data> {
+    /// Loads data from all sources
+    /// # Errors
+    /// If less than `quorum` sources returned equal data, or several distinct results were returned by `quorum` sources
+    fn load_data(&self) -> impl Future<Output = Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>>> + Send {
+        // Futures are created before async block, so that provider itself is not required to be Sync
+        let mut pending: Vec<_> = self.sources.iter()
+            .map(|source| Some(Box::pin(source.load_data())))
+            .collect();
+        let quorum = self.quorum;
+
+        async move {
+            let mut results: Vec<_> = pending.iter().map(|_| None).collect();
+            poll_fn(|cx| {
+                for (future, result) in pending.iter_mut().zip(results.iter_mut()) {
+                    if let Some(inner) = future {
+                        if let Poll::Ready(output) = Pin::new(inner).poll(cx) {
+                            *result = Some(output);
+                            *future = None;
+                        }
+                    }
+                }
+                if pending.iter().all(Option::is_none) { Poll::Ready(()) } else { Poll::Pending }
+            }).await;
+
+            // Group equal results
+            let mut groups: Vec<Vec<DataLoadResult<Data>>> = Vec::new();
+            let mut failed = 0;
+            for result in results.into_iter().flatten() {
+                match result {
+                    Ok(data) => match groups.iter_mut().find(|group| group[0].data == data.data) {
+                        Some(group) => group.push(data),
+                        None => groups.push(vec![data])
+                    },
+                    Err(_err) => {
+                        #[cfg(feature = "tracing")]
+                        tracing::warn!("Verified source failed to load data: {_err}");
+                        failed += 1;
+                    }
+                }
+            }
+
+            if groups.len() > 1 {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(sizes = ?groups.iter().map(Vec::len).collect::<Vec<_>>(), "Verified sources returned different data");
+            }
+
+            let distinct = groups.len();
+            groups.retain(|group| group.len() >= quorum.max(1));
+            let conflicting = groups.len();
+            if conflicting != 1 {
+                return Err(VerificationError { required: quorum, failed, distinct, conflicting }.into());
+            }
+            let group = groups.pop().expect("one group reached quorum");
+
+            let valid_until = group.iter().map(|result| result.valid_until).min().expect("group is not empty");
+            let must_revalidate = group.iter().any(|result| result.must_revalidate);
+            let size = group.iter().filter_map(|result| result.metadata.size).reduce(|a, b| a + b);
+            let mut result = group.into_iter().next().expect("group is not empty");
+            result.valid_until = valid_until;
+            result.must_revalidate = must_revalidate;
+            result.metadata.size = size;
+            Ok(result)
+        }
+    }
+}
+
+/// Sources of [`VerifyingProvider`] did not reach quorum
+#[derive(Debug)]
+pub struct VerificationError {
+    /// Number of sources that must agree
+    pub required: usize,
+    /// Number of sources that failed to load data
+    pub failed: usize,
+    /// Number of distinct results among loaded data
+    pub distinct: usize,
+    /// Number of distinct results that were returned by required number of sources
+    pub conflicting: usize
+}
+
+impl Display for VerificationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.conflicting > 1 {
+            return write!(f, "{} distinct results were each returned by {} sources", self.conflicting, self.required);
+        }
+        write!(f, "{} sources must agree, but {} distinct results were loaded and {} sources failed", self.required, self.distinct, self.failed)
+    }
+}
+
+impl Error for VerificationError {}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use std::time::{Duration, SystemTime};
+    use crate::data_providers::data_provider::{BoxedDataProvider, DataLoadResult, DataProvider};
+    use crate::data_providers::verifying::{VerificationError, VerifyingProvider};
+
+    struct Constant(Option<u32>, Duration);
+
+    impl DataProvider<u32> for Constant {
+        async fn load_data(&self) -> Result<DataLoadResult<u32>, Box<dyn Error + Send + Sync>> {
+            let data = self.0.ok_or("source is down")?;
+            Ok(DataLoadResult::new(data, false, SystemTime::UNIX_EPOCH + self.1))
+        }
+    }
+
+    fn sources(values: &[Option<u32>]) -> Vec<BoxedDataProvider<u32>> {
+        values.iter().enumerate()
+            .map(|(i, value)| BoxedDataProvider::new(Constant(*value, Duration::from_secs(10 + i as u64))))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn quorum() {
+        let agreeing = VerifyingProvider::new(sources(&[Some(1), Some(1)])).load_data().await.unwrap();
+        assert_eq!(agreeing.data, 1);
+        assert_eq!(agreeing.valid_until, SystemTime::UNIX_EPOCH + Duration::from_secs(10));
+
+        let err = VerifyingProvider::new(sources(&[Some(1), Some(2)])).load_data().await.unwrap_err();
+        let err = err.downcast::<VerificationError>().unwrap();
+        assert_eq!((err.required, err.failed, err.distinct, err.conflicting), (2, 0, 2, 0));
+
+        let majority = VerifyingProvider::new(sources(&[Some(2), None, Some(1), Some(1)])).with_quorum(2).load_data().await.unwrap();
+        assert_eq!(majority.data, 1);
+        assert_eq!(majority.valid_until, SystemTime::UNIX_EPOCH + Duration::from_secs(12));
+
+        assert!(VerifyingProvider::new(sources(&[None, Some(1)])).with_quorum(2).load_data().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn tied_quorum() {
+        let err = VerifyingProvider::new(sources(&[Some(1), Some(2), Some(2), Some(1)])).with_quorum(2).load_data().await.unwrap_err();
+        let err = err.downcast::<VerificationError>().unwrap();
+        assert_eq!((err.required, err.failed, err.distinct, err.conflicting), (2, 0, 2, 2));
+
+        // Quorum is ambiguous even if one of results is returned by more sources
+        let err = VerifyingProvider::new(sources(&[Some(1), Some(2), Some(2), Some(1), Some(1)])).with_quorum(2).load_data().await.unwrap_err();
+        assert_eq!(err.downcast::<VerificationError>().unwrap().conflicting, 2);
+    }
+}
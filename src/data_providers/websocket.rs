@@ -0,0 +1,230 @@
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::marker::PhantomData;
+use std::sync::Mutex;
+use std::time::Duration;
+use futures_util::{SinkExt, StreamExt};
+use tokio::task::AbortHandle;
+use tokio_tungstenite::tungstenite::{Bytes, Message};
+use crate::data_providers::data_provider::{DataLoadResult, DataProvider, Revalidation};
+use crate::data_providers::push::PushedPayloads;
+
+/// No config frame was received before timeout
+#[derive(Debug)]
+pub struct NoSnapshot {
+    /// URL of config service
+    pub url: String
+}
+
+impl Display for NoSnapshot {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "no config snapshot was received from {}", self.url)
+    }
+}
+
+impl Error for NoSnapshot {}
+
+/// This data provider keeps WebSocket connection open to config service, and receives config snapshots pushed by it.
+/// Every text or binary frame is expected to contain full snapshot, that is parsed with specified function.
+/// Service should send current snapshot as soon as client connects (after subscribe message, if it is set).
+///
+/// Connection is opened on first load, and reopened after it fails or is closed, until data provider is dropped.
+/// Every received frame invalidates loaded data, and [`DataProvider::load_data`] returns the latest received snapshot.
+/// Use [`RemoteConfig::follow_updates`](crate::config::RemoteConfig::follow_updates) to apply pushed snapshots immediately.
+/// # Examples
+/// ```
+/// use remote_config::data_providers::websocket::WebSocketDataProvider;
+///
+/// let data_provider = WebSocketDataProvider::new("wss://config.example.com/stream", |payload: &[u8]| {
+///     Ok(String::from_utf8(payload.to_vec())?)
+/// }).with_subscribe_message(r#"{"subscribe": "checkout"}"#);
+/// ```
+pub struct WebSocketDataProvider<Data: Send + Sync, Parser> {
+    url: String,
+    subscribe_message: Option<String>,
+    parser: Parser,
+    max_age: Duration,
+    snapshot_timeout: Duration,
+    frames: PushedPayloads<Bytes>,
+    /// Connection task, started on first load
+    connection: Mutex<Option<AbortHandle>>,
+    data_type: PhantomData<Data>
+}
+
+impl <Data, Parser> WebSocketDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&[u8]) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Creates data provider, that connects to specified `ws://` or `wss://` URL
+    pub fn new(url: impl Into<String>, parser: Parser) -> Self {
+        Self {
+            url: url.into(),
+            subscribe_message: None,
+            parser,
+            max_age: Duration::from_secs(60),
+            snapshot_timeout: Duration::from_secs(10),
+            frames: PushedPayloads::new(),
+            connection: Mutex::new(None),
+            data_type: PhantomData
+        }
+    }
+
+    /// Text message that is sent after every connect, e.g. to select config
+    pub fn with_subscribe_message(mut self, message: impl Into<String>) -> Self {
+        self.subscribe_message = Some(message.into());
+        self
+    }
+
+    /// Time after which latest snapshot is checked again. Default is 60 seconds.
+    /// Pushed snapshots invalidate data regardless of it.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Time that first load waits for snapshot. Default is 10 seconds.
+    pub fn with_snapshot_timeout(mut self, timeout: Duration) -> Self {
+        self.snapshot_timeout = timeout;
+        self
+    }
+
+    /// Starts connection task, if it is not started yet
+    fn start_connection(&self) {
+        let mut connection = self.connection.lock().unwrap();
+        if connection.is_some() {
+            return;
+        }
+        let url = self.url.clone();
+        let subscribe_message = self.subscribe_message.clone();
+        let frames = self.frames.publisher();
+        let handle = tokio::spawn(async move {
+            loop {
+                match tokio_tungstenite::connect_async(url.as_str()).await {
+                    Ok((mut stream, _)) => {
+                        if let Some(message) = &subscribe_message {
+                            if let Err(_err) = stream.send(Message::text(message.as_str())).await {
+                                #[cfg(feature = "tracing")]
+                                tracing::warn!(url, "Failed to send WebSocket subscribe message: {_err}");
+                            }
+                        }
+                        while let Some(message) = stream.next().await {
+                            let payload = match message {
+                                Ok(message @ (Message::Text(_) | Message::Binary(_))) => message.into_data(),
+                                Ok(_) => continue,
+                                Err(_err) => {
+                                    #[cfg(feature = "tracing")]
+                                    tracing::warn!(url, "WebSocket connection failed: {_err}");
+                                    break;
+                                }
+                            };
+                            if !frames.publish(payload) {
+                                return;
+                            }
+                        }
+                    },
+                    Err(_err) => {
+                        #[cfg(feature = "tracing")]
+                        tracing::warn!(url, "Failed to connect to WebSocket config service: {_err}");
+                    }
+                }
+                // Latest snapshot is kept until service sends new one after reconnect
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        });
+        *connection = Some(handle.abort_handle());
+    }
+
+    async fn load(&self, current_revision: Option<&str>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        self.start_connection();
+        let Some((frame, token)) = self.frames.latest(self.snapshot_timeout).await else {
+            return Err(NoSnapshot { url: self.url.clone() }.into());
+        };
+        frame.revalidate(current_revision, token, self.max_age, |payload| Ok(((self.parser)(payload)?, payload.len() as u64)))
+    }
+}
+
+impl <Data, Parser> DataProvider<Data> for WebSocketDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&[u8]) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Parses latest pushed snapshot. First load connects to service and waits for snapshot.
+    /// # Errors
+    /// If no snapshot is received before timeout, or parser returns an error
+    async fn load_data(&self) -> Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>> {
+        Ok(self.load(None).await?.into_loaded())
+    }
+
+    /// Parses latest pushed snapshot, if it was not parsed yet
+    async fn revalidate_data<'a>(&'a self, current: &'a DataLoadResult<Data>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        self.load(current.metadata.revision.as_deref()).await
+    }
+}
+
+impl <Data: Send + Sync, Parser> Drop for WebSocketDataProvider<Data, Parser> {
+    fn drop(&mut self) {
+        if let Some(handle) = self.connection.lock().unwrap().take() {
+            handle.abort();
+        }
+    }
+}
+
+impl <Data: Send + Sync, Parser> Debug for WebSocketDataProvider<Data, Parser> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WebSocketDataProvider")
+            .field("url", &self.url)
+            .field("max_age", &self.max_age)
+            .field("snapshot_timeout", &self.snapshot_timeout)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use std::time::Duration;
+    use futures_util::{SinkExt, StreamExt};
+    use tokio::net::TcpListener;
+    use tokio::sync::mpsc;
+    use tokio_tungstenite::tungstenite::Message;
+    use crate::config::RemoteConfig;
+    use crate::data_providers::websocket::WebSocketDataProvider;
+
+    fn parse(payload: &[u8]) -> Result<String, Box<dyn Error + Send + Sync>> {
+        Ok(String::from_utf8(payload.to_vec())?)
+    }
+
+    #[tokio::test]
+    async fn pushed_snapshots_are_applied() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let (push, mut pushed) = mpsc::unbounded_channel::<&'static str>();
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut stream = tokio_tungstenite::accept_async(stream).await.unwrap();
+            assert_eq!(stream.next().await.unwrap().unwrap(), Message::text("subscribe"));
+            stream.send(Message::text("first")).await.unwrap();
+            while let Some(snapshot) = pushed.recv().await {
+                stream.send(Message::text(snapshot)).await.unwrap();
+            }
+        });
+
+        let data_provider = WebSocketDataProvider::new(url, parse).with_subscribe_message("subscribe");
+        #[cfg(feature = "tracing")]
+        let config = RemoteConfig::builder("websocket".to_string(), data_provider);
+        #[cfg(not (feature = "tracing"))]
+        let config = RemoteConfig::builder(data_provider);
+        let config: &'static _ = Box::leak(Box::new(config.build().await.unwrap()));
+        assert_eq!(*config.load().await.unwrap(), "first");
+        let first = config.status().revision;
+
+        // Snapshot is applied without load
+        tokio::spawn(config.follow_updates());
+        push.send("second").unwrap();
+        for _ in 0..500 {
+            if config.status().revision != first {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_ne!(config.status().revision, first);
+        assert_eq!(*config.load().await.unwrap(), "second");
+    }
+}
@@ -0,0 +1,408 @@
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::io;
+use std::marker::PhantomData;
+use std::pin::pin;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::net::tcp::OwnedReadHalf;
+use tokio::task::AbortHandle;
+use crate::data_providers::data_provider::{DataLoadResult, DataProvider, InvalidationToken, Revalidation};
+
+/// Default requested session timeout
+pub const DEFAULT_SESSION_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Xid of watch notifications
+const WATCH_XID: i32 = -1;
+/// Xid of ping requests and responses
+const PING_XID: i32 = -2;
+const OP_GET_DATA: i32 = 4;
+const OP_PING: i32 = 11;
+/// Error code returned when znode does not exist
+const NO_NODE: i32 = -101;
+
+/// This data provider reads single znode from ZooKeeper ensemble and parses it with specified function.
+/// Modification zxid of the znode is reported as revision, and data is not parsed again if it did not change.
+///
+/// Znode is read with a watch, and session is kept open until the next load, so loaded data is
+/// invalidated as soon as znode is modified, deleted or the session is lost.
+/// # Examples
+/// ```
+/// # #[cfg(feature = "json")] {
+/// use std::collections::HashMap;
+/// use remote_config::data_providers::zookeeper::ZooKeeperDataProvider;
+///
+/// let data_provider = ZooKeeperDataProvider::new("zk1:2181,zk2:2181/services", "/app/config", |bytes: &[u8]| {
+///     Ok(serde_json::from_slice::<HashMap<String, String>>(bytes)?)
+/// });
+/// # }
+/// ```
+pub struct ZooKeeperDataProvider<Data: Send + Sync, Parser> {
+    servers: Vec<String>,
+    path: String,
+    parser: Parser,
+    max_age: Duration,
+    session_timeout: Duration,
+    /// Task that keeps session with the last watch alive
+    session: Mutex<Option<AbortHandle>>,
+    data_type: PhantomData<Data>
+}
+
+impl <Data, Parser> ZooKeeperDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&[u8]) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Creates data provider for znode at specified path.
+    /// Connect string is a comma separated list of `host:port` pairs, optionally followed by chroot path,
+    /// as in other ZooKeeper clients.
+    pub fn new(connect_string: &str, path: impl Into<String>, parser: Parser) -> Self {
+        let (hosts, chroot) = match connect_string.find('/') {
+            Some(index) => connect_string.split_at(index),
+            None => (connect_string, "")
+        };
+        let path = path.into();
+        Self {
+            servers: hosts.split(',').map(str::trim).filter(|host| !host.is_empty()).map(str::to_string).collect(),
+            path: format!("{}{path}", chroot.trim_end_matches('/')),
+            parser,
+            max_age: Duration::from_secs(60 * 60),
+            session_timeout: DEFAULT_SESSION_TIMEOUT,
+            session: Mutex::new(None),
+            data_type: PhantomData
+        }
+    }
+
+    /// Time after which znode is read again, even if watch did not fire. Default is one hour.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Requested session timeout. Server may negotiate different value.
+    pub fn with_session_timeout(mut self, timeout: Duration) -> Self {
+        self.session_timeout = timeout;
+        self
+    }
+
+    /// Keeps session alive until watch fires or connection is lost, then invalidates token.
+    /// Replaces session of previous load.
+    fn keep_watching(&self, connection: Connection, token: InvalidationToken) {
+        let Connection { stream, ping_interval, .. } = connection;
+        let (reader, mut writer) = stream.into_split();
+        let handle = tokio::spawn(async move {
+            let mut notification = pin!(wait_for_notification(reader));
+            while tokio::time::timeout(ping_interval, &mut notification).await.is_err() {
+                let mut ping = Vec::new();
+                put_i32(&mut ping, PING_XID);
+                put_i32(&mut ping, OP_PING);
+                if write_frame(&mut writer, &ping).await.is_err() {
+                    break;
+                }
+            }
+            token.invalidate();
+        });
+        if let Some(previous) = self.session.lock().unwrap().replace(handle.abort_handle()) {
+            previous.abort();
+        }
+    }
+
+    async fn load(&self, current_revision: Option<&str>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        let mut connection = Connection::open(&self.servers, self.session_timeout).await?;
+        let (bytes, mzxid) = connection.get_data(&self.path).await?;
+
+        // Watch is set by the read, so changes made after it are not missed
+        let token = InvalidationToken::new();
+        self.keep_watching(connection, token.clone());
+
+        let valid_until = SystemTime::now() + self.max_age;
+        let revision = mzxid.to_string();
+        if current_revision == Some(revision.as_str()) {
+            return Ok(Revalidation::NotModified { must_revalidate: false, valid_until, invalidation: Some(token), fetched_at: None });
+        }
+
+        let mut result = DataLoadResult::new((self.parser)(&bytes)?, false, valid_until);
+        result.metadata.size = Some(bytes.len() as u64);
+        result.metadata.revision = Some(revision);
+        result.metadata.invalidation = Some(token);
+        Ok(Revalidation::Modified(result))
+    }
+}
+
+impl <Data, Parser> DataProvider<Data> for ZooKeeperDataProvider<Data, Parser>
+where Data: Send + Sync, Parser: Fn(&[u8]) -> Result<Data, Box<dyn Error + Send + Sync>> + Send + Sync
+{
+    /// Reads and parses znode data
+    /// # Errors
+    /// If no server is reachable, znode doesn't exist or parser returns an error
+    async fn load_data(&self) -> Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>> {
+        Ok(self.load(None).await?.into_loaded())
+    }
+
+    /// Reads znode data, but parses it only if modification zxid changed
+    async fn revalidate_data<'a>(&'a self, current: &'a DataLoadResult<Data>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        self.load(current.metadata.revision.as_deref()).await
+    }
+}
+
+impl <Data: Send + Sync, Parser> Drop for ZooKeeperDataProvider<Data, Parser> {
+    fn drop(&mut self) {
+        if let Some(handle) = self.session.lock().unwrap().take() {
+            handle.abort();
+        }
+    }
+}
+
+impl <Data: Send + Sync, Parser> Debug for ZooKeeperDataProvider<Data, Parser> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ZooKeeperDataProvider")
+            .field("servers", &self.servers)
+            .field("path", &self.path)
+            .field("max_age", &self.max_age)
+            .field("session_timeout", &self.session_timeout)
+            .finish_non_exhaustive()
+    }
+}
+
+/// ZooKeeper specific errors
+#[derive(Debug)]
+pub enum ZooKeeperError {
+    /// Znode does not exist
+    NoNode,
+    /// Server returned error code
+    Server(i32),
+    /// Server did not establish session
+    SessionRejected
+}
+
+impl Display for ZooKeeperError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoNode => write!(f, "znode does not exist"),
+            Self::Server(code) => write!(f, "ZooKeeper server returned error code {code}"),
+            Self::SessionRejected => write!(f, "ZooKeeper server rejected session")
+        }
+    }
+}
+
+impl Error for ZooKeeperError {}
+
+/// Minimal client session, that can only read data with a watch
+struct Connection {
+    stream: TcpStream,
+    ping_interval: Duration,
+    xid: i32
+}
+
+impl Connection {
+    /// Connects to the first reachable server and establishes new session
+    async fn open(servers: &[String], session_timeout: Duration) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let mut last_error = io::Error::new(io::ErrorKind::InvalidInput, "no ZooKeeper servers specified");
+        for server in servers {
+            match TcpStream::connect(server.as_str()).await {
+                Ok(stream) => return Self::handshake(stream, session_timeout).await,
+                Err(err) => last_error = err
+            }
+        }
+        Err(last_error.into())
+    }
+
+    async fn handshake(mut stream: TcpStream, session_timeout: Duration) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let mut request = Vec::new();
+        put_i32(&mut request, 0); // protocol version
+        put_i64(&mut request, 0); // last seen zxid
+        put_i32(&mut request, session_timeout.as_millis().try_into().unwrap_or(i32::MAX));
+        put_i64(&mut request, 0); // session id
+        put_bytes(&mut request, &[0; 16]); // password
+        request.push(0); // read only
+        write_frame(&mut stream, &request).await?;
+
+        let response = read_frame(&mut stream).await?;
+        let mut decoder = Decoder(&response);
+        decoder.i32()?; // protocol version
+        let timeout = decoder.i32()?;
+        if timeout <= 0 {
+            return Err(ZooKeeperError::SessionRejected.into());
+        }
+        Ok(Self {
+            stream,
+            ping_interval: Duration::from_millis(timeout as u64) / 3,
+            xid: 0
+        })
+    }
+
+    /// Reads znode data and modification zxid, and sets data watch
+    async fn get_data(&mut self, path: &str) -> Result<(Vec<u8>, i64), Box<dyn Error + Send + Sync>> {
+        self.xid += 1;
+        let mut request = Vec::new();
+        put_i32(&mut request, self.xid);
+        put_i32(&mut request, OP_GET_DATA);
+        put_bytes(&mut request, path.as_bytes());
+        request.push(1); // watch
+        write_frame(&mut self.stream, &request).await?;
+
+        loop {
+            let response = read_frame(&mut self.stream).await?;
+            let mut decoder = Decoder(&response);
+            if decoder.i32()? != self.xid {
+                continue;
+            }
+            decoder.i64()?; // zxid
+            match decoder.i32()? {
+                0 => {},
+                NO_NODE => return Err(ZooKeeperError::NoNode.into()),
+                code => return Err(ZooKeeperError::Server(code).into())
+            }
+            let data = decoder.bytes()?.unwrap_or_default().to_vec();
+            decoder.i64()?; // czxid
+            return Ok((data, decoder.i64()?));
+        }
+    }
+}
+
+/// Reads frames until watch notification is received or connection is lost
+async fn wait_for_notification(mut reader: OwnedReadHalf) {
+    while let Ok(frame) = read_frame(&mut reader).await {
+        if Decoder(&frame).i32().is_ok_and(|xid| xid == WATCH_XID) {
+            return;
+        }
+    }
+}
+
+fn put_i32(buf: &mut Vec<u8>, value: i32) {
+    buf.extend_from_slice(&value.to_be_bytes());
+}
+
+fn put_i64(buf: &mut Vec<u8>, value: i64) {
+    buf.extend_from_slice(&value.to_be_bytes());
+}
+
+fn put_bytes(buf: &mut Vec<u8>, value: &[u8]) {
+    put_i32(buf, value.len() as i32);
+    buf.extend_from_slice(value);
+}
+
+async fn write_frame(writer: &mut (impl AsyncWrite + Unpin), payload: &[u8]) -> io::Result<()> {
+    let mut frame = Vec::with_capacity(payload.len() + 4);
+    put_bytes(&mut frame, payload);
+    writer.write_all(&frame).await
+}
+
+async fn read_frame(reader: &mut (impl AsyncRead + Unpin)) -> io::Result<Vec<u8>> {
+    let len = reader.read_i32().await?;
+    let len = usize::try_from(len).map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "negative frame length"))?;
+    let mut frame = vec![0; len];
+    reader.read_exact(&mut frame).await?;
+    Ok(frame)
+}
+
+/// Big-endian decoder of ZooKeeper records
+struct Decoder<'a>(&'a [u8]);
+
+impl <'a> Decoder<'a> {
+    fn take(&mut self, len: usize) -> io::Result<&'a [u8]> {
+        if self.0.len() < len {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn i32(&mut self) -> io::Result<i32> {
+        Ok(i32::from_be_bytes(self.take(4)?.try_into().expect("4 bytes")))
+    }
+
+    fn i64(&mut self) -> io::Result<i64> {
+        Ok(i64::from_be_bytes(self.take(8)?.try_into().expect("8 bytes")))
+    }
+
+    /// Reads length prefixed buffer, `None` for null buffer
+    fn bytes(&mut self) -> io::Result<Option<&'a [u8]>> {
+        match self.i32()? {
+            -1 => Ok(None),
+            len => self.take(len.try_into().map_err(|_| io::Error::from(io::ErrorKind::InvalidData))?).map(Some)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use std::time::Duration;
+    use tokio::net::{TcpListener, TcpStream};
+    use crate::data_providers::data_provider::{DataProvider, Revalidation};
+    use crate::data_providers::zookeeper::{put_bytes, put_i32, put_i64, read_frame, write_frame, Decoder, ZooKeeperDataProvider, WATCH_XID};
+
+    fn parse(bytes: &[u8]) -> Result<String, Box<dyn Error + Send + Sync>> {
+        Ok(String::from_utf8(bytes.to_vec())?)
+    }
+
+    /// Accepts session and answers single read of `/services/app/config`
+    async fn serve_read(listener: &TcpListener, data: &[u8], mzxid: i64) -> TcpStream {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        read_frame(&mut stream).await.unwrap();
+        let mut response = Vec::new();
+        put_i32(&mut response, 0);
+        put_i32(&mut response, 3000);
+        put_i64(&mut response, 1);
+        put_bytes(&mut response, &[0; 16]);
+        write_frame(&mut stream, &response).await.unwrap();
+
+        let request = read_frame(&mut stream).await.unwrap();
+        let mut decoder = Decoder(&request);
+        let xid = decoder.i32().unwrap();
+        assert_eq!(decoder.i32().unwrap(), 4);
+        assert_eq!(decoder.bytes().unwrap(), Some(b"/services/app/config".as_slice()));
+        assert_eq!(decoder.0, [1]);
+
+        let mut response = Vec::new();
+        put_i32(&mut response, xid);
+        put_i64(&mut response, mzxid);
+        put_i32(&mut response, 0);
+        put_bytes(&mut response, data);
+        put_i64(&mut response, 1);
+        put_i64(&mut response, mzxid);
+        response.extend_from_slice(&[0; 52]); // rest of stat
+        write_frame(&mut stream, &response).await.unwrap();
+        stream
+    }
+
+    #[tokio::test]
+    async fn watch_invalidates_data() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let data_provider = ZooKeeperDataProvider::new(&format!("127.0.0.1:1,{address}/services"), "/app/config", parse);
+
+        let (first, mut stream) = tokio::join!(data_provider.load_data(), serve_read(&listener, b"v1", 7));
+        let first = first.unwrap();
+        assert_eq!(first.data, "v1");
+        assert_eq!(first.metadata.revision.as_deref(), Some("7"));
+
+        // Data watch fires
+        let token = first.metadata.invalidation.clone().unwrap();
+        assert!(!token.is_invalidated());
+        let mut notification = Vec::new();
+        put_i32(&mut notification, WATCH_XID);
+        put_i64(&mut notification, -1);
+        put_i32(&mut notification, 0);
+        put_i32(&mut notification, 3); // node data changed
+        put_i32(&mut notification, 3); // connected
+        put_bytes(&mut notification, b"/services/app/config");
+        write_frame(&mut stream, &notification).await.unwrap();
+        for _ in 0..100 {
+            if token.is_invalidated() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(token.is_invalidated());
+
+        // Same zxid is returned by read, so data is not parsed again, but new watch is reported
+        let (revalidation, _stream) = tokio::join!(data_provider.revalidate_data(&first), serve_read(&listener, b"v1", 7));
+        let Revalidation::NotModified { invalidation: Some(token), .. } = revalidation.unwrap() else {
+            panic!("expected not modified data with new invalidation token");
+        };
+        assert!(!token.is_invalidated());
+    }
+}
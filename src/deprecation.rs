@@ -0,0 +1,291 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Debug, Formatter};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+/// Deprecated config keys with optional migration hints.
+/// Reading deprecated key through [`Deprecations::check`] emits tracing warning once per key.
+///
+/// With `serde` feature, it can be deserialized from config document itself,
+/// either from list of keys or from map of keys to migration hints:
+/// ```
+/// # #[cfg(feature = "json")] {
+/// use remote_config::deprecation::Deprecations;
+///
+/// #[derive(serde::Deserialize)]
+/// struct Data {
+///     #[serde(rename = "__deprecated", default)]
+///     deprecated: Deprecations,
+///     old_key: Option<String>
+/// }
+///
+/// impl Data {
+///     fn old_key(&self) -> Option<&str> {
+///         self.deprecated.check("old_key");
+///         self.old_key.as_deref()
+///     }
+/// }
+///
+/// let data: Data = serde_json::from_str(r#"{"__deprecated": {"old_key": "use new_key"}, "old_key": "value"}"#).unwrap();
+/// assert_eq!(data.old_key(), Some("value"));
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct Deprecations {
+    keys: HashMap<String, Option<String>>,
+    warned: Mutex<HashSet<String>>
+}
+
+impl Deprecations {
+    /// Creates empty list of deprecations
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Marks key as deprecated, with optional migration hint
+    pub fn with_key(mut self, key: impl Into<String>, hint: Option<String>) -> Self {
+        self.keys.insert(key.into(), hint);
+        self
+    }
+
+    /// Checks if key is deprecated. Tracing warning is emitted on first check of each deprecated key.
+    pub fn check(&self, key: &str) -> bool {
+        let Some(_hint) = self.keys.get(key) else {
+            return false;
+        };
+        if self.warned.lock().unwrap().insert(key.to_string()) {
+            #[cfg(feature = "tracing")]
+            match _hint {
+                Some(hint) => tracing::warn!("Deprecated config key '{key}' was read: {hint}"),
+                None => tracing::warn!("Deprecated config key '{key}' was read")
+            }
+        }
+        true
+    }
+
+    /// Migration hint for deprecated key
+    pub fn hint(&self, key: &str) -> Option<&str> {
+        self.keys.get(key)?.as_deref()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl <'de> serde::Deserialize<'de> for Deprecations {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use std::fmt::Formatter;
+        use serde::de::{MapAccess, SeqAccess, Visitor};
+
+        struct DeprecationsVisitor;
+
+        impl <'de> Visitor<'de> for DeprecationsVisitor {
+            type Value = Deprecations;
+
+            fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+                write!(f, "list of deprecated keys or map of deprecated keys to migration hints")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut deprecations = Deprecations::new();
+                while let Some(key) = seq.next_element::<String>()? {
+                    deprecations.keys.insert(key, None);
+                }
+                Ok(deprecations)
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut deprecations = Deprecations::new();
+                while let Some((key, hint)) = map.next_entry::<String, Option<String>>()? {
+                    deprecations.keys.insert(key, hint);
+                }
+                Ok(deprecations)
+            }
+        }
+
+        deserializer.deserialize_any(DeprecationsVisitor)
+    }
+}
+
+/// Deprecation of config endpoint, announced by origin with `Deprecation` (RFC 9745) and `Sunset` (RFC 8594) headers.
+/// Reported in [`LoadMetadata::deprecation`](crate::data_providers::data_provider::LoadMetadata::deprecation)
+/// and [`ConfigStatus::deprecation`](crate::config::ConfigStatus::deprecation).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct EndpointDeprecation {
+    /// Endpoint is deprecated (`Deprecation` header is present)
+    pub deprecated: bool,
+    /// Time when endpoint was (or will be) deprecated, if specified
+    pub deprecated_since: Option<SystemTime>,
+    /// Time after which endpoint may stop responding
+    pub sunset: Option<SystemTime>,
+    /// URL of successor endpoint (`Link` with `successor-version` relation)
+    pub successor: Option<String>,
+    /// URL of migration notes (`Link` with `deprecation` or `sunset` relation)
+    pub documentation: Option<String>
+}
+
+impl EndpointDeprecation {
+    /// Checks if origin announced neither deprecation nor sunset
+    pub fn is_empty(&self) -> bool {
+        !self.deprecated && self.sunset.is_none()
+    }
+
+    /// Time left until sunset at specified time. Zero if sunset has passed, `None` if sunset is not announced
+    pub fn time_to_sunset(&self, now: SystemTime) -> Option<Duration> {
+        self.sunset.map(|sunset| sunset.duration_since(now).unwrap_or_default())
+    }
+}
+
+/// How close endpoint is to its sunset, in order of progression
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SunsetStage {
+    /// Endpoint is deprecated, or sunset is announced
+    Announced,
+    /// Sunset is closer than warning period of [`SunsetPolicy`]
+    Approaching,
+    /// Sunset has passed
+    Passed
+}
+
+/// Callback that is called when endpoint reaches next sunset stage
+type WarningHandler = Arc<dyn Fn(&SunsetWarning) + Send + Sync>;
+
+/// Reaction to endpoint deprecation announced by origin.
+///
+/// Warning is traced and handler is called once per [`SunsetStage`]: when deprecation is first seen,
+/// when sunset is closer than warning period, and when sunset passes.
+/// Default warning period is 30 days.
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use remote_config::deprecation::SunsetPolicy;
+///
+/// let policy = SunsetPolicy::new(Duration::from_secs(14 * 24 * 60 * 60)).with_handler(|warning| {
+///     eprintln!("config endpoint sunset stage {:?}, successor: {:?}", warning.stage, warning.deprecation.successor);
+/// });
+/// ```
+#[derive(Clone)]
+pub struct SunsetPolicy {
+    warning_period: Duration,
+    handler: Option<WarningHandler>
+}
+
+impl SunsetPolicy {
+    /// Sunset is considered approaching when it is closer than `warning_period`
+    pub fn new(warning_period: Duration) -> Self {
+        Self {
+            warning_period,
+            handler: None
+        }
+    }
+
+    /// Handler that is called every time endpoint reaches next sunset stage
+    pub fn with_handler(mut self, handler: impl Fn(&SunsetWarning) + Send + Sync + 'static) -> Self {
+        self.handler = Some(Arc::new(handler));
+        self
+    }
+}
+
+impl Default for SunsetPolicy {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(30 * 24 * 60 * 60))
+    }
+}
+
+impl Debug for SunsetPolicy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SunsetPolicy")
+            .field("warning_period", &self.warning_period)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Endpoint reached next sunset stage
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SunsetWarning {
+    /// Reached stage
+    pub stage: SunsetStage,
+    /// Deprecation announced by origin
+    pub deprecation: EndpointDeprecation,
+    /// Time when warning was raised
+    pub raised_at: SystemTime
+}
+
+/// Tracks sunset stage of config endpoint
+#[derive(Debug, Default)]
+pub(crate) struct SunsetMonitor {
+    policy: SunsetPolicy,
+    stage: Option<SunsetStage>
+}
+
+impl SunsetMonitor {
+    pub fn new(policy: SunsetPolicy) -> Self {
+        Self {
+            policy,
+            stage: None
+        }
+    }
+
+    /// Records deprecation of loaded data. Returns warning if endpoint reached next stage.
+    /// Stage is reset when deprecation is withdrawn (e.g. after switch to successor endpoint).
+    pub fn record(&mut self, time: SystemTime, deprecation: Option<&EndpointDeprecation>) -> Option<SunsetWarning> {
+        let Some(deprecation) = deprecation.filter(|deprecation| !deprecation.is_empty()) else {
+            self.stage = None;
+            return None;
+        };
+        let stage = match deprecation.time_to_sunset(time) {
+            Some(remaining) if remaining.is_zero() => SunsetStage::Passed,
+            Some(remaining) if remaining < self.policy.warning_period => SunsetStage::Approaching,
+            _ => SunsetStage::Announced
+        };
+        if self.stage.is_some_and(|current| current >= stage) {
+            return None;
+        }
+        self.stage = Some(stage);
+        let warning = SunsetWarning {
+            stage,
+            deprecation: deprecation.clone(),
+            raised_at: time
+        };
+        if let Some(handler) = &self.policy.handler {
+            handler(&warning);
+        }
+        Some(warning)
+    }
+}
+
+#[cfg(all(test, feature = "json"))]
+mod tests {
+    use std::time::{Duration, SystemTime};
+    use crate::deprecation::{Deprecations, EndpointDeprecation, SunsetMonitor, SunsetPolicy, SunsetStage};
+
+    #[test]
+    fn deserialize_and_check() {
+        let list: Deprecations = serde_json::from_str(r#"["a", "b"]"#).unwrap();
+        assert!(list.check("a"));
+        assert!(list.check("a"));
+        assert!(!list.check("c"));
+        assert_eq!(list.hint("b"), None);
+
+        let map: Deprecations = serde_json::from_str(r#"{"a": "use c", "b": null}"#).unwrap();
+        assert_eq!(map.hint("a"), Some("use c"));
+        assert!(map.check("b"));
+    }
+
+    #[test]
+    fn sunset_stage_is_reported_once() {
+        let day = Duration::from_secs(24 * 60 * 60);
+        let start = SystemTime::UNIX_EPOCH + 1000 * day;
+        let mut monitor = SunsetMonitor::new(SunsetPolicy::new(7 * day));
+        let deprecation = EndpointDeprecation { sunset: Some(start + 30 * day), ..EndpointDeprecation::default() };
+
+        assert_eq!(monitor.record(start, Some(&deprecation)).unwrap().stage, SunsetStage::Announced);
+        assert_eq!(monitor.record(start + day, Some(&deprecation)), None);
+        assert_eq!(monitor.record(start + 25 * day, Some(&deprecation)).unwrap().stage, SunsetStage::Approaching);
+        assert_eq!(monitor.record(start + 26 * day, Some(&deprecation)), None);
+        assert_eq!(monitor.record(start + 31 * day, Some(&deprecation)).unwrap().stage, SunsetStage::Passed);
+
+        // Deprecation is withdrawn, e.g. after switch to successor
+        assert_eq!(monitor.record(start + 32 * day, None), None);
+        assert_eq!(monitor.record(start + 33 * day, Some(&deprecation)).unwrap().stage, SunsetStage::Passed);
+    }
+}
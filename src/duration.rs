@@ -0,0 +1,15 @@
+use std::time::Duration;
+
+/// Parses duration with optional `ms`, `s`, `m` or `h` unit. Plain number is seconds.
+pub(crate) fn parse_duration(value: &str) -> Option<Duration> {
+    let split = value.find(|char: char| !char.is_ascii_digit()).unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: u64 = number.parse().ok()?;
+    match unit.trim() {
+        "ms" => Some(Duration::from_millis(number)),
+        "" | "s" => Some(Duration::from_secs(number)),
+        "m" => Some(Duration::from_secs(number * 60)),
+        "h" => Some(Duration::from_secs(number * 60 * 60)),
+        _ => None
+    }
+}
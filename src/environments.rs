@@ -0,0 +1,233 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use crate::data_providers::data_provider::{DataLoadResult, DataProvider, Revalidation};
+use crate::hardened::{SecurityAudit, SecurityIssue};
+use crate::merge::merge;
+
+/// Inheritance chain of environment layers, from the most general one to the most specific, e.g. `default -> prod -> eu-west`.
+///
+/// Document is an object with layer names as keys, as exported by many config services:
+/// ```json
+/// {
+///     "default": {"db": {"pool": 10, "host": "localhost"}, "debug": true},
+///     "prod": {"db": {"host": "db.internal"}, "debug": false},
+///     "eu-west": {"db": {"pool": 50}}
+/// }
+/// ```
+/// Layers are merged in chain order, so more specific layers win. Objects are merged recursively, and any other value replaces previous one.
+/// # Examples
+/// ```
+/// use serde_json::json;
+/// use remote_config::environments::EnvironmentChain;
+///
+/// let chain = EnvironmentChain::new(["default", "prod"]).with_optional_layer("eu-west");
+/// let values = chain.resolve_values(&json!({
+///     "default": {"db": {"pool": 10, "host": "localhost"}},
+///     "prod": {"db": {"host": "db.internal"}}
+/// })).unwrap();
+/// assert_eq!(values["db"], json!({"pool": 10, "host": "db.internal"}));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvironmentChain {
+    /// Layer names, and whether document must contain them
+    layers: Vec<(String, bool)>
+}
+
+impl EnvironmentChain {
+    /// Creates chain of layers that document must contain, from the most general one
+    pub fn new<Layer: Into<String>>(layers: impl IntoIterator<Item = Layer>) -> Self {
+        Self { layers: layers.into_iter().map(|layer| (layer.into(), true)).collect() }
+    }
+
+    /// Appends layer that is skipped if document doesn't contain it, e.g. region that has no specific values
+    pub fn with_optional_layer(mut self, layer: impl Into<String>) -> Self {
+        self.layers.push((layer.into(), false));
+        self
+    }
+
+    /// Layer names in chain order
+    pub fn layers(&self) -> impl Iterator<Item = &str> {
+        self.layers.iter().map(|(layer, _)| layer.as_str())
+    }
+
+    /// Merges layers of document along the chain
+    /// # Errors
+    /// If document or any of its layers is not an object, or required layer is missing
+    pub fn resolve_values(&self, document: &Value) -> Result<Map<String, Value>, EnvironmentError> {
+        let layers = document.as_object().ok_or(EnvironmentError::NotAnObject(None))?;
+        let mut values = Map::new();
+        for (layer, required) in &self.layers {
+            match layers.get(layer) {
+                Some(Value::Object(layer_values)) => merge(&mut values, layer_values),
+                Some(_) => return Err(EnvironmentError::NotAnObject(Some(layer.clone()))),
+                None if *required => return Err(EnvironmentError::MissingLayer(layer.clone())),
+                None => {}
+            }
+        }
+        Ok(values)
+    }
+
+    /// Merges layers of document along the chain, and deserializes result
+    /// # Errors
+    /// If layers can't be merged (see [`EnvironmentChain::resolve_values`]), or result can't be deserialized into `T`
+    pub fn resolve<T: DeserializeOwned>(&self, document: &Value) -> Result<T, EnvironmentError> {
+        serde_json::from_value(Value::Object(self.resolve_values(document)?)).map_err(EnvironmentError::Invalid)
+    }
+}
+
+/// Environment layers can't be resolved
+#[derive(Debug)]
+pub enum EnvironmentError {
+    /// Required layer is not present in document
+    MissingLayer(String),
+    /// Document (`None`) or specified layer is not an object
+    NotAnObject(Option<String>),
+    /// Resolved values can't be deserialized
+    Invalid(serde_json::Error)
+}
+
+impl Display for EnvironmentError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingLayer(layer) => write!(f, "environment layer '{layer}' is not present in config document"),
+            Self::NotAnObject(None) => write!(f, "config document with environment layers is not an object"),
+            Self::NotAnObject(Some(layer)) => write!(f, "environment layer '{layer}' is not an object"),
+            Self::Invalid(err) => write!(f, "resolved environment config is invalid: {err}")
+        }
+    }
+}
+
+impl Error for EnvironmentError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Invalid(err) => Some(err),
+            _ => None
+        }
+    }
+}
+
+/// This data provider resolves JSON document with environment layers, loaded by inner provider, along [`EnvironmentChain`],
+/// so that config holds flattened `Data`. Metadata and freshness of inner result are kept.
+///
+/// Last loaded document is kept, so that inner provider revalidates it (e.g. with conditional requests), and layers are resolved only when it changes.
+/// # Examples
+/// ```
+/// use std::collections::HashMap;
+/// use reqwest::Url;
+/// use serde_json::Value;
+/// use remote_config::data_providers::http::HttpDataProvider;
+/// use remote_config::data_providers::http::serde_extractor::SerdeDataExtractor;
+/// use remote_config::environments::{EnvironmentChain, EnvironmentProvider};
+///
+/// let http = HttpDataProvider::new(reqwest::Client::default(), Url::parse("https://www.example.com/cfg").unwrap(), SerdeDataExtractor::<Value>::new());
+/// let data_provider: EnvironmentProvider<HashMap<String, String>, _> = EnvironmentProvider::new(http, EnvironmentChain::new(["default", "prod"]));
+/// ```
+#[derive(Debug)]
+pub struct EnvironmentProvider<Data, Provider> {
+    inner: Provider,
+    chain: EnvironmentChain,
+    /// Last loaded document
+    document: Mutex<Option<Arc<DataLoadResult<Value>>>>,
+    data_type: PhantomData<fn() -> Data>
+}
+
+impl <Data, Provider> EnvironmentProvider<Data, Provider>
+where Data: DeserializeOwned + Send + Sync, Provider: DataProvider<Value> + Sync
+{
+    /// Wraps data provider
+    pub fn new(inner: Provider, chain: EnvironmentChain) -> Self {
+        Self { inner, chain, document: Mutex::new(None), data_type: PhantomData }
+    }
+
+    /// Resolves loaded document, and keeps it for revalidation
+    fn activate(&self, document: DataLoadResult<Value>) -> Result<DataLoadResult<Data>, EnvironmentError> {
+        let mut result = DataLoadResult::new(self.chain.resolve(&document.data)?, document.must_revalidate, document.valid_until);
+        result.metadata = document.metadata.clone();
+        *self.document.lock().unwrap() = Some(Arc::new(document));
+        Ok(result)
+    }
+}
+
+impl <Data, Provider> DataProvider<Data> for EnvironmentProvider<Data, Provider>
+where Data: DeserializeOwned + Send + Sync, Provider: DataProvider<Value> + Sync
+{
+    /// Loads document with inner provider and resolves it
+    /// # Errors
+    /// If inner provider fails, or document can't be resolved
+    async fn load_data(&self) -> Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>> {
+        let document = self.inner.load_data().await?;
+        Ok(self.activate(document)?)
+    }
+
+    /// Revalidates last loaded document with inner provider, and resolves it if it was modified
+    async fn revalidate_data<'a>(&'a self, _current: &'a DataLoadResult<Data>) -> Result<Revalidation<Data>, Box<dyn Error + Send + Sync>> {
+        let Some(document) = self.document.lock().unwrap().clone() else {
+            return Ok(Revalidation::Modified(self.load_data().await?));
+        };
+        match self.inner.revalidate_data(&document).await? {
+            Revalidation::Modified(document) => Ok(Revalidation::Modified(self.activate(document)?)),
+            Revalidation::NotModified { must_revalidate, valid_until, invalidation, fetched_at } => Ok(Revalidation::NotModified { must_revalidate, valid_until, invalidation, fetched_at })
+        }
+    }
+}
+
+impl <Data, Provider: SecurityAudit> SecurityAudit for EnvironmentProvider<Data, Provider> {
+    fn security_issues(&self) -> Vec<SecurityIssue> {
+        self.inner.security_issues()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+    use serde_json::json;
+    use crate::data_providers::data_provider::{DataProvider, Revalidation};
+    use crate::data_providers::memory::InMemoryDataProvider;
+    use crate::environments::{EnvironmentChain, EnvironmentError, EnvironmentProvider};
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Settings {
+        pool: u32,
+        host: String,
+        debug: bool
+    }
+
+    #[test]
+    fn layers_are_merged_along_chain() {
+        let document = json!({
+            "default": {"pool": 10, "host": "localhost", "debug": true},
+            "staging": {"host": "db.staging"},
+            "prod": {"host": "db.internal", "debug": false},
+            "eu-west": {"pool": 50}
+        });
+        let prod: Settings = EnvironmentChain::new(["default", "prod"]).with_optional_layer("eu-west").resolve(&document).unwrap();
+        assert_eq!(prod, Settings { pool: 50, host: "db.internal".to_string(), debug: false });
+        let staging: Settings = EnvironmentChain::new(["default", "staging"]).with_optional_layer("us-east").resolve(&document).unwrap();
+        assert_eq!(staging, Settings { pool: 10, host: "db.staging".to_string(), debug: true });
+
+        let missing = EnvironmentChain::new(["default", "qa"]).resolve_values(&document).unwrap_err();
+        assert!(matches!(missing, EnvironmentError::MissingLayer(layer) if layer == "qa"));
+        let invalid = EnvironmentChain::new(["default"]).resolve_values(&json!({"default": 1})).unwrap_err();
+        assert!(matches!(invalid, EnvironmentError::NotAnObject(Some(_))));
+    }
+
+    #[tokio::test]
+    async fn provider_resolves_modified_documents() {
+        let source = InMemoryDataProvider::new(json!({"default": {"pool": 10, "host": "localhost", "debug": true}, "prod": {"debug": false}}));
+        let data_provider = EnvironmentProvider::<Settings, _>::new(source.clone(), EnvironmentChain::new(["default", "prod"]));
+        let first = data_provider.load_data().await.unwrap();
+        assert!(!first.data.debug);
+        assert!(matches!(data_provider.revalidate_data(&first).await.unwrap(), Revalidation::NotModified { .. }));
+
+        source.set(json!({"default": {"pool": 10, "host": "localhost", "debug": true}, "prod": {"pool": 20}}));
+        let Revalidation::Modified(second) = data_provider.revalidate_data(&first).await.unwrap() else {
+            panic!("document was modified");
+        };
+        assert_eq!(second.data, Settings { pool: 20, host: "localhost".to_string(), debug: true });
+        assert_eq!(second.metadata.revision.as_deref(), Some("1"));
+    }
+}
@@ -0,0 +1,271 @@
+use std::cell::RefCell;
+use std::error::Error;
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::ptr;
+use std::sync::Arc;
+use std::time::Duration;
+use reqwest::Url;
+use serde_json::Value;
+use tokio::runtime::Runtime;
+use tokio::task::AbortHandle;
+use crate::config::{NonStaticRemoteConfig, RemoteConfig};
+use crate::data_providers::http::serde_extractor::{format_content_type, SerdeDataExtractor};
+use crate::data_providers::http::HttpDataProvider;
+
+/// Change callback. Receives JSON snapshot (valid only during the call) and user data pointer passed at registration.
+/// It is called from runtime thread of config.
+pub type ChangeCallback = extern "C" fn(snapshot: *const c_char, user_data: *mut c_void);
+
+type Config = RemoteConfig<Value, HttpDataProvider<Value, SerdeDataExtractor<Value>>>;
+
+/// Remote config instance owned by foreign code, that loads data with [`HttpDataProvider`].
+/// Config data is exchanged as JSON strings. Every config owns tokio runtime, that runs revalidation and change callbacks.
+///
+/// Build the crate as `cdylib` or `staticlib` (e.g. `cargo rustc --release --features ffi --crate-type cdylib`),
+/// and use declarations from `include/remote_config.h`.
+pub struct FfiConfig {
+    runtime: Runtime,
+    config: Arc<Config>,
+    callbacks: Vec<AbortHandle>
+}
+
+/// User data pointer, that is passed back to callback on runtime thread
+struct UserData(*mut c_void);
+
+// SAFETY: caller of `remote_config_on_change` guarantees that user data may be used from another thread
+unsafe impl Send for UserData {}
+
+thread_local! {
+    /// Last error that occurred in calling thread
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(err: impl ToString) {
+    let message = CString::new(err.to_string().replace('\0', " ")).expect("nul bytes are replaced");
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+/// Reads UTF-8 C string argument
+unsafe fn argument<'a>(value: *const c_char, name: &str) -> Result<&'a str, String> {
+    if value.is_null() {
+        return Err(format!("{name} is null"));
+    }
+    CStr::from_ptr(value).to_str().map_err(|_| format!("{name} is not valid UTF-8"))
+}
+
+fn snapshot(data: &Value) -> CString {
+    // JSON escapes control characters, so serialized value never contains nul bytes
+    CString::new(data.to_string()).expect("JSON has no nul bytes")
+}
+
+fn create(url: &str, format: &str) -> Result<FfiConfig, Box<dyn Error + Send + Sync>> {
+    let content_type = format_content_type(format)?;
+    let url = Url::parse(url)?;
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(1)
+        .thread_name("remote-config-ffi")
+        .enable_all()
+        .build()?;
+    let extractor = content_type.into_iter().fold(SerdeDataExtractor::new(), SerdeDataExtractor::with_content_type);
+    let data_provider = HttpDataProvider::new(reqwest::Client::default(), url.clone(), extractor);
+    #[cfg(feature = "tracing")]
+    let builder = RemoteConfig::builder(url.to_string(), data_provider);
+    #[cfg(not (feature = "tracing"))]
+    let builder = RemoteConfig::builder(data_provider);
+    let config = runtime.block_on(builder.build())?;
+    Ok(FfiConfig { runtime, config: Arc::new(config), callbacks: Vec::new() })
+}
+
+/// Message of last error that occurred in calling thread, or null if there was none.
+/// Pointer is valid until next call of this library in the same thread.
+#[no_mangle]
+pub extern "C" fn remote_config_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(ptr::null(), |message| message.as_ptr()))
+}
+
+/// Creates config and loads initial data. Format is `json`, `yaml`, `toml` or `xml` (requires corresponding crate feature),
+/// or `auto` to select it by Content-Type of response. Returns null on error (see [`remote_config_last_error`]).
+/// # Safety
+/// `url` and `format` must be valid nul-terminated strings
+#[no_mangle]
+pub unsafe extern "C" fn remote_config_create(url: *const c_char, format: *const c_char) -> *mut FfiConfig {
+    let result = argument(url, "url")
+        .and_then(|url| Ok((url, argument(format, "format")?)))
+        .map_err(Into::into)
+        .and_then(|(url, format)| create(url, format));
+    match result {
+        Ok(config) => Box::into_raw(Box::new(config)),
+        Err(err) => {
+            set_last_error(err);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Current config data as JSON string, revalidated if it is stale. Returns null on error (see [`remote_config_last_error`]).
+/// String must be freed with [`remote_config_free_string`].
+/// # Safety
+/// `config` must be a pointer returned by [`remote_config_create`], that was not destroyed.
+/// It must not be called from change callback, which receives snapshot anyway.
+#[no_mangle]
+pub unsafe extern "C" fn remote_config_snapshot(config: *const FfiConfig) -> *mut c_char {
+    let Some(config) = config.as_ref() else {
+        set_last_error("config is null");
+        return ptr::null_mut();
+    };
+    match config.runtime.block_on(config.config.load()) {
+        Ok(data) => snapshot(&data).into_raw(),
+        Err(err) => {
+            set_last_error(err);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Registers callback, that is called with new snapshot every time config data changes.
+/// Data is revalidated in background as soon as it becomes stale. Returns false on error.
+/// # Safety
+/// `config` must be a pointer returned by [`remote_config_create`], that was not destroyed.
+/// `user_data` must stay valid, and be usable from another thread, until config is destroyed.
+#[no_mangle]
+pub unsafe extern "C" fn remote_config_on_change(config: *mut FfiConfig, callback: ChangeCallback, user_data: *mut c_void) -> bool {
+    let Some(config) = config.as_mut() else {
+        set_last_error("config is null");
+        return false;
+    };
+    let shared = config.config.clone();
+    let user_data = UserData(user_data);
+    let task = config.runtime.spawn(async move {
+        let user_data = user_data;
+        let mut last = shared.load().await.ok().map(|data| data.clone());
+        loop {
+            let Ok(data) = shared.wait_for(|data| last.as_ref() != Some(data), Duration::from_secs(3600)).await else {
+                continue;
+            };
+            let snapshot = snapshot(&data);
+            callback(snapshot.as_ptr(), user_data.0);
+            last = Some(data.clone());
+        }
+    });
+    config.callbacks.push(task.abort_handle());
+    true
+}
+
+/// Frees string returned by this library
+/// # Safety
+/// `string` must be null, or a pointer returned by this library, that was not freed
+#[no_mangle]
+pub unsafe extern "C" fn remote_config_free_string(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+/// Stops change callbacks and background revalidation, and frees config.
+/// Waits for callbacks that are running, so user data can be freed once it returns.
+/// # Safety
+/// `config` must be null, or a pointer returned by [`remote_config_create`], that was not destroyed.
+/// It must not be called from change callback.
+#[no_mangle]
+pub unsafe extern "C" fn remote_config_destroy(config: *mut FfiConfig) {
+    if config.is_null() {
+        return;
+    }
+    let config = Box::from_raw(config);
+    for callback in &config.callbacks {
+        callback.abort();
+    }
+    // Dropping runtime blocks until worker threads finish polling tasks, including callbacks in progress
+    drop(config);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::{c_char, c_void, CStr, CString};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::mpsc::{channel, Sender};
+    use std::time::Duration;
+    use crate::ffi::{remote_config_create, remote_config_destroy, remote_config_free_string, remote_config_last_error, remote_config_on_change, remote_config_snapshot};
+
+    extern "C" fn on_change(snapshot: *const c_char, user_data: *mut c_void) {
+        let sender = unsafe { &*(user_data as *const Sender<String>) };
+        let snapshot = unsafe { CStr::from_ptr(snapshot) };
+        sender.send(snapshot.to_str().unwrap().to_string()).unwrap();
+    }
+
+    struct SlowCallback {
+        started: Sender<()>,
+        finished: AtomicBool
+    }
+
+    extern "C" fn on_change_slowly(_snapshot: *const c_char, user_data: *mut c_void) {
+        let user_data = unsafe { &*(user_data as *const SlowCallback) };
+        user_data.started.send(()).unwrap();
+        std::thread::sleep(Duration::from_millis(200));
+        user_data.finished.store(true, Ordering::SeqCst);
+    }
+
+    #[test]
+    fn config_is_shared_over_c_abi() {
+        let mut server = mockito::Server::new();
+        let first = server.mock("GET", "/config")
+            .with_header("Cache-Control", "max-age=0")
+            .with_header("Content-Type", "text/plain")
+            .with_body(r#"{"limit": 1}"#)
+            .create();
+
+        let url = CString::new(format!("{}/config", server.url())).unwrap();
+        unsafe {
+            let config = remote_config_create(url.as_ptr(), c"json".as_ptr());
+            assert!(!config.is_null());
+            let snapshot = remote_config_snapshot(config);
+            assert_eq!(CStr::from_ptr(snapshot).to_str().unwrap(), r#"{"limit":1}"#);
+            remote_config_free_string(snapshot);
+
+            let (sender, receiver) = channel::<String>();
+            let sender = Box::into_raw(Box::new(sender));
+            assert!(remote_config_on_change(config, on_change, sender.cast()));
+            first.remove();
+            server.mock("GET", "/config")
+                .with_header("Cache-Control", "max-age=60")
+                .with_header("Content-Type", "application/json")
+                .with_body(r#"{"limit": 2}"#)
+                .create();
+            assert_eq!(receiver.recv_timeout(Duration::from_secs(30)).unwrap(), r#"{"limit":2}"#);
+
+            remote_config_destroy(config);
+            drop(Box::from_raw(sender));
+
+            assert!(remote_config_create(url.as_ptr(), c"ini".as_ptr()).is_null());
+            assert_eq!(CStr::from_ptr(remote_config_last_error()).to_str().unwrap(), "unsupported format: ini");
+        }
+    }
+
+    #[test]
+    fn destroy_waits_for_running_callback() {
+        let mut server = mockito::Server::new();
+        let first = server.mock("GET", "/config")
+            .with_header("Cache-Control", "max-age=0")
+            .with_body(r#"{"limit": 1}"#)
+            .create();
+
+        let url = CString::new(format!("{}/config", server.url())).unwrap();
+        unsafe {
+            let config = remote_config_create(url.as_ptr(), c"json".as_ptr());
+            assert!(!config.is_null());
+            let (started, receiver) = channel();
+            let user_data = Box::into_raw(Box::new(SlowCallback { started, finished: AtomicBool::new(false) }));
+            assert!(remote_config_on_change(config, on_change_slowly, user_data.cast()));
+            first.remove();
+            server.mock("GET", "/config")
+                .with_header("Cache-Control", "max-age=60")
+                .with_body(r#"{"limit": 2}"#)
+                .create();
+            receiver.recv_timeout(Duration::from_secs(30)).unwrap();
+
+            remote_config_destroy(config);
+            assert!((*user_data).finished.load(Ordering::SeqCst));
+            drop(Box::from_raw(user_data));
+        }
+    }
+}
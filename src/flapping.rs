@@ -0,0 +1,129 @@
+use std::collections::VecDeque;
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+/// Number of recent revisions included in [`FlappingAlarm`]
+const RECENT_REVISIONS: usize = 5;
+
+/// Callback that is called when config starts flapping
+type AlarmHandler = Arc<dyn Fn(&FlappingAlarm) + Send + Sync>;
+
+/// Threshold of config change rate. Config that changes more often usually indicates an origin bug,
+/// or two publishers overwriting each other.
+///
+/// When data is replaced more than `max_changes` times within `window`, alarm is raised:
+/// warning is traced, handler is called, and [`ConfigStatus::flapping`](crate::config::ConfigStatus::flapping) is set
+/// until change rate drops below threshold.
+/// # Examples
+/// ```
+/// use remote_config::flapping::FlappingPolicy;
+///
+/// let policy = FlappingPolicy::per_minute(10).with_handler(|alarm| {
+///     eprintln!("config changed {} times, last revisions: {:?}", alarm.changes, alarm.recent_revisions);
+/// });
+/// ```
+#[derive(Clone)]
+pub struct FlappingPolicy {
+    max_changes: usize,
+    window: Duration,
+    handler: Option<AlarmHandler>
+}
+
+impl FlappingPolicy {
+    /// Alarm is raised when data is replaced more than `max_changes` times within `window`
+    pub fn new(max_changes: usize, window: Duration) -> Self {
+        Self {
+            max_changes,
+            window,
+            handler: None
+        }
+    }
+
+    /// Alarm is raised when data is replaced more than `max_changes` times within a minute
+    pub fn per_minute(max_changes: usize) -> Self {
+        Self::new(max_changes, Duration::from_secs(60))
+    }
+
+    /// Handler that is called every time alarm is raised
+    pub fn with_handler(mut self, handler: impl Fn(&FlappingAlarm) + Send + Sync + 'static) -> Self {
+        self.handler = Some(Arc::new(handler));
+        self
+    }
+}
+
+impl Debug for FlappingPolicy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FlappingPolicy")
+            .field("max_changes", &self.max_changes)
+            .field("window", &self.window)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Config changes more often than allowed by [`FlappingPolicy`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlappingAlarm {
+    /// Number of changes within window
+    pub changes: usize,
+    /// Window of policy
+    pub window: Duration,
+    /// Revisions of last few versions, oldest first (`None` if data provider does not report revisions)
+    pub recent_revisions: Vec<Option<String>>,
+    /// Time when alarm was raised
+    pub raised_at: SystemTime
+}
+
+/// Tracks data changes of config
+#[derive(Debug)]
+pub(crate) struct FlappingDetector {
+    policy: FlappingPolicy,
+    changes: VecDeque<(SystemTime, Option<String>)>,
+    alarm: Option<FlappingAlarm>
+}
+
+impl FlappingDetector {
+    pub fn new(policy: FlappingPolicy) -> Self {
+        Self {
+            policy,
+            changes: VecDeque::new(),
+            alarm: None
+        }
+    }
+
+    /// Forgets changes outside of window, and clears alarm if rate dropped below threshold
+    fn roll(&mut self, time: SystemTime) {
+        while self.changes.front().is_some_and(|(changed, _)| *changed + self.policy.window <= time) {
+            self.changes.pop_front();
+        }
+        if self.changes.len() <= self.policy.max_changes {
+            self.alarm = None;
+        }
+    }
+
+    /// Records data change. Returns alarm if config started flapping with this change.
+    pub fn record(&mut self, time: SystemTime, revision: Option<String>) -> Option<FlappingAlarm> {
+        self.roll(time);
+        self.changes.push_back((time, revision));
+        if self.changes.len() <= self.policy.max_changes || self.alarm.is_some() {
+            return None;
+        }
+        let alarm = FlappingAlarm {
+            changes: self.changes.len(),
+            window: self.policy.window,
+            recent_revisions: self.changes.iter().rev().take(RECENT_REVISIONS).rev().map(|(_, revision)| revision.clone()).collect(),
+            raised_at: time
+        };
+        if let Some(handler) = &self.policy.handler {
+            handler(&alarm);
+        }
+        self.alarm = Some(alarm.clone());
+        Some(alarm)
+    }
+
+    /// Active alarm
+    pub fn alarm(&mut self, time: SystemTime) -> Option<FlappingAlarm> {
+        self.roll(time);
+        self.alarm.clone()
+    }
+}
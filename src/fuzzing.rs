@@ -0,0 +1,147 @@
+use std::collections::BTreeMap;
+use reqwest::header::HeaderValue;
+use crate::data_providers::http::{HttpDataExtractor, parse_cache_control};
+use crate::data_providers::http::serde_extractor::SerdeDataExtractor;
+
+/// HTTP response, as it is received from origin
+#[derive(Debug, Clone, Default, arbitrary::Arbitrary)]
+pub struct ResponseInput {
+    /// Status code. Invalid codes are replaced by 200
+    pub status: u16,
+    /// Header names and raw values. Invalid headers are skipped
+    pub headers: Vec<(String, Vec<u8>)>,
+    /// Response body
+    pub body: Vec<u8>
+}
+
+impl ResponseInput {
+    /// Converts input into [`reqwest::Response`], dropping headers that can't be represented
+    pub fn to_response(&self) -> reqwest::Response {
+        let mut builder = http::Response::builder()
+            .status(http::StatusCode::from_u16(self.status).unwrap_or(http::StatusCode::OK));
+
+        for (name, value) in &self.headers {
+            if let (Ok(name), Ok(value)) = (http::HeaderName::from_bytes(name.as_bytes()), HeaderValue::from_bytes(value)) {
+                builder = builder.header(name, value);
+            }
+        }
+
+        // All parts are validated above, so builder can't fail
+        reqwest::Response::from(builder.body(self.body.clone()).unwrap())
+    }
+}
+
+/// Fuzz target for [`parse_cache_control`]. Must not panic on any input.
+pub fn fuzz_parse_cache_control(data: &[u8]) {
+    if let Ok(value) = HeaderValue::from_bytes(data) {
+        let _ = parse_cache_control(&value);
+    }
+}
+
+/// Fuzz target for [`SerdeDataExtractor`]. Must not panic on any input.
+/// Response is deserialized into string map, so that all supported formats can succeed.
+pub fn fuzz_serde_extractor(input: &ResponseInput) {
+    let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    let extractor = SerdeDataExtractor::<BTreeMap<String, String>>::new();
+    let _ = runtime.block_on(extractor.extract(input.to_response()));
+}
+
+/// Proptest strategies for malformed origin responses
+pub mod strategies {
+    use proptest::prelude::*;
+    use crate::fuzzing::ResponseInput;
+
+    /// Valid and invalid Cache-Control header values, including out of range numbers
+    pub fn cache_control() -> impl Strategy<Value = String> {
+        let directive = prop_oneof![
+            Just("public".to_string()),
+            Just("private".to_string()),
+            Just("no-cache".to_string()),
+            Just("no-store".to_string()),
+            Just("must-revalidate".to_string()),
+            any::<u64>().prop_map(|n| format!("max-age={n}")),
+            any::<u64>().prop_map(|n| format!("s-maxage={n}")),
+            "[a-z-]{0,12}(=[ -~]{0,12})?"
+        ];
+        prop::collection::vec(directive, 0..6).prop_map(|d| d.join(", "))
+    }
+
+    /// Supported and unsupported MIME types with arbitrary parameters
+    pub fn content_type() -> impl Strategy<Value = String> {
+        let mime = prop_oneof![
+            Just("application/json".to_string()),
+            Just("application/toml".to_string()),
+            Just("application/yaml".to_string()),
+            Just("application/xml".to_string()),
+            "[ -~]{0,24}"
+        ];
+        (mime, prop::option::of("[ -~]{0,24}")).prop_map(|(mime, params)| match params {
+            Some(params) => format!("{mime}; {params}"),
+            None => mime
+        })
+    }
+
+    /// Documents in supported formats, truncated at arbitrary position, or random bytes
+    pub fn body() -> impl Strategy<Value = Vec<u8>> {
+        let document = prop_oneof![
+            Just(r#"{"key": "value", "other": "value"}"#),
+            Just("key = \"value\"\nother = \"value\"\n"),
+            Just("key: value\nother: value\n"),
+            Just("<root><key>value</key><other>value</other></root>")
+        ];
+        prop_oneof![
+            (document, any::<prop::sample::Index>()).prop_map(|(doc, idx)| doc.as_bytes()[..idx.index(doc.len() + 1)].to_vec()),
+            prop::collection::vec(any::<u8>(), 0..256)
+        ]
+    }
+
+    /// Complete responses combining strategies above
+    pub fn response() -> impl Strategy<Value = ResponseInput> {
+        (
+            prop_oneof![Just(200u16), Just(304u16), Just(404u16), any::<u16>()],
+            prop::option::of(cache_control()),
+            prop::option::of(content_type()),
+            body()
+        ).prop_map(|(status, cache_control, content_type, body)| {
+            let mut headers = Vec::new();
+            if let Some(cache_control) = cache_control {
+                headers.push(("Cache-Control".to_string(), cache_control.into_bytes()));
+            }
+            if let Some(content_type) = content_type {
+                headers.push(("Content-Type".to_string(), content_type.into_bytes()));
+            }
+            ResponseInput { status, headers, body }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+    use crate::fuzzing::{fuzz_parse_cache_control, fuzz_serde_extractor, strategies, ResponseInput};
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn huge_max_age_does_not_panic() {
+        fuzz_serde_extractor(&ResponseInput {
+            status: 200,
+            headers: vec![
+                ("Cache-Control".to_string(), format!("max-age={}", u64::MAX).into_bytes()),
+                ("Content-Type".to_string(), b"application/json".to_vec())
+            ],
+            body: br#"{"key": "value"}"#.to_vec()
+        });
+    }
+
+    proptest! {
+        #[test]
+        fn parse_cache_control_never_panics(value in strategies::cache_control()) {
+            fuzz_parse_cache_control(value.as_bytes());
+        }
+
+        #[test]
+        fn serde_extractor_never_panics(input in strategies::response()) {
+            fuzz_serde_extractor(&input);
+        }
+    }
+}
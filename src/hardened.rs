@@ -0,0 +1,133 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::future::Future;
+use std::marker::PhantomData;
+use crate::data_providers::data_provider::{DataLoadResult, DataProvider, Revalidation};
+
+/// Security weakness of data provider setup
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SecurityIssue {
+    /// Loaded data is modified by local overrides
+    LocalOverrides,
+    /// Data is fetched over plaintext connection (contains URL)
+    PlaintextTransport(String),
+    /// Client does not enforce TLS with certificate verification
+    TlsNotEnforced,
+    /// Data is accepted without signature verification
+    MissingSignatureVerification,
+    /// Origin can drive cache behavior with debug headers
+    DebugHeaders
+}
+
+impl Display for SecurityIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SecurityIssue::LocalOverrides => write!(f, "local overrides are applied"),
+            SecurityIssue::PlaintextTransport(url) => write!(f, "plaintext transport is used for {url}"),
+            SecurityIssue::TlsNotEnforced => write!(f, "client does not enforce TLS"),
+            SecurityIssue::MissingSignatureVerification => write!(f, "signature is not verified"),
+            SecurityIssue::DebugHeaders => write!(f, "debug headers of origin are honored")
+        }
+    }
+}
+
+/// Data provider that can report weaknesses of its setup.
+/// Providers that don't implement it can't be wrapped in [`Hardened`].
+pub trait SecurityAudit {
+    /// Returns all security issues of this provider, including issues of wrapped providers
+    fn security_issues(&self) -> Vec<SecurityIssue>;
+}
+
+/// Data provider that passed [`SecurityAudit`] with no issues
+///
+/// # Example
+/// ```
+/// use std::collections::HashMap;
+/// use reqwest::Url;
+/// use remote_config::data_providers::http::HttpDataProvider;
+/// use remote_config::data_providers::http::serde_extractor::SerdeDataExtractor;
+/// use remote_config::hardened::{Hardened, SecurityIssue};
+///
+/// let source = HttpDataProvider::new(reqwest::Client::default(), Url::parse("http://example.com/cfg").unwrap(), SerdeDataExtractor::<HashMap<String, String>>::new());
+/// let Err(err) = Hardened::new(source) else { unreachable!() };
+/// assert!(err.issues.contains(&SecurityIssue::TlsNotEnforced));
+/// ```
+#[derive(Debug)]
+pub struct Hardened<Data: Send + Sync, Provider> {
+    inner: Provider,
+    data_type: PhantomData<Data>
+}
+
+impl <Data: Send + Sync, Provider: DataProvider<Data> + SecurityAudit> Hardened<Data, Provider> {
+    /// Wraps provider
+    /// # Errors
+    /// If provider reports any security issue
+    pub fn new(inner: Provider) -> Result<Self, HardeningError> {
+        let issues = inner.security_issues();
+        if !issues.is_empty() {
+            return Err(HardeningError { issues });
+        }
+        Ok(Self { inner, data_type: PhantomData })
+    }
+
+    /// Wrapped provider
+    pub fn inner(&self) -> &Provider {
+        &self.inner
+    }
+}
+
+impl <Data: Send + Sync, Provider: DataProvider<Data>> DataProvider<Data> for Hardened<Data, Provider> {
+    fn load_data(&self) -> impl Future<Output = Result<DataLoadResult<Data>, Box<dyn Error + Send + Sync>>> + Send {
+        self.inner.load_data()
+    }
+
+    fn revalidate_data<'a>(&'a self, current: &'a DataLoadResult<Data>) -> impl Future<Output = Result<Revalidation<Data>, Box<dyn Error + Send + Sync>>> + Send {
+        self.inner.revalidate_data(current)
+    }
+}
+
+/// Provider can't be hardened
+#[derive(Debug)]
+pub struct HardeningError {
+    /// Reported security issues
+    pub issues: Vec<SecurityIssue>
+}
+
+impl Display for HardeningError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "data provider is not hardened: ")?;
+        for (i, issue) in self.issues.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{issue}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for HardeningError {}
+
+/// Http client that only uses https and always verifies certificates
+/// (see [`crate::data_providers::http::HttpDataProvider::new_hardened`])
+#[cfg(feature = "http")]
+#[derive(Debug, Clone)]
+pub struct HardenedClient(reqwest::Client);
+
+#[cfg(feature = "http")]
+impl HardenedClient {
+    /// Builds client, overriding https and certificate verification settings of builder
+    /// # Errors
+    /// If client can't be built
+    pub fn new(builder: reqwest::ClientBuilder) -> reqwest::Result<Self> {
+        // Without TLS features certificates are never accepted, as https can't be used at all
+        #[cfg(any(feature = "native-tls", feature = "rustls"))]
+        let builder = builder.danger_accept_invalid_certs(false);
+        builder.https_only(true).build().map(Self)
+    }
+
+    pub(crate) fn into_inner(self) -> reqwest::Client {
+        self.0
+    }
+}
@@ -3,18 +3,78 @@
 //! ## Feature flags
 //! ### Main crate features
 //! This features affect whole crate or `RemoteConfig` implementation directly
-//! + `tracing` - enables tracing with tokio 
+//! + `tracing` - enables tracing with tokio. If tokio is built with `--cfg tokio_unstable`, revalidation tasks are also named after config, so they can be told apart in tokio-console 
 //! + `non_static` - enables implementation of `RemoteConfig` that uses `&Arc<RemoteConfig>` instead of `&'static RemoteConfig`. 
 //!    As the intended use case for this crate is to store `RemoteConfig` in static tokio's `OnceCell`, this feature is not enabled by default.
+//! + `secrets` - enables `SecretResolvingProvider`, that replaces `secretref://` references in JSON config values with secrets read from environment,
+//!    Vault (with `vault` feature), SSM Parameter Store (with `ssm` feature) or custom resolvers.
+//! + `manifest` - enables `Manifest`, that builds `ConfigManager` with named configs, their sources and policies declared in TOML (or YAML with `yaml` feature) file.
+//! + `stream` - enables `RemoteConfig::stream`, that yields every activated config version as [futures](https://crates.io/crates/futures) `Stream`.
+//! + `chaos` - enables runtime switches that simulate stale data and data provider failures (see [`chaos::Chaos`]).
+//!    Intended for integration environments only.
+//! + `metrics` - records data provider usage with [metrics](https://crates.io/crates/metrics) facade. Config name is used as label, so `tracing` is enabled too.
+//! + `sealed` - enables `SealedBytes`, that keeps config data in sealed read-only memory file, and `RemoteConfigBuilder::with_sealed_storage` (Linux only, ignored on other platforms).
+//! + `zstd` - enables `CompressedBytes`, that keeps large and rarely read config data compressed in memory, optionally with LRU cache of decompressed data shared by configs.
+//! + `beacon` - enables `Beacon`, that periodically reports active config revision and health to configured endpoint.
+//! + `fuzzing` - exposes fuzz targets and proptest strategies for built-in extractors in `fuzzing` module.
+//! + `mobile` - build profile for Rust core shared by Android and iOS apps: http data provider with JSON and rustls only.
+//!    Use it with `default-features = false`, persist state with `HttpDataProvider::with_state_store`, and call `RemoteConfig::refresh`
+//!    from background fetch tasks. Current-thread tokio runtime is sufficient.
+//! + `sled` - enables `SledStateStore`, that persists config state in [sled](https://crates.io/crates/sled) database.
+//! + `redb` - enables `RedbStateStore`, that persists config state in [redb](https://crates.io/crates/redb) database.
+//! + `app_version` - enables `VersionedDocument`, that resolves values for application version from overrides for [semver](https://crates.io/crates/semver) ranges.
+//! + `environments` - enables `EnvironmentChain`, that flattens documents structured as environment layers (e.g. default, staging, prod, region)
+//!    along inheritance chain, and `EnvironmentProvider`, that applies it to JSON data loaded by any data provider.
+//! + `ffi` - exposes C ABI (see `include/remote_config.h`), so C++ plugins or Python via ctypes can read and follow config instance of the process instead of polling the origin separately.
+//!    Enables `http`, `json` and `non_static`; build the crate as `cdylib` or `staticlib` to use it.
+//! + `python` - exposes `RemoteConfig` class with blocking and asyncio APIs to Python with [PyO3](https://crates.io/crates/pyo3), so Python services share caching and retry behavior of Rust ones.
+//!    Build extension module with maturin and `python,pyo3/extension-module` features.
 //! 
 //! ### Data providers
 //! All built-in data providers and their features can be enabled or disabled using this feature flags.
 //! + `http` - enables `HttpDataProvider` that uses reqwest client to load data from remote source (enabled by default)
+//!     + `native-tls` - uses platform TLS implementation in http client (enabled by default). Without TLS feature only plain http URLs can be loaded.
+//!        `http` feature doesn't enable TLS by itself, so enable `native-tls` or `rustls` together with it when default features are disabled
+//!     + `rustls` - uses [rustls](https://crates.io/crates/rustls) with bundled root certificates in http client
 //!     + `serde` - enables convenient data extractor for http data provider, that automatically parses necessary headers and deserializes data based on content-type (enabled by default)
 //!         + `json` - json deserialization support (enabled by default). Deserializer: [serde_json](https://crates.io/crates/serde_json)
 //!         + `yaml` - yaml deserialization support. Deserializer: [serde_yaml](https://crates.io/crates/serde_yaml)
 //!         + `toml` - toml deserialization support. Deserializer: [toml](https://crates.io/crates/toml)
 //!         + `xml` - xml deserialization support. Deserializer: [serde-xml-rs](https://crates.io/crates/serde-xml-rs)
+//! + `github` - enables `GitHubDataProvider` that reads file from GitHub repository with contents API, using blob SHA as revision and revalidating it with conditional requests
+//! + `firestore` - enables `FirestoreDataProvider` that reads Firestore document or collection with REST API, using update time as revision, and optionally invalidates data with listen stream
+//! + `ipfs` - enables `IpfsDataProvider` that loads document by CID or IPNS name via IPFS gateway or RPC API of local node, using CID as revision
+//! + `cloudflare` - enables `CloudflareKvDataProvider` that reads key from Cloudflare Workers KV namespace with API token, parsing value only when its hash changes
+//! + `gitlab` - enables `GitLabDataProvider` that reads file from GitLab repository with repository files API, checking blob id with `HEAD` requests before downloading it again
+//! + `unix_socket` - enables `UnixSocketHttpDataProvider`, that sends requests to sidecar agent listening on Unix domain socket with [hyper](https://crates.io/crates/hyper), and feeds responses into http data extractor (Unix only)
+//! + `slim` - enables `SlimHttpDataProvider`, that loads data with minimal `HttpTransport` instead of reqwest, for binaries where size matters (e.g. edge agents).
+//!    Use it with `default-features = false`: core caching and revalidation of `RemoteConfig` don't depend on reqwest, and require only current-thread tokio runtime
+//!     + `ureq` - enables `UreqTransport`, that sends requests with blocking [ureq](https://crates.io/crates/ureq) client
+//! + `gcs` - enables `GcsDataProvider` that downloads objects from Google Cloud Storage bucket. Metadata server, service account key and static token authentication is supported
+//! + `firebase` - enables `FirebaseRemoteConfigProvider` that fetches Firebase Remote Config template with conditional requests and maps parameter defaults into data (`gcs` feature)
+//! + `appconfig` - enables `AppConfigDataProvider` that polls AWS AppConfig Data API sessions. Requests are signed with static or environment credentials (`aws` feature)
+//! + `ssm` - enables `SsmDataProvider` that assembles data from a parameter or path hierarchy of AWS Systems Manager Parameter Store, decrypting `SecureString` values (`aws` feature)
+//! + `secretsmanager` - enables `SecretsManagerDataProvider` that loads secret versions from AWS Secrets Manager and reports version id as revision (`aws` feature)
+//! + `dynamodb` - enables `DynamoDbDataProvider` that gets item or queries partition of Amazon DynamoDB table, optionally with consistent reads and expiration from TTL attribute (`aws` feature)
+//! + `azure` - enables `AzureAppConfigProvider` that loads key-values from Azure App Configuration by key and label filters, revalidating them with ETags and sync tokens
+//! + `consul` - enables `ConsulDataProvider` that reads values from Consul KV store, and optionally watches them with blocking queries
+//! + `vault` - enables `VaultDataProvider` that reads KV v2 and dynamic secrets from HashiCorp Vault, renewing leases and token
+//! + `kubernetes` - enables `K8sConfigMapProvider` and `K8sSecretProvider` that read ConfigMap or Secret via Kubernetes API server with [kube](https://crates.io/crates/kube), and optionally watch it
+//! + `downward_api` - enables `HintedDataProvider`, that reconfigures wrapped provider with poll interval and url hints from pod annotations exposed with Kubernetes downward API
+//! + `zookeeper` - enables `ZooKeeperDataProvider` that reads znode from ZooKeeper ensemble and invalidates data with watches
+//! + `postgres` - enables `PostgresDataProvider` that maps rows returned by query with [sqlx](https://crates.io/crates/sqlx), and optionally invalidates data with `LISTEN`/`NOTIFY`
+//! + `mysql` - enables `MySqlDataProvider` that maps rows returned by query with [sqlx](https://crates.io/crates/sqlx), optionally deserializing them with serde (`json` feature)
+//! + `sqlite` - enables `SqliteDataProvider` that maps rows of local SQLite database with [sqlx](https://crates.io/crates/sqlx), querying again only when file modification time or `user_version` changes
+//! + `mongodb` - enables `MongoDataProvider` that loads document from MongoDB collection, and optionally invalidates data with change streams
+//! + `env` - enables `EnvDataProvider` that deserializes data from prefixed environment variables with [envy](https://crates.io/crates/envy)
+//! + `dns` - enables `DnsTxtDataProvider` that resolves DNS TXT record with [hickory-resolver](https://crates.io/crates/hickory-resolver) (formerly trust-dns) and uses record TTL as data lifetime
+//! + `kafka` - enables `KafkaDataProvider` that materializes latest record of every key in compacted Kafka topic with [rdkafka](https://crates.io/crates/rdkafka), and keeps consuming it in background
+//! + `mqtt` - enables `MqttDataProvider` that subscribes to retained MQTT topic with [rumqttc](https://crates.io/crates/rumqttc) and applies messages published to it
+//! + `sse` - enables `SseDataProvider` that subscribes to server-sent event stream with reqwest, resuming it with `Last-Event-ID` after reconnect
+//! + `websocket` - enables `WebSocketDataProvider` that keeps connection to config service open with [tokio-tungstenite](https://crates.io/crates/tokio-tungstenite) and receives pushed snapshots
+//! + `sftp` - enables `SftpDataProvider` that downloads remote file over SSH with [ssh2](https://crates.io/crates/ssh2) (password or key authentication), skipping download when file modification time did not change
+//! + `ftp` - enables `FtpDataProvider` that downloads file from FTP server, plain or with implicit TLS, skipping download when modification time and size did not change
+//! + `file` - enables `FileDataProvider` that reads data from local file and watches it for changes with [notify](https://crates.io/crates/notify)
 //!
 //! # Examples
 //! ```
@@ -40,7 +100,10 @@
 //!
 //!     let data_provider = HttpDataProvider::new(client, Url::parse("https://example.com").unwrap(), SerdeDataExtractor::new());
 //!
+//!     #[cfg(feature = "tracing")]
 //!     return RemoteConfig::new("Example named config".to_owned(), data_provider, Duration::from_secs(5)).await.unwrap();
+//!     #[cfg(not (feature = "tracing"))]
+//!     return RemoteConfig::new(data_provider, Duration::from_secs(5)).await.unwrap();
 //! }
 //! // Note, that async OnceCell is used. You can use blocking OnceCell by changing init_config() to sync and using block_on() to wait for data load
 //! static CONFIG: OnceCell<RemoteConfig<Data, HttpDataProvider<Data, SerdeDataExtractor<Data>>>> = OnceCell::const_new();
@@ -55,6 +118,80 @@
 /// Remote Config instance and utility types
 /// See [`config::RemoteConfig`] struct.
 pub mod config;
+/// Time source abstraction used by RemoteConfig
+pub mod clock;
+/// Spawners that run background refresh tasks
+pub mod spawner;
+/// Named freshness policies of RemoteConfig call paths
+pub mod profile;
+/// Per-caller request context, and configs keyed by it
+pub mod context;
+/// Presets of RemoteConfig builder settings
+pub mod preset;
+/// Process-wide default settings of RemoteConfig builders
+pub mod policy;
+/// Detection of configs that change too often
+pub mod flapping;
 /// Data providers for RemoteConfig instance.
 /// Public traits are included to allow easy use of custom implementations.
 pub mod data_providers;
+/// Data provider usage accounting and budgets
+pub mod budget;
+/// Refresh policy for metered and constrained links
+pub mod bandwidth;
+/// Deferral of background refreshes while process is overloaded
+pub mod load_shedding;
+/// Origin directives that throttle or pause fetching
+pub mod control;
+/// Key-level deprecation warnings
+pub mod deprecation;
+/// Sampling of expensive operations with rate set by config
+pub mod sampler;
+/// Persistent storage for config state, that survives restarts
+pub mod state_store;
+/// Strict production mode that rejects insecure data provider setups
+pub mod hardened;
+/// Revalidation state machine shared by all RemoteConfig implementations
+mod revalidation;
+/// Randomness for splay and jitter
+mod random;
+/// Stable hashing of config content
+mod content_hash;
+/// Parsing of durations in text settings
+#[cfg(any(feature = "downward_api", feature = "manifest", feature = "http"))]
+mod duration;
+/// Recursive merging of JSON objects
+#[cfg(any(feature = "app_version", feature = "environments"))]
+mod merge;
+/// Fault injection for testing application behavior under config delivery degradation
+#[cfg(feature = "chaos")]
+pub mod chaos;
+/// Config data storage in sealed read-only memory
+#[cfg(all(feature = "sealed", target_os = "linux"))]
+pub mod sealed;
+/// Config data compressed in memory
+#[cfg(feature = "zstd")]
+pub mod compressed;
+/// Fuzz targets and proptest strategies for built-in extractors
+#[cfg(feature = "fuzzing")]
+pub mod fuzzing;
+/// Telemetry beacon that reports active config revision and health
+#[cfg(feature = "beacon")]
+pub mod beacon;
+/// Config documents with overrides for application version ranges
+#[cfg(feature = "app_version")]
+pub mod app_version;
+/// Config documents with environment layers, resolved along inheritance chain
+#[cfg(feature = "environments")]
+pub mod environments;
+/// C ABI that shares config instance with non-Rust code of the process
+#[cfg(feature = "ffi")]
+pub mod ffi;
+/// Python bindings of RemoteConfig
+#[cfg(feature = "python")]
+pub mod python;
+/// Named configs of an application
+pub mod manager;
+/// Declarative setup of configs from manifest file
+#[cfg(feature = "manifest")]
+pub mod manifest;
@@ -0,0 +1,70 @@
+use std::fmt::{Debug, Formatter};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+/// Refresh policy for overloaded process.
+/// While signal reports overload (e.g. CPU saturation or GC-like pause), background refreshes are deferred,
+/// and stale data is served instead, so that loading and deserialization don't add work at the worst moment.
+///
+/// Only refreshes that would run in background are deferred: data that must be revalidated is still revalidated,
+/// and data is refreshed regardless of signal once it was stale for longer than max deferral.
+/// Staleness is counted from expiration of data, or from the first deferred refresh if it is earlier
+/// (e.g. when pushed update invalidated data that has not expired yet).
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use remote_config::load_shedding::LoadSheddingPolicy;
+///
+/// // Defer refreshes while more than 100 tasks wait in global queue of tokio runtime
+/// let policy = LoadSheddingPolicy::from_runtime_queue_depth(100)
+///     .with_max_deferral(Duration::from_secs(30));
+/// ```
+pub struct LoadSheddingPolicy {
+    signal: Box<dyn Fn() -> bool + Send + Sync>,
+    max_deferral: Duration,
+    /// Time of the first refresh that was deferred since the last one that wasn't
+    deferred_since: Mutex<Option<SystemTime>>
+}
+
+impl LoadSheddingPolicy {
+    /// Creates policy with callback that reports whether process is overloaded.
+    /// It is called before each background refresh, so it must be cheap. Refreshes are deferred for at most one minute.
+    pub fn new(signal: impl Fn() -> bool + Send + Sync + 'static) -> Self {
+        Self {
+            signal: Box::new(signal),
+            max_deferral: Duration::from_secs(60),
+            deferred_since: Mutex::new(None)
+        }
+    }
+
+    /// Creates policy that reports overload while global queue of current tokio runtime holds more tasks than threshold.
+    /// Outside of tokio runtime process is never considered overloaded.
+    pub fn from_runtime_queue_depth(threshold: usize) -> Self {
+        Self::new(move || tokio::runtime::Handle::try_current().is_ok_and(|runtime| runtime.metrics().global_queue_depth() > threshold))
+    }
+
+    /// Maximal time that stale data is served while process is overloaded
+    pub fn with_max_deferral(mut self, max_deferral: Duration) -> Self {
+        self.max_deferral = max_deferral;
+        self
+    }
+
+    /// Checks if background refresh of stale data, that is valid until `valid_until`, must be deferred
+    pub(crate) fn is_deferred(&self, valid_until: SystemTime, time: SystemTime) -> bool {
+        let mut deferred_since = self.deferred_since.lock().unwrap();
+        let deferred = (self.signal)() && time < (*deferred_since.get_or_insert(time)).min(valid_until) + self.max_deferral;
+        if !deferred {
+            *deferred_since = None;
+        }
+        deferred
+    }
+}
+
+impl Debug for LoadSheddingPolicy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LoadSheddingPolicy")
+            .field("max_deferral", &self.max_deferral)
+            .field("deferred_since", &self.deferred_since)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,209 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+use std::time::Duration;
+use crate::config::{RemoteConfig, RemoteConfigBuilder, WaitTimeout};
+use crate::data_providers::data_provider::BoxedDataProvider;
+use crate::policy::GlobalPolicy;
+
+/// Config with type-erased data provider, as stored in [`ConfigManager`]
+pub type ManagedConfig<Data> = RemoteConfig<Data, BoxedDataProvider<Data>>;
+
+/// Named configs of an application, that share data type.
+/// Configs are stored in [`Arc`], so they can be used with `non_static` feature, or shared with background tasks.
+///
+/// Manager can be populated in code, or from manifest file with `manifest` feature
+/// (see [`Manifest`](crate::manifest::Manifest)).
+#[derive(Debug)]
+pub struct ConfigManager<Data: Send + Sync> {
+    configs: BTreeMap<String, Arc<ManagedConfig<Data>>>,
+    policy: Option<GlobalPolicy>
+}
+
+impl <Data: Send + Sync> ConfigManager<Data> {
+    /// Creates empty manager
+    pub fn new() -> Self {
+        Self { configs: BTreeMap::new(), policy: None }
+    }
+
+    /// Sets policy, that is applied (over installed [`GlobalPolicy`]) to builders created with [`ConfigManager::builder`].
+    /// Lets platform code set fetch settings of all configs managed by application, without installing process-wide policy.
+    pub fn with_policy(mut self, policy: GlobalPolicy) -> Self {
+        self.policy = Some(policy);
+        self
+    }
+
+    /// Creates builder of config with policy of this manager. Settings of policy can be overridden by builder methods.
+    pub fn builder(
+        &self,
+        #[cfg(feature = "tracing")] name: String,
+        data_provider: BoxedDataProvider<Data>
+    ) -> RemoteConfigBuilder<Data, BoxedDataProvider<Data>> {
+        #[cfg(feature = "tracing")]
+        let builder = RemoteConfig::builder(name, data_provider);
+        #[cfg(not (feature = "tracing"))]
+        let builder = RemoteConfig::builder(data_provider);
+        match &self.policy {
+            Some(policy) => builder.with_policy(policy),
+            None => builder
+        }
+    }
+
+    /// Adds config, replacing config with the same name, and returns it
+    pub fn insert(&mut self, name: impl Into<String>, config: ManagedConfig<Data>) -> Arc<ManagedConfig<Data>> {
+        let config = Arc::new(config);
+        self.configs.insert(name.into(), config.clone());
+        config
+    }
+
+    /// Config with specified name
+    pub fn get(&self, name: &str) -> Option<&Arc<ManagedConfig<Data>>> {
+        self.configs.get(name)
+    }
+
+    /// Names of configs in alphabetical order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.configs.keys().map(String::as_str)
+    }
+
+    /// Configs with their names, in alphabetical order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Arc<ManagedConfig<Data>>)> {
+        self.configs.iter().map(|(name, config)| (name.as_str(), config))
+    }
+
+    /// Number of configs
+    pub fn len(&self) -> usize {
+        self.configs.len()
+    }
+
+    /// Checks if there are no configs
+    pub fn is_empty(&self) -> bool {
+        self.configs.is_empty()
+    }
+
+    /// Waits until all named configs hold versions fetched within `max_age` (see [`CachedData::fetched_at`](crate::config::CachedData::fetched_at)),
+    /// so that job doesn't start with a mix of fresh and old documents (e.g. restored from disk cache).
+    /// Configs with older versions are refreshed, and failed refreshes are retried every retry interval of config.
+    ///
+    /// Refresh of one config gives others time to age, so configs are checked again after every pass of refreshes,
+    /// until all of them are recent at once. At most [`MAX_BARRIER_PASSES`] passes are made.
+    /// # Errors
+    /// If any of names is not a name of config in this manager, some config was not refreshed before timeout
+    /// (e.g. origin is down or config is offline), or configs didn't become recent at once
+    /// # Examples
+    /// ```ignore
+    /// manager.barrier(["flags", "limits"], Duration::from_secs(60), Duration::from_secs(30)).await?;
+    /// ```
+    pub async fn barrier<'a>(&self, names: impl IntoIterator<Item = &'a str>, max_age: Duration, timeout: Duration) -> Result<(), BarrierError> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        let configs = names.into_iter()
+            .map(|name| self.get(name).map(|config| (name, config)).ok_or_else(|| BarrierError::UnknownConfig(name.to_string())))
+            .collect::<Result<Vec<_>, _>>()?;
+        for pass in 0..=MAX_BARRIER_PASSES {
+            let outdated: Vec<_> = configs.iter().filter(|(_, config)| !config.is_fetched_within(max_age)).collect();
+            if outdated.is_empty() {
+                return Ok(());
+            }
+            if pass == MAX_BARRIER_PASSES {
+                break;
+            }
+            for (name, config) in outdated {
+                config.refresh_until_fetched_within(max_age, deadline).await
+                    .map_err(|err| BarrierError::Timeout { config: name.to_string(), source: err })?;
+            }
+        }
+        Err(BarrierError::Unsettled)
+    }
+}
+
+impl <Data: Send + Sync> Default for ConfigManager<Data> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Maximal number of refresh passes made by [`ConfigManager::barrier`]
+pub const MAX_BARRIER_PASSES: usize = 3;
+
+/// Barrier of [`ConfigManager`] can't be passed
+#[derive(Debug)]
+pub enum BarrierError {
+    /// Config with specified name is not present in manager
+    UnknownConfig(String),
+    /// Config was not refreshed before timeout
+    Timeout {
+        /// Name of config
+        config: String,
+        /// Timeout with last refresh error
+        source: WaitTimeout
+    },
+    /// Configs didn't become recent at once in [`MAX_BARRIER_PASSES`] passes, e.g. because refreshes take longer than `max_age`
+    Unsettled
+}
+
+impl Display for BarrierError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownConfig(config) => write!(f, "config '{config}' is not registered in manager"),
+            Self::Timeout { config, .. } => write!(f, "config '{config}' was not refreshed before timeout"),
+            Self::Unsettled => write!(f, "configs did not become recent at once in {MAX_BARRIER_PASSES} passes")
+        }
+    }
+}
+
+impl Error for BarrierError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Timeout { source, .. } => Some(source),
+            _ => None
+        }
+    }
+}
+
+#[cfg(all(test, feature = "file"))]
+mod tests {
+    use std::error::Error;
+    use std::time::{Duration, SystemTime};
+    use crate::config::RemoteConfig;
+    use crate::data_providers::data_provider::BoxedDataProvider;
+    use crate::data_providers::file::FileDataProvider;
+    use crate::manager::{BarrierError, ConfigManager};
+
+    fn parse(bytes: &[u8]) -> Result<String, Box<dyn Error + Send + Sync>> {
+        Ok(String::from_utf8(bytes.to_vec())?)
+    }
+
+    #[tokio::test]
+    async fn barrier_waits_for_file_written_recently() {
+        let path = std::env::temp_dir().join(format!("remote-config-barrier-{}", std::process::id()));
+        std::fs::write(&path, "v1").unwrap();
+        let written = SystemTime::now() - Duration::from_secs(24 * 60 * 60);
+        std::fs::File::options().write(true).open(&path).unwrap().set_modified(written).unwrap();
+
+        let mut manager = ConfigManager::new();
+        for (name, data_provider) in [
+            ("static", FileDataProvider::new(&path, parse).unwrap()),
+            ("cache", FileDataProvider::new(&path, parse).unwrap().with_modification_time_as_fetch_time())
+        ] {
+            #[cfg(feature = "tracing")]
+            let builder = RemoteConfig::builder(name.to_string(), BoxedDataProvider::new(data_provider));
+            #[cfg(not (feature = "tracing"))]
+            let builder = RemoteConfig::builder(BoxedDataProvider::new(data_provider));
+            manager.insert(name, builder.with_retry_interval(Duration::from_millis(10)).build().await.unwrap());
+        }
+
+        // Static file is recent once it is read
+        manager.barrier(["static"], Duration::from_secs(60), Duration::from_secs(5)).await.unwrap();
+
+        // Cache written a day ago is not recent, until it is written again
+        let err = manager.barrier(["cache"], Duration::from_secs(60), Duration::from_millis(100)).await.unwrap_err();
+        assert!(matches!(err, BarrierError::Timeout { config, .. } if config == "cache"));
+
+        std::fs::write(&path, "v2").unwrap();
+        manager.barrier(["cache"], Duration::from_secs(60), Duration::from_secs(5)).await.unwrap();
+        assert_eq!(*manager.get("cache").unwrap().refresh().await.unwrap(), "v2");
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
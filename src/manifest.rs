@@ -0,0 +1,305 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use reqwest::Url;
+use serde::Deserialize;
+use serde_json::Value;
+use crate::config::{DataProviderError, RemoteConfig, RemoteConfigBuilder};
+use crate::data_providers::data_provider::{BoxedDataProvider, DataProvider};
+#[cfg(feature = "file")]
+use crate::data_providers::file::FileDataProvider;
+use crate::data_providers::http::HttpDataProvider;
+use crate::data_providers::http::serde_extractor::{format_content_type, SerdeDataExtractor};
+use crate::duration::parse_duration;
+use crate::manager::ConfigManager;
+use crate::preset::Preset;
+
+/// Declaration of named configs, their sources and policies, that is used to build [`ConfigManager`].
+/// Manifest is written in TOML, or in YAML with `yaml` feature. Config data is loaded as [`Value`].
+///
+/// Policy in top-level `policy` table applies to every config, and config can override any setting in its own `policy` table.
+/// Durations are numbers with optional `ms`, `s`, `m` or `h` unit (plain number is seconds),
+/// and presets are `aggressive`, `conservative` or `offline_first` (see [`Preset`]).
+/// Settings of [`GlobalPolicy`](crate::policy::GlobalPolicy) apply unless manifest overrides them.
+///
+/// Config source is selected with `provider`:
+/// + `http` (default) - loads `url` with [`HttpDataProvider`]. Format is `json`, `yaml`, `toml`, `xml`, or `auto` (default) to select it by Content-Type.
+/// + `file` - reads `path` with [`FileDataProvider`] (requires `file` feature). Format is `json` (default), `yaml` or `toml`.
+/// # Examples
+/// ```toml
+/// [policy]
+/// preset = "conservative"
+/// stale_tolerance = "5m"
+///
+/// [configs.limits]
+/// url = "https://config.example.com/limits.json"
+/// format = "json"
+///
+/// [configs.features]
+/// url = "https://config.example.com/features"
+/// policy = { retry_interval = "2s" }
+///
+/// [configs.overrides]
+/// provider = "file"
+/// path = "/etc/app/overrides.toml"
+/// format = "toml"
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    #[serde(default)]
+    policy: Policy,
+    #[serde(default)]
+    configs: BTreeMap<String, ConfigEntry>
+}
+
+/// Policy settings, that are applied to config builder if set
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Policy {
+    preset: Option<String>,
+    retry_interval: Option<String>,
+    stale_tolerance: Option<String>,
+    max_revalidation_wait: Option<String>,
+    startup_splay: Option<String>
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigEntry {
+    #[serde(default = "default_provider")]
+    provider: String,
+    url: Option<String>,
+    #[cfg_attr(not(feature = "file"), allow(dead_code))]
+    path: Option<PathBuf>,
+    format: Option<String>,
+    #[serde(default)]
+    policy: Policy
+}
+
+fn default_provider() -> String {
+    "http".to_string()
+}
+
+/// Error of reading manifest or building configs declared in it
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ManifestError {
+    /// Manifest file can't be read
+    Io(std::io::Error),
+    /// Manifest can't be parsed, or it declares something that is not supported
+    Invalid(String),
+    /// Initial load of config failed
+    Load {
+        /// Config name
+        name: String,
+        /// Load error
+        source: DataProviderError
+    }
+}
+
+impl Display for ManifestError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ManifestError::Io(err) => write!(f, "manifest can't be read: {err}"),
+            ManifestError::Invalid(message) => write!(f, "invalid manifest: {message}"),
+            ManifestError::Load { name, .. } => write!(f, "initial load of config {name} failed")
+        }
+    }
+}
+
+impl Error for ManifestError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ManifestError::Io(err) => Some(err),
+            ManifestError::Invalid(_) => None,
+            ManifestError::Load { source, .. } => Some(source)
+        }
+    }
+}
+
+fn invalid(name: &str, message: impl Display) -> ManifestError {
+    ManifestError::Invalid(format!("config {name}: {message}"))
+}
+
+impl Policy {
+    fn duration(name: &str, setting: &str, value: &Option<String>) -> Result<Option<Duration>, ManifestError> {
+        value.as_deref()
+            .map(|value| parse_duration(value).ok_or_else(|| invalid(name, format!("invalid {setting} {value:?}"))))
+            .transpose()
+    }
+
+    /// Applies preset first, so that other settings override it
+    fn apply<Provider>(&self, name: &str, mut builder: RemoteConfigBuilder<Value, Provider>) -> Result<RemoteConfigBuilder<Value, Provider>, ManifestError>
+    where Provider: DataProvider<Value> + Send
+    {
+        if let Some(preset) = &self.preset {
+            builder = builder.with_preset(match preset.as_str() {
+                "aggressive" => Preset::Aggressive,
+                "conservative" => Preset::Conservative,
+                "offline_first" => Preset::OfflineFirst,
+                other => return Err(invalid(name, format!("unknown preset {other:?}")))
+            });
+        }
+        if let Some(interval) = Self::duration(name, "retry_interval", &self.retry_interval)? {
+            builder = builder.with_retry_interval(interval);
+        }
+        if let Some(tolerance) = Self::duration(name, "stale_tolerance", &self.stale_tolerance)? {
+            builder = builder.with_stale_tolerance(tolerance);
+        }
+        if let Some(wait) = Self::duration(name, "max_revalidation_wait", &self.max_revalidation_wait)? {
+            builder = builder.with_max_revalidation_wait(wait);
+        }
+        if let Some(window) = Self::duration(name, "startup_splay", &self.startup_splay)? {
+            builder = builder.with_startup_splay(window);
+        }
+        Ok(builder)
+    }
+}
+
+/// Parses local file content in specified format
+#[cfg(feature = "file")]
+fn parse_document(format: &str, bytes: &[u8]) -> Result<Value, Box<dyn Error + Send + Sync>> {
+    match format {
+        "json" => Ok(serde_json::from_slice(bytes)?),
+        "toml" => Ok(toml::from_str(std::str::from_utf8(bytes)?)?),
+        #[cfg(feature = "yaml")]
+        "yaml" => Ok(serde_yaml::from_slice(bytes)?),
+        other => Err(format!("unsupported format: {other}").into())
+    }
+}
+
+impl Manifest {
+    /// Parses TOML manifest
+    /// # Errors
+    /// If manifest is not valid TOML, or has unknown fields
+    pub fn from_toml(text: &str) -> Result<Self, ManifestError> {
+        toml::from_str(text).map_err(|err| ManifestError::Invalid(err.to_string()))
+    }
+
+    /// Parses YAML manifest
+    /// # Errors
+    /// If manifest is not valid YAML, or has unknown fields
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml(text: &str) -> Result<Self, ManifestError> {
+        serde_yaml::from_str(text).map_err(|err| ManifestError::Invalid(err.to_string()))
+    }
+
+    /// Reads manifest file. Files with `.yaml` or `.yml` extension are parsed as YAML, others as TOML.
+    /// # Errors
+    /// If file can't be read or parsed
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, ManifestError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(ManifestError::Io)?;
+        match path.extension().and_then(|extension| extension.to_str()) {
+            #[cfg(feature = "yaml")]
+            Some("yaml" | "yml") => Self::from_yaml(&text),
+            #[cfg(not(feature = "yaml"))]
+            Some("yaml" | "yml") => Err(ManifestError::Invalid("YAML manifest requires yaml feature".to_string())),
+            _ => Self::from_toml(&text)
+        }
+    }
+
+    /// Names of declared configs in alphabetical order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.configs.keys().map(String::as_str)
+    }
+
+    /// Builds every declared config and loads its initial data. HTTP configs share the client.
+    /// # Errors
+    /// If config declaration is not valid, or initial load of any config fails
+    pub async fn build(&self, client: reqwest::Client) -> Result<ConfigManager<Value>, ManifestError> {
+        let mut manager = ConfigManager::new();
+        for (name, entry) in &self.configs {
+            let data_provider = entry.data_provider(name, &client)?;
+            #[cfg(feature = "tracing")]
+            let builder = RemoteConfig::builder(name.clone(), data_provider);
+            #[cfg(not (feature = "tracing"))]
+            let builder = RemoteConfig::builder(data_provider);
+            let builder = entry.policy.apply(name, self.policy.apply(name, builder)?)?;
+            let config = builder.build().await.map_err(|source| ManifestError::Load { name: name.clone(), source })?;
+            manager.insert(name.clone(), config);
+        }
+        Ok(manager)
+    }
+}
+
+impl ConfigEntry {
+    fn data_provider(&self, name: &str, client: &reqwest::Client) -> Result<BoxedDataProvider<Value>, ManifestError> {
+        match self.provider.as_str() {
+            "http" => {
+                let url = self.url.as_deref().ok_or_else(|| invalid(name, "http provider requires url"))?;
+                let url = Url::parse(url).map_err(|err| invalid(name, format!("invalid url: {err}")))?;
+                let content_type = format_content_type(self.format.as_deref().unwrap_or("auto")).map_err(|err| invalid(name, err))?;
+                let extractor = content_type.into_iter().fold(SerdeDataExtractor::new(), SerdeDataExtractor::with_content_type);
+                Ok(BoxedDataProvider::new(HttpDataProvider::new(client.clone(), url, extractor)))
+            },
+            #[cfg(feature = "file")]
+            "file" => {
+                let path = self.path.as_ref().ok_or_else(|| invalid(name, "file provider requires path"))?;
+                let format = self.format.clone().unwrap_or_else(|| "json".to_string());
+                let data_provider = FileDataProvider::new(path, move |bytes: &[u8]| parse_document(&format, bytes))
+                    .map_err(|err| invalid(name, format!("file can't be watched: {err}")))?;
+                Ok(BoxedDataProvider::new(data_provider))
+            },
+            other => Err(invalid(name, format!("unsupported provider {other:?}")))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::manifest::{Manifest, ManifestError};
+
+    #[tokio::test]
+    async fn configs_are_built_from_manifest() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/limits")
+            .with_header("Cache-Control", "max-age=60")
+            .with_header("Content-Type", "application/json")
+            .with_body(r#"{"rps": 10}"#)
+            .create_async()
+            .await;
+        server.mock("GET", "/features")
+            .with_header("Cache-Control", "max-age=60")
+            .with_header("Content-Type", "text/plain")
+            .with_body(r#"{"new_ui": true}"#)
+            .create_async()
+            .await;
+
+        let manifest = Manifest::from_toml(&format!(r#"
+            [policy]
+            preset = "conservative"
+            startup_splay = "0s"
+
+            [configs.limits]
+            url = "{url}/limits"
+
+            [configs.features]
+            url = "{url}/features"
+            format = "json"
+            policy = {{ retry_interval = "500ms" }}
+        "#, url = server.url())).unwrap();
+        assert_eq!(manifest.names().collect::<Vec<_>>(), vec!["features", "limits"]);
+
+        let manager = manifest.build(reqwest::Client::new()).await.unwrap();
+        assert_eq!(manager.len(), 2);
+        assert_eq!(manager.get("limits").unwrap().refresh().await.unwrap()["rps"], 10);
+        assert_eq!(manager.get("features").unwrap().refresh().await.unwrap()["new_ui"], true);
+    }
+
+    #[tokio::test]
+    async fn invalid_declarations_are_rejected() {
+        assert!(matches!(Manifest::from_toml("[configs.limits]\nurl = \"https://example.com\"\nretry = \"1s\""), Err(ManifestError::Invalid(_))));
+
+        let manifest = Manifest::from_toml("[configs.limits]\nurl = \"https://example.com\"\npolicy = { retry_interval = \"soon\" }").unwrap();
+        let err = manifest.build(reqwest::Client::new()).await.unwrap_err();
+        assert_eq!(err.to_string(), "invalid manifest: config limits: invalid retry_interval \"soon\"");
+
+        let manifest = Manifest::from_toml("[configs.limits]\nprovider = \"http\"").unwrap();
+        let err = manifest.build(reqwest::Client::new()).await.unwrap_err();
+        assert_eq!(err.to_string(), "invalid manifest: config limits: http provider requires url");
+    }
+}
@@ -0,0 +1,13 @@
+use serde_json::{Map, Value};
+
+/// Merges values into target. Objects are merged recursively, and any other value replaces previous one
+pub(crate) fn merge(target: &mut Map<String, Value>, values: &Map<String, Value>) {
+    for (key, value) in values {
+        match (target.get_mut(key), value) {
+            (Some(Value::Object(target)), Value::Object(values)) => merge(target, values),
+            _ => {
+                target.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
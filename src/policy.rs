@@ -0,0 +1,100 @@
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Installed process-wide policy
+static GLOBAL: OnceLock<GlobalPolicy> = OnceLock::new();
+
+/// Default settings inherited by every [`RemoteConfigBuilder`](crate::config::RemoteConfigBuilder) of the process.
+/// Lets platform code enforce fleet-wide fetch settings, while application code only configures what is special about its config:
+/// builder methods called on individual config (including [`with_preset`](crate::config::RemoteConfigBuilder::with_preset)) override policy.
+///
+/// Policy is installed once with [`GlobalPolicy::install`], before the first config is created.
+/// Settings that are not set in policy keep crate defaults.
+/// Policy can also be set for configs of one [`ConfigManager`](crate::manager::ConfigManager) with [`ConfigManager::with_policy`](crate::manager::ConfigManager::with_policy).
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use remote_config::policy::GlobalPolicy;
+///
+/// // At startup, before any config is created
+/// GlobalPolicy::new()
+///     .with_retry_interval(Duration::from_secs(30))
+///     .with_retry_backoff(Duration::from_secs(300))
+///     .with_load_timeout(Duration::from_secs(10))
+///     .with_max_revalidation_wait(Duration::from_secs(2))
+///     .with_startup_splay(Duration::from_secs(10))
+///     .install()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GlobalPolicy {
+    pub(crate) retry_interval: Option<Duration>,
+    pub(crate) max_retry_interval: Option<Duration>,
+    pub(crate) load_timeout: Option<Duration>,
+    pub(crate) max_revalidation_wait: Option<Duration>,
+    pub(crate) stale_tolerance: Option<Duration>,
+    pub(crate) startup_splay: Option<Duration>,
+    #[cfg(feature = "metrics")] pub(crate) metrics: Option<bool>
+}
+
+impl GlobalPolicy {
+    /// Creates policy that doesn't change any defaults
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Default retry interval (see [`RemoteConfigBuilder::with_retry_interval`](crate::config::RemoteConfigBuilder::with_retry_interval))
+    pub fn with_retry_interval(mut self, retry_interval: Duration) -> Self {
+        self.retry_interval = Some(retry_interval);
+        self
+    }
+
+    /// Default limit of retry interval backoff (see [`RemoteConfigBuilder::with_retry_backoff`](crate::config::RemoteConfigBuilder::with_retry_backoff))
+    pub fn with_retry_backoff(mut self, max_retry_interval: Duration) -> Self {
+        self.max_retry_interval = Some(max_retry_interval);
+        self
+    }
+
+    /// Default load timeout (see [`RemoteConfigBuilder::with_load_timeout`](crate::config::RemoteConfigBuilder::with_load_timeout))
+    pub fn with_load_timeout(mut self, timeout: Duration) -> Self {
+        self.load_timeout = Some(timeout);
+        self
+    }
+
+    /// Default max revalidation wait (see [`RemoteConfigBuilder::with_max_revalidation_wait`](crate::config::RemoteConfigBuilder::with_max_revalidation_wait))
+    pub fn with_max_revalidation_wait(mut self, wait: Duration) -> Self {
+        self.max_revalidation_wait = Some(wait);
+        self
+    }
+
+    /// Default stale tolerance (see [`RemoteConfigBuilder::with_stale_tolerance`](crate::config::RemoteConfigBuilder::with_stale_tolerance))
+    pub fn with_stale_tolerance(mut self, tolerance: Duration) -> Self {
+        self.stale_tolerance = Some(tolerance);
+        self
+    }
+
+    /// Default startup splay window (see [`RemoteConfigBuilder::with_startup_splay`](crate::config::RemoteConfigBuilder::with_startup_splay))
+    pub fn with_startup_splay(mut self, window: Duration) -> Self {
+        self.startup_splay = Some(window);
+        self
+    }
+
+    /// Whether configs record metrics by default (see [`RemoteConfigBuilder::with_metrics`](crate::config::RemoteConfigBuilder::with_metrics))
+    #[cfg(feature = "metrics")]
+    pub fn with_metrics(mut self, enabled: bool) -> Self {
+        self.metrics = Some(enabled);
+        self
+    }
+
+    /// Installs policy for the whole process.
+    /// # Errors
+    /// Returns policy back if policy was already installed, or any config was created before.
+    pub fn install(self) -> Result<(), GlobalPolicy> {
+        GLOBAL.set(self)
+    }
+
+    /// Installed policy. If none was installed, empty policy is installed and returned.
+    pub fn current() -> &'static GlobalPolicy {
+        GLOBAL.get_or_init(GlobalPolicy::default)
+    }
+}
@@ -0,0 +1,101 @@
+use std::time::Duration;
+
+/// Named combination of [`RemoteConfigBuilder`](crate::config::RemoteConfigBuilder) settings,
+/// applied with [`RemoteConfigBuilder::with_preset`](crate::config::RemoteConfigBuilder::with_preset).
+/// Settings can still be overridden by calling builder methods after preset is applied.
+///
+/// | Preset         | Retry interval | Retry backoff limit | Load timeout | TTL limits     | Stale tolerance | Max revalidation wait | Startup splay |
+/// |----------------|----------------|---------------------|--------------|----------------|-----------------|-----------------------|---------------|
+/// | `Aggressive`   | 1 s            | 10 s                | 10 s         | at most 1 min  | zero            | 30 s                  | zero          |
+/// | `Conservative` | 30 s           | 10 min              | 30 s         | at least 1 min | 10 s            | 5 s                   | 10 s          |
+/// | `OfflineFirst` | 15 s           | 5 min               | 5 s          | at least 5 min | 1 day           | zero                  | 5 s           |
+///
+/// Retry backoff limit is set with [`RemoteConfigBuilder::with_retry_backoff`](crate::config::RemoteConfigBuilder::with_retry_backoff),
+/// TTL limits with [`with_min_ttl`](crate::config::RemoteConfigBuilder::with_min_ttl) and [`with_max_ttl`](crate::config::RemoteConfigBuilder::with_max_ttl).
+/// Stale tolerance applies to loads without access profile (see [`AccessProfile`](crate::profile::AccessProfile)).
+/// # Examples
+/// ```ignore
+/// let config = RemoteConfig::builder(data_provider)
+///     .with_preset(Preset::Conservative)
+///     .with_retry_interval(Duration::from_secs(10))
+///     .build()
+///     .await?;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Preset {
+    /// Prefers fresh data: failed loads are retried quickly, data is revalidated at least every minute,
+    /// and data that must be revalidated is never served stale, unless revalidation takes too long
+    Aggressive,
+    /// Protects data source: retries are rare and back off while it fails, initial loads of many instances are spread,
+    /// data is not revalidated more often than once a minute, and recently expired data is served while revalidation runs in background
+    Conservative,
+    /// Prefers availability: cached data is served without waiting for revalidation, even if it must be revalidated,
+    /// and slow loads are abandoned quickly
+    OfflineFirst
+}
+
+impl Preset {
+    pub(crate) fn retry_interval(self) -> Duration {
+        match self {
+            Preset::Aggressive => Duration::from_secs(1),
+            Preset::Conservative => Duration::from_secs(30),
+            Preset::OfflineFirst => Duration::from_secs(15)
+        }
+    }
+
+    pub(crate) fn max_retry_interval(self) -> Duration {
+        match self {
+            Preset::Aggressive => Duration::from_secs(10),
+            Preset::Conservative => Duration::from_secs(10 * 60),
+            Preset::OfflineFirst => Duration::from_secs(5 * 60)
+        }
+    }
+
+    pub(crate) fn load_timeout(self) -> Duration {
+        match self {
+            Preset::Aggressive => Duration::from_secs(10),
+            Preset::Conservative => Duration::from_secs(30),
+            Preset::OfflineFirst => Duration::from_secs(5)
+        }
+    }
+
+    pub(crate) fn min_ttl(self) -> Duration {
+        match self {
+            Preset::Aggressive => Duration::ZERO,
+            Preset::Conservative => Duration::from_secs(60),
+            Preset::OfflineFirst => Duration::from_secs(5 * 60)
+        }
+    }
+
+    pub(crate) fn max_ttl(self) -> Option<Duration> {
+        match self {
+            Preset::Aggressive => Some(Duration::from_secs(60)),
+            Preset::Conservative | Preset::OfflineFirst => None
+        }
+    }
+
+    pub(crate) fn stale_tolerance(self) -> Duration {
+        match self {
+            Preset::Aggressive => Duration::ZERO,
+            Preset::Conservative => Duration::from_secs(10),
+            Preset::OfflineFirst => Duration::from_secs(24 * 60 * 60)
+        }
+    }
+
+    pub(crate) fn max_revalidation_wait(self) -> Duration {
+        match self {
+            Preset::Aggressive => Duration::from_secs(30),
+            Preset::Conservative => Duration::from_secs(5),
+            Preset::OfflineFirst => Duration::ZERO
+        }
+    }
+
+    pub(crate) fn startup_splay(self) -> Duration {
+        match self {
+            Preset::Aggressive => Duration::ZERO,
+            Preset::Conservative => Duration::from_secs(10),
+            Preset::OfflineFirst => Duration::from_secs(5)
+        }
+    }
+}
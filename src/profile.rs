@@ -0,0 +1,42 @@
+use std::time::Duration;
+
+/// Freshness policy of a call path, registered on [`RemoteConfig`](crate::config::RemoteConfig) by name
+/// with [`RemoteConfigBuilder::with_access_profile`](crate::config::RemoteConfigBuilder::with_access_profile),
+/// and selected per call with [`RemoteConfig::load_as`](crate::config::RemoteConfig::load_as).
+/// It allows latency-sensitive and consistency-sensitive paths to share one config instance.
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use remote_config::profile::AccessProfile;
+///
+/// // Never block hot path, even if data must be revalidated
+/// let fast_path = AccessProfile::new()
+///     .with_stale_tolerance(Duration::from_secs(30))
+///     .with_max_revalidation_wait(Duration::ZERO);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AccessProfile {
+    pub(crate) stale_tolerance: Duration,
+    pub(crate) max_revalidation_wait: Option<Duration>
+}
+
+impl AccessProfile {
+    /// Creates profile with default config policy
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Data that must be revalidated is served without waiting for revalidation, if it expired less than
+    /// specified time ago. Revalidation is started in background. Default is zero.
+    pub fn with_stale_tolerance(mut self, tolerance: Duration) -> Self {
+        self.stale_tolerance = tolerance;
+        self
+    }
+
+    /// Maximal time to wait for revalidation of stale data that must be revalidated, before serving it anyway.
+    /// Default is taken from [`RemoteConfigBuilder::with_max_revalidation_wait`](crate::config::RemoteConfigBuilder::with_max_revalidation_wait).
+    pub fn with_max_revalidation_wait(mut self, wait: Duration) -> Self {
+        self.max_revalidation_wait = Some(wait);
+        self
+    }
+}
@@ -0,0 +1,255 @@
+use std::sync::Arc;
+use std::time::Duration;
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList, PyString};
+use reqwest::Url;
+use serde_json::Value;
+use tokio::runtime::Runtime;
+use crate::config::{NonStaticRemoteConfig, RemoteConfig};
+use crate::data_providers::http::serde_extractor::{format_content_type, SerdeDataExtractor};
+use crate::data_providers::http::HttpDataProvider;
+
+create_exception!(remote_config, RemoteConfigError, PyException, "Config data could not be loaded");
+
+type Config = RemoteConfig<Value, HttpDataProvider<Value, SerdeDataExtractor<Value>>>;
+
+/// Python class `remote_config.RemoteConfig`, that loads JSON-compatible document with [`HttpDataProvider`],
+/// and returns it as plain Python objects (dicts, lists, strings, numbers, booleans and `None`).
+/// Caching and revalidation are the same as in Rust: stale data is revalidated on access, and failed loads are retried
+/// no more often than retry interval. Every config owns tokio runtime, that runs revalidation.
+/// Runtime is shut down when config is garbage collected, and awaitables returned by `load_async`, that are still pending, raise `RemoteConfigError`.
+///
+/// Build extension module with [maturin](https://www.maturin.rs) and `python,pyo3/extension-module` features.
+/// ```python
+/// from remote_config import RemoteConfig
+///
+/// config = RemoteConfig("https://config.example.com/app.json", format="json", retry_interval=10.0)
+/// limits = config.load()["limits"]  # blocks until data is loaded
+/// limits = (await config.load_async())["limits"]  # inside asyncio event loop
+/// ```
+#[pyclass(name = "RemoteConfig", module = "remote_config", frozen)]
+pub struct PyRemoteConfig {
+    /// Always present until config is dropped
+    runtime: Option<Runtime>,
+    config: Arc<Config>
+}
+
+/// Converts JSON value to plain Python object
+fn to_python(py: Python<'_>, value: &Value) -> PyResult<PyObject> {
+    Ok(match value {
+        Value::Null => py.None(),
+        Value::Bool(value) => value.into_pyobject(py)?.to_owned().into_any().unbind(),
+        Value::Number(number) => match (number.as_i64(), number.as_u64()) {
+            (Some(integer), _) => integer.into_pyobject(py)?.into_any().unbind(),
+            (None, Some(integer)) => integer.into_pyobject(py)?.into_any().unbind(),
+            _ => number.as_f64().unwrap_or(f64::NAN).into_pyobject(py)?.into_any().unbind()
+        },
+        Value::String(string) => PyString::new(py, string).into_any().unbind(),
+        Value::Array(items) => {
+            let list = PyList::empty(py);
+            for item in items {
+                list.append(to_python(py, item)?)?;
+            }
+            list.into_any().unbind()
+        },
+        Value::Object(object) => {
+            let dict = PyDict::new(py);
+            for (key, value) in object {
+                dict.set_item(key, to_python(py, value)?)?;
+            }
+            dict.into_any().unbind()
+        }
+    })
+}
+
+/// Completes asyncio future on its event loop thread, unless it was cancelled
+#[pyfunction]
+fn resolve(future: &Bound<'_, PyAny>, result: PyObject, failed: bool) -> PyResult<()> {
+    if future.call_method0("done")?.is_truthy()? {
+        return Ok(());
+    }
+    future.call_method1(if failed { "set_exception" } else { "set_result" }, (result,))?;
+    Ok(())
+}
+
+/// Asyncio future returned by `load_async`, that is rejected if it is dropped unresolved, i.e. when runtime of config is shut down
+struct PendingFuture {
+    event_loop: PyObject,
+    future: Option<PyObject>
+}
+
+impl PendingFuture {
+    /// Schedules completion of future on its event loop
+    fn resolve(&mut self, py: Python<'_>, result: PyObject, failed: bool) {
+        if let Some(future) = self.future.take() {
+            // Fails only if event loop is already closed, and then there is nobody to notify
+            let _ = wrap_pyfunction!(resolve, py)
+                .and_then(|resolve| self.event_loop.call_method1(py, "call_soon_threadsafe", (resolve, future, result, failed)));
+        }
+    }
+}
+
+impl Drop for PendingFuture {
+    fn drop(&mut self) {
+        if self.future.is_some() {
+            Python::with_gil(|py| {
+                let err = RemoteConfigError::new_err("config was dropped before data was loaded").into_value(py).into_any();
+                self.resolve(py, err, true);
+            });
+        }
+    }
+}
+
+#[pymethods]
+impl PyRemoteConfig {
+    /// Creates config and loads initial data, releasing GIL while waiting.
+    /// Format is `json`, `yaml`, `toml` or `xml` (requires corresponding crate feature), or `auto` to select it by Content-Type.
+    /// Retry interval is in seconds.
+    #[new]
+    #[pyo3(signature = (url, format = "auto", retry_interval = None))]
+    fn new(py: Python<'_>, url: &str, format: &str, retry_interval: Option<f64>) -> PyResult<Self> {
+        let content_type = format_content_type(format).map_err(RemoteConfigError::new_err)?;
+        let url = Url::parse(url).map_err(|err| RemoteConfigError::new_err(err.to_string()))?;
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("remote-config-python")
+            .enable_all()
+            .build()?;
+        let extractor = content_type.into_iter().fold(SerdeDataExtractor::new(), SerdeDataExtractor::with_content_type);
+        let data_provider = HttpDataProvider::new(reqwest::Client::default(), url.clone(), extractor);
+        #[cfg(feature = "tracing")]
+        let mut builder = RemoteConfig::builder(url.to_string(), data_provider);
+        #[cfg(not (feature = "tracing"))]
+        let mut builder = RemoteConfig::builder(data_provider);
+        if let Some(retry_interval) = retry_interval {
+            let retry_interval = Duration::try_from_secs_f64(retry_interval).map_err(|err| RemoteConfigError::new_err(err.to_string()))?;
+            builder = builder.with_retry_interval(retry_interval);
+        }
+        let config = py.allow_threads(|| runtime.block_on(builder.build()))
+            .map_err(|err| RemoteConfigError::new_err(err.to_string()))?;
+        Ok(Self { runtime: Some(runtime), config: Arc::new(config) })
+    }
+
+    /// Returns current data, revalidating it first if needed. GIL is released while waiting.
+    fn load(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let data = py.allow_threads(|| self.runtime().block_on(self.config.load()).map(|data| data.clone()))
+            .map_err(|err| RemoteConfigError::new_err(err.to_string()))?;
+        to_python(py, &data)
+    }
+
+    /// Returns awaitable with current data, that must be awaited in running asyncio event loop
+    fn load_async(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let event_loop = py.import("asyncio")?.call_method0("get_running_loop")?;
+        let future = event_loop.call_method0("create_future")?;
+        let mut pending = PendingFuture { event_loop: event_loop.unbind(), future: Some(future.clone().unbind()) };
+        let config = self.config.clone();
+        self.runtime().spawn(async move {
+            let result = config.load().await.map(|data| data.clone());
+            Python::with_gil(|py| {
+                let (result, failed) = match result {
+                    Ok(data) => match to_python(py, &data) {
+                        Ok(data) => (data, false),
+                        Err(err) => (err.into_value(py).into_any(), true)
+                    },
+                    Err(err) => (RemoteConfigError::new_err(err.to_string()).into_value(py).into_any(), true)
+                };
+                pending.resolve(py, result, failed);
+            });
+        });
+        Ok(future.unbind())
+    }
+
+    /// Revalidates data now, even if it is fresh, and returns it. GIL is released while waiting.
+    fn refresh(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let data = py.allow_threads(|| self.runtime().block_on(self.config.refresh()).map(|data| data.clone()))
+            .map_err(|err| RemoteConfigError::new_err(err.to_string()))?;
+        to_python(py, &data)
+    }
+
+    /// Revision of cached data (e.g. ETag), if origin reports it
+    #[getter]
+    fn revision(&self) -> Option<String> {
+        self.config.status().revision
+    }
+}
+
+impl PyRemoteConfig {
+    fn runtime(&self) -> &Runtime {
+        self.runtime.as_ref().expect("runtime is present until config is dropped")
+    }
+}
+
+impl Drop for PyRemoteConfig {
+    /// Shuts down runtime without waiting for its tasks. Config is usually dropped by garbage collector with GIL held,
+    /// so GIL is released while runtime stops, otherwise worker thread, that waits for GIL to resolve future, would deadlock.
+    fn drop(&mut self) {
+        if let Some(runtime) = self.runtime.take() {
+            Python::with_gil(|py| py.allow_threads(|| runtime.shutdown_background()));
+        }
+    }
+}
+
+/// Python module `remote_config`
+#[pymodule]
+#[pyo3(name = "remote_config")]
+pub fn remote_config(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyRemoteConfig>()?;
+    m.add("RemoteConfigError", m.py().get_type::<RemoteConfigError>())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use pyo3::ffi::c_str;
+    use pyo3::prelude::*;
+    use pyo3::types::PyDict;
+    use crate::python::remote_config;
+
+    #[test]
+    fn blocking_and_async_loads() {
+        let mut server = mockito::Server::new();
+        server.mock("GET", "/config")
+            .with_header("Cache-Control", "max-age=60")
+            .with_header("Content-Type", "text/plain")
+            .with_header("ETag", "\"v1\"")
+            .with_body(r#"{"limits": {"rps": 10, "burst": 1.5}, "regions": ["eu"], "enabled": true, "owner": null}"#)
+            .create();
+
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let globals = PyDict::new(py);
+            globals.set_item("url", format!("{}/config", server.url())).unwrap();
+            globals.set_item("remote_config", PyModule::new(py, "remote_config").unwrap()).unwrap();
+            remote_config(&globals.get_item("remote_config").unwrap().unwrap().downcast_into().unwrap()).unwrap();
+            py.run(c_str!(r#"
+import asyncio
+
+config = remote_config.RemoteConfig(url, format="json", retry_interval=1.0)
+expected = {"limits": {"rps": 10, "burst": 1.5}, "regions": ["eu"], "enabled": True, "owner": None}
+assert config.load() == expected
+assert config.revision == '"v1"'
+
+async def main():
+    return await config.load_async()
+assert asyncio.run(main()) == expected
+
+# Config is dropped while future is pending, that must not deadlock, and future must not hang
+async def dropped():
+    future = remote_config.RemoteConfig(url, format="json").load_async()
+    try:
+        return await future
+    except remote_config.RemoteConfigError as err:
+        return str(err)
+assert asyncio.run(dropped()) in (expected, "config was dropped before data was loaded")
+
+try:
+    remote_config.RemoteConfig(url, format="ini")
+    assert False
+except remote_config.RemoteConfigError as err:
+    assert str(err) == "unsupported format: ini"
+"#), Some(&globals), None).unwrap();
+        });
+    }
+}
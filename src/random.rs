@@ -0,0 +1,37 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::{Duration, SystemTime};
+
+/// Random number from randomly seeded hasher. Not suitable for cryptography
+pub(crate) fn random_u64() -> u64 {
+    let mut hasher = RandomState::new().build_hasher();
+    if let Ok(elapsed) = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
+        hasher.write_u128(elapsed.as_nanos());
+    }
+    hasher.finish()
+}
+
+/// Uniformly distributed duration in `[0, max)`
+pub(crate) fn random_duration(max: Duration) -> Duration {
+    let nanos = max.as_nanos();
+    if nanos == 0 {
+        return Duration::ZERO;
+    }
+    let nanos = (random_u64() as u128 * nanos) >> 64;
+    Duration::new((nanos / 1_000_000_000) as u64, (nanos % 1_000_000_000) as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use crate::random::random_duration;
+
+    #[test]
+    fn duration_in_range() {
+        assert_eq!(random_duration(Duration::ZERO), Duration::ZERO);
+        let max = Duration::from_secs(10);
+        let samples: Vec<_> = (0..100).map(|_| random_duration(max)).collect();
+        assert!(samples.iter().all(|sample| *sample < max));
+        assert!(samples.iter().any(|sample| *sample != samples[0]));
+    }
+}
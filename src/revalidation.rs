@@ -0,0 +1,225 @@
+// This module performs no synchronization itself. Loom model in `loom_tests` replays load sequence of `RemoteConfig`
+// (check, lock, load again, decide, then fetch and store under lock) with this decision table under every interleaving.
+// Run loom model with `RUSTFLAGS="--cfg remote_config_loom" cargo test --lib revalidation`
+use std::time::{Duration, SystemTime};
+
+/// State of cached data at the moment of decision
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Freshness {
+    pub stale: bool,
+    pub must_revalidate: bool
+}
+
+/// State of revalidation lock
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Lock {
+    /// Revalidation is in progress in other task
+    Busy,
+    /// Lock is held by current task. Timestamp of last failed revalidation is included.
+    /// If `suppressed` is true, data provider must not be called (e.g. budget is exhausted)
+    Acquired { last_error: Option<SystemTime>, suppressed: bool }
+}
+
+/// What load attempt should do
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) enum Decision {
+    /// Data is fresh
+    Serve,
+    /// Data is stale, but can be used
+    ServeStale,
+    /// Wait for revalidation in progress and return its result
+    WaitForRevalidation,
+    /// Last revalidation attempt failed recently, return its error
+    ReturnError,
+    /// Start revalidation. If `wait` is true, its result must be returned, otherwise stale data is served
+    Revalidate { wait: bool }
+}
+
+/// Decides what to do with cached data.
+/// When lock is acquired, freshness must be checked again after acquiring it,
+/// because revalidation could finish between previous check and lock acquisition.
+pub(crate) fn decide(freshness: Freshness, lock: Lock, time: SystemTime, retry_interval: Duration) -> Decision {
+    if !freshness.stale {
+        return Decision::Serve;
+    }
+    match lock {
+        Lock::Busy if freshness.must_revalidate => Decision::WaitForRevalidation,
+        Lock::Busy => Decision::ServeStale,
+        // Degraded mode, stale data is served regardless of revalidation policy
+        Lock::Acquired { suppressed: true, .. } => Decision::ServeStale,
+        Lock::Acquired { last_error: Some(timestamp), .. } if time < timestamp + retry_interval => {
+            // Too early to retry after error
+            if freshness.must_revalidate {
+                Decision::ReturnError
+            } else {
+                Decision::ServeStale
+            }
+        },
+        Lock::Acquired { .. } => Decision::Revalidate { wait: freshness.must_revalidate }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+    use crate::revalidation::{decide, Decision, Freshness, Lock};
+
+    const RETRY: Duration = Duration::from_secs(1);
+
+    fn freshness(stale: bool, must_revalidate: bool) -> Freshness {
+        Freshness { stale, must_revalidate }
+    }
+
+    #[test]
+    fn decision_table() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(100);
+        let recent = Lock::Acquired { last_error: Some(now - RETRY / 2), suppressed: false };
+        let old = Lock::Acquired { last_error: Some(now - RETRY), suppressed: false };
+        let none = Lock::Acquired { last_error: None, suppressed: false };
+        let suppressed = Lock::Acquired { last_error: None, suppressed: true };
+
+        for must_revalidate in [false, true] {
+            for lock in [Lock::Busy, recent, old, none, suppressed] {
+                assert_eq!(decide(freshness(false, must_revalidate), lock, now, RETRY), Decision::Serve);
+            }
+        }
+
+        assert_eq!(decide(freshness(true, false), Lock::Busy, now, RETRY), Decision::ServeStale);
+        assert_eq!(decide(freshness(true, true), Lock::Busy, now, RETRY), Decision::WaitForRevalidation);
+
+        assert_eq!(decide(freshness(true, false), recent, now, RETRY), Decision::ServeStale);
+        assert_eq!(decide(freshness(true, true), recent, now, RETRY), Decision::ReturnError);
+
+        assert_eq!(decide(freshness(true, false), suppressed, now, RETRY), Decision::ServeStale);
+        assert_eq!(decide(freshness(true, true), suppressed, now, RETRY), Decision::ServeStale);
+
+        for lock in [old, none] {
+            assert_eq!(decide(freshness(true, false), lock, now, RETRY), Decision::Revalidate { wait: false });
+            assert_eq!(decide(freshness(true, true), lock, now, RETRY), Decision::Revalidate { wait: true });
+        }
+    }
+}
+
+#[cfg(all(test, remote_config_loom))]
+mod loom_tests {
+    use std::time::{Duration, SystemTime};
+    use loom::sync::{Arc, Mutex};
+    use loom::sync::atomic::{AtomicUsize, Ordering};
+    use loom::thread;
+    use crate::revalidation::{decide, Decision, Freshness, Lock};
+
+    /// Cached data. Version 0 is stale and must be revalidated
+    #[derive(Debug)]
+    struct Entry {
+        version: usize
+    }
+
+    /// State guarded by revalidation lock
+    struct Revalidator {
+        /// Timestamp of last failed revalidation
+        revalidation_error: Option<SystemTime>,
+        /// Whether data provider succeeds
+        succeeds: bool
+    }
+
+    /// Model of `RemoteConfig::load_shared` and `RemoteConfig::revalidate`.
+    /// Cached response is swapped atomically (`ArcSwap` in config) and read without revalidation lock,
+    /// so it is modeled with separate mutex, that is held only to load or store entry.
+    struct Model {
+        cached_response: Mutex<Arc<Entry>>,
+        revalidator: Mutex<Revalidator>,
+        fetches: AtomicUsize
+    }
+
+    impl Model {
+        fn new(succeeds: bool) -> Self {
+            Self {
+                cached_response: Mutex::new(Arc::new(Entry { version: 0 })),
+                revalidator: Mutex::new(Revalidator { revalidation_error: None, succeeds }),
+                fetches: AtomicUsize::new(0)
+            }
+        }
+
+        fn cached(&self) -> Arc<Entry> {
+            self.cached_response.lock().unwrap().clone()
+        }
+
+        fn freshness(entry: &Entry) -> Freshness {
+            Freshness { stale: entry.version == 0, must_revalidate: true }
+        }
+
+        /// Fetches new version and stores it, or records error, while revalidation lock is held
+        fn revalidate(&self, revalidator: &mut Revalidator) -> Result<usize, ()> {
+            self.fetches.fetch_add(1, Ordering::SeqCst);
+            if revalidator.succeeds {
+                *self.cached_response.lock().unwrap() = Arc::new(Entry { version: 1 });
+                revalidator.revalidation_error = None;
+                Ok(self.cached().version)
+            } else {
+                revalidator.revalidation_error = Some(SystemTime::UNIX_EPOCH);
+                Err(())
+            }
+        }
+
+        /// Returns served version, or error of revalidation
+        fn load(&self) -> Result<usize, ()> {
+            let time = SystemTime::UNIX_EPOCH;
+            let retry_interval = Duration::from_secs(1);
+
+            let curr = self.cached();
+            if !Self::freshness(&curr).stale {
+                return Ok(curr.version);
+            }
+
+            let (mut guard, curr) = match self.revalidator.try_lock() {
+                // Revalidation could finish before lock was acquired, so data is loaded again
+                Ok(guard) => (guard, self.cached()),
+                Err(_) => {
+                    assert_eq!(decide(Self::freshness(&curr), Lock::Busy, time, retry_interval), Decision::WaitForRevalidation);
+                    let guard = self.revalidator.lock().unwrap();
+                    return match guard.revalidation_error {
+                        Some(_) => Err(()),
+                        None => Ok(self.cached().version)
+                    };
+                }
+            };
+
+            let last_error = guard.revalidation_error;
+            match decide(Self::freshness(&curr), Lock::Acquired { last_error, suppressed: false }, time, retry_interval) {
+                Decision::Serve => Ok(curr.version),
+                Decision::ReturnError => Err(()),
+                Decision::Revalidate { wait: true } => self.revalidate(&mut guard),
+                other => panic!("unexpected decision {other:?}")
+            }
+        }
+    }
+
+    fn run_concurrent_loads(succeeds: bool) -> (Vec<Result<usize, ()>>, usize) {
+        let model = Arc::new(Model::new(succeeds));
+        let handles: Vec<_> = (0..2).map(|_| {
+            let model = model.clone();
+            thread::spawn(move || model.load())
+        }).collect();
+        let results = handles.into_iter().map(|handle| handle.join().unwrap()).collect();
+        (results, model.fetches.load(Ordering::SeqCst))
+    }
+
+    #[test]
+    fn concurrent_loads_fetch_once() {
+        loom::model(|| {
+            let (results, fetches) = run_concurrent_loads(true);
+            assert_eq!(results, vec![Ok(1), Ok(1)]);
+            assert_eq!(fetches, 1);
+        });
+    }
+
+    #[test]
+    fn concurrent_loads_share_revalidation_error() {
+        loom::model(|| {
+            // Load that comes after failed revalidation returns its error until retry interval passes
+            let (results, fetches) = run_concurrent_loads(false);
+            assert_eq!(results, vec![Err(()), Err(())]);
+            assert_eq!(fetches, 1);
+        });
+    }
+}
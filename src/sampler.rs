@@ -0,0 +1,162 @@
+use std::cell::Cell;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Weak};
+use std::sync::atomic::{AtomicU64, Ordering};
+use crate::config::RemoteConfig;
+use crate::data_providers::data_provider::DataProvider;
+use crate::random::random_u64;
+
+/// Data that sample rates can be read from
+pub trait SampleRates {
+    /// Sample rate stored under the key, if it is present and is a number
+    fn sample_rate(&self, key: &str) -> Option<f64>;
+}
+
+impl SampleRates for HashMap<String, String> {
+    fn sample_rate(&self, key: &str) -> Option<f64> {
+        self.get(key)?.trim().parse().ok()
+    }
+}
+
+impl SampleRates for BTreeMap<String, String> {
+    fn sample_rate(&self, key: &str) -> Option<f64> {
+        self.get(key)?.trim().parse().ok()
+    }
+}
+
+/// Key is a dot separated path
+#[cfg(feature = "json")]
+impl SampleRates for serde_json::Value {
+    fn sample_rate(&self, key: &str) -> Option<f64> {
+        key.split('.').try_fold(self, |value, segment| value.get(segment))?.as_f64()
+    }
+}
+
+/// Decides whether expensive operation (e.g. trace or profile) should run, with probability set by config.
+///
+/// Sample rate is kept in an atomic, so [`Sampler::sample`] doesn't lock or load config, and is cheap enough for hot paths.
+/// Sampler created with [`Sampler::from_config`] follows config in background task, and updates rate every time new version is activated.
+/// Clones share rate, and task stops after next change once all clones are dropped.
+/// # Examples
+/// ```ignore
+/// let sampler = Sampler::from_config(CONFIG.get().unwrap(), "trace_sample_rate");
+/// if sampler.sample() {
+///     record_trace();
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Sampler {
+    /// Operation is sampled if random number is below threshold
+    threshold: Arc<AtomicU64>
+}
+
+/// Rate 1.0 maps to `u64::MAX`, which is always sampled
+fn threshold(rate: f64) -> u64 {
+    if rate.is_nan() {
+        return 0;
+    }
+    (rate.clamp(0.0, 1.0) * u64::MAX as f64) as u64
+}
+
+/// Fast non-cryptographic random number (xorshift64*), with per-thread state
+fn fast_random() -> u64 {
+    thread_local! {
+        static STATE: Cell<u64> = Cell::new(random_u64() | 1);
+    }
+    STATE.with(|state| {
+        let mut x = state.get();
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        state.set(x);
+        x.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    })
+}
+
+impl Sampler {
+    /// Creates sampler with fixed rate, clamped to `[0, 1]`
+    pub fn new(rate: f64) -> Self {
+        Self { threshold: Arc::new(AtomicU64::new(threshold(rate))) }
+    }
+
+    /// Creates sampler with rate stored under the key of config data. Rate is zero while key is missing or is not a number.
+    ///
+    /// Must be called within tokio runtime, that runs task following config.
+    /// Task revalidates data as soon as it becomes stale, so rate is updated even if config is not loaded elsewhere.
+    pub fn from_config<Data, Provider>(config: &'static RemoteConfig<Data, Provider>, key: impl Into<String>) -> Self
+    where Data: SampleRates + Send + Sync + 'static, Provider: DataProvider<Data> + Send + 'static
+    {
+        let key = key.into();
+        let sampler = Self::new(0.0);
+        let shared = Arc::downgrade(&sampler.threshold);
+        tokio::spawn(RemoteConfig::follow_versions(config, move |data| update(&shared, data.sample_rate(&key))));
+        sampler
+    }
+
+    /// Returns `true` with probability equal to current rate
+    pub fn sample(&self) -> bool {
+        let threshold = self.threshold.load(Ordering::Relaxed);
+        threshold == u64::MAX || fast_random() < threshold
+    }
+
+    /// Current sample rate
+    pub fn rate(&self) -> f64 {
+        self.threshold.load(Ordering::Relaxed) as f64 / u64::MAX as f64
+    }
+}
+
+/// Stores new rate. Returns `false` if all samplers were dropped
+fn update(shared: &Weak<AtomicU64>, rate: Option<f64>) -> bool {
+    let Some(shared) = shared.upgrade() else {
+        return false;
+    };
+    shared.store(threshold(rate.unwrap_or_default()), Ordering::Relaxed);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use crate::config::RemoteConfig;
+    use crate::data_providers::memory::InMemoryDataProvider;
+    use crate::sampler::Sampler;
+
+    #[test]
+    fn fixed_rates() {
+        assert!((0..1000).all(|_| Sampler::new(1.0).sample()));
+        assert!(!(0..1000).any(|_| Sampler::new(0.0).sample()));
+        assert!(!Sampler::new(f64::NAN).sample());
+        assert_eq!(Sampler::new(7.0).rate(), 1.0);
+
+        let sampled = (0..10_000).filter(|_| Sampler::new(0.25).sample()).count();
+        assert!((2000..3000).contains(&sampled), "{sampled} of 10000 sampled");
+    }
+
+    #[tokio::test]
+    async fn follows_config() {
+        let data_provider = InMemoryDataProvider::new(HashMap::from([("trace_sample_rate".to_string(), "1".to_string())]));
+        let handle = data_provider.clone();
+        #[cfg(feature = "tracing")]
+        let config = RemoteConfig::builder("Sampled".to_string(), data_provider).build().await.unwrap();
+        #[cfg(not(feature = "tracing"))]
+        let config = RemoteConfig::builder(data_provider).build().await.unwrap();
+        let config = Box::leak(Box::new(config));
+
+        let sampler = Sampler::from_config(config, "trace_sample_rate");
+        assert!(sampler.sample());
+
+        handle.set(HashMap::from([("trace_sample_rate".to_string(), "0.5".to_string())]));
+        config.refresh().await.unwrap();
+        for _ in 0..10 {
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(sampler.rate(), 0.5);
+
+        handle.set(HashMap::new());
+        config.refresh().await.unwrap();
+        for _ in 0..10 {
+            tokio::task::yield_now().await;
+        }
+        assert!(!sampler.sample());
+    }
+}
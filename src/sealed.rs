@@ -0,0 +1,148 @@
+use std::error::Error;
+use std::fmt::{Debug, Formatter};
+use std::io::{self, Write};
+use std::marker::PhantomData;
+use std::ops::Deref;
+use memfd::{FileSeal, MemfdOptions};
+use memmap2::Mmap;
+use crate::data_providers::data_provider::{DataLoadResult, DataProvider, Revalidation};
+
+/// Immutable bytes stored in sealed anonymous memory file (memfd).
+/// File is sealed against writes and resizing, and is mapped read-only,
+/// so in-process tampering with data requires changing memory protection first.
+///
+/// Storage is opt-in: select it with [`RemoteConfigBuilder::with_sealed_storage`](crate::config::RemoteConfigBuilder::with_sealed_storage)
+/// for data provider that loads bytes, or produce it in parser of data provider (e.g. file data provider) or custom extractor.
+/// Deserialize values that borrow from it when they are needed.
+/// # Examples
+/// ```
+/// use remote_config::sealed::SealedBytes;
+///
+/// let bytes = SealedBytes::new(br#"{"key": "value"}"#).unwrap();
+/// assert_eq!(&bytes[..], br#"{"key": "value"}"#);
+/// ```
+pub struct SealedBytes {
+    map: Mmap
+}
+
+impl SealedBytes {
+    /// Copies bytes into new sealed memory file
+    /// # Errors
+    /// If memory file can't be created, written, sealed or mapped
+    pub fn new(bytes: &[u8]) -> io::Result<Self> {
+        let memfd = MemfdOptions::default()
+            .allow_sealing(true)
+            .close_on_exec(true)
+            .create("remote-config")
+            .map_err(io::Error::other)?;
+        memfd.as_file().write_all(bytes)?;
+        memfd.add_seals(&[FileSeal::SealShrink, FileSeal::SealGrow, FileSeal::SealWrite, FileSeal::SealSeal])
+            .map_err(io::Error::other)?;
+
+        // SAFETY: file is sealed, so its contents and size can't change while mapping exists
+        let map = unsafe { Mmap::map(memfd.as_file())? };
+        Ok(Self { map })
+    }
+}
+
+impl Deref for SealedBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.map
+    }
+}
+
+impl AsRef<[u8]> for SealedBytes {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl Debug for SealedBytes {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SealedBytes").field("len", &self.map.len()).finish_non_exhaustive()
+    }
+}
+
+/// This data provider copies bytes loaded by inner provider into [`SealedBytes`]. Created with
+/// [`RemoteConfigBuilder::with_sealed_storage`](crate::config::RemoteConfigBuilder::with_sealed_storage).
+pub struct SealingDataProvider<Data, Provider> {
+    inner: Provider,
+    data_type: PhantomData<Data>
+}
+
+impl <Data, Provider> SealingDataProvider<Data, Provider> {
+    /// Creates data provider, that seals data loaded by inner provider
+    pub fn new(inner: Provider) -> Self {
+        Self { inner, data_type: PhantomData }
+    }
+
+    /// Inner data provider
+    pub fn inner(&self) -> &Provider {
+        &self.inner
+    }
+}
+
+fn seal<Data: AsRef<[u8]>>(result: DataLoadResult<Data>) -> io::Result<DataLoadResult<SealedBytes>> {
+    let mut sealed = DataLoadResult::new(SealedBytes::new(result.data.as_ref())?, result.must_revalidate, result.valid_until);
+    sealed.metadata = result.metadata;
+    Ok(sealed)
+}
+
+impl <Data, Provider> DataProvider<SealedBytes> for SealingDataProvider<Data, Provider>
+where Data: AsRef<[u8]> + From<Vec<u8>> + Send + Sync, Provider: DataProvider<Data> + Send + Sync
+{
+    /// Loads data with inner provider and seals it
+    /// # Errors
+    /// If inner provider fails, or memory file can't be created
+    async fn load_data(&self) -> Result<DataLoadResult<SealedBytes>, Box<dyn Error + Send + Sync>> {
+        Ok(seal(self.inner.load_data().await?)?)
+    }
+
+    /// Revalidates copy of sealed data with inner provider, and seals modified data
+    async fn revalidate_data<'a>(&'a self, current: &'a DataLoadResult<SealedBytes>) -> Result<Revalidation<SealedBytes>, Box<dyn Error + Send + Sync>> {
+        let mut copy = DataLoadResult::new(Data::from(current.data.to_vec()), current.must_revalidate, current.valid_until);
+        copy.metadata = current.metadata.clone();
+        Ok(match self.inner.revalidate_data(&copy).await? {
+            Revalidation::Modified(result) => Revalidation::Modified(seal(result)?),
+            Revalidation::NotModified { must_revalidate, valid_until, invalidation, fetched_at } =>
+                Revalidation::NotModified { must_revalidate, valid_until, invalidation, fetched_at }
+        })
+    }
+}
+
+impl <Data, Provider: Debug> Debug for SealingDataProvider<Data, Provider> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SealingDataProvider").field("inner", &self.inner).finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::RemoteConfig;
+    use crate::data_providers::memory::InMemoryDataProvider;
+    use crate::sealed::SealedBytes;
+
+    #[test]
+    fn sealed_bytes() {
+        let bytes = SealedBytes::new(b"config").unwrap();
+        assert_eq!(&bytes[..], b"config");
+        assert!(SealedBytes::new(b"").unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn builder_keeps_loaded_data_sealed() {
+        let data_provider = InMemoryDataProvider::new(b"first".to_vec());
+        let handle = data_provider.clone();
+        #[cfg(feature = "tracing")]
+        let builder = RemoteConfig::builder("Sealed".to_string(), data_provider);
+        #[cfg(not(feature = "tracing"))]
+        let builder = RemoteConfig::builder(data_provider);
+        let config: &_ = Box::leak(Box::new(builder.with_sealed_storage().build().await.unwrap()));
+        assert_eq!(&config.load().await.unwrap()[..], b"first");
+
+        handle.set(b"second".to_vec());
+        assert_eq!(&config.refresh().await.unwrap()[..], b"second");
+    }
+}
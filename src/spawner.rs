@@ -0,0 +1,89 @@
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use tokio::task::JoinSet;
+
+/// Background task started by [`RemoteConfig`](crate::config::RemoteConfig)
+pub type Task = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
+
+/// Runs background refresh tasks of [`RemoteConfig`](crate::config::RemoteConfig).
+/// Custom implementations allow applications that use structured concurrency to own these tasks,
+/// and to await or abort them on graceful shutdown.
+///
+/// If task is dropped before completion, revalidation is treated as failed with [`TaskCancelled`] error.
+pub trait Spawner: Debug + Send + Sync {
+    /// Starts task
+    fn spawn(&self, task: Task);
+
+    /// Starts task with specified name (e.g. for tokio-console). Default implementation ignores name.
+    fn spawn_named(&self, name: &str, task: Task) {
+        let _ = name;
+        self.spawn(task)
+    }
+}
+
+/// Spawner that detaches tasks with [`tokio::spawn`]. Used by default.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TokioSpawner;
+
+impl Spawner for TokioSpawner {
+    fn spawn(&self, task: Task) {
+        tokio::spawn(task);
+    }
+
+    /// Task is named only if tokio is built with `--cfg tokio_unstable`
+    #[cfg(all(tokio_unstable, feature = "tracing"))]
+    fn spawn_named(&self, name: &str, task: Task) {
+        tokio::task::Builder::new().name(name).spawn(task).expect("task is spawned inside tokio runtime");
+    }
+}
+
+/// Spawns tasks into shared [`JoinSet`]
+impl Spawner for Arc<Mutex<JoinSet<()>>> {
+    fn spawn(&self, task: Task) {
+        self.lock().unwrap().spawn(task);
+    }
+
+    #[cfg(all(tokio_unstable, feature = "tracing"))]
+    fn spawn_named(&self, name: &str, task: Task) {
+        self.lock().unwrap().build_task().name(name).spawn(task).expect("task is spawned inside tokio runtime");
+    }
+}
+
+/// Spawner that passes tasks to closure, e.g. to `TaskTracker::spawn` from tokio-util
+/// # Examples
+/// ```
+/// use remote_config::spawner::{FnSpawner, Task};
+///
+/// let spawner = FnSpawner(|task: Task| {
+///     tokio::spawn(task);
+/// });
+/// ```
+#[derive(Clone, Copy)]
+pub struct FnSpawner<F>(pub F);
+
+impl <F> Debug for FnSpawner<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("FnSpawner")
+    }
+}
+
+impl <F: Fn(Task) + Send + Sync> Spawner for FnSpawner<F> {
+    fn spawn(&self, task: Task) {
+        (self.0)(task)
+    }
+}
+
+/// Revalidation task was dropped before completion (e.g. aborted on shutdown or panicked)
+#[derive(Debug)]
+pub struct TaskCancelled;
+
+impl Display for TaskCancelled {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "revalidation task was dropped before completion")
+    }
+}
+
+impl Error for TaskCancelled {}
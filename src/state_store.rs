@@ -0,0 +1,578 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Debug, Display};
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tokio::task::AbortHandle;
+#[cfg(feature = "redb")]
+use redb::{ReadableDatabase, ReadableTable};
+
+/// Persistent storage for state of config, that survives restarts: last snapshot of data, and validators that allow
+/// to revalidate it (e.g. `ETag`). Both are opaque bytes encoded by their user, and stored under the same key.
+///
+/// Implement this trait to keep state in storage of target platform (e.g. key-value store of mobile app),
+/// built-in implementations are [`FileStateStore`], `SledStateStore` (`sled` feature) and `RedbStateStore` (`redb` feature).
+/// HTTP data provider uses it with `HttpDataProvider::with_state_store`.
+/// # Errors
+/// Any error can be returned by save methods. It is reported with tracing (if enabled), but does not fail data load.
+pub trait StateStore: Debug + Send + Sync {
+    /// Loads stored snapshot
+    fn load_snapshot(&self, key: &str) -> Option<Vec<u8>>;
+    /// Saves snapshot, replacing previous one
+    fn save_snapshot(&self, key: &str, snapshot: &[u8]) -> Result<(), Box<dyn Error + Send + Sync>>;
+    /// Loads validators of stored snapshot
+    fn load_validators(&self, key: &str) -> Option<Vec<u8>>;
+    /// Saves validators, replacing previous ones. Validators are saved after snapshot they belong to.
+    fn save_validators(&self, key: &str, validators: &[u8]) -> Result<(), Box<dyn Error + Send + Sync>>;
+}
+
+/// File name is key with all characters except ASCII alphanumerics, `-` and `.` escaped
+pub(crate) fn escape_file_name(key: &str) -> String {
+    let mut name = String::with_capacity(key.len() + 8);
+    for byte in key.bytes() {
+        if byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'.' {
+            name.push(byte as char);
+        } else {
+            name.push_str(&format!("_{byte:02x}"));
+        }
+    }
+    name
+}
+
+/// Replaces file atomically, by writing temporary file and renaming it
+pub(crate) fn write_atomically(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    let tmp = path.with_extension(format!("{}.tmp", std::process::id()));
+    let mut file = fs::File::create(&tmp)?;
+    file.write_all(bytes)?;
+    file.sync_all()?;
+    fs::rename(tmp, path)
+}
+
+/// Limits of persisted entries (e.g. snapshots of URLs or contexts that are no longer used), enforced by compaction.
+/// Entries are ranked from the most recently saved one, and entries that exceed any limit are removed.
+/// Default policy has no limits.
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use remote_config::state_store::{FileStateStore, RetentionPolicy};
+///
+/// let policy = RetentionPolicy::new().with_max_count(16).with_max_age(Duration::from_secs(30 * 24 * 60 * 60));
+/// let store = FileStateStore::new("/var/lib/agent/config").with_retention(policy);
+/// ```
+/// `SledStateStore` and `RedbStateStore` enforce the same policy, using time of last save, that they store with state.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    max_count: Option<usize>,
+    max_age: Option<Duration>,
+    max_bytes: Option<u64>
+}
+
+impl RetentionPolicy {
+    /// Creates policy without limits
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Maximum number of entries
+    pub fn with_max_count(mut self, max_count: usize) -> Self {
+        self.max_count = Some(max_count);
+        self
+    }
+
+    /// Maximum time since entry was saved
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Maximum total size of entries
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Keys of entries, that exceed policy. Every entry is key, time of last save and size.
+    pub(crate) fn expired<Key>(&self, mut entries: Vec<(Key, SystemTime, u64)>, now: SystemTime) -> Vec<Key> {
+        entries.sort_by_key(|(_, saved, _)| Reverse(*saved));
+        let mut total_bytes = 0;
+        entries.into_iter().enumerate().filter_map(|(index, (key, saved, bytes))| {
+            total_bytes += bytes;
+            let retained = self.max_count.is_none_or(|max_count| index < max_count) &&
+                self.max_age.is_none_or(|max_age| now.duration_since(saved).unwrap_or_default() <= max_age) &&
+                self.max_bytes.is_none_or(|max_bytes| total_bytes <= max_bytes);
+            (!retained).then_some(key)
+        }).collect()
+    }
+}
+
+/// Temporary files older than this are left by interrupted writes
+const ABANDONED_TMP_AGE: Duration = Duration::from_secs(60);
+
+/// Removes file, that may be already removed by concurrent compaction
+fn remove_file(path: &Path) -> std::io::Result<()> {
+    match fs::remove_file(path) {
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+        result => result
+    }
+}
+
+/// Removes files of entries that exceed retention policy, and abandoned temporary files.
+/// Files are grouped into entries by key returned for file name. Returns number of removed entries.
+/// Files removed by another process during compaction are skipped.
+pub(crate) fn compact_dir(dir: &Path, policy: &RetentionPolicy, entry_key: fn(&str) -> &str) -> std::io::Result<usize> {
+    let now = SystemTime::now();
+    // Key -> (files, last save, total size)
+    let mut entries: HashMap<String, (Vec<PathBuf>, SystemTime, u64)> = HashMap::new();
+    let files = match fs::read_dir(dir) {
+        Ok(files) => files,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(0),
+        Err(err) => return Err(err)
+    };
+    for file in files {
+        let file = file?;
+        let metadata = match file.metadata() {
+            Ok(metadata) => metadata,
+            Err(err) if err.kind() == ErrorKind::NotFound => continue,
+            Err(err) => return Err(err)
+        };
+        let Some(name) = file.file_name().to_str().map(str::to_string).filter(|_| metadata.is_file()) else {
+            continue;
+        };
+        let modified = metadata.modified()?;
+        if name.ends_with(".tmp") {
+            if now.duration_since(modified).unwrap_or_default() > ABANDONED_TMP_AGE {
+                remove_file(&file.path())?;
+            }
+            continue;
+        }
+        let entry = entries.entry(entry_key(&name).to_string()).or_insert((Vec::new(), SystemTime::UNIX_EPOCH, 0));
+        entry.0.push(file.path());
+        entry.1 = entry.1.max(modified);
+        entry.2 += metadata.len();
+    }
+
+    let expired = policy.expired(entries.into_values().collect(), now);
+    for file in expired.iter().flatten() {
+        remove_file(file)?;
+    }
+    Ok(expired.len())
+}
+
+/// Runs compaction on blocking thread pool with specified interval, until task is aborted
+pub(crate) fn spawn_gc<CompactError: Display>(compact: impl Fn() -> Result<usize, CompactError> + Send + Sync + 'static, interval: Duration) -> AbortHandle {
+    let compact = std::sync::Arc::new(compact);
+    tokio::spawn(async move {
+        let mut ticks = tokio::time::interval(interval);
+        loop {
+            ticks.tick().await;
+            let compact = compact.clone();
+            match tokio::task::spawn_blocking(move || compact().map_err(|err| err.to_string())).await {
+                Ok(Ok(_)) => {},
+                Ok(Err(_err)) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!("Failed to compact persisted config state: {_err}");
+                },
+                Err(_) => return
+            }
+        }
+    }).abort_handle()
+}
+
+/// Milliseconds since Unix epoch, that databases store as time of last save
+#[cfg(any(feature = "sled", feature = "redb"))]
+fn saved_millis(time: SystemTime) -> u64 {
+    time.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+}
+
+/// Stores state in files inside specified directory, two files per key (`.snapshot` and `.validators`).
+/// Files are replaced atomically, so the directory can be shared by several processes.
+///
+/// Snapshot is stored as is, so directory permissions should match sensitivity of config data.
+/// Keys are never removed on their own, use [`FileStateStore::with_retention`] to limit accumulated state.
+#[derive(Debug, Clone)]
+pub struct FileStateStore {
+    dir: PathBuf,
+    retention: RetentionPolicy
+}
+
+impl FileStateStore {
+    /// Creates store in specified directory. Directory is created on first save if it doesn't exist.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into(), retention: RetentionPolicy::default() }
+    }
+
+    /// Retention policy enforced by [`FileStateStore::compact`]. Snapshot and validators of key are one entry.
+    pub fn with_retention(mut self, policy: RetentionPolicy) -> Self {
+        self.retention = policy;
+        self
+    }
+
+    /// Removes keys that exceed retention policy, and temporary files left by interrupted saves.
+    /// Returns number of removed keys.
+    /// # Errors
+    /// If directory can't be read or file can't be removed
+    pub fn compact(&self) -> std::io::Result<usize> {
+        compact_dir(&self.dir, &self.retention, Self::entry_key)
+    }
+
+    /// Starts task, that compacts store with specified interval (first time immediately) until it is aborted
+    pub fn spawn_gc(&self, interval: Duration) -> AbortHandle {
+        let store = self.clone();
+        spawn_gc(move || store.compact(), interval)
+    }
+
+    fn entry_key(file_name: &str) -> &str {
+        file_name.strip_suffix(".snapshot").or_else(|| file_name.strip_suffix(".validators")).unwrap_or(file_name)
+    }
+
+    fn path(&self, key: &str, extension: &str) -> PathBuf {
+        self.dir.join(format!("{}.{extension}", escape_file_name(key)))
+    }
+
+    fn save(&self, path: PathBuf, bytes: &[u8]) -> Result<(), Box<dyn Error + Send + Sync>> {
+        fs::create_dir_all(&self.dir)?;
+        write_atomically(&path, bytes)?;
+        Ok(())
+    }
+}
+
+impl StateStore for FileStateStore {
+    fn load_snapshot(&self, key: &str) -> Option<Vec<u8>> {
+        fs::read(self.path(key, "snapshot")).ok()
+    }
+
+    fn save_snapshot(&self, key: &str, snapshot: &[u8]) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.save(self.path(key, "snapshot"), snapshot)
+    }
+
+    fn load_validators(&self, key: &str) -> Option<Vec<u8>> {
+        fs::read(self.path(key, "validators")).ok()
+    }
+
+    fn save_validators(&self, key: &str, validators: &[u8]) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.save(self.path(key, "validators"), validators)
+    }
+}
+
+/// Stores state in [sled](https://crates.io/crates/sled) database, in `snapshots` and `validators` trees,
+/// and time of last save of every key in `saved` tree. Every save is flushed to disk.
+/// Keys are never removed on their own, use [`SledStateStore::with_retention`] to limit accumulated state.
+#[cfg(feature = "sled")]
+#[derive(Debug, Clone)]
+pub struct SledStateStore {
+    snapshots: sled::Tree,
+    validators: sled::Tree,
+    saved: sled::Tree,
+    retention: RetentionPolicy
+}
+
+#[cfg(feature = "sled")]
+impl SledStateStore {
+    /// Creates store in opened database
+    /// # Errors
+    /// If trees can't be opened
+    pub fn new(db: &sled::Db) -> sled::Result<Self> {
+        Ok(Self {
+            snapshots: db.open_tree("snapshots")?,
+            validators: db.open_tree("validators")?,
+            saved: db.open_tree("saved")?,
+            retention: RetentionPolicy::default()
+        })
+    }
+
+    /// Retention policy enforced by [`SledStateStore::compact`]. Snapshot and validators of key are one entry.
+    pub fn with_retention(mut self, policy: RetentionPolicy) -> Self {
+        self.retention = policy;
+        self
+    }
+
+    /// Removes keys that exceed retention policy, and returns their number.
+    /// Keys saved before time of save was stored (by older versions) are considered saved at first compaction.
+    /// # Errors
+    /// If database can't be read or written
+    pub fn compact(&self) -> sled::Result<usize> {
+        let now = SystemTime::now();
+        let mut entries: HashMap<sled::IVec, (SystemTime, u64)> = HashMap::new();
+        for tree in [&self.snapshots, &self.validators] {
+            for entry in tree.iter() {
+                let (key, value) = entry?;
+                entries.entry(key).or_insert((now, 0)).1 += value.len() as u64;
+            }
+        }
+        for (key, (saved, _)) in &mut entries {
+            match self.saved.get(key)?.and_then(|millis| <[u8; 8]>::try_from(millis.as_ref()).ok()) {
+                Some(millis) => *saved = SystemTime::UNIX_EPOCH + Duration::from_millis(u64::from_be_bytes(millis)),
+                None => {
+                    self.saved.insert(key, &saved_millis(now).to_be_bytes())?;
+                }
+            }
+        }
+
+        let expired = self.retention.expired(entries.into_iter().map(|(key, (saved, bytes))| (key, saved, bytes)).collect(), now);
+        for key in &expired {
+            self.snapshots.remove(key)?;
+            self.validators.remove(key)?;
+            self.saved.remove(key)?;
+        }
+        self.saved.flush()?;
+        Ok(expired.len())
+    }
+
+    /// Starts task, that compacts store with specified interval (first time immediately) until it is aborted
+    pub fn spawn_gc(&self, interval: Duration) -> AbortHandle {
+        let store = self.clone();
+        spawn_gc(move || store.compact(), interval)
+    }
+
+    fn save(&self, tree: &sled::Tree, key: &str, bytes: &[u8]) -> Result<(), Box<dyn Error + Send + Sync>> {
+        tree.insert(key, bytes)?;
+        self.saved.insert(key, &saved_millis(SystemTime::now()).to_be_bytes())?;
+        tree.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "sled")]
+impl StateStore for SledStateStore {
+    fn load_snapshot(&self, key: &str) -> Option<Vec<u8>> {
+        Some(self.snapshots.get(key).ok()??.to_vec())
+    }
+
+    fn save_snapshot(&self, key: &str, snapshot: &[u8]) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.save(&self.snapshots, key, snapshot)
+    }
+
+    fn load_validators(&self, key: &str) -> Option<Vec<u8>> {
+        Some(self.validators.get(key).ok()??.to_vec())
+    }
+
+    fn save_validators(&self, key: &str, validators: &[u8]) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.save(&self.validators, key, validators)
+    }
+}
+
+#[cfg(feature = "redb")]
+const REDB_SNAPSHOTS: redb::TableDefinition<&str, &[u8]> = redb::TableDefinition::new("snapshots");
+#[cfg(feature = "redb")]
+const REDB_VALIDATORS: redb::TableDefinition<&str, &[u8]> = redb::TableDefinition::new("validators");
+/// Milliseconds since Unix epoch of last save of key
+#[cfg(feature = "redb")]
+const REDB_SAVED: redb::TableDefinition<&str, u64> = redb::TableDefinition::new("saved");
+
+/// Stores state in [redb](https://crates.io/crates/redb) database, in `snapshots` and `validators` tables,
+/// and time of last save of every key in `saved` table. Every save is committed in its own write transaction.
+/// Keys are never removed on their own, use [`RedbStateStore::with_retention`] to limit accumulated state.
+#[cfg(feature = "redb")]
+#[derive(Debug, Clone)]
+pub struct RedbStateStore {
+    db: std::sync::Arc<redb::Database>,
+    retention: RetentionPolicy
+}
+
+#[cfg(feature = "redb")]
+impl RedbStateStore {
+    /// Creates store in opened database
+    pub fn new(db: redb::Database) -> Self {
+        Self { db: std::sync::Arc::new(db), retention: RetentionPolicy::default() }
+    }
+
+    /// Retention policy enforced by [`RedbStateStore::compact`]. Snapshot and validators of key are one entry.
+    pub fn with_retention(mut self, policy: RetentionPolicy) -> Self {
+        self.retention = policy;
+        self
+    }
+
+    /// Removes keys that exceed retention policy in one write transaction, and returns their number.
+    /// Keys saved before time of save was stored (by older versions) are considered saved at first compaction.
+    /// # Errors
+    /// If database can't be read or written
+    pub fn compact(&self) -> Result<usize, redb::Error> {
+        let now = SystemTime::now();
+        let transaction = self.db.begin_write()?;
+        let expired = {
+            let mut saved = transaction.open_table(REDB_SAVED)?;
+            let mut entries: HashMap<String, (SystemTime, u64)> = HashMap::new();
+            for definition in [REDB_SNAPSHOTS, REDB_VALIDATORS] {
+                for entry in transaction.open_table(definition)?.iter()? {
+                    let (key, value) = entry?;
+                    entries.entry(key.value().to_string()).or_insert((now, 0)).1 += value.value().len() as u64;
+                }
+            }
+            for (key, (time, _)) in &mut entries {
+                let millis = saved.get(key.as_str())?.map(|millis| millis.value());
+                match millis {
+                    Some(millis) => *time = SystemTime::UNIX_EPOCH + Duration::from_millis(millis),
+                    None => {
+                        saved.insert(key.as_str(), saved_millis(now))?;
+                    }
+                }
+            }
+
+            let expired = self.retention.expired(entries.into_iter().map(|(key, (time, bytes))| (key, time, bytes)).collect(), now);
+            let (mut snapshots, mut validators) = (transaction.open_table(REDB_SNAPSHOTS)?, transaction.open_table(REDB_VALIDATORS)?);
+            for key in &expired {
+                snapshots.remove(key.as_str())?;
+                validators.remove(key.as_str())?;
+                saved.remove(key.as_str())?;
+            }
+            expired.len()
+        };
+        transaction.commit()?;
+        Ok(expired)
+    }
+
+    /// Starts task, that compacts store with specified interval (first time immediately) until it is aborted
+    pub fn spawn_gc(&self, interval: Duration) -> AbortHandle {
+        let store = self.clone();
+        spawn_gc(move || store.compact(), interval)
+    }
+
+    fn load(&self, table: redb::TableDefinition<&str, &[u8]>, key: &str) -> Option<Vec<u8>> {
+        let transaction = self.db.begin_read().ok()?;
+        // Table does not exist until first save
+        let table = transaction.open_table(table).ok()?;
+        Some(table.get(key).ok()??.value().to_vec())
+    }
+
+    fn save(&self, table: redb::TableDefinition<&str, &[u8]>, key: &str, bytes: &[u8]) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let transaction = self.db.begin_write()?;
+        transaction.open_table(table)?.insert(key, bytes)?;
+        transaction.open_table(REDB_SAVED)?.insert(key, saved_millis(SystemTime::now()))?;
+        transaction.commit()?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "redb")]
+impl StateStore for RedbStateStore {
+    fn load_snapshot(&self, key: &str) -> Option<Vec<u8>> {
+        self.load(REDB_SNAPSHOTS, key)
+    }
+
+    fn save_snapshot(&self, key: &str, snapshot: &[u8]) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.save(REDB_SNAPSHOTS, key, snapshot)
+    }
+
+    fn load_validators(&self, key: &str) -> Option<Vec<u8>> {
+        self.load(REDB_VALIDATORS, key)
+    }
+
+    fn save_validators(&self, key: &str, validators: &[u8]) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.save(REDB_VALIDATORS, key, validators)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::state_store::{FileStateStore, StateStore};
+
+    fn round_trip(store: &dyn StateStore) {
+        assert_eq!(store.load_snapshot("https://example.com/cfg"), None);
+        store.save_snapshot("https://example.com/cfg", b"{}").unwrap();
+        store.save_validators("https://example.com/cfg", b"etag: \"1\"").unwrap();
+        store.save_snapshot("https://example.com/cfg", b"{\"a\": 1}").unwrap();
+        assert_eq!(store.load_snapshot("https://example.com/cfg").as_deref(), Some(&b"{\"a\": 1}"[..]));
+        assert_eq!(store.load_validators("https://example.com/cfg").as_deref(), Some(&b"etag: \"1\""[..]));
+        assert_eq!(store.load_validators("https://example.com/other"), None);
+    }
+
+    #[test]
+    fn compaction_enforces_retention() {
+        use std::time::{Duration, SystemTime};
+        use crate::state_store::RetentionPolicy;
+
+        let dir = std::env::temp_dir().join(format!("remote_config_retention_{}", std::process::id()));
+        let store = FileStateStore::new(&dir);
+        // Keys are saved a minute apart, "k0" is the oldest
+        for index in 0..4 {
+            let key = format!("k{index}");
+            store.save_snapshot(&key, &[0; 100]).unwrap();
+            store.save_validators(&key, &[0; 10]).unwrap();
+            let modified = SystemTime::now() - Duration::from_secs(60 * (4 - index));
+            for extension in ["snapshot", "validators"] {
+                std::fs::File::options().write(true).open(dir.join(format!("{key}.{extension}"))).unwrap().set_modified(modified).unwrap();
+            }
+        }
+        std::fs::write(dir.join("k9.snapshot.1.tmp"), b"").unwrap();
+        std::fs::File::options().write(true).open(dir.join("k9.snapshot.1.tmp")).unwrap()
+            .set_modified(SystemTime::now() - Duration::from_secs(3600)).unwrap();
+
+        assert_eq!(store.compact().unwrap(), 0);
+        assert_eq!(store.clone().with_retention(RetentionPolicy::new().with_max_age(Duration::from_secs(210))).compact().unwrap(), 1);
+        assert_eq!(store.load_snapshot("k0"), None);
+        assert!(!dir.join("k9.snapshot.1.tmp").exists());
+        assert_eq!(store.clone().with_retention(RetentionPolicy::new().with_max_bytes(250)).compact().unwrap(), 1);
+        assert_eq!(store.load_validators("k1"), None);
+        assert_eq!(store.clone().with_retention(RetentionPolicy::new().with_max_count(1)).compact().unwrap(), 1);
+        assert_eq!(store.load_snapshot("k2"), None);
+        assert!(store.load_snapshot("k3").is_some());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn file_store_round_trip() {
+        let dir = std::env::temp_dir().join(format!("remote_config_state_{}", std::process::id()));
+        round_trip(&FileStateStore::new(&dir));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "sled")]
+    fn sled_store_round_trip() {
+        let db = sled::Config::new().temporary(true).open().unwrap();
+        round_trip(&crate::state_store::SledStateStore::new(&db).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "redb")]
+    fn redb_store_round_trip() {
+        let db = redb::Database::builder().create_with_backend(redb::backends::InMemoryBackend::new()).unwrap();
+        round_trip(&crate::state_store::RedbStateStore::new(db));
+    }
+
+    /// Saves three keys, "k0" is the oldest
+    #[cfg(any(feature = "sled", feature = "redb"))]
+    fn save_keys(store: &dyn StateStore) {
+        for index in 0..3 {
+            let key = format!("k{index}");
+            store.save_snapshot(&key, &[0; 100]).unwrap();
+            store.save_validators(&key, &[0; 10]).unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "sled")]
+    fn sled_compaction_enforces_retention() {
+        use crate::state_store::{RetentionPolicy, SledStateStore};
+
+        let db = sled::Config::new().temporary(true).open().unwrap();
+        let store = SledStateStore::new(&db).unwrap();
+        save_keys(&store);
+        assert_eq!(store.compact().unwrap(), 0);
+        assert_eq!(store.clone().with_retention(RetentionPolicy::new().with_max_bytes(250)).compact().unwrap(), 1);
+        assert_eq!(store.load_snapshot("k0"), None);
+        assert_eq!(store.clone().with_retention(RetentionPolicy::new().with_max_count(1)).compact().unwrap(), 1);
+        assert_eq!(store.load_validators("k1"), None);
+        assert!(store.load_snapshot("k2").is_some());
+    }
+
+    #[test]
+    #[cfg(feature = "redb")]
+    fn redb_compaction_enforces_retention() {
+        use crate::state_store::{RedbStateStore, RetentionPolicy};
+
+        let db = redb::Database::builder().create_with_backend(redb::backends::InMemoryBackend::new()).unwrap();
+        let store = RedbStateStore::new(db);
+        assert_eq!(store.compact().unwrap(), 0);
+        save_keys(&store);
+        assert_eq!(store.clone().with_retention(RetentionPolicy::new().with_max_bytes(250)).compact().unwrap(), 1);
+        assert_eq!(store.load_snapshot("k0"), None);
+        assert_eq!(store.clone().with_retention(RetentionPolicy::new().with_max_count(1)).compact().unwrap(), 1);
+        assert_eq!(store.load_validators("k1"), None);
+        assert!(store.load_snapshot("k2").is_some());
+    }
+}
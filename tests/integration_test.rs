@@ -177,4 +177,47 @@ async fn test_without_must_revalidate() {
     {
         test_arc_with_cache_control(false, Duration::from_secs(1)).await;
     }
-}
\ No newline at end of file
+}
+
+#[cfg(feature = "chaos")]
+#[tokio::test]
+async fn test_chaos() {
+    use std::error::Error;
+    use remote_config::chaos::InjectedFailure;
+
+    static CONF: OnceCell<RConfTest> = OnceCell::const_new();
+    static MOCK_DATA: MockData = MockData{test_number: 999};
+
+    let mut server = mockito::Server::new_async().await;
+
+    let mock = server
+        .mock("GET", "/mock")
+        .with_header("Content-Type", "application/json")
+        .with_header("Cache-Control", "private, max-age=60, must-revalidate")
+        .with_body(serde_json::to_string(&MOCK_DATA).unwrap())
+        .expect(2)
+        .create_async()
+        .await;
+
+    let url = server.url() + "/mock";
+    let conf = CONF.get_or_init(|| init_config(&url)).await;
+
+    conf.chaos().force_stale();
+    conf.chaos().fail_next_loads(1);
+
+    // Injected failure is returned, because data must be revalidated
+    let err = conf.load().await.expect_err("Expected injected failure");
+    assert!(err.source().unwrap().is::<InjectedFailure>());
+
+    // Wait for retry interval to pass
+    sleep(Duration::from_millis(1100)).await;
+
+    // Data is still forced stale, so data provider is called
+    assert_eq!(conf.load().await.unwrap().deref(), &MOCK_DATA);
+
+    // Fresh data is served from cache
+    conf.chaos().reset();
+    assert_eq!(conf.load().await.unwrap().deref(), &MOCK_DATA);
+
+    mock.assert_async().await;
+}
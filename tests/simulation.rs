@@ -0,0 +1,998 @@
+//! Deterministic simulation of RemoteConfig timing logic.
+//! Tokio clock is paused, and RemoteConfig reads time from it through [`TokioClock`],
+//! so scripted scenarios run instantly and always produce the same sequence of events.
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::ops::Deref;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::{Duration, SystemTime};
+use tokio::task::{yield_now, JoinSet};
+use tokio::time::{advance, Instant};
+use remote_config::bandwidth::{BandwidthPolicy, LinkState};
+use remote_config::budget::Budget;
+use remote_config::clock::Clock;
+use remote_config::config::{LoadTimeout, RemoteConfig, RemoteConfigBuilder};
+use remote_config::control::Control;
+use remote_config::flapping::FlappingPolicy;
+use remote_config::load_shedding::LoadSheddingPolicy;
+use remote_config::policy::GlobalPolicy;
+use remote_config::preset::Preset;
+use remote_config::profile::AccessProfile;
+use remote_config::data_providers::data_provider::{BoxedDataProvider, DataLoadResult, DataProvider, InvalidationToken, RetryHint, Revalidation};
+use remote_config::data_providers::memory::InMemoryDataProvider;
+use remote_config::manager::{BarrierError, ConfigManager, MAX_BARRIER_PASSES};
+
+/// Clock that follows paused tokio time
+#[derive(Debug, Clone)]
+struct TokioClock {
+    start: Instant,
+    start_time: SystemTime
+}
+
+impl TokioClock {
+    fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            start_time: SystemTime::UNIX_EPOCH
+        }
+    }
+}
+
+impl Clock for TokioClock {
+    fn now(&self) -> SystemTime {
+        self.start_time + self.start.elapsed()
+    }
+}
+
+/// Scripted data provider response
+enum Step {
+    /// Successfully load specified version
+    Load { version: u32, ttl: Duration, must_revalidate: bool },
+    /// Load specified version restored from cache, that was fetched from origin `age` ago
+    Restored { version: u32, age: Duration },
+    /// Report that data was not modified
+    NotModified { ttl: Duration },
+    /// Fail
+    Fail,
+    /// Fail, asking to retry after specified delay
+    FailRetryAfter(Duration),
+    /// Delay next revalidation
+    Sleep(Duration)
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Event {
+    /// Data provider was called and returned specified version
+    Loaded(u32),
+    /// Data provider was called and reported that data was not modified
+    NotModified,
+    /// Data provider was called and failed
+    Failed
+}
+
+#[derive(Debug)]
+struct ScriptError;
+
+impl Display for ScriptError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "scripted failure")
+    }
+}
+
+impl Error for ScriptError {}
+
+#[derive(Clone)]
+struct Script {
+    steps: Arc<Mutex<VecDeque<Step>>>,
+    events: Arc<Mutex<Vec<Event>>>,
+    /// Invalidation token of last loaded version
+    token: Arc<Mutex<InvalidationToken>>
+}
+
+impl Script {
+    fn new(steps: Vec<Step>) -> Self {
+        Self {
+            steps: Arc::new(Mutex::new(steps.into())),
+            events: Arc::new(Mutex::new(Vec::new())),
+            token: Arc::new(Mutex::new(InvalidationToken::new()))
+        }
+    }
+
+    fn events(&self) -> Vec<Event> {
+        self.events.lock().unwrap().clone()
+    }
+
+    fn invalidate(&self) {
+        self.token.lock().unwrap().invalidate();
+    }
+}
+
+struct ScriptedProvider {
+    script: Script,
+    clock: TokioClock
+}
+
+impl ScriptedProvider {
+    fn next(&self) -> Result<Revalidation<u32>, Box<dyn Error + Send + Sync>> {
+        let step = self.script.steps.lock().unwrap().pop_front().expect("Data provider called more times than scripted");
+        let mut events = self.script.events.lock().unwrap();
+        match step {
+            Step::Load { version, ttl, must_revalidate } => {
+                events.push(Event::Loaded(version));
+                let mut result = DataLoadResult::new(version, must_revalidate, self.clock.now() + ttl);
+                let token = InvalidationToken::new();
+                *self.script.token.lock().unwrap() = token.clone();
+                result.metadata.invalidation = Some(token);
+                Ok(Revalidation::Modified(result))
+            },
+            Step::Restored { version, age } => {
+                events.push(Event::Loaded(version));
+                let mut result = DataLoadResult::new(version, false, self.clock.now() + Duration::from_secs(3600));
+                result.metadata.fetched_at = Some(self.clock.now() - age);
+                Ok(Revalidation::Modified(result))
+            },
+            Step::NotModified { ttl } => {
+                events.push(Event::NotModified);
+                Ok(Revalidation::NotModified { must_revalidate: true, valid_until: self.clock.now() + ttl, invalidation: None, fetched_at: None })
+            },
+            Step::Fail => {
+                events.push(Event::Failed);
+                Err(Box::new(ScriptError))
+            },
+            Step::FailRetryAfter(delay) => {
+                events.push(Event::Failed);
+                Err(Box::new(RetryHint::new(delay, Box::new(ScriptError))))
+            },
+            Step::Sleep(_) => panic!("Sleep must be followed by another step")
+        }
+    }
+}
+
+impl DataProvider<u32> for ScriptedProvider {
+    async fn load_data(&self) -> Result<DataLoadResult<u32>, Box<dyn Error + Send + Sync>> {
+        match self.next()? {
+            Revalidation::Modified(result) => Ok(result),
+            Revalidation::NotModified { .. } => panic!("Initial load can't be scripted as not modified")
+        }
+    }
+
+    async fn revalidate_data<'a>(&'a self, _current: &'a DataLoadResult<u32>) -> Result<Revalidation<u32>, Box<dyn Error + Send + Sync>> {
+        let delay = {
+            let mut steps = self.script.steps.lock().unwrap();
+            match steps.front() {
+                Some(&Step::Sleep(delay)) => {
+                    steps.pop_front();
+                    Some(delay)
+                },
+                _ => None
+            }
+        };
+        if let Some(delay) = delay {
+            tokio::time::sleep(delay).await;
+        }
+        self.next()
+    }
+}
+
+type SimConfig = RemoteConfig<u32, ScriptedProvider>;
+type SimBuilder = RemoteConfigBuilder<u32, ScriptedProvider>;
+
+/// Builds config with retry interval of 1 second
+async fn init_config(steps: Vec<Step>) -> (&'static SimConfig, Script, TokioClock) {
+    init_config_with(steps, |builder| builder.with_retry_interval(Duration::from_secs(1))).await
+}
+
+/// Builds config with settings applied by `customize`
+async fn init_config_with(steps: Vec<Step>, customize: impl FnOnce(SimBuilder) -> SimBuilder) -> (&'static SimConfig, Script, TokioClock) {
+    let clock = TokioClock::new();
+    let script = Script::new(steps);
+    let data_provider = ScriptedProvider {
+        script: script.clone(),
+        clock: clock.clone()
+    };
+    #[cfg(feature = "tracing")]
+    let builder = RemoteConfig::builder("Simulation".to_string(), data_provider);
+    #[cfg(not (feature = "tracing"))]
+    let builder = RemoteConfig::builder(data_provider);
+
+    let config = customize(builder.with_clock(clock.clone()))
+        .build()
+        .await
+        .unwrap();
+
+    (Box::leak(Box::new(config)), script, clock)
+}
+
+/// Let background revalidation tasks run to completion
+async fn settle() {
+    for _ in 0..10 {
+        yield_now().await;
+    }
+}
+
+async fn served(config: &'static SimConfig) -> Option<u32> {
+    config.load().await.ok().map(|data| *data.deref())
+}
+
+#[tokio::test(start_paused = true)]
+async fn must_revalidate_fail_and_recover() {
+    let ttl = Duration::from_secs(10);
+    let (config, script, _) = init_config(vec![
+        Step::Load { version: 1, ttl, must_revalidate: true },
+        Step::Fail,
+        Step::Load { version: 2, ttl, must_revalidate: true }
+    ]).await;
+
+    // Fresh data
+    advance(Duration::from_secs(5)).await;
+    assert_eq!(served(config).await, Some(1));
+
+    // Expired, revalidation fails and stale data is not served
+    advance(Duration::from_secs(6)).await;
+    assert_eq!(served(config).await, None);
+
+    // Too early to retry, data provider is not called
+    advance(Duration::from_millis(500)).await;
+    assert_eq!(served(config).await, None);
+
+    // Recovered
+    advance(Duration::from_millis(500)).await;
+    assert_eq!(served(config).await, Some(2));
+    assert_eq!(served(config).await, Some(2));
+
+    assert_eq!(script.events(), vec![Event::Loaded(1), Event::Failed, Event::Loaded(2)]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn retry_hint_replaces_retry_interval() {
+    let ttl = Duration::from_secs(10);
+    let (config, script, _) = init_config(vec![
+        Step::Load { version: 1, ttl, must_revalidate: true },
+        Step::FailRetryAfter(Duration::from_secs(30)),
+        Step::Load { version: 2, ttl, must_revalidate: true }
+    ]).await;
+
+    advance(Duration::from_secs(11)).await;
+    let err = config.load().await.unwrap_err();
+    assert_eq!(err.retry_after(), Some(Duration::from_secs(30)));
+
+    // Retry interval has passed, but origin asked to wait longer
+    advance(Duration::from_secs(20)).await;
+    assert_eq!(served(config).await, None);
+
+    advance(Duration::from_secs(10)).await;
+    assert_eq!(served(config).await, Some(2));
+    assert_eq!(script.events(), vec![Event::Loaded(1), Event::Failed, Event::Loaded(2)]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn stale_data_served_during_background_revalidation() {
+    let ttl = Duration::from_secs(10);
+    let (config, script, _) = init_config(vec![
+        Step::Load { version: 1, ttl, must_revalidate: false },
+        Step::Fail,
+        Step::Load { version: 2, ttl, must_revalidate: false }
+    ]).await;
+
+    // Expired, stale data is served while revalidation fails in background
+    advance(Duration::from_secs(11)).await;
+    assert_eq!(served(config).await, Some(1));
+    settle().await;
+    assert_eq!(served(config).await, Some(1));
+    assert_eq!(script.events(), vec![Event::Loaded(1), Event::Failed]);
+
+    // Retry in background, stale data is served one last time
+    advance(Duration::from_secs(1)).await;
+    assert_eq!(served(config).await, Some(1));
+    settle().await;
+    assert_eq!(served(config).await, Some(2));
+
+    assert_eq!(script.events(), vec![Event::Loaded(1), Event::Failed, Event::Loaded(2)]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn boxed_provider() {
+    let clock = TokioClock::new();
+    let script = Script::new(vec![
+        Step::Load { version: 1, ttl: Duration::from_secs(10), must_revalidate: true },
+        Step::Load { version: 2, ttl: Duration::from_secs(10), must_revalidate: true }
+    ]);
+    let data_provider = BoxedDataProvider::new(ScriptedProvider {
+        script: script.clone(),
+        clock: clock.clone()
+    });
+    #[cfg(feature = "tracing")]
+    let builder = RemoteConfig::builder("Simulation".to_string(), data_provider);
+    #[cfg(not (feature = "tracing"))]
+    let builder = RemoteConfig::builder(data_provider);
+    let config: &'static RemoteConfig<u32, BoxedDataProvider<u32>> = Box::leak(Box::new(builder.with_clock(clock).build().await.unwrap()));
+
+    assert_eq!(*config.load().await.unwrap(), 1);
+    advance(Duration::from_secs(11)).await;
+    assert_eq!(*config.load().await.unwrap(), 2);
+    assert_eq!(script.events(), vec![Event::Loaded(1), Event::Loaded(2)]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn replaced_provider_keeps_cached_data() {
+    let ttl = Duration::from_secs(10);
+    let (config, script, clock) = init_config(vec![
+        Step::Load { version: 1, ttl, must_revalidate: true },
+        Step::Fail
+    ]).await;
+
+    // Old provider fails
+    advance(Duration::from_secs(11)).await;
+    assert_eq!(served(config).await, None);
+
+    let replacement = Script::new(vec![Step::Load { version: 2, ttl, must_revalidate: true }]);
+    config.replace_provider(ScriptedProvider {
+        script: replacement.clone(),
+        clock
+    }).await;
+
+    // Retry interval is reset, new provider is used immediately
+    assert_eq!(served(config).await, Some(2));
+    assert_eq!(served(config).await, Some(2));
+    assert_eq!(script.events(), vec![Event::Loaded(1), Event::Failed]);
+    assert_eq!(replacement.events(), vec![Event::Loaded(2)]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn exhausted_budget_stops_fetching() {
+    let ttl = Duration::from_secs(10);
+    let (config, script, _) = init_config_with(vec![
+        Step::Load { version: 1, ttl, must_revalidate: true },
+        Step::Load { version: 2, ttl, must_revalidate: true },
+        Step::Load { version: 3, ttl, must_revalidate: true }
+    ], |builder| builder.with_budget(Budget::new(Duration::from_secs(60)).with_max_requests(2))).await;
+
+    advance(Duration::from_secs(11)).await;
+    assert_eq!(served(config).await, Some(2));
+    assert!(config.status().budget_exhausted);
+    assert_eq!(*config.refresh().await.unwrap(), 2);
+
+    // Stale data is served, even though it must be revalidated
+    advance(Duration::from_secs(11)).await;
+    assert_eq!(served(config).await, Some(2));
+    assert!(config.status().stale);
+
+    // Budget window ends
+    advance(Duration::from_secs(60)).await;
+    assert_eq!(served(config).await, Some(3));
+
+    let status = config.status();
+    assert!(!status.budget_exhausted);
+    assert_eq!(status.usage.requests, 3);
+    assert_eq!(status.reads, 3);
+    assert_eq!(status.last_accessed, Some(SystemTime::UNIX_EPOCH + Duration::from_secs(82)));
+    assert_eq!(script.events(), vec![Event::Loaded(1), Event::Loaded(2), Event::Loaded(3)]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn startup_splay_delays_initial_load() {
+    let window = Duration::from_secs(60);
+    let steps = vec![Step::Load { version: 1, ttl: Duration::from_secs(600), must_revalidate: true }];
+    let (config, script, _) = init_config_with(steps, |builder| builder.with_startup_splay(window)).await;
+
+    // Data is loaded after splay, so its expiration is shifted by the same delay
+    let delay = config.status().valid_until - Duration::from_secs(600);
+    assert!(delay.duration_since(SystemTime::UNIX_EPOCH).unwrap() < window);
+    assert_eq!(script.events(), vec![Event::Loaded(1)]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn invalidated_data_is_revalidated() {
+    let ttl = Duration::from_secs(600);
+    let (config, script, _) = init_config(vec![
+        Step::Load { version: 1, ttl, must_revalidate: true },
+        Step::Load { version: 2, ttl, must_revalidate: true }
+    ]).await;
+
+    assert_eq!(served(config).await, Some(1));
+    script.invalidate();
+    assert_eq!(served(config).await, Some(2));
+    assert_eq!(served(config).await, Some(2));
+    assert_eq!(script.events(), vec![Event::Loaded(1), Event::Loaded(2)]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn offline_mode_serves_cached_data() {
+    let ttl = Duration::from_secs(10);
+    let (config, script, _) = init_config(vec![
+        Step::Load { version: 1, ttl, must_revalidate: true },
+        Step::Load { version: 2, ttl, must_revalidate: true }
+    ]).await;
+
+    config.set_offline(true);
+    advance(Duration::from_secs(60 * 60)).await;
+    assert_eq!(served(config).await, Some(1));
+    assert!(config.status().offline);
+    assert_eq!(script.events(), vec![Event::Loaded(1)]);
+
+    config.set_offline(false);
+    assert_eq!(served(config).await, Some(2));
+    assert_eq!(script.events(), vec![Event::Loaded(1), Event::Loaded(2)]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn metered_link_stretches_refresh_interval() {
+    let ttl = Duration::from_secs(10);
+    let (config, script, _) = init_config_with(vec![
+        Step::Load { version: 1, ttl, must_revalidate: true },
+        Step::Load { version: 2, ttl, must_revalidate: true },
+        Step::Load { version: 3, ttl, must_revalidate: true }
+    ], |builder| builder.with_bandwidth_policy(BandwidthPolicy::new().with_metered_interval(Duration::from_secs(60)))).await;
+
+    config.set_link_state(LinkState::Metered);
+    advance(Duration::from_secs(30)).await;
+    assert_eq!(served(config).await, Some(1));
+
+    advance(Duration::from_secs(30)).await;
+    assert_eq!(served(config).await, Some(2));
+
+    // Link is unmetered again, usual TTL applies
+    config.set_link_state(LinkState::Unmetered);
+    advance(Duration::from_secs(11)).await;
+    assert_eq!(served(config).await, Some(3));
+    assert_eq!(script.events(), vec![Event::Loaded(1), Event::Loaded(2), Event::Loaded(3)]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn overload_defers_background_refresh() {
+    let ttl = Duration::from_secs(10);
+    let overloaded = Arc::new(AtomicBool::new(true));
+    let signal = overloaded.clone();
+    let policy = LoadSheddingPolicy::new(move || signal.load(Ordering::Relaxed)).with_max_deferral(Duration::from_secs(30));
+    let (config, script, _) = init_config_with(vec![
+        Step::Load { version: 1, ttl, must_revalidate: false },
+        Step::Load { version: 2, ttl, must_revalidate: false },
+        Step::Load { version: 3, ttl, must_revalidate: true },
+        Step::Load { version: 4, ttl, must_revalidate: true },
+        Step::Load { version: 5, ttl: Duration::from_secs(1000), must_revalidate: false },
+        Step::Load { version: 6, ttl, must_revalidate: false }
+    ], |builder| builder.with_load_shedding(policy)).await;
+
+    // Stale data is served while process is overloaded
+    advance(Duration::from_secs(30)).await;
+    assert_eq!(served(config).await, Some(1));
+    settle().await;
+    assert_eq!(script.events(), vec![Event::Loaded(1)]);
+
+    // Data was stale for longer than max deferral
+    advance(Duration::from_secs(10)).await;
+    assert_eq!(served(config).await, Some(1));
+    settle().await;
+    assert_eq!(served(config).await, Some(2));
+
+    // Refresh is no longer deferred once overload is over
+    advance(Duration::from_secs(11)).await;
+    assert_eq!(served(config).await, Some(2));
+    settle().await;
+    assert_eq!(served(config).await, Some(2));
+    overloaded.store(false, Ordering::Relaxed);
+    assert_eq!(served(config).await, Some(2));
+    settle().await;
+    assert_eq!(served(config).await, Some(3));
+
+    // Data that must be revalidated is not deferred
+    overloaded.store(true, Ordering::Relaxed);
+    advance(Duration::from_secs(11)).await;
+    assert_eq!(served(config).await, Some(4));
+    assert_eq!(script.events(), vec![Event::Loaded(1), Event::Loaded(2), Event::Loaded(3), Event::Loaded(4)]);
+
+    // Invalidated data, that did not expire yet, is deferred for max deferral since the first deferred refresh
+    advance(Duration::from_secs(11)).await;
+    assert_eq!(served(config).await, Some(5));
+    script.invalidate();
+    assert_eq!(served(config).await, Some(5));
+    advance(Duration::from_secs(20)).await;
+    assert_eq!(served(config).await, Some(5));
+    settle().await;
+    assert_eq!(served(config).await, Some(5));
+    advance(Duration::from_secs(11)).await;
+    assert_eq!(served(config).await, Some(5));
+    settle().await;
+    assert_eq!(served(config).await, Some(6));
+}
+
+#[tokio::test(start_paused = true)]
+async fn not_modified_keeps_data_and_extends_freshness() {
+    let ttl = Duration::from_secs(10);
+    let (config, script, _) = init_config(vec![
+        Step::Load { version: 1, ttl, must_revalidate: true },
+        Step::NotModified { ttl: Duration::from_secs(100) }
+    ]).await;
+
+    let before = config.load().await.unwrap();
+    advance(Duration::from_secs(11)).await;
+    let after = config.load().await.unwrap();
+    assert!(std::ptr::eq(&*before, &*after));
+
+    // Freshness is extended, so data provider is not called again
+    advance(Duration::from_secs(50)).await;
+    assert_eq!(served(config).await, Some(1));
+    assert!(!config.status().stale);
+    assert_eq!(script.events(), vec![Event::Loaded(1), Event::NotModified]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn control_pauses_updates_until_forced_refresh() {
+    let ttl = Duration::from_secs(10);
+    let (config, script, clock) = init_config(vec![
+        Step::Load { version: 1, ttl, must_revalidate: true },
+        Step::Load { version: 2, ttl, must_revalidate: true }
+    ]).await;
+
+    let mut pause = Control::default();
+    pause.pause_updates_until = Some(clock.now() + Duration::from_secs(60 * 60));
+    config.apply_control(pause);
+
+    // Paused, stale data is served, even if refresh is requested
+    advance(Duration::from_secs(60)).await;
+    assert_eq!(served(config).await, Some(1));
+    assert_eq!(*config.refresh().await.unwrap(), 1);
+    assert_eq!(script.events(), vec![Event::Loaded(1)]);
+
+    let mut force = Control::default();
+    force.force_refresh_now = true;
+    config.apply_control(force);
+    assert_eq!(served(config).await, Some(2));
+    assert!(!config.status().stale);
+    assert_eq!(script.events(), vec![Event::Loaded(1), Event::Loaded(2)]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn revalidation_runs_in_join_set() {
+    let ttl = Duration::from_secs(10);
+    let tasks = Arc::new(Mutex::new(JoinSet::new()));
+    let (config, script, _) = init_config_with(vec![
+        Step::Load { version: 1, ttl, must_revalidate: false },
+        Step::Load { version: 2, ttl, must_revalidate: false }
+    ], |builder| builder.with_spawner(tasks.clone())).await;
+
+    // Background revalidation is owned by join set, and can be awaited on shutdown
+    advance(Duration::from_secs(11)).await;
+    assert_eq!(served(config).await, Some(1));
+    let mut tasks = std::mem::take(&mut *tasks.lock().unwrap());
+    assert_eq!(tasks.len(), 1);
+    while tasks.join_next().await.is_some() {}
+    assert_eq!(served(config).await, Some(2));
+    assert_eq!(script.events(), vec![Event::Loaded(1), Event::Loaded(2)]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn slow_revalidation_serves_stale_data_after_max_wait() {
+    let ttl = Duration::from_secs(10);
+    let (config, script, _) = init_config_with(vec![
+        Step::Load { version: 1, ttl, must_revalidate: true },
+        Step::Sleep(Duration::from_millis(300)),
+        Step::Load { version: 2, ttl, must_revalidate: true }
+    ], |builder| builder.with_max_revalidation_wait(Duration::from_millis(100))).await;
+
+    // Revalidation takes too long, so stale data is served
+    advance(Duration::from_secs(11)).await;
+    assert_eq!(served(config).await, Some(1));
+    // Revalidation is still in progress, waiting for it is bounded too
+    assert_eq!(served(config).await, Some(1));
+    assert_eq!(config.status().background_tasks, 1);
+
+    // Revalidation completes in background
+    advance(Duration::from_millis(100)).await;
+    settle().await;
+    assert_eq!(served(config).await, Some(2));
+    assert_eq!(config.status().background_tasks, 0);
+    assert_eq!(script.events(), vec![Event::Loaded(1), Event::Loaded(2)]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn offline_first_preset_serves_cached_data_without_waiting() {
+    let ttl = Duration::from_secs(10);
+    let (config, script, _) = init_config_with(vec![
+        Step::Load { version: 1, ttl, must_revalidate: true },
+        Step::Sleep(Duration::from_secs(1)),
+        Step::Load { version: 2, ttl, must_revalidate: true }
+    ], |builder| builder
+        .with_preset(Preset::OfflineFirst)
+        .with_startup_splay(Duration::ZERO)
+    ).await;
+
+    // Data must be revalidated after minimal TTL of preset, but it is served without waiting
+    advance(Duration::from_secs(5 * 60 + 1)).await;
+    let started = Instant::now();
+    assert_eq!(served(config).await, Some(1));
+    assert_eq!(started.elapsed(), Duration::ZERO);
+
+    // Revalidation completes in background
+    settle().await;
+    advance(Duration::from_secs(1)).await;
+    settle().await;
+    assert_eq!(served(config).await, Some(2));
+    assert_eq!(script.events(), vec![Event::Loaded(1), Event::Loaded(2)]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn retry_interval_backs_off_after_consecutive_failures() {
+    let ttl = Duration::from_secs(10);
+    let (config, script, _) = init_config_with(vec![
+        Step::Load { version: 1, ttl, must_revalidate: true },
+        Step::Fail,
+        Step::Fail,
+        Step::Fail,
+        Step::Load { version: 2, ttl, must_revalidate: true }
+    ], |builder| builder
+        .with_retry_interval(Duration::from_secs(1))
+        .with_retry_backoff(Duration::from_secs(4))
+    ).await;
+
+    advance(Duration::from_secs(11)).await;
+    assert_eq!(served(config).await, None);
+    advance(Duration::from_secs(1)).await;
+    assert_eq!(served(config).await, None);
+    // Retry interval is doubled after second failure
+    advance(Duration::from_secs(1)).await;
+    assert_eq!(served(config).await, None);
+    assert_eq!(script.events().len(), 3);
+    advance(Duration::from_secs(1)).await;
+    assert_eq!(served(config).await, None);
+    assert_eq!(script.events().len(), 4);
+
+    // And is limited after third one
+    advance(Duration::from_secs(3)).await;
+    assert_eq!(served(config).await, None);
+    advance(Duration::from_secs(1)).await;
+    assert_eq!(served(config).await, Some(2));
+    assert_eq!(script.events(), vec![Event::Loaded(1), Event::Failed, Event::Failed, Event::Failed, Event::Loaded(2)]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn slow_load_fails_after_load_timeout() {
+    let ttl = Duration::from_secs(10);
+    let (config, script, _) = init_config_with(vec![
+        Step::Load { version: 1, ttl, must_revalidate: true },
+        Step::Sleep(Duration::from_secs(10)),
+        Step::Load { version: 2, ttl, must_revalidate: true }
+    ], |builder| builder
+        .with_retry_interval(Duration::from_secs(1))
+        .with_load_timeout(Duration::from_secs(2))
+    ).await;
+
+    advance(Duration::from_secs(11)).await;
+    let started = Instant::now();
+    let err = config.load().await.unwrap_err();
+    assert_eq!(started.elapsed(), Duration::from_secs(2));
+    assert!(err.source().unwrap().downcast_ref::<LoadTimeout>().is_some());
+
+    // Abandoned load is started again after retry interval
+    advance(Duration::from_secs(1)).await;
+    assert_eq!(served(config).await, Some(2));
+    assert_eq!(script.events(), vec![Event::Loaded(1), Event::Loaded(2)]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn ttl_limits_override_reported_lifetime() {
+    let (config, script, _) = init_config_with(vec![
+        Step::Load { version: 1, ttl: Duration::ZERO, must_revalidate: true },
+        Step::Load { version: 2, ttl: Duration::from_secs(3600), must_revalidate: true },
+        Step::Load { version: 3, ttl: Duration::ZERO, must_revalidate: true }
+    ], |builder| builder
+        .with_min_ttl(Duration::from_secs(5))
+        .with_max_ttl(Duration::from_secs(20))
+    ).await;
+
+    // Data that expired immediately is fresh for minimal TTL
+    advance(Duration::from_secs(4)).await;
+    assert_eq!(served(config).await, Some(1));
+    advance(Duration::from_secs(2)).await;
+    assert_eq!(served(config).await, Some(2));
+
+    // Data with long lifetime is revalidated after maximal TTL
+    advance(Duration::from_secs(19)).await;
+    assert_eq!(served(config).await, Some(2));
+    advance(Duration::from_secs(2)).await;
+    assert_eq!(served(config).await, Some(3));
+    assert_eq!(script.events(), vec![Event::Loaded(1), Event::Loaded(2), Event::Loaded(3)]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn policy_settings_are_inherited_unless_overridden() {
+    let ttl = Duration::from_secs(10);
+    let policy = GlobalPolicy::new()
+        .with_retry_interval(Duration::from_secs(60))
+        .with_stale_tolerance(Duration::from_secs(5));
+    let (config, script, _) = init_config_with(vec![
+        Step::Load { version: 1, ttl, must_revalidate: true },
+        Step::Fail,
+        Step::Load { version: 2, ttl, must_revalidate: true }
+    ], |builder| builder
+        .with_policy(&policy)
+        .with_stale_tolerance(Duration::ZERO)
+    ).await;
+
+    // Stale tolerance of policy is overridden, so failed revalidation is reported
+    advance(Duration::from_secs(11)).await;
+    assert_eq!(served(config).await, None);
+
+    // Retry interval of policy is inherited
+    advance(Duration::from_secs(30)).await;
+    assert_eq!(served(config).await, None);
+    advance(Duration::from_secs(30)).await;
+    assert_eq!(served(config).await, Some(2));
+    assert_eq!(script.events(), vec![Event::Loaded(1), Event::Failed, Event::Loaded(2)]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn manager_policy_sets_backoff_and_load_timeout() {
+    let clock = TokioClock::new();
+    let ttl = Duration::from_secs(10);
+    let manager = ConfigManager::new().with_policy(GlobalPolicy::new()
+        .with_retry_interval(Duration::from_secs(1))
+        .with_retry_backoff(Duration::from_secs(4))
+        .with_load_timeout(Duration::from_secs(2)));
+    let script = Script::new(vec![
+        Step::Load { version: 1, ttl, must_revalidate: true },
+        Step::Sleep(Duration::from_secs(10)),
+        Step::Fail,
+        Step::Load { version: 2, ttl, must_revalidate: true }
+    ]);
+    let data_provider = BoxedDataProvider::new(ScriptedProvider { script: script.clone(), clock: clock.clone() });
+    #[cfg(feature = "tracing")]
+    let builder = manager.builder("flags".to_string(), data_provider);
+    #[cfg(not (feature = "tracing"))]
+    let builder = manager.builder(data_provider);
+    let config: &_ = Box::leak(Box::new(builder.with_clock(clock).build().await.unwrap()));
+
+    // Load timeout of policy is applied
+    advance(Duration::from_secs(11)).await;
+    let started = Instant::now();
+    let err = config.load().await.unwrap_err();
+    assert_eq!(started.elapsed(), Duration::from_secs(2));
+    assert!(err.source().unwrap().downcast_ref::<LoadTimeout>().is_some());
+
+    // Retry interval is doubled after second failure
+    advance(Duration::from_secs(1)).await;
+    assert_eq!(config.load().await.ok().map(|data| *data), None);
+    advance(Duration::from_secs(1)).await;
+    assert_eq!(config.load().await.ok().map(|data| *data), None);
+    assert_eq!(script.events(), vec![Event::Loaded(1), Event::Failed]);
+    advance(Duration::from_secs(1)).await;
+    assert_eq!(config.load().await.ok().map(|data| *data), Some(2));
+    assert_eq!(script.events(), vec![Event::Loaded(1), Event::Failed, Event::Loaded(2)]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn access_profiles_select_freshness_policy() {
+    let ttl = Duration::from_secs(10);
+    let (config, script, _) = init_config_with(vec![
+        Step::Load { version: 1, ttl, must_revalidate: true },
+        Step::Sleep(Duration::from_secs(1)),
+        Step::Load { version: 2, ttl, must_revalidate: true },
+        Step::Fail
+    ], |builder| builder.with_access_profile("fast-path", AccessProfile::new().with_stale_tolerance(Duration::from_secs(5)))).await;
+
+    // Fast path serves stale data within tolerance and revalidates in background
+    advance(Duration::from_secs(11)).await;
+    assert_eq!(config.load_as("fast-path").await.ok().map(|data| *data), Some(1));
+    // Default path waits for revalidation in progress
+    assert_eq!(served(config).await, Some(2));
+
+    // Out of tolerance, fast path waits too, and gets revalidation error
+    advance(Duration::from_secs(16)).await;
+    assert!(config.load_as("fast-path").await.is_err());
+    assert_eq!(script.events(), vec![Event::Loaded(1), Event::Loaded(2), Event::Failed]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn wait_for_condition() {
+    let ttl = Duration::from_secs(10);
+    let (config, script, _) = init_config(vec![
+        Step::Load { version: 1, ttl, must_revalidate: false },
+        Step::Fail,
+        Step::Load { version: 2, ttl, must_revalidate: false },
+        Step::Load { version: 3, ttl, must_revalidate: false }
+    ]).await;
+
+    // Data expires after 10 seconds, first revalidation fails, and retry succeeds one retry interval later
+    let started = Instant::now();
+    let data = config.wait_for(|version| *version >= 2, Duration::from_secs(60)).await.unwrap();
+    assert_eq!(*data, 2);
+    assert_eq!(started.elapsed(), Duration::from_secs(12));
+
+    let err = config.wait_for(|version| *version > 5, Duration::from_secs(15)).await.unwrap_err();
+    assert!(err.last_error.is_none());
+    assert_eq!(script.events(), vec![Event::Loaded(1), Event::Failed, Event::Loaded(2), Event::Loaded(3)]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn expect_published_version() {
+    let data_provider = InMemoryDataProvider::new(0u32).with_max_age(Duration::from_secs(600));
+    let publisher = data_provider.clone();
+    #[cfg(feature = "tracing")]
+    let builder = RemoteConfig::builder("Simulation".to_string(), data_provider);
+    #[cfg(not (feature = "tracing"))]
+    let builder = RemoteConfig::builder(data_provider);
+    let config = builder.with_retry_interval(Duration::from_secs(1)).build().await.unwrap();
+
+    // Version 2 is published after 2.5 seconds, and is activated by the next refresh
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(2500)).await;
+        publisher.set(1);
+        publisher.set(2);
+    });
+    let started = Instant::now();
+    let data = config.expect_version("2", Duration::from_secs(10)).await.unwrap();
+    assert_eq!(*data, 2);
+    assert_eq!(started.elapsed(), Duration::from_secs(3));
+    assert!(config.expect_version("2", Duration::ZERO).await.is_ok());
+
+    let err = config.expect_version("3", Duration::from_secs(5)).await.unwrap_err();
+    assert!(err.last_error.is_none());
+}
+
+#[cfg(feature = "stream")]
+#[tokio::test(start_paused = true)]
+async fn stream_yields_activated_versions() {
+    use futures_util::StreamExt;
+
+    let ttl = Duration::from_secs(10);
+    let (config, script, _) = init_config(vec![
+        Step::Load { version: 1, ttl, must_revalidate: true },
+        Step::NotModified { ttl },
+        Step::Fail,
+        Step::Load { version: 2, ttl, must_revalidate: true }
+    ]).await;
+
+    // Unmodified version is skipped, and failed revalidation is retried after retry interval
+    let started = Instant::now();
+    let versions: Vec<u32> = config.stream().map(|data| *data).take(2).collect().await;
+    assert_eq!(versions, vec![1, 2]);
+    assert_eq!(started.elapsed(), Duration::from_secs(23));
+    assert_eq!(script.events(), vec![Event::Loaded(1), Event::NotModified, Event::Failed, Event::Loaded(2)]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn frequent_changes_raise_flapping_alarm() {
+    let ttl = Duration::from_secs(1);
+    let alarms = Arc::new(AtomicUsize::new(0));
+    let policy = FlappingPolicy::new(2, Duration::from_secs(60)).with_handler({
+        let alarms = alarms.clone();
+        move |_| {
+            alarms.fetch_add(1, Ordering::Relaxed);
+        }
+    });
+    let steps = (1..=4).map(|version| Step::Load { version, ttl, must_revalidate: false }).collect();
+    let (config, _, _) = init_config_with(steps, |builder| builder.with_flapping_detection(policy)).await;
+
+    // Two changes within window are allowed
+    for version in 2..=3 {
+        advance(Duration::from_secs(2)).await;
+        served(config).await;
+        settle().await;
+        assert_eq!(served(config).await, Some(version));
+    }
+    assert_eq!(config.status().flapping, None);
+
+    // Third change raises alarm once
+    advance(Duration::from_secs(2)).await;
+    served(config).await;
+    settle().await;
+    assert_eq!(served(config).await, Some(4));
+    let alarm = config.status().flapping.expect("config must be flapping");
+    assert_eq!(alarm.changes, 3);
+    assert_eq!(alarm.recent_revisions, vec![None, None, None]);
+    assert_eq!(alarms.load(Ordering::Relaxed), 1);
+
+    // Alarm is cleared when changes leave window
+    advance(Duration::from_secs(60)).await;
+    assert_eq!(config.status().flapping, None);
+    assert_eq!(alarms.load(Ordering::Relaxed), 1);
+}
+
+#[cfg(feature = "json")]
+#[tokio::test(start_paused = true)]
+async fn content_hash_changes_with_data() {
+    let ttl = Duration::from_secs(10);
+    let (config, _, _) = init_config_with(vec![
+        Step::Load { version: 1, ttl, must_revalidate: true },
+        Step::NotModified { ttl },
+        Step::Load { version: 2, ttl, must_revalidate: true }
+    ], |builder| builder.with_content_hash()).await;
+
+    let first = config.load().await.unwrap().content_hash();
+    assert!(first.is_some());
+
+    // Hash is kept while data is not modified
+    advance(ttl + Duration::from_secs(1)).await;
+    assert_eq!(config.load().await.unwrap().content_hash(), first);
+
+    advance(ttl + Duration::from_secs(1)).await;
+    let second = config.load().await.unwrap();
+    assert_eq!(*second, 2);
+    assert_ne!(second.content_hash(), first);
+}
+
+#[tokio::test(start_paused = true)]
+async fn refresh_revalidates_fresh_data() {
+    let ttl = Duration::from_secs(600);
+    let (config, script, _) = init_config(vec![
+        Step::Load { version: 1, ttl, must_revalidate: true },
+        Step::Fail,
+        Step::Load { version: 2, ttl, must_revalidate: true }
+    ]).await;
+
+    // Failed refresh keeps cached data
+    assert!(config.refresh().await.is_err());
+    assert_eq!(served(config).await, Some(1));
+    assert_eq!(*config.refresh().await.unwrap(), 2);
+
+    config.set_offline(true);
+    assert_eq!(*config.refresh().await.unwrap(), 2);
+    assert_eq!(script.events(), vec![Event::Loaded(1), Event::Failed, Event::Loaded(2)]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn barrier_waits_for_recent_versions() {
+    let clock = TokioClock::new();
+    let mut manager = ConfigManager::new();
+    let mut scripts = Vec::new();
+    let day = Duration::from_secs(24 * 60 * 60);
+    for (name, steps) in [
+        ("flags", vec![Step::Restored { version: 1, age: day }, Step::Fail, Step::Load { version: 2, ttl: day, must_revalidate: false }]),
+        ("limits", vec![Step::Load { version: 1, ttl: day, must_revalidate: false }])
+    ] {
+        let script = Script::new(steps);
+        let data_provider = BoxedDataProvider::new(ScriptedProvider { script: script.clone(), clock: clock.clone() });
+        #[cfg(feature = "tracing")]
+        let builder = RemoteConfig::builder(name.to_string(), data_provider);
+        #[cfg(not (feature = "tracing"))]
+        let builder = RemoteConfig::builder(data_provider);
+        manager.insert(name, builder.with_retry_interval(Duration::from_secs(1)).with_clock(clock.clone()).build().await.unwrap());
+        scripts.push(script);
+    }
+
+    // Day-old flags are refreshed, and failed refresh is retried after retry interval
+    let started = Instant::now();
+    manager.barrier(["flags", "limits"], Duration::from_secs(60), Duration::from_secs(10)).await.unwrap();
+    assert_eq!(started.elapsed(), Duration::from_secs(1));
+    assert_eq!(scripts[0].events(), vec![Event::Loaded(1), Event::Failed, Event::Loaded(2)]);
+    assert_eq!(scripts[1].events(), vec![Event::Loaded(1)]);
+
+    let err = manager.barrier(["flags", "features"], Duration::from_secs(60), Duration::from_secs(10)).await.unwrap_err();
+    assert!(matches!(err, BarrierError::UnknownConfig(name) if name == "features"));
+
+    // Offline config returns cached data without refresh, so barrier gives up at timeout
+    manager.get("limits").unwrap().set_offline(true);
+    advance(day).await;
+    let started = Instant::now();
+    let err = manager.barrier(["limits"], Duration::from_secs(60), Duration::from_secs(5)).await.unwrap_err();
+    assert!(matches!(err, BarrierError::Timeout { config, source } if config == "limits" && source.last_error.is_none()));
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
+
+#[tokio::test(start_paused = true)]
+async fn barrier_gives_up_when_configs_age_during_refresh() {
+    let clock = TokioClock::new();
+    let mut manager = ConfigManager::new();
+    let ttl = Duration::from_secs(24 * 60 * 60);
+    for name in ["a", "b"] {
+        // Every refresh takes longer than allowed age
+        let mut steps = vec![Step::Restored { version: 0, age: ttl }];
+        for version in 1..=MAX_BARRIER_PASSES as u32 {
+            steps.extend([Step::Sleep(Duration::from_secs(90)), Step::Load { version, ttl, must_revalidate: false }]);
+        }
+        let data_provider = BoxedDataProvider::new(ScriptedProvider { script: Script::new(steps), clock: clock.clone() });
+        #[cfg(feature = "tracing")]
+        let builder = RemoteConfig::builder(name.to_string(), data_provider);
+        #[cfg(not (feature = "tracing"))]
+        let builder = RemoteConfig::builder(data_provider);
+        manager.insert(name, builder.with_clock(clock.clone()).build().await.unwrap());
+    }
+
+    let started = Instant::now();
+    let err = manager.barrier(["a", "b"], Duration::from_secs(60), Duration::from_secs(3600)).await.unwrap_err();
+    assert!(matches!(err, BarrierError::Unsettled));
+    // First pass refreshes both configs, and next passes refresh the one that aged during refresh of the other
+    assert_eq!(started.elapsed(), Duration::from_secs(90 * (MAX_BARRIER_PASSES as u64 + 1)));
+}